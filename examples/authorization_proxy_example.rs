@@ -31,9 +31,9 @@ use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler, handler::server::router::tool::ToolRouter,
     model::*, service::RequestContext, tool, tool_handler, tool_router,
 };
-use rmcp_actix_web::transport::{AuthorizationHeader, StreamableHttpService};
+use rmcp_actix_web::transport::{AuthorizationHeader, CircuitBreaker, StreamableHttpService};
 use serde_json::json;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
 /// Example MCP service that acts as a proxy to backend APIs.
@@ -46,6 +46,8 @@ use tokio::sync::Mutex;
 struct ProxyService {
     /// Stores the Authorization header for the session
     authorization: Arc<Mutex<Option<String>>>,
+    /// Fails backend calls fast while the backend API is down
+    backend: CircuitBreaker,
     /// Router for tool dispatch
     #[expect(
         dead_code,
@@ -59,6 +61,8 @@ impl ProxyService {
     fn new() -> Self {
         Self {
             authorization: Arc::new(Mutex::new(None)),
+            backend: CircuitBreaker::new(5, Duration::from_secs(30))
+                .with_call_timeout(Duration::from_secs(10)),
             tool_router: Self::tool_router(),
         }
     }
@@ -77,14 +81,21 @@ impl ProxyService {
             //     .send()
             //     .await?;
 
+            // Backend calls go through the circuit breaker, so an unavailable
+            // backend fails fast instead of piling up timeouts.
             // For this example, we'll simulate a response
-            let simulated_response = json!({
-                "user_id": "12345",
-                "name": "John Doe",
-                "email": "john.doe@example.com",
-                "auth_used": auth_header,
-                "note": "This is simulated data. In production, this would come from your backend API."
-            });
+            let simulated_response = self
+                .backend
+                .call(async {
+                    Ok(json!({
+                        "user_id": "12345",
+                        "name": "John Doe",
+                        "email": "john.doe@example.com",
+                        "auth_used": auth_header,
+                        "note": "This is simulated data. In production, this would come from your backend API."
+                    }))
+                })
+                .await?;
 
             Ok(CallToolResult::success(vec![Content::text(
                 simulated_response.to_string(),
@@ -171,8 +182,9 @@ impl ServerHandler for ProxyService {
         if let Some(auth) = context.extensions.get::<AuthorizationHeader>() {
             let mut stored_auth = self.authorization.lock().await;
             *stored_auth = Some(auth.0.clone());
-            println!("✓ Authorization header captured: {}", auth.0);
-            tracing::info!("Authorization header stored for proxy use: {}", auth.0);
+            // Never log the token itself; the Debug output hides the credentials
+            println!("✓ Authorization header captured: {auth:?}");
+            tracing::info!(?auth, "Authorization header stored for proxy use");
         } else {
             println!("ℹ No Authorization header provided");
             tracing::info!("No Authorization header found - proxy calls will fail");
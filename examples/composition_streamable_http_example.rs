@@ -11,6 +11,7 @@
 //! - Integration with existing actix-web middleware and routes
 //! - Session management for stateful MCP communication
 //! - Unified builder pattern consistent with SseService
+//! - Describing the mounted services with a `ServiceDirectory`
 //!
 //! ## Usage
 //!
@@ -32,11 +33,14 @@
 //!      -H "Accept: application/json, text/event-stream" \
 //!      -H "Mcp-Session-Id: <session_id>" \
 //!      -d '{"jsonrpc":"2.0","id":2,"method":"tools/list","params":{}}'
+//!
+//! # Describe the mounted services and their tools
+//! curl http://127.0.0.1:8080/api/info
 //! ```
 
 use actix_web::{App, HttpResponse, HttpServer, Result, middleware, web};
 use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
-use rmcp_actix_web::transport::StreamableHttpService;
+use rmcp_actix_web::transport::{ServiceDirectory, StreamableHttpService};
 use std::{sync::Arc, time::Duration};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -53,26 +57,6 @@ async fn health_check() -> Result<HttpResponse> {
     })))
 }
 
-/// API information endpoint
-async fn api_info() -> Result<HttpResponse> {
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "api_version": "v1",
-        "services": {
-            "calculator": {
-                "path": "/api/v1/calculator/",
-                "transport": "streamable-http",
-                "methods": ["GET", "POST", "DELETE"],
-                "description": "MCP Calculator service with session management"
-            }
-        },
-        "usage": {
-            "initialize": "POST with initialize method to create session",
-            "requests": "POST with Mcp-Session-Id header for subsequent requests",
-            "streaming": "GET with Mcp-Session-Id header to receive streaming responses"
-        }
-    })))
-}
-
 /// Root endpoint that shows available services
 async fn root() -> Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -114,6 +98,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .sse_keep_alive(Duration::from_secs(30)) // Keep-alive pings
         .build();
 
+    // Describe every mounted service, with its tools, in one document
+    let directory =
+        ServiceDirectory::new().service(calculator_service.directory_entry("/api/v1/calculator"));
+
     // Create the main HTTP server with framework-level composition
     let server =
         HttpServer::new(move || {
@@ -134,7 +122,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 // Add custom application routes
                 .route("/", web::get().to(root))
                 .route("/health", web::get().to(health_check))
-                .route("/api/info", web::get().to(api_info))
+                .service(directory.resource("/api/info"))
                 // Mount the MCP calculator service at a custom API path (cloned for each worker)
                 .service(web::scope("/api").service(web::scope("/v1").service(
                     web::scope("/calculator").service(calculator_service.clone().scope()),
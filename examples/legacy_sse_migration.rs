@@ -0,0 +1,91 @@
+//! Legacy SSE migration example.
+//!
+//! This example serves the Streamable HTTP transport and the legacy HTTP+SSE
+//! wire contract (`/sse` + `/message?sessionId=`) from a single
+//! `StreamableHttpService`. Old clients keep working while new clients use the
+//! Streamable HTTP endpoint, and both kinds of session live in the same
+//! `SessionManager`, so no separate SSE server has to be run during the
+//! migration.
+//!
+//! ## Running the Example
+//!
+//! ```bash
+//! cargo run --example legacy_sse_migration
+//! ```
+//!
+//! ## Testing with curl
+//!
+//! Open a legacy event stream; the first event is the `endpoint` to post to:
+//! ```bash
+//! curl -N -H "Accept: text/event-stream" http://localhost:8080/mcp/sse
+//! ```
+//!
+//! Post a message on the advertised endpoint; the response arrives on the
+//! event stream:
+//! ```bash
+//! curl -X POST "http://localhost:8080/mcp/message?sessionId=<session id>" \
+//!   -H "Content-Type: application/json" \
+//!   -d '{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05","capabilities":{},"clientInfo":{"name":"curl","version":"1.0"}}}'
+//! ```
+//!
+//! New clients use the Streamable HTTP endpoint at `http://localhost:8080/mcp`.
+//!
+//! ## Migration Path
+//!
+//! 1. Enable `legacy_sse` on the Streamable HTTP service and retire the old SSE
+//!    server; existing client configurations keep pointing at `/sse`.
+//! 2. Move clients to the Streamable HTTP endpoint at their own pace.
+//! 3. Disable `legacy_sse` once no client uses `/sse` anymore.
+
+use actix_web::{App, HttpServer, middleware};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use std::{sync::Arc, time::Duration};
+use tracing_subscriber::{
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+    {self},
+};
+
+mod common;
+use common::counter::Counter;
+
+const BIND_ADDRESS: &str = "127.0.0.1:8080";
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "debug".to_string().into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    println!("\n🚀 MCP server (actix-web) running at http://{BIND_ADDRESS}/mcp");
+    println!("📡 GET /mcp/sse - Legacy SSE event stream");
+    println!("📮 POST /mcp/message?sessionId=... - Legacy message endpoint");
+    println!("🔄 GET/POST/DELETE /mcp - Streamable HTTP endpoint");
+    println!("\nPress Ctrl+C to stop the server\n");
+
+    // Create the service OUTSIDE HttpServer::new() to share sessions across workers
+    let http_service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Counter::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .sse_keep_alive(Duration::from_secs(15))
+        .legacy_sse(true) // Serve /sse and /message next to the Streamable HTTP endpoint
+        .legacy_sse_resume_window(Duration::from_secs(30)) // Let legacy clients reconnect
+        .build();
+
+    HttpServer::new(move || {
+        App::new()
+            .wrap(middleware::Logger::default())
+            .service(http_service.clone().scope_with_path("/mcp"))
+    })
+    .bind(BIND_ADDRESS)?
+    .run()
+    .await?;
+
+    println!("✅ Server stopped");
+    Ok(())
+}
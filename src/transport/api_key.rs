@@ -0,0 +1,131 @@
+//! API key authentication.
+//!
+//! For internal deployments where OAuth is more machinery than needed,
+//! [`require_api_key`](crate::transport::StreamableHttpServiceBuilder::require_api_key)
+//! makes every request to the MCP endpoints present a key in the `X-Api-Key`
+//! header (or another header of choice), checked before any MCP processing.
+//! Requests without a key are answered with `401 Unauthorized`, requests with
+//! an unknown key with `403 Forbidden`, both with a [problem](super::problem)
+//! whose `code` is `missing_api_key` or `invalid_api_key`:
+//!
+//! ```json
+//! {"type": "about:blank", "title": "Forbidden", "status": 403, "detail": "Invalid API key", "code": "invalid_api_key"}
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rmcp_actix_web::transport::ApiKeyAuth;
+//!
+//! // A fixed set of keys, in a custom header
+//! let auth = ApiKeyAuth::keys(["key-1", "key-2"]).header("X-Internal-Key");
+//!
+//! // Or any validation logic, e.g. a lookup in a key registry
+//! let auth = ApiKeyAuth::validator(|key| key.starts_with("svc_") && key.len() == 36);
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode};
+
+use super::Problem;
+
+/// Header carrying the API key by default.
+pub const DEFAULT_API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Validation function of an [`ApiKeyAuth`].
+type Validator = dyn Fn(&str) -> bool + Send + Sync + 'static;
+
+/// API key requirement for the MCP endpoints.
+///
+/// Converts from a list of accepted keys, so the builder accepts
+/// `.require_api_key(["key-1", "key-2"])`.
+#[derive(Clone)]
+pub struct ApiKeyAuth {
+    header: String,
+    validator: Arc<Validator>,
+}
+
+impl fmt::Debug for ApiKeyAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeyAuth")
+            .field("header", &self.header)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiKeyAuth {
+    /// Accepts any of `keys`.
+    ///
+    /// Keys are compared in constant time.
+    pub fn keys<I, K>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        let keys: Vec<String> = keys.into_iter().map(Into::into).collect();
+        Self::validator(move |candidate| {
+            // Every key is compared, so timing does not reveal which one matched
+            keys.iter()
+                .fold(false, |found, key| constant_time_eq(key, candidate) | found)
+        })
+    }
+
+    /// Accepts the keys for which `validator` returns `true`.
+    pub fn validator(validator: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        Self {
+            header: DEFAULT_API_KEY_HEADER.to_string(),
+            validator: Arc::new(validator),
+        }
+    }
+
+    /// Reads the key from `header` instead of `X-Api-Key`.
+    pub fn header(mut self, header: impl Into<String>) -> Self {
+        self.header = header.into();
+        self
+    }
+
+    /// Returns the rejection response for `req`, if its key is missing or invalid.
+    pub(crate) fn check(&self, req: &HttpRequest) -> Option<HttpResponse> {
+        let Some(key) = req.headers().get(self.header.as_str()) else {
+            tracing::debug!(header = %self.header, "Request rejected: missing API key");
+            return Some(
+                Problem::new(
+                    StatusCode::UNAUTHORIZED,
+                    "missing_api_key",
+                    "Missing API key",
+                )
+                .into_response(),
+            );
+        };
+        if key.to_str().is_ok_and(|key| (self.validator)(key)) {
+            return None;
+        }
+        tracing::debug!(header = %self.header, "Request rejected: invalid API key");
+        Some(
+            Problem::new(StatusCode::FORBIDDEN, "invalid_api_key", "Invalid API key")
+                .into_response(),
+        )
+    }
+}
+
+impl<K: Into<String>> From<Vec<K>> for ApiKeyAuth {
+    fn from(keys: Vec<K>) -> Self {
+        Self::keys(keys)
+    }
+}
+
+impl<K: Into<String>, const N: usize> From<[K; N]> for ApiKeyAuth {
+    fn from(keys: [K; N]) -> Self {
+        Self::keys(keys)
+    }
+}
+
+/// Compares two strings in time depending only on their lengths.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
@@ -0,0 +1,237 @@
+//! Side channels for large binary contents.
+//!
+//! ## Downloads
+//!
+//! Embedding megabytes of base64 in a JSON-RPC message slows down every
+//! intermediary on the SSE path and holds the stream until the last byte is
+//! sent. With [`blob_ttl`](crate::transport::StreamableHttpServiceBuilder::blob_ttl)
+//! configured, handlers can instead publish the bytes with the [`BlobLinks`]
+//! handle found in the `RequestContext` extensions, and return the short-lived
+//! URL it yields (e.g. in a `resource_link` content, or in the `uri` of a
+//! resource) for the client to download them over plain HTTP.
+//!
+//! The transport keeps published blobs in memory until they expire or their
+//! session closes. A blob published in a session can only be downloaded with
+//! that session's `Mcp-Session-Id` header; the `request_guard` hook and origin
+//! checks apply to downloads as well.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rmcp_actix_web::transport::BlobLinks;
+//!
+//! async fn call_tool(
+//!     &self,
+//!     request: CallToolRequestParams,
+//!     context: RequestContext<RoleServer>,
+//! ) -> Result<CallToolResult, McpError> {
+//!     let report = self.render_report().await?;
+//!     let links = context.extensions.get::<BlobLinks>().expect("blob_ttl is configured");
+//!     let url = links.publish(report, "application/pdf");
+//!     Ok(CallToolResult::success(vec![Content::text(url)]))
+//! }
+//! ```
+//!
+//! ## Uploads
+//!
+//! The other way around, with
+//! [`upload_ttl`](crate::transport::StreamableHttpServiceBuilder::upload_ttl)
+//! configured, a client can POST a large payload as the raw body of a request
+//! to the `uploads` route below the MCP endpoint, with its `Mcp-Session-Id`
+//! header in stateful mode. The route answers `201 Created` with a JSON body
+//! `{"handle": "<handle>"}`; the client passes the handle in its tool arguments
+//! instead of the payload, and the handler resolves it with the [`Uploads`]
+//! handle found in the `RequestContext` extensions. Uploads are only visible to
+//! the session that sent them, and kept until they expire or the session
+//! closes.
+//!
+//! ```rust,ignore
+//! use rmcp_actix_web::transport::Uploads;
+//!
+//! async fn call_tool(
+//!     &self,
+//!     request: CallToolRequestParams,
+//!     context: RequestContext<RoleServer>,
+//! ) -> Result<CallToolResult, McpError> {
+//!     let handle = request.arguments.as_ref().and_then(|a| a.get("upload")).and_then(|v| v.as_str());
+//!     let upload = handle
+//!         .and_then(|handle| context.extensions.get::<Uploads>()?.get(handle))
+//!         .ok_or_else(|| McpError::invalid_params("unknown upload", None))?;
+//!     self.ingest(&upload.mime_type, &upload.data).await
+//! }
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::web::Bytes;
+use rmcp::transport::{
+    common::server_side_http::session_id as random_id, streamable_http_server::session::SessionId,
+};
+
+/// A stored blob.
+struct Blob {
+    session_id: Option<SessionId>,
+    mime_type: String,
+    data: Bytes,
+    expires_at: Instant,
+}
+
+/// Blobs published by the handlers of a service, or uploaded by its clients, by id.
+#[derive(Clone, Default)]
+pub(crate) struct BlobStore {
+    blobs: Arc<Mutex<HashMap<String, Blob>>>,
+}
+
+impl BlobStore {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Blob>> {
+        self.blobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Stores a blob of `session_id` for `ttl`, forgetting the expired ones first.
+    pub(crate) fn insert(
+        &self,
+        session_id: Option<SessionId>,
+        mime_type: String,
+        data: Bytes,
+        ttl: Duration,
+    ) -> String {
+        let blob = Blob {
+            session_id,
+            mime_type,
+            data,
+            expires_at: Instant::now() + ttl,
+        };
+        let id = random_id().to_string();
+        let mut blobs = self.lock();
+        let now = Instant::now();
+        blobs.retain(|_, blob| blob.expires_at > now);
+        blobs.insert(id.clone(), blob);
+        id
+    }
+
+    /// Returns the blob `id` if it has not expired and `session_id` may read it.
+    pub(crate) fn get(&self, id: &str, session_id: Option<&str>) -> Option<(String, Bytes)> {
+        let blobs = self.lock();
+        let blob = blobs.get(id)?;
+        if blob.expires_at <= Instant::now() {
+            return None;
+        }
+        if let Some(ref owner) = blob.session_id
+            && session_id != Some(owner.as_ref())
+        {
+            return None;
+        }
+        Some((blob.mime_type.clone(), blob.data.clone()))
+    }
+
+    /// Forgets every blob of a closed session.
+    pub(crate) fn remove_session(&self, session_id: &SessionId) {
+        self.lock()
+            .retain(|_, blob| blob.session_id.as_ref() != Some(session_id));
+    }
+}
+
+/// Handle for publishing large binary contents outside of the SSE stream.
+///
+/// Inserted into the `RequestContext` extensions of every POSTed request when
+/// [`blob_ttl`](crate::transport::StreamableHttpServiceBuilder::blob_ttl) is
+/// configured. See the [module documentation](self).
+#[derive(Clone)]
+pub struct BlobLinks {
+    store: BlobStore,
+    session_id: Option<SessionId>,
+    base_url: String,
+    ttl: Duration,
+}
+
+impl fmt::Debug for BlobLinks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlobLinks")
+            .field("session_id", &self.session_id)
+            .field("base_url", &self.base_url)
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BlobLinks {
+    pub(crate) fn new(
+        store: BlobStore,
+        session_id: Option<SessionId>,
+        base_url: String,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            store,
+            session_id,
+            base_url,
+            ttl,
+        }
+    }
+
+    /// Publishes `data`, returning the absolute URL it can be downloaded from
+    /// with a `GET` until it expires.
+    ///
+    /// The download is answered with `mime_type` as its `Content-Type`.
+    pub fn publish(&self, data: impl Into<Bytes>, mime_type: impl Into<String>) -> String {
+        let id = self.store.insert(
+            self.session_id.clone(),
+            mime_type.into(),
+            data.into(),
+            self.ttl,
+        );
+        format!("{}/{id}", self.base_url)
+    }
+
+    /// How long a published blob can be downloaded.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+}
+
+/// A payload uploaded by a client.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Upload {
+    /// `Content-Type` of the upload request, `application/octet-stream` if unset.
+    pub mime_type: String,
+    /// The uploaded bytes.
+    pub data: Bytes,
+}
+
+/// Handle for resolving the payloads uploaded by the client of a request.
+///
+/// Inserted into the `RequestContext` extensions of every POSTed request when
+/// [`upload_ttl`](crate::transport::StreamableHttpServiceBuilder::upload_ttl) is
+/// configured. See the [module documentation](self).
+#[derive(Clone)]
+pub struct Uploads {
+    store: BlobStore,
+    session_id: Option<SessionId>,
+}
+
+impl fmt::Debug for Uploads {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Uploads")
+            .field("session_id", &self.session_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Uploads {
+    pub(crate) fn new(store: BlobStore, session_id: Option<SessionId>) -> Self {
+        Self { store, session_id }
+    }
+
+    /// Returns the upload `handle`, if it was sent in this session and has not
+    /// expired.
+    pub fn get(&self, handle: &str) -> Option<Upload> {
+        let (mime_type, data) = self.store.get(handle, self.session_id.as_deref())?;
+        Some(Upload { mime_type, data })
+    }
+}
@@ -0,0 +1,112 @@
+//! Serving of synchronous, blocking handler code.
+//!
+//! The transport runs handlers on the actix-web workers, which also write SSE
+//! streams and accept connections. A handler that blocks, e.g. by calling a
+//! synchronous database driver or doing CPU-bound work, freezes its worker for
+//! the duration. Such code is moved to tokio's blocking thread pool with either
+//! of the following:
+//!
+//! - [`BlockingHandler`] wraps a whole handler, running every request it handles
+//!   on the blocking pool.
+//! - [`run_blocking`] runs a single blocking closure, typically the body of one
+//!   tool, on the blocking pool and awaits its result.
+//!
+//! Code on the blocking pool cannot be aborted: a cancelled request keeps its
+//! thread until the code returns. Long-running code should check the
+//! [`RequestCancellation`](super::RequestCancellation) of its request.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rmcp_actix_web::transport::{BlockingHandler, StreamableHttpService, run_blocking};
+//!
+//! // Whole handler
+//! let service = StreamableHttpService::builder()
+//!     .service_factory(Arc::new(|| Ok(BlockingHandler::new(LegacyInventory::open()?))))
+//!     .session_manager(Arc::new(LocalSessionManager::default()))
+//!     .build();
+//!
+//! // Single tool
+//! #[tool(description = "Compute the checksum of a file")]
+//! async fn checksum(&self, Parameters(request): Parameters<ChecksumRequest>) -> Result<String, McpError> {
+//!     run_blocking(move || sha256_file(&request.path).map_err(io_error)).await
+//! }
+//! ```
+
+use std::sync::Arc;
+
+use rmcp::{
+    ErrorData, RoleServer, Service,
+    model::{ClientNotification, ClientRequest, ServerInfo, ServerResult},
+    service::{NotificationContext, RequestContext},
+};
+use tokio::runtime::Handle;
+
+/// Runs `f` on tokio's blocking thread pool and returns its result.
+///
+/// A panic in `f` is reported as an internal error.
+pub async fn run_blocking<T, F>(f: F) -> Result<T, ErrorData>
+where
+    F: FnOnce() -> Result<T, ErrorData> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| Err(blocking_task_failed(e)))
+}
+
+/// MCP handler whose requests run on tokio's blocking thread pool.
+///
+/// Each request is handled by driving the future of the wrapped handler to
+/// completion on a blocking thread, so that handler may block anywhere in its
+/// request handlers. Notifications are handled in place and must not block.
+pub struct BlockingHandler<S>(Arc<S>);
+
+impl<S> BlockingHandler<S> {
+    /// Wraps a handler that blocks while handling requests.
+    pub fn new(handler: S) -> Self {
+        Self(Arc::new(handler))
+    }
+}
+
+impl<S> std::fmt::Debug for BlockingHandler<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BlockingHandler").finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<RoleServer> for BlockingHandler<S>
+where
+    S: Service<RoleServer>,
+{
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, ErrorData> {
+        let handler = self.0.clone();
+        let runtime = Handle::current();
+        tokio::task::spawn_blocking(move || {
+            runtime.block_on(handler.handle_request(request, context))
+        })
+        .await
+        .unwrap_or_else(|e| Err(blocking_task_failed(e)))
+    }
+
+    fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        self.0.handle_notification(notification, context)
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        self.0.get_info()
+    }
+}
+
+fn blocking_task_failed(error: tokio::task::JoinError) -> ErrorData {
+    tracing::error!("Blocking task failed: {error}");
+    ErrorData::internal_error("Blocking task failed", None)
+}
@@ -0,0 +1,59 @@
+//! Opt-in result caching for idempotent tools.
+//!
+//! A [`CacheableTools`] map lists the tools whose results the transport may
+//! cache, with the time each result stays fresh. A `tools/call` request for a
+//! listed tool is answered from the cache when a result for the same tool name
+//! and arguments is fresh; otherwise it reaches the handler and its result is
+//! cached. Arguments are compared in canonical form, so key order does not
+//! matter. Results flagged with `isError` are never cached.
+//!
+//! The cache is shared by every session of the service, so only list read-only
+//! tools whose result does not depend on the caller, such as lookups in public
+//! reference data.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use rmcp_actix_web::transport::CacheableTools;
+//!
+//! let cacheable = CacheableTools::new()
+//!     .cache("lookup_country", Duration::from_secs(3600))
+//!     .cache("exchange_rate", Duration::from_secs(60));
+//! assert_eq!(cacheable.ttl("exchange_rate"), Some(Duration::from_secs(60)));
+//! assert_eq!(cacheable.ttl("send_email"), None);
+//! ```
+
+use std::{collections::HashMap, time::Duration};
+
+/// Tools whose results may be cached, with their time to live.
+///
+/// The default map caches nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheableTools {
+    ttls: HashMap<String, Duration>,
+}
+
+impl CacheableTools {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caches the results of `tool` for `ttl`, replacing any previous TTL.
+    pub fn cache(mut self, tool: impl Into<String>, ttl: Duration) -> Self {
+        self.ttls.insert(tool.into(), ttl);
+        self
+    }
+
+    /// Returns whether no tool is cached.
+    pub fn is_empty(&self) -> bool {
+        self.ttls.is_empty()
+    }
+
+    /// Returns how long results of `tool` are cached, if they are.
+    pub fn ttl(&self, tool: &str) -> Option<Duration> {
+        self.ttls.get(tool).copied()
+    }
+}
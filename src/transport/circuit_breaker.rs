@@ -0,0 +1,212 @@
+//! Circuit breaker for upstream calls made by proxying handlers.
+//!
+//! MCP services deployed as proxies or gateways forward tool calls to an
+//! upstream MCP server or API. When that upstream goes down, every call waits
+//! for its own timeout, tying up handlers and leaving clients hanging. A
+//! [`CircuitBreaker`] wrapped around those calls counts consecutive failures;
+//! once they reach the threshold the circuit opens and further calls fail at
+//! once with a JSON-RPC error instead of reaching the upstream.
+//!
+//! After the open period, the circuit is half-open: a single trial call is let
+//! through while the others keep failing fast. A successful trial closes the
+//! circuit again; a failed one reopens it for another period.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use std::time::Duration;
+//!
+//! use rmcp_actix_web::transport::CircuitBreaker;
+//!
+//! // Open after 5 consecutive failures, retry the upstream after 30 seconds
+//! let upstream = CircuitBreaker::new(5, Duration::from_secs(30))
+//!     .with_call_timeout(Duration::from_secs(10));
+//!
+//! async fn call_tool(
+//!     &self,
+//!     request: CallToolRequestParams,
+//!     context: RequestContext<RoleServer>,
+//! ) -> Result<CallToolResult, McpError> {
+//!     self.upstream
+//!         .call(async { self.client.call_tool(request).await.map_err(to_mcp_error) })
+//!         .await
+//! }
+//! ```
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use rmcp::model::{ErrorCode, ErrorData};
+use serde_json::json;
+use tokio::time::Instant;
+
+/// JSON-RPC error code for calls refused while the circuit is open.
+pub const UPSTREAM_UNAVAILABLE_CODE: ErrorCode = ErrorCode(-32006);
+
+const UPSTREAM_UNAVAILABLE_MESSAGE: &str = "Upstream unavailable";
+const UPSTREAM_TIMEOUT_MESSAGE: &str = "Upstream call timed out";
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls reach the upstream; failures are counted.
+    Closed,
+    /// Calls fail fast until the open period ends.
+    Open,
+    /// The open period has ended; the next call is a trial.
+    HalfOpen,
+}
+
+/// Consecutive-failure circuit breaker around upstream calls.
+///
+/// Clones share the same circuit, so a breaker built once can be stored in the
+/// service and cloned into every session.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    open_duration: Duration,
+    call_timeout: Option<Duration>,
+    circuit: Arc<Mutex<Circuit>>,
+}
+
+#[derive(Debug, Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker that opens after `failure_threshold` consecutive
+    /// failures and stays open for `open_duration`.
+    ///
+    /// A threshold of 0 is treated as 1.
+    pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            open_duration,
+            call_timeout: None,
+            circuit: Arc::default(),
+        }
+    }
+
+    /// Fails calls that take longer than `timeout`, counting them as failures.
+    pub fn with_call_timeout(mut self, timeout: Duration) -> Self {
+        self.call_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        let circuit = self.lock();
+        match circuit.open_until {
+            None => CircuitState::Closed,
+            Some(until) if Instant::now() < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Runs `call` unless the circuit is open, recording its outcome.
+    ///
+    /// While the circuit is open, and while a half-open trial is running, `call`
+    /// is dropped unpolled and an [`UPSTREAM_UNAVAILABLE_CODE`] error is returned,
+    /// whose data holds the seconds left before the next trial as
+    /// `retry_after_secs`.
+    pub async fn call<T, F>(&self, call: F) -> Result<T, ErrorData>
+    where
+        F: Future<Output = Result<T, ErrorData>>,
+    {
+        let trial = self.admit()?;
+        let result = match self.call_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, call)
+                .await
+                .unwrap_or_else(|_| Err(ErrorData::internal_error(UPSTREAM_TIMEOUT_MESSAGE, None))),
+            None => call.await,
+        };
+        trial.record(result.is_ok());
+        result
+    }
+
+    /// Lets a call through, or returns the fast failure of an open circuit.
+    fn admit(&self) -> Result<Attempt<'_>, ErrorData> {
+        let mut circuit = self.lock();
+        let Some(until) = circuit.open_until else {
+            return Ok(Attempt::new(self, false));
+        };
+        let now = Instant::now();
+        if now < until || circuit.trial_in_flight {
+            let retry_after = until.saturating_duration_since(now).as_secs();
+            tracing::debug!(retry_after, "Circuit open, failing upstream call fast");
+            return Err(ErrorData::new(
+                UPSTREAM_UNAVAILABLE_CODE,
+                UPSTREAM_UNAVAILABLE_MESSAGE,
+                Some(json!({ "retry_after_secs": retry_after })),
+            ));
+        }
+        circuit.trial_in_flight = true;
+        Ok(Attempt::new(self, true))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Circuit> {
+        self.circuit.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A call let through by the breaker, recorded once its outcome is known.
+///
+/// A trial dropped before completing frees the half-open slot without changing
+/// the state, so the next call becomes the trial.
+struct Attempt<'a> {
+    breaker: &'a CircuitBreaker,
+    trial: bool,
+    recorded: bool,
+}
+
+impl<'a> Attempt<'a> {
+    fn new(breaker: &'a CircuitBreaker, trial: bool) -> Self {
+        Self {
+            breaker,
+            trial,
+            recorded: false,
+        }
+    }
+
+    fn record(mut self, success: bool) {
+        self.recorded = true;
+        let breaker = self.breaker;
+        let mut circuit = breaker.lock();
+        if self.trial {
+            circuit.trial_in_flight = false;
+        }
+        if success {
+            if circuit.open_until.is_some() {
+                tracing::info!("Upstream recovered, closing circuit");
+            }
+            *circuit = Circuit::default();
+            return;
+        }
+        // Calls let through before the circuit opened must not extend the open period
+        if !self.trial && circuit.open_until.is_some() {
+            return;
+        }
+        circuit.consecutive_failures = circuit.consecutive_failures.saturating_add(1);
+        if self.trial || circuit.consecutive_failures >= breaker.failure_threshold {
+            tracing::warn!(
+                failures = circuit.consecutive_failures,
+                "Upstream failing, opening circuit"
+            );
+            circuit.open_until = Some(Instant::now() + breaker.open_duration);
+        }
+    }
+}
+
+impl Drop for Attempt<'_> {
+    fn drop(&mut self) {
+        if self.trial && !self.recorded {
+            self.breaker.lock().trial_in_flight = false;
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Transport settings loaded from files or the environment.
+//!
+//! A [`TransportConfig`] gathers the deployment-specific knobs of the
+//! transport, so operators can tune them without recompiling. It can be
+//! deserialized from any serde format, read from JSON with
+//! [`TransportConfig::from_json_file`], from TOML with
+//! `TransportConfig::from_toml_file` (with the `toml` feature), or from `MCP_*`
+//! environment variables with [`TransportConfig::from_env`]. Fields missing
+//! from the source keep their default values.
+//!
+//! [`StreamableHttpService::from_config`](super::StreamableHttpService::from_config)
+//! returns a service builder with the configuration applied, on which the rest
+//! of the service is set up; `bind_address` is meant for the `HttpServer`.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let config = TransportConfig::from_json_file("mcp.json")?;
+//! // or, with the `toml` feature: TransportConfig::from_toml_file("mcp.toml")?
+//!
+//! let service = StreamableHttpService::from_config(&config)
+//!     .service_factory(Arc::new(|| Ok(MyService::new())))
+//!     .session_manager(Arc::new(LocalSessionManager::default()))
+//!     .build();
+//!
+//! HttpServer::new(move || App::new().service(service.clone().scope_with_path("/mcp")))
+//!     .bind(&config.bind_address)?
+//!     .run()
+//!     .await
+//! ```
+//!
+//! ## Environment Variables
+//!
+//! | Variable | Field |
+//! |----------|-------|
+//! | `MCP_BIND_ADDRESS` | `bind_address` |
+//! | `MCP_STATEFUL_MODE` | `stateful_mode` (`true`/`false`) |
+//! | `MCP_SSE_KEEP_ALIVE_SECS` | `sse_keep_alive_secs` |
+//! | `MCP_MAX_BODY_SIZE` | `max_body_size`, in bytes |
+//! | `MCP_ALLOWED_ORIGINS` | `allowed_origins`, comma-separated |
+//! | `MCP_CORS` | `cors` (`true`/`false`) |
+//! | `MCP_LEGACY_SSE` | `legacy_sse` (`true`/`false`) |
+
+use std::{fmt, path::Path, str::FromStr, time::Duration};
+
+use serde::Deserialize;
+
+/// Prefix of the environment variables read by [`TransportConfig::from_env`].
+const ENV_PREFIX: &str = "MCP_";
+
+/// Deployment settings of the Streamable HTTP transport.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransportConfig {
+    /// Address the HTTP server binds to. Defaults to `127.0.0.1:8080`.
+    pub bind_address: String,
+    /// Whether to enable stateful session management. Defaults to `true`.
+    pub stateful_mode: bool,
+    /// Keep-alive interval for SSE streams, in seconds. Disabled by default.
+    pub sse_keep_alive_secs: Option<u64>,
+    /// Maximum size of a POSTed request body, in bytes. Defaults to actix-web's
+    /// limit.
+    pub max_body_size: Option<usize>,
+    /// Origins allowed to call the MCP endpoints. Empty allows every origin.
+    pub allowed_origins: Vec<String>,
+    /// Whether to answer CORS preflights and add CORS headers for the allowed
+    /// origins. Defaults to `false`.
+    pub cors: bool,
+    /// Whether to also serve the legacy HTTP+SSE endpoints. Defaults to `false`.
+    pub legacy_sse: bool,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: "127.0.0.1:8080".to_string(),
+            stateful_mode: true,
+            sse_keep_alive_secs: None,
+            max_body_size: None,
+            allowed_origins: Vec::new(),
+            cors: false,
+            legacy_sse: false,
+        }
+    }
+}
+
+impl TransportConfig {
+    /// Reads the configuration from `MCP_*` environment variables.
+    ///
+    /// Unset variables keep their default values.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Parses the configuration from a JSON document.
+    pub fn from_json_str(json: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(json).map_err(ConfigError::Json)
+    }
+
+    /// Reads the configuration from a JSON file.
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let json = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        Self::from_json_str(&json)
+    }
+
+    /// Parses the configuration from a TOML document.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(toml: &str) -> Result<Self, ConfigError> {
+        toml::from_str(toml).map_err(ConfigError::Toml)
+    }
+
+    /// Reads the configuration from a TOML file.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let toml = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        Self::from_toml_str(&toml)
+    }
+
+    /// Returns the SSE keep-alive interval.
+    pub fn sse_keep_alive(&self) -> Option<Duration> {
+        self.sse_keep_alive_secs.map(Duration::from_secs)
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let var =
+            |name: &'static str| lookup(&format!("{ENV_PREFIX}{name}")).map(|value| (name, value));
+        let mut config = Self::default();
+        if let Some((_, value)) = var("BIND_ADDRESS") {
+            config.bind_address = value;
+        }
+        if let Some((name, value)) = var("STATEFUL_MODE") {
+            config.stateful_mode = parse_env(name, &value)?;
+        }
+        if let Some((name, value)) = var("SSE_KEEP_ALIVE_SECS") {
+            config.sse_keep_alive_secs = Some(parse_env(name, &value)?);
+        }
+        if let Some((name, value)) = var("MAX_BODY_SIZE") {
+            config.max_body_size = Some(parse_env(name, &value)?);
+        }
+        if let Some((_, value)) = var("ALLOWED_ORIGINS") {
+            config.allowed_origins = value
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        if let Some((name, value)) = var("CORS") {
+            config.cors = parse_env(name, &value)?;
+        }
+        if let Some((name, value)) = var("LEGACY_SSE") {
+            config.legacy_sse = parse_env(name, &value)?;
+        }
+        Ok(config)
+    }
+}
+
+fn parse_env<T: FromStr>(name: &'static str, value: &str) -> Result<T, ConfigError> {
+    value.trim().parse().map_err(|_| ConfigError::InvalidEnv {
+        name: format!("{ENV_PREFIX}{name}"),
+        value: value.to_string(),
+    })
+}
+
+/// Error returned when loading a [`TransportConfig`] fails.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The configuration file could not be read.
+    Io(std::io::Error),
+    /// The JSON document is malformed or contains unknown fields.
+    Json(serde_json::Error),
+    /// The TOML document is malformed or contains unknown fields.
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    /// An environment variable holds a value of the wrong type.
+    InvalidEnv {
+        /// Name of the variable.
+        name: String,
+        /// The rejected value.
+        value: String,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read transport configuration: {e}"),
+            Self::Json(e) => write!(f, "invalid transport configuration: {e}"),
+            #[cfg(feature = "toml")]
+            Self::Toml(e) => write!(f, "invalid transport configuration: {e}"),
+            Self::InvalidEnv { name, value } => write!(f, "invalid value for {name}: {value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Json(e) => Some(e),
+            #[cfg(feature = "toml")]
+            Self::Toml(e) => Some(e),
+            Self::InvalidEnv { .. } => None,
+        }
+    }
+}
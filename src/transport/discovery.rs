@@ -0,0 +1,488 @@
+//! Well-known discovery manifest for MCP endpoints.
+//!
+//! The manifest is a small JSON document describing how to connect to an MCP
+//! server (name, version, transport type, endpoint path and authentication
+//! requirements), derived from the handler's `ServerInfo`. Serving it lets
+//! clients and orchestration tools discover the endpoint without out-of-band
+//! configuration.
+//!
+//! The route is opt-in: mount the resource returned by
+//! [`StreamableHttpService::well_known_manifest`](super::StreamableHttpService::well_known_manifest)
+//! at the application root, next to the MCP scope.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use rmcp_actix_web::transport::{DiscoveryAuth, StreamableHttpService};
+//! use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+//! use actix_web::{App, HttpServer};
+//! use std::sync::Arc;
+//!
+//! # use rmcp::{ServerHandler, model::ServerInfo};
+//! # #[derive(Clone)]
+//! # struct MyService;
+//! # impl ServerHandler for MyService {
+//! #     fn get_info(&self) -> ServerInfo { ServerInfo::default() }
+//! # }
+//! # impl MyService { fn new() -> Self { Self } }
+//! #[actix_web::main]
+//! async fn main() -> std::io::Result<()> {
+//!     let service = StreamableHttpService::builder()
+//!         .service_factory(Arc::new(|| Ok(MyService::new())))
+//!         .session_manager(Arc::new(LocalSessionManager::default()))
+//!         .discovery_auth(DiscoveryAuth::Bearer { resource_metadata: None })
+//!         .build();
+//!
+//!     HttpServer::new(move || {
+//!         App::new()
+//!             // Serves GET /.well-known/mcp.json
+//!             .service(service.well_known_manifest("/mcp"))
+//!             .service(service.clone().scope_with_path("/mcp"))
+//!     })
+//!     .bind("127.0.0.1:8080")?
+//!     .run()
+//!     .await
+//! }
+//! ```
+//!
+//! ## Combined Discovery
+//!
+//! Deployments mounting several MCP services describe all of them in one
+//! document with a [`ServiceDirectory`]: each service contributes a
+//! [`DirectoryEntry`], and the directory serves their manifests together with
+//! their tool lists. Tool lists are read from a handler of each service and
+//! cached for [`DEFAULT_DIRECTORY_CACHE_TTL`] unless configured otherwise; tools
+//! denied by the [`MessageFilter`](super::MessageFilter) of a service or disabled
+//! at runtime are left out.
+//!
+//! ```rust,ignore
+//! let directory = ServiceDirectory::new()
+//!     .service(calculator.directory_entry("/api/v1/calculator"))
+//!     .service(weather.directory_entry("/api/v1/weather"));
+//!
+//! HttpServer::new(move || {
+//!     App::new()
+//!         // Serves GET /api/v1/services
+//!         .service(directory.resource("/api/v1/services"))
+//!         .service(calculator.clone().scope_with_path("/api/v1/calculator"))
+//!         .service(weather.clone().scope_with_path("/api/v1/weather"))
+//! })
+//! ```
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    HttpResponse, Resource,
+    http::StatusCode,
+    web::{self, Data},
+};
+use futures::future::{BoxFuture, join_all};
+use rmcp::{
+    model::{
+        ClientJsonRpcMessage, ClientRequest, ListToolsRequest, PaginatedRequestParams, RequestId,
+        ServerCapabilities, ServerInfo, ServerJsonRpcMessage, ServerResult, Tool,
+    },
+    service::serve_directly,
+    transport::OneshotTransport,
+};
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use super::{DynServerHandler, Problem};
+
+/// Path at which the discovery manifest is served by default.
+pub const WELL_KNOWN_MANIFEST_PATH: &str = "/.well-known/mcp.json";
+
+/// Transport type advertised for the Streamable HTTP transport.
+pub const STREAMABLE_HTTP_TRANSPORT: &str = "streamable-http";
+
+/// How long a [`ServiceDirectory`] keeps the tool lists of its services by default.
+pub const DEFAULT_DIRECTORY_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Most `tools/list` pages read from a handler; a handler returning more is
+/// reported unavailable rather than paged forever.
+const MAX_TOOL_PAGES: usize = 100;
+
+/// Authentication requirements advertised in the discovery manifest.
+///
+/// The transport does not enforce these; they describe what the deployment
+/// (middleware, reverse proxy, or the handler itself) expects from clients.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DiscoveryAuth {
+    /// No authentication is required.
+    #[default]
+    None,
+    /// An OAuth 2.1 bearer token is required in the `Authorization` header.
+    #[serde(rename_all = "camelCase")]
+    Bearer {
+        /// URL of the OAuth protected resource metadata document (RFC 9728).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        resource_metadata: Option<String>,
+    },
+    /// A static API key is required in the given header.
+    #[serde(rename_all = "camelCase")]
+    ApiKey {
+        /// Name of the header carrying the key.
+        header: String,
+    },
+}
+
+/// Transport section of the discovery manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransportDescriptor {
+    /// Transport type, e.g. `streamable-http`.
+    #[serde(rename = "type")]
+    pub transport_type: String,
+    /// Path of the MCP endpoint.
+    pub endpoint: String,
+    /// Whether the endpoint issues `Mcp-Session-Id` sessions.
+    pub stateful: bool,
+}
+
+/// Machine-readable description of an MCP endpoint.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryManifest {
+    /// Server name from `ServerInfo::server_info`.
+    pub name: String,
+    /// Optional human-readable server title.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Server version from `ServerInfo::server_info`.
+    pub version: String,
+    /// Optional server description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MCP protocol version the server implements.
+    pub protocol_version: String,
+    /// How to reach the server.
+    pub transport: TransportDescriptor,
+    /// What clients must present to connect.
+    pub authentication: DiscoveryAuth,
+    /// Capabilities announced by the server.
+    pub capabilities: ServerCapabilities,
+}
+
+impl DiscoveryManifest {
+    /// Builds a manifest for a Streamable HTTP endpoint from the handler's `ServerInfo`.
+    pub fn from_server_info(
+        info: ServerInfo,
+        endpoint: impl Into<String>,
+        stateful: bool,
+        authentication: DiscoveryAuth,
+    ) -> Self {
+        Self {
+            name: info.server_info.name,
+            title: info.server_info.title,
+            version: info.server_info.version,
+            description: info.server_info.description,
+            protocol_version: info.protocol_version.to_string(),
+            transport: TransportDescriptor {
+                transport_type: STREAMABLE_HTTP_TRANSPORT.to_string(),
+                endpoint: endpoint.into(),
+                stateful,
+            },
+            authentication,
+            capabilities: info.capabilities,
+        }
+    }
+}
+
+/// Produces the handler's `ServerInfo` on demand.
+pub(crate) type ServerInfoProvider =
+    Arc<dyn Fn() -> Result<ServerInfo, std::io::Error> + Send + Sync>;
+
+struct ManifestData {
+    server_info: ServerInfoProvider,
+    endpoint: String,
+    stateful: bool,
+    authentication: DiscoveryAuth,
+}
+
+/// Builds the actix-web resource serving the manifest at `path`.
+pub(crate) fn manifest_resource(
+    path: &str,
+    server_info: ServerInfoProvider,
+    endpoint: String,
+    stateful: bool,
+    authentication: DiscoveryAuth,
+) -> Resource {
+    web::resource(path)
+        .app_data(Data::new(ManifestData {
+            server_info,
+            endpoint,
+            stateful,
+            authentication,
+        }))
+        .route(web::get().to(handle_manifest))
+}
+
+async fn handle_manifest(data: Data<ManifestData>) -> HttpResponse {
+    match (data.server_info)() {
+        Ok(info) => HttpResponse::Ok().json(DiscoveryManifest::from_server_info(
+            info,
+            data.endpoint.clone(),
+            data.stateful,
+            data.authentication.clone(),
+        )),
+        Err(e) => {
+            tracing::error!("Failed to build discovery manifest: {e}");
+            Problem::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "discovery_unavailable",
+                "Failed to build discovery manifest",
+            )
+            .into_response()
+        }
+    }
+}
+
+/// Lists the tools of a fresh handler instance.
+pub(crate) type ToolsProvider =
+    Arc<dyn Fn() -> BoxFuture<'static, Result<Vec<Tool>, std::io::Error>> + Send + Sync>;
+
+/// Decides whether a tool is offered to clients.
+pub(crate) type ToolVisibility = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// Lists every tool of `handler`, following up to [`MAX_TOOL_PAGES`] pagination
+/// cursors.
+pub(crate) async fn list_tools(handler: DynServerHandler) -> Result<Vec<Tool>, std::io::Error> {
+    let mut tools = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_TOOL_PAGES {
+        let request = ClientRequest::ListToolsRequest(ListToolsRequest::with_param(
+            PaginatedRequestParams::default().with_cursor(cursor),
+        ));
+        let (transport, mut receiver) =
+            OneshotTransport::new(ClientJsonRpcMessage::request(request, RequestId::Number(1)));
+        let _service = serve_directly(handler.clone(), transport, None);
+        let page = loop {
+            match receiver.recv().await {
+                Some(ServerJsonRpcMessage::Response(response)) => match response.result {
+                    ServerResult::ListToolsResult(page) => break page,
+                    other => {
+                        return Err(std::io::Error::other(format!(
+                            "unexpected tools/list result: {other:?}"
+                        )));
+                    }
+                },
+                Some(ServerJsonRpcMessage::Error(error)) => {
+                    return Err(std::io::Error::other(error.error.message));
+                }
+                // Notifications sent while listing are irrelevant here
+                Some(_) => continue,
+                None => return Err(std::io::Error::other("tools/list was not answered")),
+            }
+        };
+        tools.extend(page.tools);
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(tools),
+        }
+    }
+    Err(std::io::Error::other(format!(
+        "tools/list returned more than {MAX_TOOL_PAGES} pages"
+    )))
+}
+
+/// One service listed in a [`ServiceDirectory`].
+///
+/// Created with
+/// [`StreamableHttpService::directory_entry`](super::StreamableHttpService::directory_entry).
+#[derive(Clone)]
+pub struct DirectoryEntry {
+    server_info: ServerInfoProvider,
+    tools: ToolsProvider,
+    tool_visible: ToolVisibility,
+    endpoint: String,
+    stateful: bool,
+    authentication: DiscoveryAuth,
+}
+
+impl DirectoryEntry {
+    pub(crate) fn new(
+        server_info: ServerInfoProvider,
+        tools: ToolsProvider,
+        tool_visible: ToolVisibility,
+        endpoint: String,
+        stateful: bool,
+        authentication: DiscoveryAuth,
+    ) -> Self {
+        Self {
+            server_info,
+            tools,
+            tool_visible,
+            endpoint,
+            stateful,
+            authentication,
+        }
+    }
+
+    /// Describes the service, listing all its tools if it announces the capability.
+    async fn describe(&self) -> Result<ServiceDescription, std::io::Error> {
+        let info = (self.server_info)()?;
+        let tools = match info.capabilities.tools {
+            Some(_) => (self.tools)().await?,
+            None => Vec::new(),
+        };
+        Ok(ServiceDescription {
+            manifest: DiscoveryManifest::from_server_info(
+                info,
+                self.endpoint.clone(),
+                self.stateful,
+                self.authentication.clone(),
+            ),
+            tools,
+        })
+    }
+
+    /// Returns `description` without the tools that are not offered to clients.
+    fn visible(&self, description: &ServiceDescription) -> ServiceDescription {
+        let mut description = description.clone();
+        description
+            .tools
+            .retain(|tool| (self.tool_visible)(&tool.name));
+        description
+    }
+}
+
+impl std::fmt::Debug for DirectoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DirectoryEntry")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Description of one service in a [`ServiceDirectoryDocument`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceDescription {
+    /// Manifest of the service, as served at its well-known URI.
+    #[serde(flatten)]
+    pub manifest: DiscoveryManifest,
+    /// Tools offered by the service; empty if it does not offer tools.
+    pub tools: Vec<Tool>,
+}
+
+/// Combined discovery document of every service in a [`ServiceDirectory`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServiceDirectoryDocument {
+    /// Services that could be described, in registration order.
+    pub services: Vec<ServiceDescription>,
+    /// Endpoints of the services whose description failed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unavailable: Vec<String>,
+}
+
+/// A service description kept by a [`ServiceDirectory`], with all its tools.
+#[derive(Debug)]
+struct CachedDescription {
+    description: ServiceDescription,
+    /// `None` when the TTL reaches past what `Instant` can represent
+    expires_at: Option<Instant>,
+}
+
+/// Combined discovery of several mounted MCP services.
+///
+/// Clones share the same entries and cached descriptions.
+#[derive(Debug, Clone)]
+pub struct ServiceDirectory {
+    entries: Arc<Vec<DirectoryEntry>>,
+    cache_ttl: Duration,
+    /// Description of each entry, by position; `None` until described
+    cache: Arc<Mutex<Vec<Option<CachedDescription>>>>,
+}
+
+impl Default for ServiceDirectory {
+    fn default() -> Self {
+        Self {
+            entries: Arc::default(),
+            cache_ttl: DEFAULT_DIRECTORY_CACHE_TTL,
+            cache: Arc::default(),
+        }
+    }
+}
+
+impl ServiceDirectory {
+    /// Creates an empty directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a service to the directory.
+    pub fn service(mut self, entry: DirectoryEntry) -> Self {
+        Arc::make_mut(&mut self.entries).push(entry);
+        self.cache = Arc::default();
+        self
+    }
+
+    /// Sets how long the description of a service is kept before its handler is
+    /// asked again. Defaults to [`DEFAULT_DIRECTORY_CACHE_TTL`].
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Describes every service.
+    ///
+    /// Descriptions are read from a fresh handler instance of each service and
+    /// kept for the cache TTL; concurrent calls wait for one refresh. A service
+    /// whose handler cannot be created or fails to list its tools is reported as
+    /// unavailable instead of failing the whole document. Tools denied by the
+    /// message filter of a service or disabled at runtime are left out.
+    pub async fn document(&self) -> ServiceDirectoryDocument {
+        let mut cache = self.cache.lock().await;
+        cache.resize_with(self.entries.len(), || None);
+        let now = Instant::now();
+        let stale: Vec<usize> = cache
+            .iter()
+            .enumerate()
+            .filter(|(_, cached)| {
+                cached
+                    .as_ref()
+                    .is_none_or(|c| c.expires_at.is_some_and(|at| at <= now))
+            })
+            .map(|(index, _)| index)
+            .collect();
+        let descriptions =
+            join_all(stale.iter().map(|&index| self.entries[index].describe())).await;
+        for (index, description) in stale.into_iter().zip(descriptions) {
+            let entry = &self.entries[index];
+            cache[index] = match description {
+                Ok(description) => Some(CachedDescription {
+                    description,
+                    expires_at: Instant::now().checked_add(self.cache_ttl),
+                }),
+                Err(e) => {
+                    tracing::error!(endpoint = %entry.endpoint, "Failed to describe service: {e}");
+                    None
+                }
+            };
+        }
+
+        let mut document = ServiceDirectoryDocument::default();
+        for (entry, cached) in self.entries.iter().zip(cache.iter()) {
+            match cached {
+                Some(cached) => document.services.push(entry.visible(&cached.description)),
+                None => document.unavailable.push(entry.endpoint.clone()),
+            }
+        }
+        document
+    }
+
+    /// Builds the actix-web resource serving the combined document at `path`.
+    pub fn resource(&self, path: &str) -> Resource {
+        web::resource(path)
+            .app_data(Data::new(self.clone()))
+            .route(web::get().to(handle_directory))
+    }
+}
+
+async fn handle_directory(directory: Data<ServiceDirectory>) -> HttpResponse {
+    HttpResponse::Ok().json(directory.document().await)
+}
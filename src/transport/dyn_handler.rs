@@ -0,0 +1,82 @@
+//! Type-erased MCP handlers.
+//!
+//! [`StreamableHttpService`] is generic over its handler type, which fixes the
+//! handler at compile time. Plugin systems and deployments that pick the
+//! handler from configuration wrap it in a [`DynServerHandler`] instead, and
+//! serve it with a [`DynStreamableHttpService`]. The handler is shared by
+//! reference counting, so the wrapper is cheap to clone.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use std::sync::Arc;
+//!
+//! use rmcp_actix_web::transport::{DynServerHandler, DynStreamableHttpService, StreamableHttpService};
+//!
+//! let handler = match config.backend.as_str() {
+//!     "calculator" => DynServerHandler::new(Calculator::new()),
+//!     "weather" => DynServerHandler::new(Weather::new()),
+//!     other => panic!("unknown backend {other}"),
+//! };
+//!
+//! let service: DynStreamableHttpService = StreamableHttpService::builder()
+//!     .service_factory(Arc::new(move || Ok(handler.clone())))
+//!     .session_manager(Arc::new(LocalSessionManager::default()))
+//!     .build();
+//! ```
+
+use std::sync::Arc;
+
+use rmcp::{
+    ErrorData, RoleServer, Service,
+    model::{ClientNotification, ClientRequest, ServerInfo, ServerResult},
+    service::{DynService, NotificationContext, RequestContext},
+};
+
+use super::StreamableHttpService;
+
+/// [`StreamableHttpService`] serving a handler chosen at runtime.
+pub type DynStreamableHttpService<
+    M = rmcp::transport::streamable_http_server::session::local::LocalSessionManager,
+> = StreamableHttpService<DynServerHandler, M>;
+
+/// MCP handler whose concrete type is erased.
+///
+/// Clones share the same handler.
+#[derive(Clone)]
+pub struct DynServerHandler(Arc<dyn DynService<RoleServer>>);
+
+impl DynServerHandler {
+    /// Erases the type of `handler`, typically a `ServerHandler`.
+    pub fn new(handler: impl Service<RoleServer>) -> Self {
+        Self(Arc::new(handler))
+    }
+}
+
+impl std::fmt::Debug for DynServerHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DynServerHandler").finish_non_exhaustive()
+    }
+}
+
+impl Service<RoleServer> for DynServerHandler {
+    fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<ServerResult, ErrorData>> + Send + '_ {
+        DynService::handle_request(self.0.as_ref(), request, context)
+    }
+
+    fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        DynService::handle_notification(self.0.as_ref(), notification, context)
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        DynService::get_info(self.0.as_ref())
+    }
+}
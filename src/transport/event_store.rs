@@ -0,0 +1,183 @@
+//! Storage for replayable SSE events.
+//!
+//! Clients that lose an SSE stream reconnect with a `Last-Event-ID` header to
+//! receive the events they missed. By default the session manager alone
+//! answers these requests from its own cache, so replayable events live
+//! wherever the sessions live. An [`EventStore`] configured with
+//! [`event_store`](crate::transport::StreamableHttpServiceBuilder::event_store)
+//! keeps the two concerns apart: the transport records every event written to
+//! a session's streams in the store, and replays missed events from it before
+//! handing the stream back to the session manager for live delivery.
+//!
+//! Event ids are the ones assigned by the session manager; the store only
+//! remembers which stream each event was written to, so a replay never mixes
+//! the events of different streams.
+//!
+//! [`InMemoryEventStore`] keeps a bounded number of events per session in
+//! process memory. Implement [`EventStore`] to persist events externally.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use std::sync::Arc;
+//!
+//! use rmcp_actix_web::transport::{EventStore, InMemoryEventStore};
+//!
+//! let store: Arc<dyn EventStore> = Arc::new(InMemoryEventStore::new(256));
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use futures::future::BoxFuture;
+use rmcp::{model::ServerJsonRpcMessage, transport::streamable_http_server::session::SessionId};
+
+/// Error returned by an [`EventStore`].
+pub type EventStoreError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Stream id under which events of the standalone GET stream are recorded.
+pub const STANDALONE_STREAM_ID: &str = "standalone";
+
+/// An SSE event recorded in an [`EventStore`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct StoredEvent {
+    /// Stream the event was written to: [`STANDALONE_STREAM_ID`], or the
+    /// JSON-RPC id of the request whose response stream carried it.
+    pub stream_id: String,
+    /// Event id sent to the client.
+    pub event_id: String,
+    /// The message carried by the event.
+    pub message: Arc<ServerJsonRpcMessage>,
+}
+
+impl StoredEvent {
+    /// Creates a stored event.
+    pub fn new(
+        stream_id: impl Into<String>,
+        event_id: impl Into<String>,
+        message: Arc<ServerJsonRpcMessage>,
+    ) -> Self {
+        Self {
+            stream_id: stream_id.into(),
+            event_id: event_id.into(),
+            message,
+        }
+    }
+}
+
+/// Events to replay to a client resuming a stream.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct EventReplay {
+    /// Stream the `Last-Event-ID` belongs to.
+    pub stream_id: String,
+    /// Events written to that stream after the `Last-Event-ID`, oldest first.
+    pub events: Vec<StoredEvent>,
+}
+
+impl EventReplay {
+    /// Creates a replay of `events` on `stream_id`.
+    pub fn new(stream_id: impl Into<String>, events: Vec<StoredEvent>) -> Self {
+        Self {
+            stream_id: stream_id.into(),
+            events,
+        }
+    }
+}
+
+/// Storage for the SSE events of sessions, used to answer `Last-Event-ID`
+/// resumption independently of the session manager.
+pub trait EventStore: Send + Sync + 'static {
+    /// Records an event written to a stream of `session_id`.
+    ///
+    /// Events of a session are appended in the order they were written.
+    fn append(
+        &self,
+        session_id: &SessionId,
+        event: StoredEvent,
+    ) -> BoxFuture<'_, Result<(), EventStoreError>>;
+
+    /// Returns the events written after `last_event_id` on the same stream.
+    ///
+    /// Returns `None` if the store does not know `last_event_id`, in which case
+    /// the session manager answers the resumption on its own.
+    fn replay(
+        &self,
+        session_id: &SessionId,
+        last_event_id: &str,
+    ) -> BoxFuture<'_, Result<Option<EventReplay>, EventStoreError>>;
+
+    /// Forgets every event of a closed session.
+    fn remove_session(&self, session_id: &SessionId) -> BoxFuture<'_, ()>;
+}
+
+/// An [`EventStore`] keeping the most recent events of each session in memory.
+#[derive(Debug)]
+pub struct InMemoryEventStore {
+    capacity: usize,
+    sessions: Mutex<HashMap<SessionId, VecDeque<StoredEvent>>>,
+}
+
+impl InMemoryEventStore {
+    /// Creates a store keeping up to `capacity` events per session.
+    ///
+    /// Once a session reaches the limit, its oldest events are dropped and can
+    /// no longer be resumed from.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            sessions: Mutex::default(),
+        }
+    }
+}
+
+impl EventStore for InMemoryEventStore {
+    fn append(
+        &self,
+        session_id: &SessionId,
+        event: StoredEvent,
+    ) -> BoxFuture<'_, Result<(), EventStoreError>> {
+        if self.capacity > 0 {
+            let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+            let events = sessions.entry(session_id.clone()).or_default();
+            if events.len() == self.capacity {
+                events.pop_front();
+            }
+            events.push_back(event);
+        }
+        Box::pin(std::future::ready(Ok(())))
+    }
+
+    fn replay(
+        &self,
+        session_id: &SessionId,
+        last_event_id: &str,
+    ) -> BoxFuture<'_, Result<Option<EventReplay>, EventStoreError>> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let replay = sessions.get(session_id).and_then(|events| {
+            let position = events
+                .iter()
+                .position(|event| event.event_id == last_event_id)?;
+            let stream_id = &events[position].stream_id;
+            let missed = events
+                .iter()
+                .skip(position + 1)
+                .filter(|event| &event.stream_id == stream_id)
+                .cloned()
+                .collect();
+            Some(EventReplay::new(stream_id.clone(), missed))
+        });
+        Box::pin(std::future::ready(Ok(replay)))
+    }
+
+    fn remove_session(&self, session_id: &SessionId) -> BoxFuture<'_, ()> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(session_id);
+        Box::pin(std::future::ready(()))
+    }
+}
@@ -0,0 +1,85 @@
+//! Automatic propagation of actix-web request extensions.
+//!
+//! actix-web middleware commonly stores typed values (JWT claims, tenant,
+//! request id) in the `HttpRequest` extensions. An [`ExtensionAllowList`]
+//! names the types to copy from there into the rmcp `RequestContext`
+//! extensions on every MCP request, so they do not have to be enumerated one
+//! by one in an `on_request` hook.
+//!
+//! actix-web extensions are a type map that cannot be iterated, so the types
+//! to copy must be listed explicitly. Only types that are `Clone` can be
+//! copied; types missing from a given request are skipped.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rmcp_actix_web::transport::ExtensionAllowList;
+//!
+//! #[derive(Clone)]
+//! struct JwtClaims { sub: String }
+//!
+//! #[derive(Clone)]
+//! struct TenantId(String);
+//!
+//! let allow_list = ExtensionAllowList::new()
+//!     .allow::<JwtClaims>()
+//!     .allow::<TenantId>();
+//! ```
+
+use std::{any::type_name, fmt, sync::Arc};
+
+use actix_web::{HttpMessage, HttpRequest};
+use rmcp::model::Extensions;
+
+type CopyFn = Arc<dyn Fn(&HttpRequest, &mut Extensions) + Send + Sync>;
+
+/// Extension types copied from the `HttpRequest` into rmcp `Extensions`.
+///
+/// The default allow list is empty and copies nothing.
+#[derive(Clone, Default)]
+pub struct ExtensionAllowList {
+    copiers: Vec<(&'static str, CopyFn)>,
+}
+
+impl ExtensionAllowList {
+    /// Creates an empty allow list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `T` to the types copied on every request.
+    pub fn allow<T>(mut self) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.copiers.push((
+            type_name::<T>(),
+            Arc::new(|req, extensions| {
+                if let Some(value) = req.extensions().get::<T>() {
+                    extensions.insert(value.clone());
+                }
+            }),
+        ));
+        self
+    }
+
+    /// Returns `true` if no type is allowed.
+    pub fn is_empty(&self) -> bool {
+        self.copiers.is_empty()
+    }
+
+    /// Copies every allowed extension present on `req` into `extensions`.
+    pub(crate) fn copy(&self, req: &HttpRequest, extensions: &mut Extensions) {
+        for (_, copy) in &self.copiers {
+            copy(req, extensions);
+        }
+    }
+}
+
+impl fmt::Debug for ExtensionAllowList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.copiers.iter().map(|(name, _)| name))
+            .finish()
+    }
+}
@@ -0,0 +1,91 @@
+//! Client address allow/deny lists.
+//!
+//! An [`IpFilter`] restricts which client addresses may reach the MCP
+//! endpoints, e.g. to lock an internal server to corporate ranges without an
+//! extra proxy layer. It is checked before any other request processing;
+//! rejected requests get `403 Forbidden`.
+//!
+//! Networks are given in CIDR form. An address in a denied network is always
+//! rejected; when allowed networks are set, every address outside them is
+//! rejected as well, including requests whose address is unknown. The client
+//! address is the socket peer, or the address resolved through the
+//! [`TrustedProxies`](crate::transport::TrustedProxies) when configured, so
+//! forwarding headers from untrusted peers cannot bypass the lists.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! use rmcp_actix_web::transport::IpFilter;
+//!
+//! // Corporate range only, except the guest Wi-Fi
+//! let filter = IpFilter::new()
+//!     .allow_network(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8)
+//!     .deny_network(IpAddr::V4(Ipv4Addr::new(10, 66, 0, 0)), 16);
+//! ```
+
+use std::net::IpAddr;
+
+use super::trusted_proxies::{canonical_network, in_network};
+
+/// Allow/deny lists for client addresses.
+///
+/// The default filter allows every address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpFilter {
+    allowed: Option<Vec<(IpAddr, u8)>>,
+    denied: Vec<(IpAddr, u8)>,
+}
+
+impl IpFilter {
+    /// Creates a filter that allows every address.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allows the addresses of a network (plus any other allowed network).
+    ///
+    /// `prefix_len` is capped to the length of the address. An IPv4-mapped IPv6
+    /// network is treated as the IPv4 network it maps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an IPv4-mapped network has a prefix shorter than 96 bits.
+    pub fn allow_network(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.allowed
+            .get_or_insert_default()
+            .push(canonical_network(addr, prefix_len));
+        self
+    }
+
+    /// Rejects the addresses of a network.
+    ///
+    /// `prefix_len` is capped to the length of the address. An IPv4-mapped IPv6
+    /// network is treated as the IPv4 network it maps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an IPv4-mapped network has a prefix shorter than 96 bits.
+    pub fn deny_network(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.denied.push(canonical_network(addr, prefix_len));
+        self
+    }
+
+    /// Returns whether no list is set.
+    pub fn is_empty(&self) -> bool {
+        self.allowed.is_none() && self.denied.is_empty()
+    }
+
+    /// Returns `true` if a client at `ip` may be served.
+    ///
+    /// Unknown addresses are only allowed when no allowed networks are set.
+    pub fn is_allowed(&self, ip: Option<IpAddr>) -> bool {
+        let Some(ip) = ip.map(|ip| ip.to_canonical()) else {
+            return self.allowed.is_none();
+        };
+        let matches =
+            |networks: &[(IpAddr, u8)]| networks.iter().any(|&(n, len)| in_network(ip, n, len));
+        !matches(&self.denied) && self.allowed.as_deref().is_none_or(matches)
+    }
+}
@@ -0,0 +1,154 @@
+//! Method and tool allow/deny lists.
+//!
+//! A [`MessageFilter`] lets operators disable JSON-RPC methods or individual
+//! tools per deployment, without code changes in the MCP service. Rejected
+//! requests are answered by the transport with a JSON-RPC error and never
+//! reach the handler.
+//!
+//! Lists are matched on exact names. A name that appears on a deny list is
+//! always rejected; when an allow list is set, every name missing from it is
+//! rejected as well. `initialize` and `ping` are never filtered, so a
+//! restrictive allow list cannot break the session lifecycle. Notifications
+//! and client responses are not filtered either, since they have no way to
+//! carry an error back.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rmcp_actix_web::transport::MessageFilter;
+//!
+//! // Read-only deployment: no prompts, and no tool that writes to disk
+//! let filter = MessageFilter::new()
+//!     .deny_methods(["prompts/get", "prompts/list"])
+//!     .deny_tools(["write_file", "delete_file"]);
+//! ```
+
+use std::collections::HashSet;
+
+use rmcp::model::{
+    ClientJsonRpcMessage, ClientRequest, ErrorCode, ErrorData, JsonRpcRequest, ServerJsonRpcMessage,
+};
+
+/// Methods that are always allowed, regardless of the configured lists.
+const LIFECYCLE_METHODS: &[&str] = &["initialize", "ping"];
+
+/// Allow/deny lists for JSON-RPC methods and `tools/call` tool names.
+///
+/// The default filter allows everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageFilter {
+    allowed_methods: Option<HashSet<String>>,
+    denied_methods: HashSet<String>,
+    allowed_tools: Option<HashSet<String>>,
+    denied_tools: HashSet<String>,
+}
+
+impl MessageFilter {
+    /// Creates a filter that allows everything.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only allows the given methods (plus `initialize` and `ping`).
+    pub fn allow_methods<I, T>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.allowed_methods
+            .get_or_insert_default()
+            .extend(methods.into_iter().map(Into::into));
+        self
+    }
+
+    /// Rejects the given methods.
+    pub fn deny_methods<I, T>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.denied_methods
+            .extend(methods.into_iter().map(Into::into));
+        self
+    }
+
+    /// Only allows `tools/call` for the given tool names.
+    pub fn allow_tools<I, T>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.allowed_tools
+            .get_or_insert_default()
+            .extend(tools.into_iter().map(Into::into));
+        self
+    }
+
+    /// Rejects `tools/call` for the given tool names.
+    pub fn deny_tools<I, T>(mut self, tools: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.denied_tools.extend(tools.into_iter().map(Into::into));
+        self
+    }
+
+    /// Checks a client request against the lists.
+    ///
+    /// Returns the JSON-RPC error to answer with if the request is rejected.
+    pub fn check_request(&self, request: &ClientRequest) -> Result<(), ErrorData> {
+        let method = request.method();
+        if LIFECYCLE_METHODS.contains(&method) {
+            return Ok(());
+        }
+
+        if !is_allowed(method, self.allowed_methods.as_ref(), &self.denied_methods) {
+            return Err(ErrorData::new(
+                ErrorCode::METHOD_NOT_FOUND,
+                format!("Method not allowed: {method}"),
+                None,
+            ));
+        }
+
+        if let ClientRequest::CallToolRequest(call) = request {
+            let tool = call.params.name.as_ref();
+            if !is_allowed(tool, self.allowed_tools.as_ref(), &self.denied_tools) {
+                return Err(ErrorData::invalid_params(
+                    format!("Tool not allowed: {tool}"),
+                    None,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the tool `name` is offered to clients: `tools/list` and
+    /// `tools/call` pass the method lists, and the tool passes the tool lists.
+    pub(crate) fn offers_tool(&self, name: &str) -> bool {
+        ["tools/list", "tools/call"]
+            .iter()
+            .all(|method| is_allowed(method, self.allowed_methods.as_ref(), &self.denied_methods))
+            && is_allowed(name, self.allowed_tools.as_ref(), &self.denied_tools)
+    }
+
+    /// Checks a client message, returning the error response to send back if
+    /// it is a rejected request.
+    pub(crate) fn reject(&self, message: &ClientJsonRpcMessage) -> Option<ServerJsonRpcMessage> {
+        let ClientJsonRpcMessage::Request(JsonRpcRequest { id, request, .. }) = message else {
+            return None;
+        };
+        let error = self.check_request(request).err()?;
+        tracing::info!(
+            method = request.method(),
+            reason = %error.message,
+            "Request rejected by message filter"
+        );
+        Some(ServerJsonRpcMessage::error(error, Some(id.clone())))
+    }
+}
+
+fn is_allowed(name: &str, allowed: Option<&HashSet<String>>, denied: &HashSet<String>) -> bool {
+    !denied.contains(name) && allowed.is_none_or(|allowed| allowed.contains(name))
+}
@@ -82,6 +82,13 @@
 //! The propagated extensions are then accessible in your MCP service handlers
 //! via `RequestContext::extensions`.
 //!
+//! ## Migrating from the Axum Transport
+//!
+//! Applications that already build rmcp's tower-based `StreamableHttpService`
+//! can mount it unchanged with [`TowerStreamableHttpService`], keeping their
+//! session manager and `StreamableHttpServerConfig` while moving the HTTP layer
+//! to actix-web. See the [`tower_compat`] module for details.
+//!
 //! ## Protocol Compatibility
 //!
 //! The transport implements the [MCP protocol specification][mcp] and is compatible
@@ -98,9 +105,149 @@
 pub mod streamable_http_server;
 #[cfg(feature = "transport-streamable-http")]
 pub use streamable_http_server::{
-    OnRequestHook, StreamableHttpServerConfig, StreamableHttpService, StreamableHttpServiceBuilder,
+    AuthenticatedIdentity, AuthorizeHook, BuildInfo, ConfiguredServiceBuilder, ErrorReport,
+    ErrorReportKind, EventIdHook, Histogram, HistogramBucket, InboundMessageHook, KeepAliveFrame,
+    LEGACY_SESSION_CLOSED_CODE, LEGACY_SESSION_NOT_FOUND_CODE, OnInternalErrorHook, OnRequestHook,
+    OnSessionClosedHook, OnSessionCreatedHook, OutboundMessageHook, PAYLOAD_SIZE_BUCKETS,
+    PathNormalization, PayloadStats, QUOTA_EXCEEDED_CODE, ReinitializePolicy, RequestGuardHook,
+    RuntimeStats, SERIALIZATION_TIME_BUCKETS, SessionCapabilities, SessionCloseReason,
+    SessionCreationLimit, SessionExtensionsHook, SessionLiveness, SlowConsumerPolicy,
+    SourcePayloadStats, SseOverflowPolicy, StreamEndReason, StreamableHttpServerConfig,
+    StreamableHttpService, StreamableHttpServiceBuilder, TenantId, TenantQuota, TenantQuotas,
+    TenantServiceFactory, TenantUsage, TransportEvent,
+};
+
+/// Type-erased handlers chosen at runtime.
+#[cfg(feature = "transport-streamable-http")]
+pub mod dyn_handler;
+#[cfg(feature = "transport-streamable-http")]
+pub use dyn_handler::{DynServerHandler, DynStreamableHttpService};
+
+/// Serving of synchronous, blocking handler code.
+#[cfg(feature = "transport-streamable-http")]
+pub mod blocking;
+#[cfg(feature = "transport-streamable-http")]
+pub use blocking::{BlockingHandler, run_blocking};
+
+/// Session manager partitioned into independently locked shards.
+#[cfg(feature = "transport-streamable-http")]
+pub mod sharded_session_manager;
+#[cfg(feature = "transport-streamable-http")]
+pub use sharded_session_manager::ShardedSessionManager;
+
+/// Well-known discovery manifest for MCP endpoints.
+#[cfg(feature = "transport-streamable-http")]
+pub mod discovery;
+#[cfg(feature = "transport-streamable-http")]
+pub use discovery::{
+    DirectoryEntry, DiscoveryAuth, DiscoveryManifest, ServiceDescription, ServiceDirectory,
+    ServiceDirectoryDocument, TransportDescriptor,
 };
 
+/// API key authentication.
+#[cfg(feature = "transport-streamable-http")]
+pub mod api_key;
+#[cfg(feature = "transport-streamable-http")]
+pub use api_key::ApiKeyAuth;
+
+/// Problem details for HTTP-level failures.
+#[cfg(feature = "transport-streamable-http")]
+pub mod problem;
+#[cfg(feature = "transport-streamable-http")]
+pub use problem::Problem;
+
+/// Redaction of sensitive data in log output.
+#[cfg(feature = "transport-streamable-http")]
+pub mod redaction;
+#[cfg(feature = "transport-streamable-http")]
+pub use redaction::LogRedaction;
+
+/// Side channels for large binary contents.
+#[cfg(feature = "transport-streamable-http")]
+pub mod blobs;
+#[cfg(feature = "transport-streamable-http")]
+pub use blobs::{BlobLinks, Upload, Uploads};
+
+/// Storage for the events replayed on `Last-Event-ID` resumption.
+#[cfg(feature = "transport-streamable-http")]
+pub mod event_store;
+#[cfg(feature = "transport-streamable-http")]
+pub use event_store::{EventReplay, EventStore, EventStoreError, InMemoryEventStore, StoredEvent};
+
+/// Automatic propagation of actix-web request extensions.
+#[cfg(feature = "transport-streamable-http")]
+pub mod extension_propagation;
+#[cfg(feature = "transport-streamable-http")]
+pub use extension_propagation::ExtensionAllowList;
+
+/// Transport settings loaded from files or the environment.
+#[cfg(feature = "transport-streamable-http")]
+pub mod config;
+#[cfg(feature = "transport-streamable-http")]
+pub use config::{ConfigError, TransportConfig};
+
+/// Method and tool allow/deny lists.
+#[cfg(feature = "transport-streamable-http")]
+pub mod message_filter;
+#[cfg(feature = "transport-streamable-http")]
+pub use message_filter::MessageFilter;
+
+/// OAuth scope requirements for tools.
+#[cfg(feature = "transport-streamable-http")]
+pub mod tool_scopes;
+#[cfg(feature = "transport-streamable-http")]
+pub use tool_scopes::{GrantedScopes, ToolScopes};
+
+/// Stateless signed session tokens.
+#[cfg(feature = "signed-session-tokens")]
+pub mod session_tokens;
+#[cfg(feature = "signed-session-tokens")]
+pub use session_tokens::{SessionToken, SessionTokens};
+
+/// Programmatic per-request authorization.
+#[cfg(feature = "transport-streamable-http")]
+pub mod request_authorization;
+#[cfg(feature = "transport-streamable-http")]
+pub use request_authorization::AuthorizationRequest;
+
+/// Client address resolution behind trusted reverse proxies.
+#[cfg(feature = "transport-streamable-http")]
+pub mod trusted_proxies;
+#[cfg(feature = "transport-streamable-http")]
+pub use trusted_proxies::TrustedProxies;
+
+/// Client address allow/deny lists.
+#[cfg(feature = "transport-streamable-http")]
+pub mod ip_filter;
+#[cfg(feature = "transport-streamable-http")]
+pub use ip_filter::IpFilter;
+
+/// Opt-in result caching for idempotent tools.
+#[cfg(feature = "transport-streamable-http")]
+pub mod cacheable_tools;
+#[cfg(feature = "transport-streamable-http")]
+pub use cacheable_tools::CacheableTools;
+
+/// Circuit breaker for upstream calls in proxy deployments.
+#[cfg(feature = "transport-streamable-http")]
+pub mod circuit_breaker;
+#[cfg(feature = "transport-streamable-http")]
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+
+/// Serving over TLS with rustls.
+#[cfg(feature = "rustls")]
+pub mod tls;
+#[cfg(feature = "rustls")]
+pub use tls::rustls_config;
+
+/// Compatibility shim for rmcp's tower-based Streamable HTTP service.
+///
+/// Mounts an existing rmcp `StreamableHttpService` inside an actix-web scope.
+#[cfg(feature = "transport-streamable-http")]
+pub mod tower_compat;
+#[cfg(feature = "transport-streamable-http")]
+pub use tower_compat::TowerStreamableHttpService;
+
 /// Re-export of rmcp's Extensions type for use with on_request hook.
 pub use rmcp::model::Extensions;
 
@@ -130,5 +277,334 @@ pub use rmcp::model::Extensions;
 ///     // ...
 /// }
 /// ```
-#[derive(Clone, Debug)]
+///
+/// The `Debug` output hides the credentials, so the header can be logged safely.
+#[derive(Clone)]
 pub struct AuthorizationHeader(pub String);
+
+impl std::fmt::Debug for AuthorizationHeader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scheme = self.0.split_whitespace().next().unwrap_or_default();
+        write!(f, "AuthorizationHeader(\"{scheme} [REDACTED]\")")
+    }
+}
+
+/// Connection details of the HTTP client behind an MCP request.
+///
+/// The transport inserts this type into the `RequestContext` extensions of every
+/// request, so MCP handlers can log callers or apply IP-based logic without
+/// access to the actix-web `HttpRequest`.
+///
+/// `realip_remote_addr`, `scheme` and `host` honor the `Forwarded` and
+/// `X-Forwarded-*` headers (see actix-web's `ConnectionInfo`). Those headers are
+/// set by the client unless a trusted reverse proxy overwrites them. When the
+/// [`trusted_proxies`](StreamableHttpServiceBuilder::trusted_proxies) option is
+/// set, `realip_remote_addr` is instead resolved with
+/// [`TrustedProxies::client_ip`] and cannot be spoofed by clients; otherwise use
+/// `peer_addr` when the value must not be spoofable.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rmcp_actix_web::transport::ClientConnectionInfo;
+///
+/// async fn handle_request(
+///     &self,
+///     request: SomeRequest,
+///     context: RequestContext<RoleServer>,
+/// ) -> Result<Response, McpError> {
+///     if let Some(conn) = context.extensions.get::<ClientConnectionInfo>() {
+///         tracing::info!(ip = ?conn.realip_remote_addr, "tool called");
+///     }
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClientConnectionInfo {
+    /// Address of the socket peer (the client or the closest proxy).
+    pub peer_addr: Option<std::net::SocketAddr>,
+    /// Client address as reported by forwarding headers, falling back to the peer address.
+    pub realip_remote_addr: Option<String>,
+    /// Request scheme, e.g. `http` or `https`.
+    pub scheme: String,
+    /// Requested host, including the port if present.
+    pub host: String,
+    /// Value of the `User-Agent` header, if present and valid UTF-8.
+    pub user_agent: Option<String>,
+}
+
+impl ClientConnectionInfo {
+    /// Extracts the connection details of an actix-web request.
+    pub fn from_request(req: &actix_web::HttpRequest) -> Self {
+        let info = req.connection_info();
+        Self {
+            peer_addr: req.peer_addr(),
+            realip_remote_addr: info.realip_remote_addr().map(str::to_string),
+            scheme: info.scheme().to_string(),
+            host: info.host().to_string(),
+            user_agent: req
+                .headers()
+                .get(actix_web::http::header::USER_AGENT)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Filtered snapshot of the HTTP request headers behind an MCP request.
+///
+/// Only headers listed with the
+/// [`snapshot_headers`](StreamableHttpServiceBuilder::snapshot_headers) builder
+/// option are captured; the snapshot is inserted into the `RequestContext`
+/// extensions of every request when that list is not empty. Header names are
+/// matched case-insensitively.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rmcp_actix_web::transport::HeaderSnapshot;
+///
+/// // Builder: .snapshot_headers(vec!["accept-language".into(), "x-correlation-id".into()])
+///
+/// async fn handle_request(
+///     &self,
+///     request: SomeRequest,
+///     context: RequestContext<RoleServer>,
+/// ) -> Result<Response, McpError> {
+///     let language = context
+///         .extensions
+///         .get::<HeaderSnapshot>()
+///         .and_then(|headers| headers.get("accept-language"));
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeaderSnapshot {
+    headers: Vec<(String, String)>,
+}
+
+impl HeaderSnapshot {
+    /// Captures the headers of `req` whose (lowercase) name is in `names`.
+    ///
+    /// Values that are not valid UTF-8 are skipped.
+    pub fn capture(req: &actix_web::HttpRequest, names: &[String]) -> Self {
+        let headers = req
+            .headers()
+            .iter()
+            .filter(|(name, _)| names.iter().any(|n| name.as_str().eq_ignore_ascii_case(n)))
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+        Self { headers }
+    }
+
+    /// Returns the first value of the header `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns every value of the header `name`, in request order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.headers
+            .iter()
+            .filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Iterates over every captured `(name, value)` pair.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+}
+
+/// HTTP response headers set by an MCP handler.
+///
+/// When the [`response_headers`](StreamableHttpServiceBuilder::response_headers)
+/// builder option is enabled, the transport inserts a `ResponseHeaders` handle into
+/// the `RequestContext` extensions of every POSTed request. Headers a handler sets
+/// on it before producing its first message (response, progress notification, or
+/// server request) are added to the HTTP response of that POST, e.g. to surface
+/// rate-limit headers from a downstream API or to set cache headers. Headers set
+/// later are ignored, since the HTTP response head has already been sent.
+///
+/// Clones share the same headers.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rmcp_actix_web::transport::ResponseHeaders;
+///
+/// async fn call_tool(
+///     &self,
+///     request: CallToolRequestParams,
+///     context: RequestContext<RoleServer>,
+/// ) -> Result<CallToolResult, McpError> {
+///     let remaining = self.upstream.rate_limit_remaining().await;
+///     if let Some(headers) = context.extensions.get::<ResponseHeaders>() {
+///         headers.insert("X-RateLimit-Remaining", remaining.to_string());
+///     }
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ResponseHeaders(std::sync::Arc<std::sync::Mutex<Vec<(String, String)>>>);
+
+impl ResponseHeaders {
+    /// Sets a header, replacing any value previously set for the same name.
+    pub fn insert(&self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        let mut headers = self.lock();
+        headers.retain(|(n, _)| !n.eq_ignore_ascii_case(&name));
+        headers.push((name, value.into()));
+    }
+
+    /// Adds a header value, keeping values previously set for the same name.
+    pub fn append(&self, name: impl Into<String>, value: impl Into<String>) {
+        self.lock().push((name.into(), value.into()));
+    }
+
+    /// Removes and returns every header set so far.
+    pub(crate) fn take(&self) -> Vec<(String, String)> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(String, String)>> {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Cancellation signal of an MCP request.
+///
+/// The transport inserts a `RequestCancellation` into the `RequestContext`
+/// extensions of every request. It is cancelled when the client disconnects from
+/// a stream it cannot resume, when the request times out, when its session is
+/// closed (`DELETE`, expiry, liveness) and when the service is shut down, so
+/// long-running tools can abort calls to external systems cooperatively.
+///
+/// Clones share the same signal.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rmcp_actix_web::transport::RequestCancellation;
+///
+/// async fn call_tool(
+///     &self,
+///     request: CallToolRequestParams,
+///     context: RequestContext<RoleServer>,
+/// ) -> Result<CallToolResult, McpError> {
+///     let cancellation = context.extensions.get::<RequestCancellation>().cloned();
+///     let report = match cancellation {
+///         Some(cancellation) => tokio::select! {
+///             report = self.upstream.build_report() => report,
+///             _ = cancellation.cancelled() => {
+///                 return Err(McpError::internal_error("Request cancelled", None));
+///             }
+///         },
+///         None => self.upstream.build_report().await,
+///     };
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RequestCancellation(tokio_util::sync::CancellationToken);
+
+impl RequestCancellation {
+    pub(crate) fn new(token: tokio_util::sync::CancellationToken) -> Self {
+        Self(token)
+    }
+
+    /// Returns `true` once the request has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled()
+    }
+
+    /// Resolves once the request has been cancelled.
+    pub async fn cancelled(&self) {
+        self.0.cancelled().await;
+    }
+
+    /// Returns the underlying cancellation token, e.g. to hand it to a client
+    /// library that accepts one.
+    pub fn token(&self) -> &tokio_util::sync::CancellationToken {
+        &self.0
+    }
+
+    /// Cancels the request.
+    pub(crate) fn cancel(&self) {
+        self.0.cancel();
+    }
+}
+
+/// Time budget of an MCP request.
+///
+/// The transport inserts a `RequestDeadline` into the `RequestContext` extensions
+/// of every POSTed request that has a budget: the
+/// [`request_timeout`](StreamableHttpServiceBuilder::request_timeout) of the
+/// service and, when the
+/// [`request_deadline_headers`](StreamableHttpServiceBuilder::request_deadline_headers)
+/// option is enabled, the budget announced by the client in a `Request-Timeout`
+/// or `X-Request-Deadline` header, whichever ends first. The transport answers
+/// the request with a timeout error once the deadline has passed; handlers use
+/// the remaining budget to bound their own work and to forward the deadline to
+/// the services they call.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rmcp_actix_web::transport::RequestDeadline;
+///
+/// async fn call_tool(
+///     &self,
+///     request: CallToolRequestParams,
+///     context: RequestContext<RoleServer>,
+/// ) -> Result<CallToolResult, McpError> {
+///     let mut upstream = self.http.get(&self.upstream_url);
+///     if let Some(deadline) = context.extensions.get::<RequestDeadline>() {
+///         upstream = upstream
+///             .timeout(deadline.remaining())
+///             .header(RequestDeadline::TIMEOUT_HEADER, deadline.timeout_header_value());
+///     }
+///     // ...
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RequestDeadline(std::time::Instant);
+
+impl RequestDeadline {
+    /// Header carrying the remaining budget in seconds, e.g. `2.5`.
+    pub const TIMEOUT_HEADER: &str = "Request-Timeout";
+    /// Header carrying the deadline as Unix time in milliseconds.
+    pub const DEADLINE_HEADER: &str = "X-Request-Deadline";
+
+    pub(crate) fn new(deadline: std::time::Instant) -> Self {
+        Self(deadline)
+    }
+
+    /// Returns the instant the request must be answered by.
+    pub fn deadline(&self) -> std::time::Instant {
+        self.0
+    }
+
+    /// Returns the time left until the deadline, zero once it has passed.
+    pub fn remaining(&self) -> std::time::Duration {
+        self.0.saturating_duration_since(std::time::Instant::now())
+    }
+
+    /// Returns `true` once the deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Formats the remaining budget as a [`TIMEOUT_HEADER`](Self::TIMEOUT_HEADER)
+    /// value, to propagate the deadline to downstream services.
+    pub fn timeout_header_value(&self) -> String {
+        format!("{:.3}", self.remaining().as_secs_f64())
+    }
+}
@@ -0,0 +1,158 @@
+//! Problem details for HTTP-level failures.
+//!
+//! Requests the transport refuses at the HTTP level, before any JSON-RPC
+//! processing (a missing or unknown session, a rejected origin, a wrong
+//! `Accept` or `Content-Type` header, a missing API key, ...), are answered with
+//! an [RFC 7807] `application/problem+json` document:
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Not Found",
+//!   "status": 404,
+//!   "detail": "Session not found",
+//!   "code": "session_not_found",
+//!   "sessionId": "5f0c..."
+//! }
+//! ```
+//!
+//! Besides the standard members, every problem carries a stable `code` that
+//! clients can match on, and, when the failure concerns a session, its
+//! `sessionId`. Failures answering a JSON-RPC request, such as refused tool
+//! calls or quota rejections, remain JSON-RPC error responses carrying the
+//! request id.
+//!
+//! [`Problem`] is public, so request guards can refuse requests the same way:
+//!
+//! ```rust
+//! use actix_web::http::StatusCode;
+//! use rmcp_actix_web::transport::Problem;
+//!
+//! let response = Problem::new(
+//!     StatusCode::SERVICE_UNAVAILABLE,
+//!     "maintenance",
+//!     "Down for maintenance",
+//! )
+//! .into_response();
+//! assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+//! ```
+//!
+//! [RFC 7807]: https://www.rfc-editor.org/rfc/rfc7807
+
+use actix_web::{
+    HttpResponse,
+    body::{BoxBody, MessageBody},
+    dev::ServiceResponse,
+    http::{StatusCode, header},
+};
+use serde::{Deserialize, Serialize};
+
+/// Media type of problem details documents.
+pub const PROBLEM_JSON_MIME_TYPE: &str = "application/problem+json";
+
+/// Problem type of problems identified by their status and `code` alone.
+const ABOUT_BLANK: &str = "about:blank";
+
+/// An RFC 7807 problem details document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Problem {
+    /// URI identifying the problem type, `about:blank` by default.
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Short summary of the problem type, the reason phrase of the status.
+    pub title: String,
+    /// HTTP status code of the response.
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Stable, machine-readable identifier of the problem, e.g. `session_not_found`.
+    pub code: String,
+    /// Session the problem concerns, if any.
+    #[serde(rename = "sessionId", default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+impl Problem {
+    /// Creates a problem answered with `status`.
+    pub fn new(status: StatusCode, code: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            problem_type: ABOUT_BLANK.to_string(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail: Some(detail.into()),
+            code: code.into(),
+            session_id: None,
+        }
+    }
+
+    /// Creates a problem whose `code` is derived from `status`, e.g. `bad_request`.
+    pub(crate) fn from_status(status: StatusCode, detail: impl Into<String>) -> Self {
+        let code = status
+            .canonical_reason()
+            .unwrap_or("error")
+            .to_ascii_lowercase()
+            .replace([' ', '-'], "_");
+        Self::new(status, code, detail)
+    }
+
+    /// Sets the session the problem concerns.
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Sets the URI identifying the problem type.
+    pub fn with_type(mut self, problem_type: impl Into<String>) -> Self {
+        self.problem_type = problem_type.into();
+        self
+    }
+
+    /// Builds the response carrying this problem.
+    pub fn into_response(self) -> HttpResponse {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        HttpResponse::build(status)
+            .content_type(PROBLEM_JSON_MIME_TYPE)
+            .body(serde_json::to_string(&self).unwrap_or_else(|_| "{}".to_string()))
+    }
+}
+
+impl From<Problem> for HttpResponse {
+    fn from(problem: Problem) -> Self {
+        problem.into_response()
+    }
+}
+
+/// Renders the error response of a failed request as a problem, unless it is
+/// already JSON.
+///
+/// Covers the errors raised through `?` in the handlers and by actix-web
+/// extractors (payload too large, undecodable body, ...), whose bodies would
+/// otherwise be the plain-text display of the error. The status, the other
+/// headers and the error itself, for logging middleware, are kept.
+pub(crate) fn render_error<B>(res: ServiceResponse<B>) -> ServiceResponse<BoxBody>
+where
+    B: MessageBody + 'static,
+{
+    let Some(error) = res.response().error() else {
+        return res.map_into_boxed_body();
+    };
+    let is_json = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|content_type| content_type.contains("json"));
+    if is_json {
+        return res.map_into_boxed_body();
+    }
+    let problem = Problem::from_status(res.status(), error.to_string());
+    let body = serde_json::to_string(&problem).unwrap_or_else(|_| "{}".to_string());
+    res.map_body(|head, _| {
+        head.headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(PROBLEM_JSON_MIME_TYPE),
+        );
+        BoxBody::new(body)
+    })
+}
@@ -0,0 +1,272 @@
+//! Redaction of sensitive data in log output.
+//!
+//! The transport logs the JSON-RPC messages it receives and sends at debug
+//! level, and tool arguments or results routinely carry credentials, personal
+//! data or documents. A [`LogRedaction`], configured with
+//! [`log_redaction`](crate::transport::StreamableHttpServiceBuilder::log_redaction),
+//! decides what of a message reaches the logs: the structure of the message is
+//! kept, but scalar values are replaced with `[REDACTED]` unless their field
+//! name is on the allow-list.
+//!
+//! The default allow-list keeps the fields needed to follow a conversation
+//! (`jsonrpc`, `id`, `method`, tool `name`, progress, log levels, protocol
+//! version and client or server info) and hides everything else, tool
+//! arguments and results included. Its fields are only kept outside the
+//! payloads of a message: tool and prompt `params.arguments`, log
+//! `params.data`, `params.messages`, `result.content`, `result.structuredContent`,
+//! `result.contents`, `result.messages` and `error.data`. An argument named
+//! `code` or `name` is therefore hidden like any other. Fields added with
+//! [`LogRedaction::allow`] or [`LogRedaction::allowing`] are kept everywhere,
+//! payloads included.
+//!
+//! Bearer tokens never reach the logs: [`AuthorizationHeader`]'s `Debug`
+//! implementation hides the credentials.
+//!
+//! [`AuthorizationHeader`]: crate::transport::AuthorizationHeader
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rmcp_actix_web::transport::LogRedaction;
+//! use serde_json::json;
+//!
+//! let redaction = LogRedaction::default().allow("uri");
+//! let message = json!({
+//!     "jsonrpc": "2.0",
+//!     "id": 1,
+//!     "method": "tools/call",
+//!     "params": { "name": "login", "arguments": { "password": "hunter2" } }
+//! });
+//! assert_eq!(
+//!     redaction.redact(&message)["params"]["arguments"]["password"],
+//!     "[REDACTED]"
+//! );
+//! ```
+
+use std::{collections::HashSet, fmt, sync::Arc};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Replacement for redacted values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Fields kept by [`LogRedaction::default`].
+const DEFAULT_ALLOWED_FIELDS: &[&str] = &[
+    "jsonrpc",
+    "id",
+    "method",
+    "name",
+    "code",
+    "level",
+    "logger",
+    "progressToken",
+    "progress",
+    "total",
+    "requestId",
+    "protocolVersion",
+    "version",
+    "isError",
+];
+
+/// Payload fields of the `params`, `result` and `error` members of a message,
+/// in which the default fields are not kept.
+const PAYLOAD_FIELDS: &[(&str, &str)] = &[
+    ("params", "arguments"),
+    ("params", "data"),
+    ("params", "messages"),
+    ("result", "content"),
+    ("result", "structuredContent"),
+    ("result", "contents"),
+    ("result", "messages"),
+    ("error", "data"),
+];
+
+/// Policy deciding which values of logged messages are kept.
+///
+/// Clones share the same allow-list.
+#[derive(Clone)]
+pub struct LogRedaction {
+    /// Fields whose scalar values are kept, or `None` to keep everything.
+    allowed: Option<Arc<AllowedFields>>,
+}
+
+#[derive(Clone, Default)]
+struct AllowedFields {
+    /// Field names kept anywhere in a message.
+    anywhere: HashSet<String>,
+    /// Field names kept outside the payloads of a message.
+    outside_payloads: HashSet<String>,
+}
+
+impl AllowedFields {
+    fn keeps(&self, field: &str, at: Position) -> bool {
+        self.anywhere.contains(field)
+            || (at != Position::Payload && self.outside_payloads.contains(field))
+    }
+}
+
+/// Where a value sits in a JSON-RPC message.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// The message itself, or a message of a batch.
+    Message,
+    /// The `params`, `result` or `error` member of a message.
+    Member(&'static str),
+    /// Within a payload of a message, see [`PAYLOAD_FIELDS`].
+    Payload,
+    /// Anywhere else.
+    Other,
+}
+
+impl Position {
+    fn field(self, field: &str) -> Self {
+        match self {
+            Position::Message => ["params", "result", "error"]
+                .into_iter()
+                .find(|member| *member == field)
+                .map_or(Position::Other, Position::Member),
+            Position::Member(member) if PAYLOAD_FIELDS.contains(&(member, field)) => {
+                Position::Payload
+            }
+            Position::Member(_) => Position::Other,
+            position => position,
+        }
+    }
+
+    fn item(self) -> Self {
+        match self {
+            Position::Member(_) => Position::Other,
+            position => position,
+        }
+    }
+}
+
+impl Default for LogRedaction {
+    /// Keeps the fields needed to follow a conversation, see the
+    /// [module documentation](self).
+    fn default() -> Self {
+        Self {
+            allowed: Some(Arc::new(AllowedFields {
+                anywhere: HashSet::new(),
+                outside_payloads: DEFAULT_ALLOWED_FIELDS
+                    .iter()
+                    .map(|field| field.to_string())
+                    .collect(),
+            })),
+        }
+    }
+}
+
+impl fmt::Debug for LogRedaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.allowed {
+            Some(ref allowed) => {
+                let mut fields: Vec<_> =
+                    allowed.anywhere.union(&allowed.outside_payloads).collect();
+                fields.sort();
+                f.debug_struct("LogRedaction")
+                    .field("allowed", &fields)
+                    .finish()
+            }
+            None => f.write_str("LogRedaction(disabled)"),
+        }
+    }
+}
+
+impl LogRedaction {
+    /// Keeps the values of exactly the given fields, anywhere in a message.
+    pub fn allowing<I, F>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = F>,
+        F: Into<String>,
+    {
+        Self {
+            allowed: Some(Arc::new(AllowedFields {
+                anywhere: fields.into_iter().map(Into::into).collect(),
+                outside_payloads: HashSet::new(),
+            })),
+        }
+    }
+
+    /// Logs messages in full. Only use this where logs are as protected as the
+    /// data they carry.
+    pub fn disabled() -> Self {
+        Self { allowed: None }
+    }
+
+    /// Adds a field to the allow-list, kept anywhere in a message, tool
+    /// arguments and results included.
+    ///
+    /// Has no effect when redaction is disabled.
+    pub fn allow(mut self, field: impl Into<String>) -> Self {
+        if let Some(ref mut allowed) = self.allowed {
+            Arc::make_mut(allowed).anywhere.insert(field.into());
+        }
+        self
+    }
+
+    /// Returns `value` with the scalar values of fields missing from the
+    /// allow-list replaced by [`REDACTED`].
+    ///
+    /// Objects and arrays are always traversed, so their shape stays visible;
+    /// array items are judged by the field holding the array. `value` is read
+    /// as a JSON-RPC message, or a batch of them, to tell its payloads apart.
+    pub fn redact(&self, value: &Value) -> Value {
+        match self.allowed {
+            Some(ref allowed) => redact_value(allowed, Position::Message, None, value),
+            None => value.clone(),
+        }
+    }
+
+    /// Wraps a message for logging, e.g. `tracing::debug!(message = %redaction.display(&message))`.
+    ///
+    /// The message is only serialized if the log event is enabled.
+    pub fn display<'a, T: Serialize>(&'a self, message: &'a T) -> impl fmt::Display + 'a {
+        Redacted {
+            redaction: self,
+            message,
+        }
+    }
+}
+
+fn redact_value(
+    allowed: &AllowedFields,
+    at: Position,
+    field: Option<&str>,
+    value: &Value,
+) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| {
+                    let redacted = redact_value(allowed, at.field(key), Some(key), value);
+                    (key.clone(), redacted)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| redact_value(allowed, at.item(), field, item))
+                .collect(),
+        ),
+        Value::Null => Value::Null,
+        _ if field.is_some_and(|field| allowed.keeps(field, at)) => value.clone(),
+        _ => Value::String(REDACTED.to_string()),
+    }
+}
+
+struct Redacted<'a, T> {
+    redaction: &'a LogRedaction,
+    message: &'a T,
+}
+
+impl<T: Serialize> fmt::Display for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_value(self.message) {
+            Ok(value) => write!(f, "{}", self.redaction.redact(&value)),
+            Err(_) => f.write_str(REDACTED),
+        }
+    }
+}
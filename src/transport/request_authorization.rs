@@ -0,0 +1,85 @@
+//! Programmatic per-request authorization.
+//!
+//! For policies a static [`ToolScopes`](crate::transport::ToolScopes) map
+//! cannot express (ownership checks, quotas, lookups in a policy service),
+//! the [`authorize`](crate::transport::StreamableHttpServiceBuilder::authorize)
+//! hook is called for every JSON-RPC request with an [`AuthorizationRequest`]:
+//! the HTTP request, whose extensions carry the identity set by the
+//! authentication middleware, the JSON-RPC method, and the tool, prompt, or
+//! resource the request targets. The hook is async; returning an error denies
+//! the request, which is answered with that JSON-RPC error by the transport
+//! and never reaches the handler.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rmcp::model::ErrorData;
+//!
+//! StreamableHttpService::builder()
+//!     .authorize_fn(|auth_req| async move {
+//!         let user = auth_req.http_request.extensions().get::<User>().cloned();
+//!         match (auth_req.target.as_deref(), user) {
+//!             (Some(tool), Some(user)) if policy::may_call(&user, tool).await => Ok(()),
+//!             _ => Err(ErrorData::invalid_request("Not authorized", None)),
+//!         }
+//!     })
+//!     .build()
+//! ```
+
+use actix_web::HttpRequest;
+use rmcp::model::{ClientJsonRpcMessage, ClientRequest, JsonRpcRequest, ServerJsonRpcMessage};
+
+use super::streamable_http_server::AuthorizeHook;
+
+/// A JSON-RPC request submitted to the `authorize` hook.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct AuthorizationRequest {
+    /// The HTTP request carrying the message, with the extensions set by the
+    /// authentication middleware.
+    pub http_request: HttpRequest,
+    /// The JSON-RPC method, e.g. `tools/call`.
+    pub method: String,
+    /// The tool name (`tools/call`), prompt name (`prompts/get`), or resource
+    /// URI (`resources/read`, `resources/subscribe`, `resources/unsubscribe`)
+    /// the request targets; `None` for other methods.
+    pub target: Option<String>,
+}
+
+impl AuthorizationRequest {
+    fn new(http_request: &HttpRequest, request: &ClientRequest) -> Self {
+        let target = match request {
+            ClientRequest::CallToolRequest(r) => Some(r.params.name.to_string()),
+            ClientRequest::GetPromptRequest(r) => Some(r.params.name.clone()),
+            ClientRequest::ReadResourceRequest(r) => Some(r.params.uri.clone()),
+            ClientRequest::SubscribeRequest(r) => Some(r.params.uri.clone()),
+            ClientRequest::UnsubscribeRequest(r) => Some(r.params.uri.clone()),
+            _ => None,
+        };
+        Self {
+            http_request: http_request.clone(),
+            method: request.method().to_string(),
+            target,
+        }
+    }
+}
+
+/// Runs the hook on a client message, returning the error response to send
+/// back if it is a denied request.
+pub(crate) async fn reject(
+    hook: Option<&AuthorizeHook>,
+    req: &HttpRequest,
+    message: &ClientJsonRpcMessage,
+) -> Option<ServerJsonRpcMessage> {
+    let hook = hook?;
+    let ClientJsonRpcMessage::Request(JsonRpcRequest { id, request, .. }) = message else {
+        return None;
+    };
+    let error = hook(AuthorizationRequest::new(req, request)).await.err()?;
+    tracing::info!(
+        method = request.method(),
+        reason = %error.message,
+        "Request denied by authorization hook"
+    );
+    Some(ServerJsonRpcMessage::error(error, Some(id.clone())))
+}
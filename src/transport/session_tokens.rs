@@ -0,0 +1,160 @@
+//! Stateless signed session tokens.
+//!
+//! In stateless mode no session manager keeps track of clients, so requests
+//! cannot be tied together. With [`SessionTokens`] configured, the
+//! `Mcp-Session-Id` returned by `initialize` is a self-contained token signed
+//! with HMAC-SHA256: it carries a random session id, its creation and expiry
+//! times, and the [`AuthenticatedIdentity`] of the client that created it.
+//! Later requests must present it; it is validated by signature and expiry
+//! alone, so any replica sharing the secret accepts it, without a session
+//! store.
+//!
+//! Requests without a token get `400 Bad Request`; tokens that are forged,
+//! expired, or presented by a different identity get `404 Not Found`, which
+//! makes clients start a new session. The decoded [`SessionToken`] is
+//! inserted into the request extensions, where MCP handlers can key
+//! per-client state on [`SessionToken::id`].
+//!
+//! Tokens cannot be revoked before they expire; keep the TTL short where
+//! that matters.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//!
+//! use rmcp_actix_web::transport::SessionTokens;
+//!
+//! let tokens = SessionTokens::new(b"shared secret of every replica").ttl(Duration::from_secs(3600));
+//! let (token, session) = tokens.issue(None);
+//! assert_eq!(tokens.verify(&token), Some(session));
+//! ```
+
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ring::hmac;
+use rmcp::transport::common::server_side_http::session_id;
+use serde::{Deserialize, Serialize};
+
+use super::AuthenticatedIdentity;
+
+/// Default lifetime of a session token.
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest lifetime of a session token, so that expiry times stay representable.
+const MAX_TTL: Duration = Duration::from_secs(100 * 365 * 24 * 60 * 60);
+
+/// Issues and validates signed session tokens.
+#[derive(Clone)]
+pub struct SessionTokens {
+    key: hmac::Key,
+    ttl: Duration,
+}
+
+impl fmt::Debug for SessionTokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionTokens")
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A validated session token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SessionToken {
+    /// Random id of the session, stable for the token's lifetime.
+    pub id: String,
+    /// When the session was created.
+    pub issued_at: SystemTime,
+    /// When the token stops being accepted.
+    pub expires_at: SystemTime,
+    /// Identity that created the session, if the request carried one.
+    pub identity: Option<AuthenticatedIdentity>,
+}
+
+/// Signed token payload.
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    sid: String,
+    iat: u64,
+    exp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sub: Option<String>,
+}
+
+impl SessionTokens {
+    /// Signs tokens with `secret`, valid for 24 hours.
+    ///
+    /// Every replica accepting the tokens must use the same secret, which
+    /// should be at least 32 random bytes.
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret.as_ref()),
+            ttl: DEFAULT_TTL,
+        }
+    }
+
+    /// Sets how long issued tokens are accepted.
+    ///
+    /// Lifetimes above 100 years are capped to 100 years.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl.min(MAX_TTL);
+        self
+    }
+
+    /// Issues a token for a new session created by `identity`.
+    ///
+    /// Returns the encoded token and its content.
+    pub fn issue(&self, identity: Option<AuthenticatedIdentity>) -> (String, SessionToken) {
+        // Whole seconds, as encoded in the token
+        let issued_at = UNIX_EPOCH + Duration::from_secs(unix_seconds(SystemTime::now()));
+        let session = SessionToken {
+            id: session_id().to_string(),
+            issued_at,
+            expires_at: issued_at + self.ttl,
+            identity,
+        };
+        let claims = Claims {
+            sid: session.id.clone(),
+            iat: unix_seconds(session.issued_at),
+            exp: unix_seconds(session.expires_at),
+            sub: session.identity.as_ref().map(|identity| identity.0.clone()),
+        };
+        let payload =
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).expect("claims serialize"));
+        let signature = hmac::sign(&self.key, payload.as_bytes());
+        let token = format!("{payload}.{}", URL_SAFE_NO_PAD.encode(signature.as_ref()));
+        (token, session)
+    }
+
+    /// Validates a token, returning its content if the signature is genuine and
+    /// the token has not expired.
+    pub fn verify(&self, token: &str) -> Option<SessionToken> {
+        let (payload, signature) = token.split_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        hmac::verify(&self.key, payload.as_bytes(), &signature).ok()?;
+
+        let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+        let expires_at = UNIX_EPOCH.checked_add(Duration::from_secs(claims.exp))?;
+        if SystemTime::now() >= expires_at {
+            return None;
+        }
+        Some(SessionToken {
+            id: claims.sid,
+            issued_at: UNIX_EPOCH.checked_add(Duration::from_secs(claims.iat))?,
+            expires_at,
+            identity: claims.sub.map(AuthenticatedIdentity),
+        })
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,168 @@
+//! Session manager partitioned into independently locked shards.
+//!
+//! rmcp's `LocalSessionManager` keeps every session in one map behind a single
+//! `RwLock`, which every message of every session takes. With many concurrent
+//! sessions, that lock becomes a point of contention between workers. A
+//! [`ShardedSessionManager`] spreads the sessions over several inner managers,
+//! so sessions of different shards never contend for the same lock.
+//!
+//! New sessions are assigned to shards in turn, and the shard is encoded in the
+//! session id handed to the client (`s<shard>.<inner id>`), so routing a message
+//! to its shard needs no shared state. Ids without that prefix, e.g. sessions
+//! restored from an external store, are routed by a hash of the id.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! use rmcp_actix_web::transport::{ShardedSessionManager, StreamableHttpService};
+//!
+//! // One shard per available CPU
+//! let session_manager = ShardedSessionManager::<LocalSessionManager>::default();
+//!
+//! let service = StreamableHttpService::builder()
+//!     .service_factory(Arc::new(|| Ok(MyService::new())))
+//!     .session_manager(Arc::new(session_manager))
+//!     .build();
+//! ```
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use futures::Stream;
+use rmcp::{
+    model::{ClientJsonRpcMessage, ServerJsonRpcMessage},
+    transport::streamable_http_server::session::{
+        RestoreOutcome, ServerSseMessage, SessionId, SessionManager,
+    },
+};
+
+/// Message stream of an inner manager, detached from the id it was opened with.
+type ShardStream = Pin<Box<dyn Stream<Item = ServerSseMessage> + Send + Sync>>;
+
+/// Session manager spreading sessions over several inner managers.
+#[derive(Debug)]
+pub struct ShardedSessionManager<M> {
+    shards: Vec<M>,
+    next_shard: AtomicUsize,
+}
+
+impl<M> ShardedSessionManager<M> {
+    /// Creates a manager with `count` shards, each created by `make_shard`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is zero.
+    pub fn new(count: usize, make_shard: impl FnMut() -> M) -> Self {
+        assert!(
+            count > 0,
+            "a sharded session manager needs at least one shard"
+        );
+        Self {
+            shards: std::iter::repeat_with(make_shard).take(count).collect(),
+            next_shard: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the shard of the session `id` and the id of the session within it.
+    fn route(&self, id: &SessionId) -> (&M, SessionId) {
+        if let Some((shard, inner)) = id.strip_prefix('s').and_then(|rest| rest.split_once('.'))
+            && let Ok(shard) = shard.parse::<usize>()
+            && let Some(manager) = self.shards.get(shard)
+        {
+            return (manager, SessionId::from(inner));
+        }
+        let mut hasher = DefaultHasher::new();
+        id.hash(&mut hasher);
+        let shard = (hasher.finish() % self.shards.len() as u64) as usize;
+        (&self.shards[shard], id.clone())
+    }
+}
+
+impl<M: Default> Default for ShardedSessionManager<M> {
+    /// Creates a manager with one shard per available CPU.
+    fn default() -> Self {
+        let count = std::thread::available_parallelism().map_or(1, usize::from);
+        Self::new(count, M::default)
+    }
+}
+
+impl<M: SessionManager> SessionManager for ShardedSessionManager<M> {
+    type Error = M::Error;
+    type Transport = M::Transport;
+
+    async fn create_session(&self) -> Result<(SessionId, Self::Transport), Self::Error> {
+        let shard = self.next_shard.fetch_add(1, Ordering::Relaxed) % self.shards.len();
+        let (id, transport) = self.shards[shard].create_session().await?;
+        Ok((SessionId::from(format!("s{shard}.{id}")), transport))
+    }
+
+    async fn initialize_session(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<ServerJsonRpcMessage, Self::Error> {
+        let (shard, id) = self.route(id);
+        shard.initialize_session(&id, message).await
+    }
+
+    async fn has_session(&self, id: &SessionId) -> Result<bool, Self::Error> {
+        let (shard, id) = self.route(id);
+        shard.has_session(&id).await
+    }
+
+    async fn close_session(&self, id: &SessionId) -> Result<(), Self::Error> {
+        let (shard, id) = self.route(id);
+        shard.close_session(&id).await
+    }
+
+    async fn create_stream(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        let (shard, id) = self.route(id);
+        Ok(Box::pin(shard.create_stream(&id, message).await?) as ShardStream)
+    }
+
+    async fn accept_message(
+        &self,
+        id: &SessionId,
+        message: ClientJsonRpcMessage,
+    ) -> Result<(), Self::Error> {
+        let (shard, id) = self.route(id);
+        shard.accept_message(&id, message).await
+    }
+
+    async fn create_standalone_stream(
+        &self,
+        id: &SessionId,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        let (shard, id) = self.route(id);
+        Ok(Box::pin(shard.create_standalone_stream(&id).await?) as ShardStream)
+    }
+
+    async fn resume(
+        &self,
+        id: &SessionId,
+        last_event_id: String,
+    ) -> Result<impl Stream<Item = ServerSseMessage> + Send + Sync + 'static, Self::Error> {
+        let (shard, id) = self.route(id);
+        Ok(Box::pin(shard.resume(&id, last_event_id).await?) as ShardStream)
+    }
+
+    async fn restore_session(
+        &self,
+        id: SessionId,
+    ) -> Result<RestoreOutcome<Self::Transport>, Self::Error> {
+        let (shard, id) = self.route(&id);
+        shard.restore_session(id).await
+    }
+}
@@ -10,6 +10,30 @@
 //! - **POST**: Send JSON-RPC requests (returns SSE stream with responses)
 //! - **DELETE**: Close session and cleanup resources
 //!
+//! Optionally, the legacy HTTP+SSE endpoints (`/sse` and `/message`) can be
+//! served alongside, sharing the same handlers and session space, so existing
+//! SSE clients keep working during a migration. See
+//! [`legacy_sse`](StreamableHttpServiceBuilder::legacy_sse).
+//!
+//! ## Route Names
+//!
+//! The routes are registered under stable resource names, so applications can
+//! build URLs to them with [`HttpRequest::url_for`], for instance in discovery
+//! documents or redirects. With the default
+//! [`route_name`](StreamableHttpServiceBuilder::route_name) of `mcp`:
+//!
+//! | Name          | Route                                       |
+//! |---------------|---------------------------------------------|
+//! | `mcp`         | The MCP endpoint                            |
+//! | `mcp_blob`    | Blob downloads, with the `blob_id` argument |
+//! | `mcp_upload`  | Uploads                                     |
+//! | `mcp_sse`     | Legacy SSE endpoint                         |
+//! | `mcp_message` | Legacy message endpoint                     |
+//!
+//! ```rust,ignore
+//! let endpoint = req.url_for_static("mcp")?;
+//! ```
+//!
 //! ## Features
 //!
 //! - Full bidirectional communication
@@ -24,6 +48,62 @@
 //! - `LocalSessionManager`: In-memory session storage (default)
 //! - Custom implementations via the `SessionManager` trait
 //!
+//! ## Server-Initiated Messages
+//!
+//! Handlers reach the client through the `Peer` of their `RequestContext`. A
+//! message answering a POSTed request (its response, and progress notifications
+//! carrying the request's progress token) travels on that request's SSE stream.
+//! Everything else, such as resource `updated` and `list_changed`
+//! notifications, is delivered on the standalone stream the client opens with
+//! `GET`. Messages sent before that stream is open are kept by the session
+//! manager and delivered once it opens. Clone the peer to send messages after
+//! the request has completed:
+//!
+//! ```rust,ignore
+//! use rmcp::model::ResourceUpdatedNotificationParam;
+//!
+//! async fn subscribe(&self, context: RequestContext<RoleServer>) -> Result<CallToolResult, McpError> {
+//!     let peer = context.peer.clone();
+//!     tokio::spawn(async move {
+//!         while let Some(uri) = changes.recv().await {
+//!             let _ = peer
+//!                 .notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+//!                 .await;
+//!         }
+//!     });
+//!     Ok(CallToolResult::success(vec![]))
+//! }
+//! ```
+//!
+//! Server-initiated requests such as `sampling/createMessage` and
+//! `elicitation/create` (the latter behind rmcp's `elicitation` feature) are
+//! also delivered on the standalone stream. The client answers them with a
+//! POST carrying the JSON-RPC response, which is accepted with `202 Accepted`
+//! and resolves the handler's pending call. A handler can therefore await the
+//! client in the middle of a tool call:
+//!
+//! ```rust,ignore
+//! use rmcp::model::{CreateMessageRequestParams, SamplingMessage};
+//!
+//! async fn ask(&self, context: RequestContext<RoleServer>) -> Result<CallToolResult, McpError> {
+//!     let answer = context
+//!         .peer
+//!         .create_message(CreateMessageRequestParams::new(
+//!             vec![SamplingMessage::user_text("Summarize the report")],
+//!             500,
+//!         ))
+//!         .await
+//!         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+//!     // ...
+//! }
+//! ```
+//!
+//! Clients that do not open the standalone stream never see these requests, so
+//! such calls should be bounded, for example with `tokio::time::timeout`.
+//!
+//! Stateless mode has no standalone stream: only messages related to the
+//! request reach the client.
+//!
 //! ## Example
 //!
 //! ```rust,no_run
@@ -59,19 +139,32 @@
 //! }
 //! ```
 
-use std::{sync::Arc, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    ops::RangeInclusive,
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
 use actix_web::{
-    HttpRequest, HttpResponse, Result, Scope,
+    HttpMessage, HttpRequest, HttpResponse, Result, Scope,
+    dev::Service as _,
     error::InternalError,
     http::{
-        StatusCode,
+        Method, StatusCode,
         header::{self, CACHE_CONTROL},
     },
-    middleware,
     web::{self, Bytes, Data},
 };
-use futures::{Stream, StreamExt};
+use futures::{
+    Stream, StreamExt,
+    future::{FutureExt, LocalBoxFuture},
+    stream::BoxStream,
+};
 use tokio_stream::wrappers::ReceiverStream;
 
 /// Type alias for the on_request hook function.
@@ -80,29 +173,204 @@ use tokio_stream::wrappers::ReceiverStream;
 /// typed extensions from the actix-web `HttpRequest` to rmcp's `RequestContext::extensions`.
 pub type OnRequestHook = dyn Fn(&HttpRequest, &mut rmcp::model::Extensions) + Send + Sync + 'static;
 
+/// Type alias for the request_guard hook function.
+///
+/// This hook is called for each incoming request before any session or service work
+/// happens. Returning `Err(response)` rejects the request with that HTTP response.
+pub type RequestGuardHook =
+    dyn Fn(&HttpRequest) -> Result<(), HttpResponse> + Send + Sync + 'static;
+
+/// Type alias for the transform_inbound hook function.
+///
+/// This hook is called with every message a client sends, right after it is parsed and
+/// before it is dispatched to the session or handler.
+pub type InboundMessageHook =
+    dyn Fn(&HttpRequest, &mut ClientJsonRpcMessage) + Send + Sync + 'static;
+
+/// Type alias for the transform_outbound hook function.
+///
+/// This hook is called with every message the server sends, right before it is
+/// serialized onto the wire.
+pub type OutboundMessageHook = dyn Fn(&mut ServerJsonRpcMessage) + Send + Sync + 'static;
+
+/// Type alias for the session_extensions hook function.
+///
+/// This hook is called once per session with the HTTP request carrying the `initialize`
+/// request. The extensions it inserts are stored with the session and injected into
+/// every subsequent request of that session.
+pub type SessionExtensionsHook =
+    dyn Fn(&HttpRequest, &mut rmcp::model::Extensions) + Send + Sync + 'static;
+
+/// Type alias for the on_session_created hook function.
+///
+/// This hook is called once per new session with the HTTP request carrying the
+/// `initialize` request, the new session id, and the client's initialize parameters
+/// (client info, capabilities, requested protocol version).
+pub type OnSessionCreatedHook =
+    dyn Fn(&HttpRequest, &SessionId, &InitializeRequestParams) + Send + Sync + 'static;
+
+/// Type alias for the on_session_closed hook function.
+///
+/// This hook is called exactly once per session when it is closed, with the reason
+/// it was closed.
+pub type OnSessionClosedHook = dyn Fn(&SessionId, SessionCloseReason) + Send + Sync + 'static;
+
+/// Type alias for the on_internal_error hook function.
+///
+/// This hook is called whenever an MCP handler panics or returns a JSON-RPC internal
+/// error, with the failure and the session, request and connection it occurred in.
+pub type OnInternalErrorHook = dyn Fn(&ErrorReport) + Send + Sync + 'static;
+
+/// Type alias for the event_id_generator hook function.
+///
+/// This hook is called for every SSE event of a session's streams that carries an
+/// event id, with the session id and the id assigned by the session manager, and
+/// returns the id sent to the client instead.
+pub type EventIdHook = dyn Fn(&SessionId, &str) -> String + Send + Sync + 'static;
+
+/// Type alias for the tenant_service_factory function.
+///
+/// This factory is called with the tenant of the request whenever a service instance
+/// is created for a request carrying a [`TenantId`].
+pub type TenantServiceFactory<S> =
+    dyn Fn(&TenantId) -> Result<S, std::io::Error> + Send + Sync + 'static;
+
+/// Type alias for the authorize hook function.
+///
+/// This hook is called for every JSON-RPC request a client sends, after the message
+/// filter and tool scopes. Resolving to `Err(error)` denies the request with that
+/// JSON-RPC error.
+pub type AuthorizeHook = dyn Fn(AuthorizationRequest) -> LocalBoxFuture<'static, Result<(), ErrorData>>
+    + Send
+    + Sync
+    + 'static;
+
 use rmcp::{
     RoleServer,
-    model::{ClientJsonRpcMessage, ClientRequest},
-    serve_server,
-    service::serve_directly,
+    model::{
+        ClientCapabilities, ClientJsonRpcMessage, ClientRequest, ErrorCode, ErrorData,
+        InitializeRequestParams, LoggingLevel, LoggingMessageNotificationParam, ProtocolVersion,
+        RequestId, ServerInfo, ServerJsonRpcMessage, ServerNotification, ServerResult,
+    },
+    service::{serve_directly, serve_server_with_ct},
     transport::{
         OneshotTransport, TransportAdapterIdentity,
         common::http_header::{HEADER_LAST_EVENT_ID, HEADER_SESSION_ID},
-        streamable_http_server::session::SessionManager,
+        streamable_http_server::session::{ServerSseMessage, SessionId, SessionManager},
     },
 };
 
 use rmcp::model::GetExtensions;
 
+use super::{
+    ClientConnectionInfo, HeaderSnapshot, LogRedaction, RequestCancellation, RequestDeadline,
+    ResponseHeaders,
+    api_key::ApiKeyAuth,
+    blobs::{BlobLinks, BlobStore, Uploads},
+    cacheable_tools::CacheableTools,
+    config::TransportConfig,
+    discovery::{self, DirectoryEntry, DiscoveryAuth},
+    dyn_handler::DynServerHandler,
+    event_store::{EventStore, STANDALONE_STREAM_ID, StoredEvent},
+    extension_propagation::ExtensionAllowList,
+    ip_filter::IpFilter,
+    message_filter::MessageFilter,
+    problem::{self, Problem},
+    request_authorization::{self, AuthorizationRequest},
+    tool_scopes::ToolScopes,
+    trusted_proxies::TrustedProxies,
+};
+
 #[cfg(feature = "authorization-token-passthrough")]
 use super::AuthorizationHeader;
 
+mod body_timeout;
+mod cors;
+mod disconnect;
+mod error_reports;
+pub use error_reports::{ErrorReport, ErrorReportKind};
+mod event_ids;
+mod handler_runtime;
+mod hybrid;
+mod idempotency;
+mod keep_alive;
+mod lazy_service;
+mod path_normalization;
+mod query_token;
+mod reinitialize;
+mod response_cache;
+mod runtime_stats;
+pub use keep_alive::KeepAliveFrame;
+pub use path_normalization::PathNormalization;
+pub use reinitialize::ReinitializePolicy;
+pub use runtime_stats::{BuildInfo, RuntimeStats};
+mod legacy_sse;
+pub use legacy_sse::{
+    LEGACY_SESSION_CLOSED_CODE, LEGACY_SESSION_NOT_FOUND_CODE, SseOverflowPolicy,
+};
+mod liveness;
+mod payload_encoding;
+mod payload_stats;
+pub use liveness::SessionLiveness;
+use payload_encoding::PayloadEncoding;
+pub use payload_stats::{
+    Histogram, HistogramBucket, PAYLOAD_SIZE_BUCKETS, PayloadStats, SERIALIZATION_TIME_BUCKETS,
+    SourcePayloadStats,
+};
+mod protocol_version;
+mod request_deadline;
+mod request_timeout;
+mod session_push;
+mod session_rate_limit;
+mod tenant_quotas;
+pub use tenant_quotas::{QUOTA_EXCEEDED_CODE, TenantQuota, TenantQuotas, TenantUsage};
+mod tool_toggles;
+pub use session_rate_limit::SessionCreationLimit;
+mod session_backpressure;
+mod session_cancellation;
+mod session_cookie;
+mod session_log;
+mod sessions;
+mod shutdown;
+mod slow_consumer;
+mod warm_pool;
+pub use slow_consumer::SlowConsumerPolicy;
+mod sse_connections;
+mod stream_end;
+pub use stream_end::StreamEndReason;
+mod transport_events;
+pub use transport_events::TransportEvent;
+
+pub use sessions::{AuthenticatedIdentity, SessionCapabilities, SessionCloseReason, TenantId};
+
 // Local constants
 const HEADER_X_ACCEL_BUFFERING: &str = "X-Accel-Buffering";
+/// Header through which a client requests its own keep-alive interval, in seconds.
+const HEADER_SSE_KEEP_ALIVE: &str = "X-SSE-Keep-Alive";
+/// Query parameter alternative to [`HEADER_SSE_KEEP_ALIVE`], for clients such as
+/// `EventSource` that cannot set headers.
+const KEEP_ALIVE_QUERY_PARAM: &str = "keepAlive";
+const SESSION_ID_QUERY_PARAM: &str = "session_id";
 const EVENT_STREAM_MIME_TYPE: &str = "text/event-stream";
 const JSON_MIME_TYPE: &str = "application/json";
-const MISSING_SESSION_ID_BODY: &str = "Bad Request: Mcp-Session-Id header is required";
-const SESSION_NOT_FOUND_BODY: &str = "Session not found";
+const MISSING_SESSION_ID_MESSAGE: &str = "Mcp-Session-Id header is required";
+const SESSION_NOT_FOUND_MESSAGE: &str = "Session not found";
+/// Path of the blob download route, relative to the service scope.
+const BLOB_PATH: &str = "/blobs/{blob_id}";
+const BLOB_NOT_FOUND_MESSAGE: &str = "Blob not found";
+/// Path of the upload route, relative to the service scope.
+const UPLOAD_PATH: &str = "/uploads";
+/// Default name of the MCP endpoint route, prefixing the names of the other routes.
+const DEFAULT_ROUTE_NAME: &str = "mcp";
+/// Default limit on the size of an upload, in bytes.
+const DEFAULT_MAX_UPLOAD_SIZE: usize = 64 * 1024 * 1024;
+/// Function creating the MCP service instances.
+type ServiceFactory<S> = Arc<dyn Fn() -> Result<S, std::io::Error> + Send + Sync>;
+const SERVICE_UNAVAILABLE_MESSAGE: &str = "Service temporarily unavailable";
+/// Delay suggested to clients, in seconds, before retrying after the service
+/// factory failed.
+const SERVICE_UNAVAILABLE_RETRY_AFTER_SECS: u32 = 5;
+const SESSION_RATE_LIMITED_MESSAGE: &str = "Too many new sessions, retry later";
 
 /// Configuration for the streamable HTTP server transport.
 ///
@@ -132,7 +400,8 @@ impl Default for StreamableHttpServerConfig {
 ///
 /// # Type Parameters
 ///
-/// * `S` - The MCP service type that handles protocol messages
+/// * `S` - The MCP service type that handles protocol messages. Every session gets
+///   an instance of its own from the service factory, so it need not be `Clone`
 /// * `M` - The session manager type (defaults to `LocalSessionManager`)
 ///
 /// # Architecture
@@ -187,6 +456,28 @@ pub struct StreamableHttpService<
     /// The service factory function that creates new MCP service instances
     service_factory: Arc<dyn Fn() -> Result<S, std::io::Error> + Send + Sync>,
 
+    /// Optional factory creating the service instances of a tenant.
+    ///
+    /// When set, requests carrying a [`TenantId`] extension (inserted by the
+    /// middleware resolving tenants) get a service instance from this factory instead
+    /// of `service_factory`, so each tenant's handler is constructed with its own
+    /// credentials, database and tool configuration. Requests without a tenant still
+    /// use `service_factory`. A session is bound to the tenant of its `initialize`
+    /// request: later requests of another tenant, or of none, are answered as if the
+    /// session did not exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .service_factory(Arc::new(|| Ok(MyService::anonymous())))
+    ///     .tenant_service_factory(Arc::new(|tenant: &TenantId| {
+    ///         Ok(MyService::new(tenants.database_url(&tenant.0)?))
+    ///     }))
+    ///     .build()
+    /// ```
+    tenant_service_factory: Option<Arc<TenantServiceFactory<S>>>,
+
     /// The session manager for tracking client connections
     session_manager: Arc<M>,
 
@@ -194,245 +485,3934 @@ pub struct StreamableHttpService<
     #[builder(default = true)]
     stateful_mode: bool,
 
-    /// Optional keep-alive interval for SSE connections
-    sse_keep_alive: Option<Duration>,
+    /// Whether requests without `Mcp-Session-Id` are served statelessly.
+    ///
+    /// Only applies in stateful mode. `initialize` requests still create sessions
+    /// and requests carrying a session id are handled in their session, but other
+    /// requests without one are answered like in stateless mode instead of being
+    /// refused with `400 Bad Request`. They share a single handler, created by
+    /// `service_factory` on the first of them, unless a `tenant_service_factory`
+    /// applies. One endpoint then serves both one-shot clients and session-based
+    /// clients. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .stateful_mode(true)
+    ///     .hybrid_mode(true)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    hybrid_mode: bool,
 
-    /// Optional hook called for each request to propagate extensions from HttpRequest to RequestContext.
+    /// Whether clients may terminate their sessions with `DELETE`.
     ///
-    /// This allows middleware-populated data (e.g., JWT claims) to be accessed in MCP handlers.
+    /// Deployments managing sessions centrally can disable it: `DELETE` on the MCP
+    /// endpoint (and on the legacy message endpoint) is then answered with `405
+    /// Method Not Allowed` and an `Allow` header without `DELETE`, and sessions only end
+    /// through the server, for instance on idle timeout or
+    /// [`shutdown`](StreamableHttpService::shutdown). Enabled by default.
+    #[builder(default = true)]
+    session_delete: bool,
+
+    /// What to do with an `initialize` request sent with the id of an existing session.
+    ///
+    /// By default such requests are refused with `400 Bad Request` and a JSON-RPC
+    /// error, leaving the session untouched. [`ReinitializePolicy::Recreate`] closes
+    /// the session and creates a new one, answered with a new `Mcp-Session-Id`;
+    /// [`ReinitializePolicy::Replay`] answers with the result of the session's
+    /// original `initialize`. Either way, the request never reaches the session's
+    /// service, which has already been initialized.
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// use std::sync::Arc;
-    /// use actix_web::HttpMessage;
+    /// StreamableHttpService::builder()
+    ///     .reinitialize_policy(ReinitializePolicy::Recreate)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    reinitialize_policy: ReinitializePolicy,
+
+    /// Name of a cookie also carrying the session id.
     ///
+    /// For browser clients whose `fetch` or `EventSource` wrappers make custom headers
+    /// awkward. When set, the response creating a session also sets this cookie,
+    /// `HttpOnly` and `SameSite=Strict`, scoped to the service path and `Secure` over
+    /// HTTPS, and requests without `Mcp-Session-Id` are attributed to the session of
+    /// the cookie. The header takes precedence when both are sent. An `initialize`
+    /// request ignores the cookie, so a client whose session expired can start a new
+    /// one, and a successful `DELETE` clears it. With
+    /// [`cors`](StreamableHttpServiceBuilder::cors), CORS responses allow credentials,
+    /// so that pages on other origins of the same site send the cookie. Not set by
+    /// default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
     /// StreamableHttpService::builder()
-    ///     .on_request(Arc::new(|http_req, ext| {
-    ///         if let Some(claims) = http_req.extensions().get::<MyClaims>() {
-    ///             ext.insert(claims.clone());
-    ///         }
-    ///     }))
+    ///     .session_cookie("mcp_session")
     ///     .build()
     /// ```
-    on_request: Option<Arc<OnRequestHook>>,
-}
+    #[builder(into)]
+    session_cookie: Option<String>,
 
-impl<S, M> Clone for StreamableHttpService<S, M> {
-    fn clone(&self) -> Self {
-        Self {
-            service_factory: self.service_factory.clone(),
-            session_manager: self.session_manager.clone(),
-            stateful_mode: self.stateful_mode,
-            sse_keep_alive: self.sse_keep_alive,
-            on_request: self.on_request.clone(),
-        }
-    }
-}
+    /// Whether `GET` streams accept the session id in a `session_id` query parameter.
+    ///
+    /// Browsers' `EventSource` cannot set custom headers, so with this enabled a
+    /// browser client can open the standalone stream directly, as in
+    /// `GET /mcp?session_id=...`. `Mcp-Session-Id` takes precedence when both are
+    /// sent. Session ids in URLs can end up in access logs and browser history, so
+    /// this is disabled by default.
+    #[builder(default)]
+    session_id_query: bool,
 
-// Convenience methods for StreamableHttpServiceBuilder
-impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
-where
-    State::OnRequest: streamable_http_service_builder::IsUnset,
-{
-    /// Sets the on_request hook using a closure.
+    /// Query parameter `GET` requests may carry an access token in.
     ///
-    /// This is a convenience method that automatically wraps the closure in an `Arc`,
-    /// making it easier to use without manual Arc wrapping.
+    /// Browsers' `EventSource` cannot send an `Authorization` header, so with this
+    /// set a browser client can authenticate its streams with a URL such as
+    /// `GET /mcp?access_token=...`. The token is moved into an `Authorization: Bearer`
+    /// header before the request guard, the API key check and the handlers see the
+    /// request, and removed from its URI, so it is not logged by the transport.
+    /// An `Authorization` header sent with the request takes precedence. Middleware
+    /// wrapping the scope, such as actix-web's `Logger`, still sees the original URI:
+    /// log the path (`%U`) rather than the request line (`%r`). URLs also end up in
+    /// browser history and proxy logs, so tokens passed this way should be
+    /// short-lived. Not set by default.
     ///
     /// # Example
     ///
     /// ```rust,ignore
-    /// use actix_web::HttpMessage;
-    ///
     /// StreamableHttpService::builder()
-    ///     .on_request_fn(|http_req, ext| {
-    ///         if let Some(claims) = http_req.extensions().get::<MyClaims>() {
-    ///             ext.insert(claims.clone());
-    ///         }
-    ///     })
+    ///     .query_token_param("access_token")
     ///     .build()
     /// ```
-    pub fn on_request_fn(
-        self,
-        hook: impl Fn(&HttpRequest, &mut rmcp::model::Extensions) + Send + Sync + 'static,
-    ) -> StreamableHttpServiceBuilder<S, M, streamable_http_service_builder::SetOnRequest<State>>
-    {
-        self.on_request(Arc::new(hook))
-    }
-}
+    #[builder(into)]
+    query_token_param: Option<String>,
 
-/// Internal data structure used by handlers to store service configuration
-/// with Arc-wrapped session manager for thread safety.
-#[derive(Clone)]
-struct AppData<S, M> {
-    /// The service factory function that creates new MCP service instances
-    service_factory: Arc<dyn Fn() -> Result<S, std::io::Error> + Send + Sync>,
-    /// The session manager wrapped in Arc for thread safety
-    session_manager: Arc<M>,
-    /// Whether the service operates in stateful mode
-    stateful_mode: bool,
     /// Optional keep-alive interval for SSE connections
     sse_keep_alive: Option<Duration>,
-    /// Optional hook for propagating extensions from HttpRequest to RequestContext
-    on_request: Option<Arc<OnRequestHook>>,
-}
 
-impl<S, M> AppData<S, M> {
-    fn get_service(&self) -> Result<S, std::io::Error> {
-        (self.service_factory)()
-    }
-}
+    /// Bounds within which clients may override the keep-alive interval of their SSE
+    /// streams.
+    ///
+    /// When set, a client can request an interval in seconds with the
+    /// `X-SSE-Keep-Alive` header or the `keepAlive` query parameter; the value is
+    /// clamped to these bounds. Clients behind aggressive proxies can then ask for
+    /// faster pings than the global default. When unset (the default), overrides are
+    /// ignored.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .sse_keep_alive(Duration::from_secs(30))
+    ///     .sse_keep_alive_bounds(Duration::from_secs(5)..=Duration::from_secs(60))
+    ///     .build()
+    /// ```
+    sse_keep_alive_bounds: Option<RangeInclusive<Duration>>,
 
-// SSE Stream Helper Functions
-//
-// These functions provide reusable SSE keep-alive functionality to avoid code duplication.
+    /// Frame sent on SSE streams at every keep-alive interval.
+    ///
+    /// Defaults to the `:ping` comment. Some intermediaries strip SSE comments
+    /// and then close the stream as idle; a named event passes through them.
+    /// Applies to the legacy SSE endpoints as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .sse_keep_alive(Duration::from_secs(15))
+    ///     .keep_alive_frame(KeepAliveFrame::event("keepalive", "{}"))
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    keep_alive_frame: KeepAliveFrame,
 
-/// Serialize a `ServerSseMessage` as a single SSE event.
-///
-/// Priming events ([SEP-1699](https://github.com/modelcontextprotocol/modelcontextprotocol/issues/1699))
-/// carry no JSON-RPC payload (`message == None`) and MUST be emitted with an empty `data` field
-/// (`data:\n\n`), not the JSON literal `null`.
-fn format_sse_event(
-    event_id: Option<&str>,
-    message: Option<&rmcp::model::ServerJsonRpcMessage>,
-) -> Bytes {
-    let mut output = String::new();
-    if let Some(id) = event_id {
-        output.push_str(&format!("id: {id}\n"));
-    }
-    match message {
-        Some(message) => {
-            let data = serde_json::to_string(message).unwrap_or_else(|_| "{}".to_string());
-            output.push_str(&format!("data: {data}\n\n"));
-        }
-        None => output.push_str("data:\n\n"),
-    }
-    Bytes::from(output)
-}
+    /// Overall time allowed for answering a POSTed JSON-RPC request.
+    ///
+    /// When a request has not received its final response in time, the transport
+    /// sends a JSON-RPC error (code `-32008`) on the request's stream, closes the
+    /// stream, and cancels the handler, so a hung upstream API cannot pin a
+    /// connection forever. Applies to the legacy SSE endpoints as well. Disabled by
+    /// default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .request_timeout(Duration::from_secs(60))
+    ///     .build()
+    /// ```
+    request_timeout: Option<Duration>,
 
-/// Wraps any SSE-formatted stream with keep-alive ping support.
-///
-/// Adds periodic `:ping\n\n` messages during silent periods to prevent connection timeouts.
-/// The wrapper automatically stops when the underlying stream ends, allowing POST responses
-/// to close properly per MCP spec.
-///
-/// # Arguments
-///
-/// * `stream` - A stream of SSE-formatted bytes (already formatted as `data: ...\n\n`)
-/// * `keep_alive` - Optional keep-alive interval. If `Some`, sends `:ping\n\n` at this interval
-///   during silent periods. If `None`, no pings are sent.
-///
-/// # Returns
-///
-/// A stream that multiplexes the input stream with keep-alive pings, ending when the input ends.
-fn wrap_with_sse_keepalive<S>(
-    stream: S,
-    keep_alive: Option<Duration>,
-) -> impl Stream<Item = Result<Bytes, actix_web::Error>>
-where
-    S: Stream<Item = Result<Bytes, actix_web::Error>> + Send + 'static,
-{
-    async_stream::stream! {
-        let mut stream = Box::pin(stream);
-        let mut keep_alive_timer = keep_alive.map(|duration| tokio::time::interval(duration));
+    /// Whether clients may shorten the request timeout of their requests with a
+    /// `Request-Timeout` (remaining seconds) or `X-Request-Deadline` (Unix time in
+    /// milliseconds) header.
+    ///
+    /// An announced deadline is enforced like `request_timeout` when it ends first,
+    /// so deadlines propagate end to end through MCP proxies. Handlers find the
+    /// remaining budget in the [`RequestDeadline`] extension either way. Disabled by
+    /// default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .request_timeout(Duration::from_secs(60))
+    ///     .request_deadline_headers(true)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    request_deadline_headers: bool,
 
-        // Consume the immediate first tick if keep-alive is enabled
-        if let Some(ref mut timer) = keep_alive_timer {
-            timer.tick().await;
-        }
+    /// How long a new session may stay without any follow-up to `initialize`.
+    ///
+    /// A client is expected to send `notifications/initialized` right after the
+    /// `initialize` response. When no further message for a session arrives within
+    /// this window, typically because its client crashed mid-handshake, the transport
+    /// closes it and the `on_session_closed` hook sees
+    /// [`SessionCloseReason::InitializeTimeout`]. Legacy SSE sessions are covered
+    /// from the moment their event stream opens. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .initialize_timeout(Duration::from_secs(30))
+    ///     .build()
+    /// ```
+    initialize_timeout: Option<Duration>,
 
-        loop {
-            tokio::select! {
-                result = stream.next() => {
-                    match result {
-                        Some(msg) => yield msg,
-                        None => break, // Stream ended, stop sending pings
-                    }
-                }
-                _ = async {
-                    match keep_alive_timer.as_mut() {
-                        Some(timer) => {
-                            timer.tick().await;
-                        }
-                        None => {
-                            std::future::pending::<()>().await;
-                        }
-                    }
-                } => {
-                    yield Ok(Bytes::from(":ping\n\n"));
-                }
-            }
+    /// Interval at which clients are pinged on their standalone stream.
+    ///
+    /// While a client keeps its `GET` stream open, the transport sends it an MCP
+    /// `ping` request at this interval and records the round-trip time of the
+    /// answers, available through
+    /// [`StreamableHttpService::session_liveness`]. A ping still unanswered when
+    /// the next one is due counts as missed. Answers are consumed by the transport
+    /// and never reach the handler. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .ping_interval(Duration::from_secs(30))
+    ///     .ping_max_missed(2)
+    ///     .build()
+    /// ```
+    ping_interval: Option<Duration>,
+
+    /// Consecutive missed pings after which a session is closed.
+    ///
+    /// Only used with
+    /// [`ping_interval`](StreamableHttpServiceBuilder::ping_interval). The
+    /// `on_session_closed` hook sees [`SessionCloseReason::Unresponsive`]. `0`
+    /// keeps pinging without ever closing the session. Defaults to 3.
+    #[builder(default = liveness::DEFAULT_PING_MAX_MISSED)]
+    ping_max_missed: u32,
+
+    /// Optional store for the events replayed on `Last-Event-ID` resumption.
+    ///
+    /// By default the session manager answers resumption requests from its own
+    /// cache. With an event store, every event written to a session's request
+    /// and standalone streams is also recorded in the store, and a client
+    /// reconnecting with `Last-Event-ID` first receives the events it missed from
+    /// the store, then the live remainder of the stream from the session manager.
+    /// This keeps replayable events apart from session state, e.g. sessions in
+    /// memory and events in an external database. Events of a session are removed
+    /// from the store when the session closes.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::InMemoryEventStore;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .event_store(Arc::new(InMemoryEventStore::new(1024)))
+    ///     .build()
+    /// ```
+    event_store: Option<Arc<dyn EventStore>>,
+
+    /// Optional hook generating the SSE event ids sent to clients.
+    ///
+    /// By default clients see the ids assigned by the session manager, which are
+    /// only unique within a session. The hook receives the session id and that
+    /// native id, and returns the id to send instead, e.g. a ULID or
+    /// `{session}-{seq}`, so ids stay unique and sortable across a fleet. The
+    /// transport remembers the last 1024 generated ids of each session and maps a
+    /// `Last-Event-ID` among them back to the native id, so resumption keeps
+    /// working. The event store, if any, records native ids. Events replayed on
+    /// resumption go through the hook again.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .event_id_generator(Arc::new(|session_id, native| format!("{session_id}-{native}")))
+    ///     .build()
+    /// ```
+    event_id_generator: Option<Arc<EventIdHook>>,
+
+    /// How long the response of a request is kept to answer duplicate deliveries.
+    ///
+    /// Retry middleware may POST a request again when the connection dropped
+    /// before the response arrived. With a window set, requests of a session are
+    /// identified by their `Idempotency-Key` header, or by their JSON-RPC id when
+    /// the header is absent. A duplicate delivery never reaches the handler: it is
+    /// answered with the response of the original request, waiting for it if the
+    /// original is still running. Since the original must complete for its
+    /// response to be recorded, its stream keeps running after the client
+    /// disconnects. Applies to stateful mode only. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .idempotency_window(Duration::from_secs(300))
+    ///     .build()
+    /// ```
+    idempotency_window: Option<Duration>,
+
+    /// How long `tools/list` and `prompts/list` results are cached.
+    ///
+    /// With a TTL set, the result of each discovery request (per method and
+    /// pagination cursor) is kept for that long and answers the same request from
+    /// every session without reaching the handler. A
+    /// `notifications/tools/list_changed` or `notifications/prompts/list_changed`
+    /// sent by the service of any session drops the cached pages of that list.
+    /// With a `tenant_service_factory`, each tenant has its own cached pages.
+    /// Only enable it when the lists do not depend on the caller. Applies to
+    /// Streamable HTTP POSTs. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .discovery_cache_ttl(Duration::from_secs(60))
+    ///     .build()
+    /// ```
+    discovery_cache_ttl: Option<Duration>,
+
+    /// Tools whose results are cached, with how long each result stays fresh.
+    ///
+    /// A `tools/call` request for a listed tool is answered with the cached result
+    /// of a call with the same name and arguments, from any session, without
+    /// reaching the handler. Results flagged with `isError` are not cached. With a
+    /// `tenant_service_factory`, each tenant has its own cached results. Only
+    /// list read-only tools whose result does not depend on the caller. Applies to
+    /// Streamable HTTP POSTs. Caches nothing by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::CacheableTools;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .cacheable_tools(CacheableTools::new().cache("lookup_country", Duration::from_secs(3600)))
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    cacheable_tools: CacheableTools,
+
+    /// Whether clients may exchange MessagePack instead of JSON.
+    ///
+    /// When enabled, POST bodies may be sent as `application/msgpack`, and a
+    /// client listing `application/msgpack` in its `Accept` header receives SSE
+    /// events whose `data` is the base64-encoded MessagePack form of each message,
+    /// announced by an `X-MCP-Payload-Encoding: msgpack` response header. This
+    /// cuts parsing cost for high-frequency machine-to-machine traffic, and the
+    /// size of request bodies; base64 makes event payloads about a third larger
+    /// than their binary form, often larger than the JSON they replace. Plain
+    /// JSON clients are unaffected. Requires the `msgpack` feature; disabled by
+    /// default.
+    ///
+    /// CBOR (`application/cbor`) is negotiated the same way when the crate is
+    /// built with the `cbor` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .msgpack(true)
+    ///     .build()
+    /// ```
+    #[cfg(feature = "msgpack")]
+    #[builder(default)]
+    msgpack: bool,
+
+    /// Whether SSE message events are named.
+    ///
+    /// When enabled, every event carrying a JSON-RPC message has an
+    /// `event: message` line, or `event: error` for JSON-RPC errors, for
+    /// clients whose `EventSource` wrappers only dispatch named events. Priming
+    /// events stay unnamed. Disabled by default, which keeps the unnamed events
+    /// of the MCP specification.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .named_events(true)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    named_events: bool,
+
+    /// Whether message payload sizes and serialization times are recorded.
+    ///
+    /// When enabled, the size and serialization time of every message written to
+    /// a Streamable HTTP stream are counted in histograms per tool and method,
+    /// read with [`payload_stats`](StreamableHttpService::payload_stats), to find
+    /// tools returning pathological result sizes. Disabled by default, which
+    /// avoids timing every message.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .payload_stats(true)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    payload_stats: bool,
+
+    /// What to do when a client reads an SSE stream slower than the service writes it.
+    ///
+    /// Defaults to [`SlowConsumerPolicy::Block`], which stalls the service task until
+    /// the client catches up. The other policies buffer up to `slow_consumer_buffer`
+    /// messages per stream, then drop the oldest notifications or end the stream with
+    /// an error event. Legacy SSE connections use `legacy_sse_overflow` instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::SlowConsumerPolicy;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .slow_consumer_policy(SlowConsumerPolicy::DropOldestNotifications)
+    ///     .slow_consumer_buffer(256)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    slow_consumer_policy: SlowConsumerPolicy,
+
+    /// Messages buffered per SSE stream under a non-blocking `slow_consumer_policy`.
+    ///
+    /// Defaults to 64 messages.
+    #[builder(default = slow_consumer::DEFAULT_SLOW_CONSUMER_BUFFER)]
+    slow_consumer_buffer: usize,
+
+    /// What of the JSON-RPC messages logged by the transport reaches the logs.
+    ///
+    /// Messages are logged at debug level. By default scalar values are replaced
+    /// with `[REDACTED]` unless their field is on a short allow-list (`jsonrpc`,
+    /// `id`, `method`, tool `name`, ...), so tool arguments and results never leak
+    /// into log pipelines. See the [`redaction`](crate::transport::redaction)
+    /// module.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::LogRedaction;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .log_redaction(LogRedaction::default().allow("uri"))
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    log_redaction: LogRedaction,
+
+    /// Optional hook called for each request to propagate extensions from HttpRequest to RequestContext.
+    ///
+    /// This allows middleware-populated data (e.g., JWT claims) to be accessed in MCP handlers.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    /// use actix_web::HttpMessage;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .on_request(Arc::new(|http_req, ext| {
+    ///         if let Some(claims) = http_req.extensions().get::<MyClaims>() {
+    ///             ext.insert(claims.clone());
+    ///         }
+    ///     }))
+    ///     .build()
+    /// ```
+    on_request: Option<Arc<OnRequestHook>>,
+
+    /// Types copied from the actix-web `HttpRequest` extensions into rmcp's
+    /// `RequestContext::extensions` on every request.
+    ///
+    /// Saves writing an `on_request` hook that only forwards middleware-provided values.
+    /// The copy happens before `on_request` runs, so the hook can still override or
+    /// add values. Defaults to copying nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::ExtensionAllowList;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .propagate_extensions(ExtensionAllowList::new().allow::<JwtClaims>())
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    propagate_extensions: ExtensionAllowList,
+
+    /// Names of request headers captured into a [`HeaderSnapshot`] extension.
+    ///
+    /// When not empty, every request gets a `HeaderSnapshot` in its
+    /// `RequestContext::extensions` holding the listed headers, for handlers that need
+    /// values such as `Accept-Language` or correlation ids. Names are matched
+    /// case-insensitively. Defaults to capturing nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .snapshot_headers(vec!["accept-language".into(), "x-correlation-id".into()])
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    snapshot_headers: Vec<String>,
+
+    /// Whether handlers can set HTTP response headers through a [`ResponseHeaders`]
+    /// extension.
+    ///
+    /// When enabled, every POSTed request gets a `ResponseHeaders` handle in its
+    /// `RequestContext::extensions`, and the transport holds back the HTTP response
+    /// head until the handler produced its first message, so headers set until then
+    /// are applied. Keep-alive pings only start once the head is sent, so a handler
+    /// that stays silent for longer than an intermediary's idle timeout should send
+    /// progress notifications. Not applied to the legacy SSE endpoints, whose responses
+    /// travel on the GET stream.
+    #[builder(default)]
+    response_headers: bool,
+
+    /// How long blobs published by handlers can be downloaded.
+    ///
+    /// When set, every POSTed request gets a [`BlobLinks`] handle in its
+    /// `RequestContext::extensions`. A handler can publish large binary contents
+    /// with it and return the short-lived URL it yields instead of embedding the
+    /// bytes as base64, and the client downloads them from a `blobs/{id}` route
+    /// below the MCP endpoint. Blobs are kept in memory until they expire or their
+    /// session closes, and can only be downloaded with the `Mcp-Session-Id` of the
+    /// session that published them. Disabled by default; see the
+    /// [`blobs`](crate::transport::blobs) module.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .blob_ttl(Duration::from_secs(300))
+    ///     .build()
+    /// ```
+    blob_ttl: Option<Duration>,
+
+    /// How long payloads uploaded by clients are kept.
+    ///
+    /// When set, clients can POST a large payload as the raw body of a request to
+    /// an `uploads` route below the MCP endpoint and pass the returned handle in
+    /// their tool arguments instead of the payload itself. Every POSTed request
+    /// gets an [`Uploads`] handle in its `RequestContext::extensions` to resolve
+    /// such handles. Uploads are kept in memory until they expire or their session
+    /// closes, and are only visible to the session that sent them. Disabled by
+    /// default; see the [`blobs`](crate::transport::blobs) module.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .upload_ttl(Duration::from_secs(300))
+    ///     .max_upload_size(256 * 1024 * 1024)
+    ///     .build()
+    /// ```
+    upload_ttl: Option<Duration>,
+
+    /// Maximum size of an upload, in bytes. Defaults to 64 MiB.
+    ///
+    /// Only used with [`upload_ttl`](StreamableHttpServiceBuilder::upload_ttl).
+    /// Larger uploads are rejected with `413 Payload Too Large`.
+    #[builder(default = DEFAULT_MAX_UPLOAD_SIZE)]
+    max_upload_size: usize,
+
+    /// Optional hook computing extensions that stick to a session.
+    ///
+    /// Runs once, on the `initialize` request. Whatever it inserts (validated claims,
+    /// tenant, resolved user) is stored with the session and injected into the
+    /// `RequestContext::extensions` of the `initialize` request and of every later request
+    /// of the session, so `on_request` does not have to re-derive it on each POST and tools
+    /// see a consistent identity. Sticky extensions are inserted before `on_request` runs,
+    /// so a per-request value of the same type replaces the sticky one.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .session_extensions(Arc::new(|http_req, ext| {
+    ///         if let Some(claims) = http_req.extensions().get::<JwtClaims>() {
+    ///             ext.insert(claims.clone());
+    ///         }
+    ///     }))
+    ///     .build()
+    /// ```
+    session_extensions: Option<Arc<SessionExtensionsHook>>,
+
+    /// Optional guard deciding whether a request is served at all.
+    ///
+    /// Runs first on every request to the MCP endpoints (including the legacy SSE
+    /// endpoints when enabled), before session lookup, session creation, or body
+    /// parsing. Returning `Err(response)` short-circuits the request with that response,
+    /// which makes it the place for transport-level policy such as maintenance mode,
+    /// quotas, or revoked tokens.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    /// use actix_web::HttpResponse;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .request_guard(Arc::new(|http_req| {
+    ///         if MAINTENANCE.load(Ordering::Relaxed) {
+    ///             return Err(HttpResponse::ServiceUnavailable().body("Down for maintenance"));
+    ///         }
+    ///         Ok(())
+    ///     }))
+    ///     .build()
+    /// ```
+    request_guard: Option<Arc<RequestGuardHook>>,
+
+    /// Optional API key every request to the MCP endpoints must present.
+    ///
+    /// Checked after the origin check and before the `request_guard` hook, ahead of
+    /// any MCP processing. A request without the key header gets `401
+    /// Unauthorized`, one with a rejected key `403 Forbidden`, both with a JSON
+    /// body. Accepts a list of keys, or an [`ApiKeyAuth`] for a custom header or
+    /// validation logic. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .require_api_key([std::env::var("MCP_API_KEY")?])
+    ///     .build()
+    /// ```
+    #[builder(into)]
+    require_api_key: Option<ApiKeyAuth>,
+
+    /// Bind each session to the identity that created it.
+    ///
+    /// When enabled, the [`AuthenticatedIdentity`] request extension (inserted by the
+    /// authentication middleware, e.g. with the token subject) of the `initialize`
+    /// request is recorded with the session, and later requests on the session presenting
+    /// a different identity (or none) are answered as if the session did not exist.
+    /// Otherwise anyone who learns a session id can use it with any credentials. Defaults
+    /// to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .bind_session_identity(true)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    bind_session_identity: bool,
+
+    /// Signed session tokens for stateless mode.
+    ///
+    /// When set and `stateful_mode` is `false`, `initialize` responses carry an
+    /// HMAC-signed `Mcp-Session-Id` token that every later request must present. Tokens
+    /// are validated by signature and expiry alone, so replicas sharing the secret need
+    /// no session store, and the decoded [`SessionToken`](crate::transport::SessionToken)
+    /// is inserted into the request extensions. Ignored in stateful mode. Requires the
+    /// `signed-session-tokens` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::SessionTokens;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .stateful_mode(false)
+    ///     .session_tokens(SessionTokens::new(secret))
+    ///     .build()
+    /// ```
+    #[cfg(feature = "signed-session-tokens")]
+    session_tokens: Option<crate::transport::SessionTokens>,
+
+    /// Origins allowed to call the MCP endpoints.
+    ///
+    /// When not empty, requests carrying an `Origin` header that is not listed are
+    /// rejected with `403 Forbidden` before any other processing, which protects
+    /// locally running servers against DNS rebinding. Requests without an `Origin`
+    /// header (non-browser clients) are always accepted. Entries are compared
+    /// case-insensitively against the full origin, such as `https://app.example.com`.
+    /// Defaults to accepting every origin. CORS response headers are only emitted
+    /// with [`cors`](StreamableHttpServiceBuilder::cors) enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .allowed_origins(vec!["https://app.example.com".into()])
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    allowed_origins: Vec<String>,
+
+    /// Whether to answer CORS preflights and add CORS headers to responses.
+    ///
+    /// When enabled, browser clients on other origins can call the MCP endpoints
+    /// without a separate CORS middleware: `OPTIONS` preflights from origins accepted
+    /// by `allowed_origins` are answered with the methods of the route and the
+    /// requested headers, and responses to those origins carry
+    /// `Access-Control-Allow-Origin` and expose `Mcp-Session-Id`. Origins rejected by
+    /// `allowed_origins` get `403 Forbidden`, preflights included. With an empty
+    /// `allowed_origins`, every origin is allowed. Disabled by default; `OPTIONS`
+    /// requests are then answered with an `Allow` header only.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .allowed_origins(vec!["https://app.example.com".into()])
+    ///     .cors(true)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    cors: bool,
+
+    /// Reverse proxies whose `Forwarded` and `X-Forwarded-For` headers are believed.
+    ///
+    /// When set, the client address is taken from the forwarding headers only if the
+    /// socket peer is one of these proxies, and the resolved address replaces
+    /// `realip_remote_addr` in the [`ClientConnectionInfo`] extension and appears in
+    /// the transport's logs. When unset (the default), `ClientConnectionInfo` follows
+    /// actix-web, which believes forwarding headers from any peer.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::TrustedProxies;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .trusted_proxies(TrustedProxies::new().trust_network("10.0.0.0".parse()?, 8))
+    ///     .build()
+    /// ```
+    trusted_proxies: Option<TrustedProxies>,
+
+    /// Allow/deny lists for client addresses, in CIDR form.
+    ///
+    /// Checked before any other request processing; rejected clients get `403
+    /// Forbidden`. The client address is the socket peer, or the address resolved
+    /// through `trusted_proxies` when set. Defaults to allowing every address.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::IpFilter;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .ip_filter(IpFilter::new().allow_network("10.0.0.0".parse()?, 8))
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    ip_filter: IpFilter,
+
+    /// Optional limit on how many sessions each client address may create per window.
+    ///
+    /// Counted separately from ordinary requests, since creating a session is far more
+    /// expensive than serving a request on one. `initialize` requests (and legacy SSE
+    /// connections) over the limit get `429 Too Many Requests` with a `Retry-After`
+    /// header. The client address is resolved like for `ip_filter`. Unlimited by
+    /// default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::SessionCreationLimit;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .session_creation_limit(SessionCreationLimit::per_minute(10))
+    ///     .build()
+    /// ```
+    session_creation_limit: Option<SessionCreationLimit>,
+
+    /// Optional quotas per tenant.
+    ///
+    /// Requests carrying a [`TenantId`] extension count against the quota of their
+    /// tenant: new sessions over its session cap get `403 Forbidden`, requests over its
+    /// rate get `429 Too Many Requests` with a `Retry-After` header, and tool calls over
+    /// its concurrency cap are answered with a JSON-RPC error. A session's tool calls
+    /// count against the tenant that created it. Usage per tenant is returned by
+    /// [`tenant_usage`](StreamableHttpService::tenant_usage). Unlimited by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::{TenantQuota, TenantQuotas};
+    ///
+    /// StreamableHttpService::builder()
+    ///     .tenant_quotas(
+    ///         TenantQuotas::new()
+    ///             .default_quota(TenantQuota::new().max_sessions(10).requests_per_minute(600))
+    ///             .tenant("acme", TenantQuota::new().max_concurrent_tool_calls(20)),
+    ///     )
+    ///     .build()
+    /// ```
+    tenant_quotas: Option<TenantQuotas>,
+
+    /// Optional cap on the SSE streams each client address may keep open at once.
+    ///
+    /// Counts standalone `GET` streams and legacy SSE connections, which each hold a
+    /// worker connection slot while open; the SSE responses to `POST` requests are not
+    /// counted. Streams over the cap get `429 Too Many Requests`. The client address is
+    /// resolved like for `ip_filter`. Unlimited by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .max_sse_streams_per_client(8)
+    ///     .build()
+    /// ```
+    max_sse_streams_per_client: Option<usize>,
+
+    /// Optional cap on the requests each session may have pending at once.
+    ///
+    /// A request is pending from the moment it is posted until its response stream
+    /// ends. Requests over the cap are refused with `429 Too Many Requests` and a
+    /// `Retry-After` header instead of being queued by the session manager;
+    /// notifications and client responses are always accepted. Unlimited by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .max_pending_requests_per_session(32)
+    ///     .build()
+    /// ```
+    max_pending_requests_per_session: Option<usize>,
+
+    /// Server info answering `initialize` before the service instance exists.
+    ///
+    /// When set, a new session answers the `initialize` request with this info
+    /// (protocol version negotiated as usual) and only calls the service factory
+    /// on the first other request or notification of the session, so clients that
+    /// initialize and disappear never cause a service instance to be created. The
+    /// `notifications/initialized` notification is held back and delivered once the
+    /// instance exists. A factory failure fails the triggering request with an
+    /// internal error and is retried on the next request. The info should match
+    /// what the service's `get_info` returns. Applies to stateful sessions only.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .lazy_service_info(ServerInfo::new(
+    ///         ServerCapabilities::builder().enable_tools().build(),
+    ///     ))
+    ///     .build()
+    /// ```
+    lazy_service_info: Option<ServerInfo>,
+
+    /// Number of service instances constructed ahead of time for new sessions.
+    ///
+    /// When set, up to this many instances are constructed in the background, on
+    /// tokio's blocking thread pool, and handed to new sessions (and to stateless
+    /// requests), hiding expensive construction such as model loading from their
+    /// latency. Each instance taken is replaced in the background; when none is
+    /// ready, the service factory is called directly. Filling starts when the
+    /// service is mounted. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .warm_pool_size(8)
+    ///     .build()
+    /// ```
+    warm_pool_size: Option<usize>,
+
+    /// Runtime on which the handler processes requests.
+    ///
+    /// By default, requests are handled on the actix-web worker that serves the
+    /// session, so CPU-heavy tools compete with SSE writes and connection handling.
+    /// With a handler runtime, each request is handled by a task on that runtime
+    /// and only its response is sent back to the worker. The task is aborted when
+    /// the request is abandoned, e.g. because the session closed. Notifications are
+    /// still handled on the worker.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let tools = tokio::runtime::Builder::new_multi_thread()
+    ///     .worker_threads(4)
+    ///     .thread_name("mcp-tools")
+    ///     .enable_all()
+    ///     .build()?;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .handler_runtime(tools.handle().clone())
+    ///     .build()
+    /// ```
+    handler_runtime: Option<tokio::runtime::Handle>,
+
+    /// Maximum size, in bytes, of a POSTed request body.
+    ///
+    /// Larger bodies are rejected with `413 Payload Too Large`. Defaults to
+    /// actix-web's payload limit of 256 KiB.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .max_body_size(4 * 1024 * 1024)
+    ///     .build()
+    /// ```
+    max_body_size: Option<usize>,
+
+    /// Maximum time to receive the complete body of a request.
+    ///
+    /// Counted from when the request reaches the service; clients sending their
+    /// body slower than that are answered with `408 Request Timeout` and
+    /// disconnected, which protects the MCP endpoints from slow-dripping
+    /// (slowloris) clients independently of actix-web's server-wide settings.
+    /// Defaults to no timeout.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .body_read_timeout(Duration::from_secs(10))
+    ///     .build()
+    /// ```
+    body_read_timeout: Option<Duration>,
+
+    /// Optional transformer applied to every inbound client message.
+    ///
+    /// Runs after the message is parsed and before `on_request` extensions are attached,
+    /// so it can rewrite tool arguments, strip fields, or inject defaults without changing
+    /// the handlers. Applies to the legacy SSE endpoints as well.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    /// use rmcp::model::{ClientJsonRpcMessage, ClientRequest};
+    ///
+    /// StreamableHttpService::builder()
+    ///     .transform_inbound(Arc::new(|http_req, message| {
+    ///         if let ClientJsonRpcMessage::Request(req) = message
+    ///             && let ClientRequest::CallToolRequest(call) = &mut req.request
+    ///             && let Some(arguments) = call.params.arguments.as_mut()
+    ///         {
+    ///             arguments.remove("debug");
+    ///         }
+    ///     }))
+    ///     .build()
+    /// ```
+    transform_inbound: Option<Arc<InboundMessageHook>>,
+
+    /// Optional transformer applied to every outbound server message.
+    ///
+    /// Runs right before each response, request, or notification is written to an SSE
+    /// stream, in both stateful and stateless mode and on the legacy SSE endpoints.
+    /// Priming events carry no message and are not passed to the hook.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    /// use rmcp::model::{ServerJsonRpcMessage, ServerResult};
+    ///
+    /// StreamableHttpService::builder()
+    ///     .transform_outbound(Arc::new(|message| {
+    ///         if let ServerJsonRpcMessage::Response(resp) = message
+    ///             && let ServerResult::CallToolResult(result) = &mut resp.result
+    ///         {
+    ///             result.meta = None;
+    ///         }
+    ///     }))
+    ///     .build()
+    /// ```
+    transform_outbound: Option<Arc<OutboundMessageHook>>,
+
+    /// Whether to mirror MCP logging notifications into `tracing`.
+    ///
+    /// When enabled, every `notifications/message` sent by a handler is also
+    /// emitted as a `tracing` event carrying the session id, the logger name and
+    /// the logged data, at the level matching the MCP logging level. This gives
+    /// operators one place to see what tools reported to clients. The events are
+    /// emitted under the `rmcp_actix_web::client_log` target. Defaults to `false`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .mirror_logging(true)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    mirror_logging: bool,
+
+    /// Allow/deny lists for JSON-RPC methods and tool names.
+    ///
+    /// Rejected requests are answered with a JSON-RPC error by the transport and never
+    /// reach the handler. The check runs after `transform_inbound`. Defaults to allowing
+    /// everything.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::MessageFilter;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .message_filter(MessageFilter::new().deny_tools(["delete_file"]))
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    message_filter: MessageFilter,
+
+    /// OAuth scopes required for calling tools.
+    ///
+    /// `tools/call` requests for a mapped tool are answered with a JSON-RPC error by the
+    /// transport unless the request holds every scope the tool is listed under. Granted
+    /// scopes are read from the [`GrantedScopes`](crate::transport::GrantedScopes)
+    /// request extension, inserted by the middleware that validates the access token.
+    /// Defaults to restricting nothing.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::ToolScopes;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .tool_scopes(ToolScopes::new().require("mcp:admin", ["delete_user"]))
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    tool_scopes: ToolScopes,
+
+    /// Optional async hook authorizing each JSON-RPC request.
+    ///
+    /// Receives the HTTP request (whose extensions carry the authenticated identity), the
+    /// JSON-RPC method, and the targeted tool, prompt, or resource. Resolving to an error
+    /// denies the request: the transport answers with that JSON-RPC error and the handler
+    /// never sees it. Runs after `message_filter` and `tool_scopes`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .authorize_fn(|auth_req| async move {
+    ///         if auth_req.target.as_deref() == Some("delete_user") {
+    ///             return Err(ErrorData::invalid_request("Not authorized", None));
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    /// ```
+    authorize: Option<Arc<AuthorizeHook>>,
+
+    /// Optional hook called when a new session is created.
+    ///
+    /// Receives the HTTP request, the session id, and the `initialize` parameters, so
+    /// applications can record audit entries or allocate per-session resources at the
+    /// transport layer. The hook runs before the `initialize` request reaches the handler.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .on_session_created(Arc::new(|http_req, session_id, params| {
+    ///         tracing::info!(%session_id, client = %params.client_info.name, "session created");
+    ///     }))
+    ///     .build()
+    /// ```
+    on_session_created: Option<Arc<OnSessionCreatedHook>>,
+
+    /// Optional hook called when a session is closed.
+    ///
+    /// Fires exactly once per session, whether it was closed by a client `DELETE`,
+    /// expired by the session manager, or ended because the service task exited. Use it
+    /// to release per-session external resources (temp files, upstream tokens, database
+    /// rows) allocated during the session.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .on_session_closed(Arc::new(|session_id, reason| {
+    ///         tracing::info!(%session_id, ?reason, "session closed");
+    ///     }))
+    ///     .build()
+    /// ```
+    on_session_closed: Option<Arc<OnSessionClosedHook>>,
+
+    /// Optional hook called when a handler panics or returns an internal error.
+    ///
+    /// Handler panics are always caught and answered with a JSON-RPC internal error,
+    /// so the session keeps running; this hook additionally receives an
+    /// [`ErrorReport`] for each of them, and for every internal error a handler
+    /// returns, to forward crashes inside tools to an error tracker such as Sentry.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::sync::Arc;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .on_internal_error(Arc::new(|report| {
+    ///         tracing::error!(session_id = ?report.session_id, method = %report.method, "{}", report.message);
+    ///     }))
+    ///     .build()
+    /// ```
+    on_internal_error: Option<Arc<OnInternalErrorHook>>,
+
+    /// Number of events buffered for each
+    /// [`subscribe_events`](StreamableHttpService::subscribe_events) subscriber.
+    ///
+    /// A subscriber falling further behind loses the oldest events. Defaults to
+    /// 1024.
+    #[builder(default = transport_events::DEFAULT_EVENT_CHANNEL_CAPACITY)]
+    event_channel_capacity: usize,
+
+    /// Authentication requirements advertised by the discovery manifest.
+    ///
+    /// Only used by [`StreamableHttpService::well_known_manifest`]; the transport
+    /// does not enforce it.
+    #[builder(default)]
+    discovery_auth: DiscoveryAuth,
+
+    /// Whether to also serve the legacy HTTP+SSE endpoints (`/sse` and `/message`).
+    ///
+    /// Legacy connections are regular sessions of the session manager, so old SSE
+    /// clients and Streamable HTTP clients share the same handlers and session space
+    /// during a migration. Requires stateful mode.
+    #[builder(default)]
+    legacy_sse: bool,
+
+    /// Resource name of the MCP endpoint route, for [`HttpRequest::url_for`].
+    ///
+    /// The other routes are named after it: `{name}_blob`, `{name}_upload`,
+    /// `{name}_sse` and `{name}_message`. Resource names must be unique within an
+    /// application, so give each service mounted in the same app its own name.
+    /// Defaults to `mcp`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .route_name("tools_mcp")
+    ///     .build()
+    /// ```
+    #[builder(into, default = DEFAULT_ROUTE_NAME.to_string())]
+    route_name: String,
+
+    /// How the service scope normalizes request paths before routing.
+    ///
+    /// Trailing slashes are trimmed by default, so `/mcp/` reaches the endpoint.
+    /// Apps normalizing paths globally (for instance with `NormalizePath` set to
+    /// merge slashes only) should pick the same behavior here, or disable it, so a
+    /// path is not routed differently inside and outside the scope.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::PathNormalization;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .path_normalization(PathNormalization::Disabled)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    path_normalization: PathNormalization,
+
+    /// Capacity of each legacy SSE connection's outbound message queue.
+    ///
+    /// Defaults to 64 messages.
+    #[builder(default = legacy_sse::DEFAULT_LEGACY_CHANNEL_CAPACITY)]
+    legacy_sse_channel_capacity: usize,
+
+    /// What to do when a legacy SSE connection's outbound queue is full.
+    ///
+    /// Defaults to [`SseOverflowPolicy::Block`], which stalls the service task until the
+    /// client catches up.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::SseOverflowPolicy;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .legacy_sse(true)
+    ///     .legacy_sse_channel_capacity(256)
+    ///     .legacy_sse_overflow(SseOverflowPolicy::DropNewest)
+    ///     .build()
+    /// ```
+    #[builder(default)]
+    legacy_sse_overflow: SseOverflowPolicy,
+
+    /// How long the session of a disconnected legacy SSE client is kept for it to
+    /// reconnect.
+    ///
+    /// A client reconnecting to `/sse` within the window with a `Last-Event-ID`
+    /// header resumes its session and receives the events it missed, up to
+    /// `legacy_sse_channel_capacity` of them. When unset (the default), the session
+    /// is closed as soon as its event stream drops.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use std::time::Duration;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .legacy_sse(true)
+    ///     .legacy_sse_resume_window(Duration::from_secs(30))
+    ///     .build()
+    /// ```
+    legacy_sse_resume_window: Option<Duration>,
+
+    #[builder(skip)]
+    shutdown: shutdown::ShutdownSignal,
+
+    // Connection and session state is created once here and shared by every clone of
+    // the service, so all workers see the same sessions.
+    #[builder(skip = legacy_sse::LegacySseConnections::new(
+        legacy_sse_channel_capacity,
+        legacy_sse_overflow,
+        legacy_sse_resume_window,
+    ))]
+    legacy_connections: legacy_sse::LegacySseConnections,
+
+    #[builder(skip)]
+    sessions: sessions::SessionRegistry,
+
+    #[builder(skip = transport_events::TransportEvents::new(event_channel_capacity))]
+    events: transport_events::TransportEvents,
+
+    #[builder(skip)]
+    idempotency: idempotency::IdempotencyCache,
+
+    #[builder(skip)]
+    blob_store: BlobStore,
+
+    #[builder(skip)]
+    upload_store: BlobStore,
+
+    #[builder(skip)]
+    forwarded_progress: Arc<AtomicU64>,
+
+    #[builder(skip = payload_stats::PayloadRecorder::new(payload_stats))]
+    payload_recorder: payload_stats::PayloadRecorder,
+
+    #[builder(skip)]
+    session_creations: session_rate_limit::SessionCreations,
+
+    #[builder(skip)]
+    tenant_counters: tenant_quotas::TenantCounters,
+
+    #[builder(skip)]
+    sse_connections: sse_connections::SseConnections,
+
+    #[builder(skip)]
+    pending_requests: session_backpressure::PendingRequests,
+
+    #[builder(skip)]
+    response_cache: response_cache::ResponseCache,
+
+    #[builder(skip)]
+    tool_toggles: tool_toggles::ToolToggles,
+
+    #[builder(skip = Instant::now())]
+    started_at: Instant,
+
+    #[builder(skip = warm_pool::WarmPool::new(warm_pool_size))]
+    warm_pool: warm_pool::WarmPool<S>,
+
+    #[builder(skip)]
+    shared_service: hybrid::SharedService<S>,
+
+    #[builder(skip)]
+    discovery_info: Arc<OnceLock<ServerInfo>>,
+}
+
+impl<S, M> Clone for StreamableHttpService<S, M> {
+    fn clone(&self) -> Self {
+        Self {
+            service_factory: self.service_factory.clone(),
+            tenant_service_factory: self.tenant_service_factory.clone(),
+            session_manager: self.session_manager.clone(),
+            stateful_mode: self.stateful_mode,
+            hybrid_mode: self.hybrid_mode,
+            session_delete: self.session_delete,
+            reinitialize_policy: self.reinitialize_policy,
+            session_cookie: self.session_cookie.clone(),
+            session_id_query: self.session_id_query,
+            query_token_param: self.query_token_param.clone(),
+            sse_keep_alive: self.sse_keep_alive,
+            sse_keep_alive_bounds: self.sse_keep_alive_bounds.clone(),
+            keep_alive_frame: self.keep_alive_frame.clone(),
+            request_timeout: self.request_timeout,
+            request_deadline_headers: self.request_deadline_headers,
+            initialize_timeout: self.initialize_timeout,
+            ping_interval: self.ping_interval,
+            ping_max_missed: self.ping_max_missed,
+            event_store: self.event_store.clone(),
+            event_id_generator: self.event_id_generator.clone(),
+            idempotency_window: self.idempotency_window,
+            discovery_cache_ttl: self.discovery_cache_ttl,
+            cacheable_tools: self.cacheable_tools.clone(),
+            response_cache: self.response_cache.clone(),
+            tool_toggles: self.tool_toggles.clone(),
+            started_at: self.started_at,
+            warm_pool: self.warm_pool.clone(),
+            shared_service: self.shared_service.clone(),
+            discovery_info: self.discovery_info.clone(),
+            #[cfg(feature = "msgpack")]
+            msgpack: self.msgpack,
+            named_events: self.named_events,
+            payload_stats: self.payload_stats,
+            slow_consumer_policy: self.slow_consumer_policy,
+            slow_consumer_buffer: self.slow_consumer_buffer,
+            log_redaction: self.log_redaction.clone(),
+            on_request: self.on_request.clone(),
+            propagate_extensions: self.propagate_extensions.clone(),
+            snapshot_headers: self.snapshot_headers.clone(),
+            response_headers: self.response_headers,
+            blob_ttl: self.blob_ttl,
+            upload_ttl: self.upload_ttl,
+            max_upload_size: self.max_upload_size,
+            session_extensions: self.session_extensions.clone(),
+            request_guard: self.request_guard.clone(),
+            require_api_key: self.require_api_key.clone(),
+            bind_session_identity: self.bind_session_identity,
+            #[cfg(feature = "signed-session-tokens")]
+            session_tokens: self.session_tokens.clone(),
+            allowed_origins: self.allowed_origins.clone(),
+            cors: self.cors,
+            trusted_proxies: self.trusted_proxies.clone(),
+            ip_filter: self.ip_filter.clone(),
+            session_creation_limit: self.session_creation_limit,
+            tenant_quotas: self.tenant_quotas.clone(),
+            tenant_counters: self.tenant_counters.clone(),
+            session_creations: self.session_creations.clone(),
+            max_sse_streams_per_client: self.max_sse_streams_per_client,
+            sse_connections: self.sse_connections.clone(),
+            max_pending_requests_per_session: self.max_pending_requests_per_session,
+            lazy_service_info: self.lazy_service_info.clone(),
+            warm_pool_size: self.warm_pool_size,
+            handler_runtime: self.handler_runtime.clone(),
+            pending_requests: self.pending_requests.clone(),
+            max_body_size: self.max_body_size,
+            body_read_timeout: self.body_read_timeout,
+            transform_inbound: self.transform_inbound.clone(),
+            transform_outbound: self.transform_outbound.clone(),
+            mirror_logging: self.mirror_logging,
+            message_filter: self.message_filter.clone(),
+            tool_scopes: self.tool_scopes.clone(),
+            authorize: self.authorize.clone(),
+            on_session_created: self.on_session_created.clone(),
+            on_session_closed: self.on_session_closed.clone(),
+            on_internal_error: self.on_internal_error.clone(),
+            event_channel_capacity: self.event_channel_capacity,
+            discovery_auth: self.discovery_auth.clone(),
+            legacy_sse: self.legacy_sse,
+            route_name: self.route_name.clone(),
+            path_normalization: self.path_normalization,
+            legacy_sse_channel_capacity: self.legacy_sse_channel_capacity,
+            legacy_sse_overflow: self.legacy_sse_overflow,
+            legacy_sse_resume_window: self.legacy_sse_resume_window,
+            shutdown: self.shutdown.clone(),
+            legacy_connections: self.legacy_connections.clone(),
+            sessions: self.sessions.clone(),
+            events: self.events.clone(),
+            idempotency: self.idempotency.clone(),
+            blob_store: self.blob_store.clone(),
+            upload_store: self.upload_store.clone(),
+            forwarded_progress: self.forwarded_progress.clone(),
+            payload_recorder: self.payload_recorder.clone(),
+        }
+    }
+}
+
+// Convenience methods for StreamableHttpServiceBuilder
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::OnRequest: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the on_request hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`,
+    /// making it easier to use without manual Arc wrapping.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use actix_web::HttpMessage;
+    ///
+    /// StreamableHttpService::builder()
+    ///     .on_request_fn(|http_req, ext| {
+    ///         if let Some(claims) = http_req.extensions().get::<MyClaims>() {
+    ///             ext.insert(claims.clone());
+    ///         }
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn on_request_fn(
+        self,
+        hook: impl Fn(&HttpRequest, &mut rmcp::model::Extensions) + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<S, M, streamable_http_service_builder::SetOnRequest<State>>
+    {
+        self.on_request(Arc::new(hook))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::SessionExtensions: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the session_extensions hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .session_extensions_fn(|http_req, ext| {
+    ///         if let Some(claims) = http_req.extensions().get::<JwtClaims>() {
+    ///             ext.insert(claims.clone());
+    ///         }
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn session_extensions_fn(
+        self,
+        hook: impl Fn(&HttpRequest, &mut rmcp::model::Extensions) + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<
+        S,
+        M,
+        streamable_http_service_builder::SetSessionExtensions<State>,
+    > {
+        self.session_extensions(Arc::new(hook))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::RequestGuard: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the request_guard hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .request_guard_fn(|http_req| {
+    ///         if http_req.headers().contains_key("x-banned") {
+    ///             return Err(HttpResponse::Forbidden().finish());
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn request_guard_fn(
+        self,
+        hook: impl Fn(&HttpRequest) -> Result<(), HttpResponse> + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<S, M, streamable_http_service_builder::SetRequestGuard<State>>
+    {
+        self.request_guard(Arc::new(hook))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::Authorize: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the authorize hook using an async closure.
+    ///
+    /// This is a convenience method that automatically boxes the returned future and
+    /// wraps the closure in an `Arc`.
+    pub fn authorize_fn<F, Fut>(
+        self,
+        hook: F,
+    ) -> StreamableHttpServiceBuilder<S, M, streamable_http_service_builder::SetAuthorize<State>>
+    where
+        F: Fn(AuthorizationRequest) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), ErrorData>> + 'static,
+    {
+        self.authorize(Arc::new(move |auth_req| hook(auth_req).boxed_local()))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::TransformInbound: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the transform_inbound hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`.
+    pub fn transform_inbound_fn(
+        self,
+        hook: impl Fn(&HttpRequest, &mut ClientJsonRpcMessage) + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<
+        S,
+        M,
+        streamable_http_service_builder::SetTransformInbound<State>,
+    > {
+        self.transform_inbound(Arc::new(hook))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::TransformOutbound: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the transform_outbound hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`.
+    pub fn transform_outbound_fn(
+        self,
+        hook: impl Fn(&mut ServerJsonRpcMessage) + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<
+        S,
+        M,
+        streamable_http_service_builder::SetTransformOutbound<State>,
+    > {
+        self.transform_outbound(Arc::new(hook))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::OnSessionCreated: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the on_session_created hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .on_session_created_fn(|_http_req, session_id, params| {
+    ///         tracing::info!(%session_id, client = %params.client_info.name, "session created");
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn on_session_created_fn(
+        self,
+        hook: impl Fn(&HttpRequest, &SessionId, &InitializeRequestParams) + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<
+        S,
+        M,
+        streamable_http_service_builder::SetOnSessionCreated<State>,
+    > {
+        self.on_session_created(Arc::new(hook))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::OnSessionClosed: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the on_session_closed hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .on_session_closed_fn(|session_id, reason| {
+    ///         tracing::info!(%session_id, ?reason, "session closed");
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn on_session_closed_fn(
+        self,
+        hook: impl Fn(&SessionId, SessionCloseReason) + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<
+        S,
+        M,
+        streamable_http_service_builder::SetOnSessionClosed<State>,
+    > {
+        self.on_session_closed(Arc::new(hook))
+    }
+}
+
+impl<S, M, State: streamable_http_service_builder::State> StreamableHttpServiceBuilder<S, M, State>
+where
+    State::OnInternalError: streamable_http_service_builder::IsUnset,
+{
+    /// Sets the on_internal_error hook using a closure.
+    ///
+    /// This is a convenience method that automatically wraps the closure in an `Arc`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// StreamableHttpService::builder()
+    ///     .on_internal_error_fn(|report| {
+    ///         tracing::error!(kind = ?report.kind, method = %report.method, "{}", report.message);
+    ///     })
+    ///     .build()
+    /// ```
+    pub fn on_internal_error_fn(
+        self,
+        hook: impl Fn(&ErrorReport) + Send + Sync + 'static,
+    ) -> StreamableHttpServiceBuilder<
+        S,
+        M,
+        streamable_http_service_builder::SetOnInternalError<State>,
+    > {
+        self.on_internal_error(Arc::new(hook))
+    }
+}
+
+/// Internal data structure used by handlers to store service configuration
+/// with Arc-wrapped session manager for thread safety.
+struct AppData<S, M> {
+    /// The service factory function that creates new MCP service instances
+    service_factory: ServiceFactory<S>,
+    /// Creates service instances of a tenant
+    tenant_service_factory: Option<Arc<TenantServiceFactory<S>>>,
+    /// The session manager wrapped in Arc for thread safety
+    session_manager: Arc<M>,
+    /// Whether the service operates in stateful mode
+    stateful_mode: bool,
+    /// Whether session-less requests are served statelessly in stateful mode
+    hybrid_mode: bool,
+    /// Handler shared by session-less requests in hybrid mode
+    shared_service: hybrid::SharedService<S>,
+    /// What to do with `initialize` requests sent within existing sessions
+    reinitialize_policy: ReinitializePolicy,
+    /// Cookie also carrying the session id
+    session_cookie: Option<Arc<session_cookie::SessionCookie>>,
+    /// Whether `GET` streams accept the session id in the query string
+    session_id_query: bool,
+    /// Optional keep-alive interval for SSE connections
+    sse_keep_alive: Option<Duration>,
+    /// Bounds for client-requested keep-alive intervals
+    sse_keep_alive_bounds: Option<RangeInclusive<Duration>>,
+    /// Serialized frame sent at every keep-alive interval
+    keep_alive_frame: Bytes,
+    /// Overall time allowed for answering a POSTed request
+    request_timeout: Option<Duration>,
+    /// Whether clients may shorten the request timeout with deadline headers
+    request_deadline_headers: bool,
+    /// How long a new session may wait for a follow-up to `initialize`
+    initialize_timeout: Option<Duration>,
+    /// Interval of the liveness pings on standalone streams
+    ping_interval: Option<Duration>,
+    /// Consecutive missed pings after which a session is closed
+    ping_max_missed: u32,
+    /// Optional store for the events replayed on resumption
+    event_store: Option<Arc<dyn EventStore>>,
+    /// Generator of the event ids sent to clients
+    event_ids: Option<event_ids::EventIds>,
+    /// How long responses are kept to answer duplicate deliveries
+    idempotency_window: Option<Duration>,
+    /// TTL of cached discovery results
+    discovery_cache_ttl: Option<Duration>,
+    /// Tools whose results are cached
+    cacheable_tools: Arc<CacheableTools>,
+    /// Cached discovery and tool results
+    response_cache: response_cache::ResponseCache,
+    /// Tools disabled at runtime
+    tool_toggles: tool_toggles::ToolToggles,
+    /// Whether clients may exchange MessagePack instead of JSON
+    msgpack: bool,
+    /// Whether SSE message events are named
+    named_events: bool,
+    /// Payload size and serialization time histograms
+    payload_recorder: payload_stats::PayloadRecorder,
+    /// What to do when a client falls behind on an SSE stream
+    slow_consumer_policy: SlowConsumerPolicy,
+    /// Messages buffered per SSE stream for slow consumers
+    slow_consumer_buffer: usize,
+    /// Redaction applied to logged messages
+    log_redaction: LogRedaction,
+    /// Responses of recent requests, for duplicate deliveries
+    idempotency: idempotency::IdempotencyCache,
+    /// Blobs published by handlers
+    blob_store: BlobStore,
+    /// Payloads uploaded by clients
+    upload_store: BlobStore,
+    /// Optional hook for propagating extensions from HttpRequest to RequestContext
+    on_request: Option<Arc<OnRequestHook>>,
+    /// Types copied from the HttpRequest extensions on every request
+    propagate_extensions: Arc<ExtensionAllowList>,
+    /// Names of request headers captured into a HeaderSnapshot
+    snapshot_headers: Arc<[String]>,
+    /// Whether handlers can set HTTP response headers
+    response_headers: bool,
+    /// How long published blobs can be downloaded
+    blob_ttl: Option<Duration>,
+    /// How long uploaded payloads are kept
+    upload_ttl: Option<Duration>,
+    /// Optional hook computing extensions that stick to a session
+    session_extensions: Option<Arc<SessionExtensionsHook>>,
+    /// Optional guard deciding whether a request is served at all
+    request_guard: Option<Arc<RequestGuardHook>>,
+    /// API key every request must present
+    require_api_key: Option<ApiKeyAuth>,
+    /// Whether sessions are bound to the identity that created them
+    bind_session_identity: bool,
+    /// Signer of the session tokens issued in stateless mode
+    #[cfg(feature = "signed-session-tokens")]
+    session_tokens: Option<crate::transport::SessionTokens>,
+    /// Origins allowed to call the endpoints; empty allows all
+    allowed_origins: Arc<[String]>,
+    /// Whether CORS preflights are answered and CORS headers added
+    cors: bool,
+    /// Reverse proxies whose forwarding headers are believed
+    trusted_proxies: Option<Arc<TrustedProxies>>,
+    /// Client addresses allowed to call the endpoints
+    ip_filter: Arc<IpFilter>,
+    /// Limit on new sessions per client address
+    session_creation_limit: Option<SessionCreationLimit>,
+    /// Quotas per tenant
+    tenant_quotas: Option<Arc<TenantQuotas>>,
+    /// Usage counted per tenant
+    tenant_counters: tenant_quotas::TenantCounters,
+    /// New sessions counted per client address
+    session_creations: session_rate_limit::SessionCreations,
+    /// Cap on open SSE streams per client address
+    max_sse_streams_per_client: Option<usize>,
+    /// Open SSE streams counted per client address
+    sse_connections: sse_connections::SseConnections,
+    /// Cap on pending requests per session
+    max_pending_requests_per_session: Option<usize>,
+    /// Server info answering `initialize` before the service instance exists
+    lazy_service_info: Option<ServerInfo>,
+    /// Runtime on which the handler processes requests
+    handler_runtime: Option<tokio::runtime::Handle>,
+    /// Pending requests counted per session
+    pending_requests: session_backpressure::PendingRequests,
+    /// Optional transformer applied to inbound client messages
+    transform_inbound: Option<Arc<InboundMessageHook>>,
+    /// Optional transformer applied to outbound server messages
+    transform_outbound: Option<Arc<OutboundMessageHook>>,
+    /// Whether logging notifications are mirrored into tracing
+    mirror_logging: bool,
+    /// Allow/deny lists for JSON-RPC methods and tool names
+    message_filter: Arc<MessageFilter>,
+    /// Scopes required for calling tools
+    tool_scopes: Arc<ToolScopes>,
+    /// Optional hook authorizing each request
+    authorize: Option<Arc<AuthorizeHook>>,
+    /// Optional hook called when a new session is created
+    on_session_created: Option<Arc<OnSessionCreatedHook>>,
+    /// Optional hook called when a session is closed
+    on_session_closed: Option<Arc<OnSessionClosedHook>>,
+    /// Optional hook called when a handler panics or returns an internal error
+    on_internal_error: Option<Arc<OnInternalErrorHook>>,
+    /// Live sessions created through this service
+    sessions: sessions::SessionRegistry,
+    /// Broadcast of transport events
+    events: transport_events::TransportEvents,
+    /// Open legacy HTTP+SSE connections, keyed by session id
+    legacy_sse: legacy_sse::LegacySseConnections,
+    shutdown: shutdown::ShutdownSignal,
+    /// Number of progress notifications written to clients
+    forwarded_progress: Arc<AtomicU64>,
+}
+
+impl<S, M> Clone for AppData<S, M> {
+    fn clone(&self) -> Self {
+        Self {
+            service_factory: self.service_factory.clone(),
+            tenant_service_factory: self.tenant_service_factory.clone(),
+            session_manager: self.session_manager.clone(),
+            stateful_mode: self.stateful_mode,
+            hybrid_mode: self.hybrid_mode,
+            shared_service: self.shared_service.clone(),
+            reinitialize_policy: self.reinitialize_policy,
+            session_cookie: self.session_cookie.clone(),
+            session_id_query: self.session_id_query,
+            sse_keep_alive: self.sse_keep_alive,
+            sse_keep_alive_bounds: self.sse_keep_alive_bounds.clone(),
+            keep_alive_frame: self.keep_alive_frame.clone(),
+            request_timeout: self.request_timeout,
+            request_deadline_headers: self.request_deadline_headers,
+            initialize_timeout: self.initialize_timeout,
+            ping_interval: self.ping_interval,
+            ping_max_missed: self.ping_max_missed,
+            event_store: self.event_store.clone(),
+            event_ids: self.event_ids.clone(),
+            idempotency_window: self.idempotency_window,
+            discovery_cache_ttl: self.discovery_cache_ttl,
+            cacheable_tools: self.cacheable_tools.clone(),
+            response_cache: self.response_cache.clone(),
+            tool_toggles: self.tool_toggles.clone(),
+            msgpack: self.msgpack,
+            named_events: self.named_events,
+            payload_recorder: self.payload_recorder.clone(),
+            slow_consumer_policy: self.slow_consumer_policy,
+            slow_consumer_buffer: self.slow_consumer_buffer,
+            log_redaction: self.log_redaction.clone(),
+            on_request: self.on_request.clone(),
+            propagate_extensions: self.propagate_extensions.clone(),
+            snapshot_headers: self.snapshot_headers.clone(),
+            response_headers: self.response_headers,
+            blob_ttl: self.blob_ttl,
+            upload_ttl: self.upload_ttl,
+            session_extensions: self.session_extensions.clone(),
+            request_guard: self.request_guard.clone(),
+            require_api_key: self.require_api_key.clone(),
+            bind_session_identity: self.bind_session_identity,
+            #[cfg(feature = "signed-session-tokens")]
+            session_tokens: self.session_tokens.clone(),
+            allowed_origins: self.allowed_origins.clone(),
+            cors: self.cors,
+            trusted_proxies: self.trusted_proxies.clone(),
+            ip_filter: self.ip_filter.clone(),
+            session_creation_limit: self.session_creation_limit,
+            tenant_quotas: self.tenant_quotas.clone(),
+            tenant_counters: self.tenant_counters.clone(),
+            session_creations: self.session_creations.clone(),
+            max_sse_streams_per_client: self.max_sse_streams_per_client,
+            sse_connections: self.sse_connections.clone(),
+            max_pending_requests_per_session: self.max_pending_requests_per_session,
+            lazy_service_info: self.lazy_service_info.clone(),
+            handler_runtime: self.handler_runtime.clone(),
+            pending_requests: self.pending_requests.clone(),
+            transform_inbound: self.transform_inbound.clone(),
+            transform_outbound: self.transform_outbound.clone(),
+            mirror_logging: self.mirror_logging,
+            message_filter: self.message_filter.clone(),
+            tool_scopes: self.tool_scopes.clone(),
+            authorize: self.authorize.clone(),
+            on_session_created: self.on_session_created.clone(),
+            on_session_closed: self.on_session_closed.clone(),
+            on_internal_error: self.on_internal_error.clone(),
+            sessions: self.sessions.clone(),
+            events: self.events.clone(),
+            idempotency: self.idempotency.clone(),
+            blob_store: self.blob_store.clone(),
+            upload_store: self.upload_store.clone(),
+            legacy_sse: self.legacy_sse.clone(),
+            shutdown: self.shutdown.clone(),
+            forwarded_progress: self.forwarded_progress.clone(),
+        }
+    }
+}
+
+impl<S, M> AppData<S, M> {
+    /// Returns the pipeline server messages of `session_id` go through before being
+    /// written.
+    async fn outbound(&self, session_id: Option<&SessionId>) -> Outbound {
+        let protocol_version = match session_id {
+            Some(session_id) => self.sessions.protocol_version_cell(session_id).await,
+            None => None,
+        };
+        Outbound {
+            hook: self.transform_outbound.clone(),
+            forwarded_progress: self.forwarded_progress.clone(),
+            mirror_logging: self.mirror_logging,
+            session_id: session_id.cloned(),
+            protocol_version,
+        }
+    }
+
+    /// Checks the `MCP-Protocol-Version` header of a request, against the version
+    /// negotiated by `session_id` if given.
+    async fn check_protocol_version(
+        &self,
+        req: &HttpRequest,
+        session_id: Option<&SessionId>,
+    ) -> Option<HttpResponse> {
+        let negotiated = match session_id {
+            Some(session_id) => self.sessions.protocol_version(session_id).await,
+            None => None,
+        };
+        protocol_version::check_header(req, negotiated.as_ref())
+    }
+
+    /// Records what the initialize handshake of a session negotiated: the protocol
+    /// version accepted by `response`, and the capabilities of both sides. The result
+    /// itself is kept for [`ReinitializePolicy::Replay`].
+    ///
+    /// `client` holds the capabilities of the `initialize` request, captured before
+    /// the request was handed to the session manager.
+    async fn record_negotiation(
+        &self,
+        session_id: &SessionId,
+        client: Option<ClientCapabilities>,
+        response: &ServerJsonRpcMessage,
+    ) {
+        let ServerJsonRpcMessage::Response(response) = response else {
+            return;
+        };
+        let ServerResult::InitializeResult(result) = &response.result else {
+            return;
+        };
+        let version = result.protocol_version.clone();
+        tracing::debug!(%session_id, %version, "Protocol version negotiated");
+        self.sessions
+            .set_protocol_version(session_id, version)
+            .await;
+        let capabilities = SessionCapabilities {
+            client: client.unwrap_or_default(),
+            server: result.capabilities.clone(),
+        };
+        self.sessions
+            .set_capabilities(session_id, capabilities)
+            .await;
+        self.sessions
+            .set_initialize_result(session_id, result.clone())
+            .await;
+    }
+
+    /// Returns the tenant whose service instances serve `req`, if handlers are
+    /// per tenant.
+    fn request_tenant(&self, req: &HttpRequest) -> Option<TenantId> {
+        self.tenant_service_factory.as_ref()?;
+        req.extensions().get::<TenantId>().cloned()
+    }
+
+    /// Returns the factory creating service instances for `req`.
+    fn service_factory_for(&self, req: &HttpRequest) -> ServiceFactory<S>
+    where
+        S: 'static,
+    {
+        match (self.request_tenant(req), &self.tenant_service_factory) {
+            (Some(tenant), Some(factory)) => {
+                let factory = factory.clone();
+                Arc::new(move || factory(&tenant))
+            }
+            _ => self.service_factory.clone(),
+        }
+    }
+
+    /// Creates a service instance for the request `id`, carried by `req`.
+    ///
+    /// A factory failure is treated as transient: the error is logged and the client
+    /// receives `503 Service Unavailable` with a `Retry-After` header and a JSON-RPC
+    /// error body.
+    fn get_service(&self, req: &HttpRequest, id: Option<RequestId>) -> Result<S>
+    where
+        S: 'static,
+    {
+        (self.service_factory_for(req))().map_err(|e| {
+            tracing::error!("Service factory failed: {e}");
+            let error = ServerJsonRpcMessage::error(
+                ErrorData::new(ErrorCode::INTERNAL_ERROR, SERVICE_UNAVAILABLE_MESSAGE, None),
+                id,
+            );
+            let response = HttpResponse::ServiceUnavailable()
+                .content_type(JSON_MIME_TYPE)
+                .insert_header((
+                    header::RETRY_AFTER,
+                    header::HeaderValue::from(SERVICE_UNAVAILABLE_RETRY_AFTER_SECS),
+                ))
+                .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()));
+            InternalError::from_response(e, response).into()
+        })
+    }
+
+    /// Creates the service of a new session, deferring the service factory when a
+    /// lazy server info is configured.
+    fn session_service(
+        &self,
+        req: &HttpRequest,
+        id: Option<RequestId>,
+    ) -> Result<lazy_service::SessionService<S>>
+    where
+        S: 'static,
+    {
+        match &self.lazy_service_info {
+            Some(info) => Ok(lazy_service::SessionService::deferred(
+                self.service_factory_for(req),
+                info.clone(),
+            )),
+            None => self
+                .get_service(req, id)
+                .map(lazy_service::SessionService::ready),
+        }
+    }
+
+    /// Records that a session has been closed and runs the on_session_closed hook.
+    ///
+    /// Safe to call from every code path that may close a session: only the first
+    /// call for a given session has any effect.
+    async fn session_closed(&self, session_id: &SessionId, reason: SessionCloseReason) {
+        self.sessions
+            .close(
+                session_id,
+                reason,
+                self.on_session_closed.as_deref(),
+                &self.events,
+            )
+            .await;
+        if let Some(ref store) = self.event_store {
+            store.remove_session(session_id).await;
+        }
+        self.idempotency.remove_session(session_id);
+        self.blob_store.remove_session(session_id);
+        self.upload_store.remove_session(session_id);
+        if let Some(ref ids) = self.event_ids {
+            ids.remove_session(session_id);
+        }
+    }
+
+    /// Keep-alive interval for an SSE stream opened by `req`.
+    ///
+    /// Honors the interval requested by the client, clamped to the configured bounds,
+    /// when client overrides are enabled.
+    fn keep_alive_for(&self, req: &HttpRequest) -> Option<Duration> {
+        let Some(ref bounds) = self.sse_keep_alive_bounds else {
+            return self.sse_keep_alive;
+        };
+        let requested = req
+            .headers()
+            .get(HEADER_SSE_KEEP_ALIVE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned)
+            .or_else(|| {
+                web::Query::<std::collections::HashMap<String, String>>::from_query(
+                    req.query_string(),
+                )
+                .ok()?
+                .remove(KEEP_ALIVE_QUERY_PARAM)
+            })
+            .and_then(|secs| secs.trim().parse::<u64>().ok());
+        match requested {
+            Some(secs) => {
+                let keep_alive = Duration::from_secs(secs).clamp(*bounds.start(), *bounds.end());
+                tracing::debug!(?keep_alive, "Using client-requested SSE keep-alive");
+                Some(keep_alive)
+            }
+            None => self.sse_keep_alive,
+        }
+    }
+
+    /// Runs the request_guard hook, returning the rejection response if any.
+    fn check_request_guard(&self, req: &HttpRequest) -> Option<HttpResponse> {
+        if let Some(response) = self.check_client_ip(req) {
+            return Some(response);
+        }
+        if let Some(response) = self.check_origin(req) {
+            return Some(response);
+        }
+        if let Some(response) = self
+            .require_api_key
+            .as_ref()
+            .and_then(|auth| auth.check(req))
+        {
+            return Some(response);
+        }
+        let guard = self.request_guard.as_ref()?;
+        match guard(req) {
+            Ok(()) => None,
+            Err(response) => {
+                tracing::debug!(path = %req.path(), status = %response.status(), "Request rejected by guard");
+                Some(response)
+            }
+        }
+    }
+
+    /// Rejects requests from client addresses not allowed by `ip_filter`.
+    fn check_client_ip(&self, req: &HttpRequest) -> Option<HttpResponse> {
+        if self.ip_filter.is_empty() {
+            return None;
+        }
+        let client_ip = self.client_ip(req);
+        if self.ip_filter.is_allowed(client_ip) {
+            return None;
+        }
+        tracing::debug!(client = ?client_ip, "Request rejected: client address not allowed");
+        Some(
+            Problem::new(
+                StatusCode::FORBIDDEN,
+                "client_address_not_allowed",
+                "Client address not allowed",
+            )
+            .into_response(),
+        )
+    }
+
+    /// Returns the address of the client: the socket peer, or the address resolved
+    /// through `trusted_proxies` when set.
+    fn client_ip(&self, req: &HttpRequest) -> Option<std::net::IpAddr> {
+        match self.trusted_proxies {
+            Some(ref proxies) => proxies.client_ip(req),
+            None => req.peer_addr().map(|addr| addr.ip()),
+        }
+    }
+
+    /// Counts a session creation against `session_creation_limit`.
+    ///
+    /// A client over the limit receives `429 Too Many Requests` with a `Retry-After`
+    /// header and a JSON-RPC error body answering the request `id`.
+    fn admit_session_creation(&self, req: &HttpRequest, id: Option<RequestId>) -> Result<()> {
+        let Some(ref limit) = self.session_creation_limit else {
+            return Ok(());
+        };
+        let client_ip = self.client_ip(req);
+        let Err(retry_after) = self.session_creations.admit(client_ip, limit) else {
+            return Ok(());
+        };
+        tracing::warn!(client = ?client_ip, ?retry_after, "Session creation rate limited");
+        let error = ServerJsonRpcMessage::error(
+            ErrorData::new(
+                ErrorCode::INTERNAL_ERROR,
+                SESSION_RATE_LIMITED_MESSAGE,
+                None,
+            ),
+            id,
+        );
+        // Rounded up, so clients do not retry before the window resets
+        let retry_after_secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+        let response = HttpResponse::TooManyRequests()
+            .content_type(JSON_MIME_TYPE)
+            .insert_header((
+                header::RETRY_AFTER,
+                header::HeaderValue::from(retry_after_secs.max(1)),
+            ))
+            .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()));
+        Err(InternalError::from_response(SESSION_RATE_LIMITED_MESSAGE, response).into())
+    }
+
+    /// Checks a new session of the tenant of `req` against its session cap.
+    ///
+    /// A tenant at its cap receives `403 Forbidden` with a JSON-RPC error body
+    /// answering the request `id`.
+    async fn admit_tenant_session(&self, req: &HttpRequest, id: Option<RequestId>) -> Result<()> {
+        let Some(tenant) = req.extensions().get::<TenantId>().cloned() else {
+            return Ok(());
+        };
+        let Some(quota) = self.tenant_quotas.as_ref().and_then(|q| q.quota(&tenant)) else {
+            return Ok(());
+        };
+        let open = self.sessions.tenant_sessions(&tenant).await;
+        if self.tenant_counters.admit_session(&tenant, quota, open) {
+            return Ok(());
+        }
+        tracing::warn!(tenant = %tenant.0, open, "Session quota of tenant exhausted");
+        let response = tenant_quotas::session_quota_response(&tenant, id);
+        Err(InternalError::from_response("Session quota of tenant exhausted", response).into())
+    }
+
+    /// Counts the request `message` of `req` against the request rate of its tenant,
+    /// returning the `429 Too Many Requests` response if the tenant is over it.
+    fn admit_tenant_request(
+        &self,
+        req: &HttpRequest,
+        message: &ClientJsonRpcMessage,
+    ) -> Option<HttpResponse> {
+        let ClientJsonRpcMessage::Request(request) = message else {
+            return None;
+        };
+        let tenant = req.extensions().get::<TenantId>().cloned()?;
+        let quota = self.tenant_quotas.as_ref()?.quota(&tenant)?;
+        let retry_after = self.tenant_counters.admit_request(&tenant, quota).err()?;
+        tracing::warn!(tenant = %tenant.0, ?retry_after, "Request rate of tenant exceeded");
+        Some(tenant_quotas::rate_limited_response(
+            &tenant,
+            request.id.clone(),
+            retry_after,
+        ))
+    }
+
+    /// Counts a new long-lived SSE stream against `max_sse_streams_per_client`,
+    /// returning the `429 Too Many Requests` response if the client is at the cap.
+    fn acquire_sse_connection(
+        &self,
+        req: &HttpRequest,
+    ) -> std::result::Result<sse_connections::SseConnection, HttpResponse> {
+        let client_ip = self.client_ip(req);
+        self.sse_connections
+            .acquire(client_ip, self.max_sse_streams_per_client)
+            .ok_or_else(|| {
+                tracing::warn!(client = ?client_ip, "Too many open SSE streams");
+                Problem::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "too_many_streams",
+                    "Too many open SSE streams from this address",
+                )
+                .into_response()
+            })
+    }
+
+    /// Counts a request of `session_id` against `max_pending_requests_per_session`,
+    /// returning the `429 Too Many Requests` response if the session is saturated.
+    fn acquire_pending_request(
+        &self,
+        session_id: &SessionId,
+        request_id: &RequestId,
+    ) -> std::result::Result<session_backpressure::PendingRequest, HttpResponse> {
+        self.pending_requests
+            .acquire(session_id, self.max_pending_requests_per_session)
+            .ok_or_else(|| {
+                tracing::warn!(%session_id, %request_id, "Too many pending requests in session");
+                session_backpressure::session_busy_response(request_id.clone())
+            })
+    }
+
+    /// Rejects requests whose `Origin` header is not in `allowed_origins`.
+    fn check_origin(&self, req: &HttpRequest) -> Option<HttpResponse> {
+        if self.allowed_origins.is_empty() {
+            return None;
+        }
+        let origin = req.headers().get(header::ORIGIN)?;
+        let allowed = origin
+            .to_str()
+            .is_ok_and(|origin| cors::origin_allowed(&self.allowed_origins, origin));
+        if allowed {
+            return None;
+        }
+        tracing::debug!(?origin, "Request rejected: origin not allowed");
+        Some(
+            Problem::new(
+                StatusCode::FORBIDDEN,
+                "origin_not_allowed",
+                "Origin not allowed",
+            )
+            .into_response(),
+        )
+    }
+
+    /// Runs the transform_inbound hook on a freshly parsed client message.
+    fn transform_inbound(&self, req: &HttpRequest, message: &mut ClientJsonRpcMessage) {
+        if let Some(ref hook) = self.transform_inbound {
+            hook(req, message);
+        }
+    }
+
+    /// Runs the on_session_created hook for an `initialize` request, if any.
+    fn notify_session_created(
+        &self,
+        req: &HttpRequest,
+        session_id: &SessionId,
+        message: &ClientJsonRpcMessage,
+    ) {
+        if let Some(ref hook) = self.on_session_created
+            && let Some(params) = initialize_params(message)
+        {
+            hook(req, session_id, params);
+        }
+    }
+
+    /// Inserts a fresh [`ResponseHeaders`] handle into `extensions` if handlers may set
+    /// response headers.
+    fn attach_response_headers(
+        &self,
+        extensions: &mut rmcp::model::Extensions,
+    ) -> Option<ResponseHeaders> {
+        let headers = self.response_headers.then(ResponseHeaders::default)?;
+        extensions.insert(headers.clone());
+        Some(headers)
+    }
+
+    /// Inserts a fresh [`RequestCancellation`] into the extensions of a request and
+    /// returns it.
+    ///
+    /// The signal is derived from the session's, or from the service's in stateless
+    /// mode, so closing the session or stopping the service cancels it.
+    async fn attach_request_cancellation(
+        &self,
+        session_id: Option<&SessionId>,
+        extensions: &mut rmcp::model::Extensions,
+    ) -> RequestCancellation {
+        let token = match session_id {
+            Some(session_id) => self.sessions.child_token(session_id).await,
+            None => None,
+        };
+        let cancellation =
+            RequestCancellation::new(token.unwrap_or_else(|| self.shutdown.child_token()));
+        extensions.insert(cancellation.clone());
+        cancellation
+    }
+
+    /// Inserts the [`RequestDeadline`] of a request into its extensions and returns
+    /// the request's timeout: the `request_timeout`, shortened by the budget the
+    /// client announced if deadline headers are honored.
+    fn attach_request_deadline(
+        &self,
+        req: &HttpRequest,
+        extensions: &mut rmcp::model::Extensions,
+    ) -> Option<Duration> {
+        let announced = self
+            .request_deadline_headers
+            .then(|| request_deadline::announced_budget(req))
+            .flatten();
+        let timeout = self.request_timeout.into_iter().chain(announced).min()?;
+        if let Some(deadline) = std::time::Instant::now().checked_add(timeout) {
+            extensions.insert(RequestDeadline::new(deadline));
+        }
+        Some(timeout)
+    }
+
+    /// Applies the `slow_consumer_policy` to a stream about to be sent to a client.
+    fn pace_for_consumer<St>(&self, stream: St) -> BoxStream<'static, St::Item>
+    where
+        St: Stream + Send + 'static,
+        St::Item: slow_consumer::StreamItem,
+    {
+        slow_consumer::apply(stream, self.slow_consumer_policy, self.slow_consumer_buffer)
+    }
+
+    /// Inserts the [`BlobLinks`] and [`Uploads`] handles into the extensions of a
+    /// request POSTed to the MCP endpoint, when blob downloads and uploads are
+    /// enabled.
+    fn attach_blob_handles(
+        &self,
+        req: &HttpRequest,
+        session_id: Option<&SessionId>,
+        extensions: &mut rmcp::model::Extensions,
+    ) {
+        if let Some(ttl) = self.blob_ttl {
+            let info = req.connection_info();
+            let base_url = format!("{}://{}{}/blobs", info.scheme(), info.host(), req.path());
+            extensions.insert(BlobLinks::new(
+                self.blob_store.clone(),
+                session_id.cloned(),
+                base_url,
+                ttl,
+            ));
+        }
+        if self.upload_ttl.is_some() {
+            extensions.insert(Uploads::new(self.upload_store.clone(), session_id.cloned()));
+        }
+    }
+
+    /// Returns the session id sent with `req`, in `Mcp-Session-Id` or else in the
+    /// session cookie.
+    fn request_session_id<'a>(&self, req: &'a HttpRequest) -> Option<&'a str> {
+        header_session_id(req).or_else(|| self.cookie_session_id(req))
+    }
+
+    /// Returns the session id in the query string of `req`, if accepted there.
+    fn query_session_id(&self, req: &HttpRequest) -> Option<String> {
+        if !self.session_id_query {
+            return None;
+        }
+        web::Query::<std::collections::HashMap<String, String>>::from_query(req.query_string())
+            .ok()?
+            .remove(SESSION_ID_QUERY_PARAM)
+            .filter(|s| !s.is_empty())
+    }
+
+    /// Returns the session id of the session cookie sent with `req`, if configured.
+    fn cookie_session_id<'a>(&self, req: &'a HttpRequest) -> Option<&'a str> {
+        self.session_cookie.as_ref()?.read(req)
+    }
+
+    /// Sends `session_id` to the client, in `Mcp-Session-Id` and the session cookie.
+    fn announce_session_id(
+        &self,
+        req: &HttpRequest,
+        response: &mut actix_web::HttpResponseBuilder,
+        session_id: &str,
+    ) {
+        response.append_header((HEADER_SESSION_ID, session_id));
+        if let Some(cookie) = self.session_cookie.as_deref() {
+            cookie.set(req, response, session_id);
+        }
+    }
+
+    /// Returns `true` if `req` may use `session_id`: always, unless sessions are bound
+    /// to identities or tenants and the session was created by a different one.
+    async fn session_identity_matches(&self, req: &HttpRequest, session_id: &SessionId) -> bool {
+        if self.tenant_service_factory.is_some() {
+            let tenant = req.extensions().get::<TenantId>().cloned();
+            if !self
+                .sessions
+                .tenant_matches(session_id, tenant.as_ref())
+                .await
+            {
+                tracing::warn!(%session_id, "Session used by a different tenant than its creator");
+                return false;
+            }
+        }
+        if !self.bind_session_identity {
+            return true;
+        }
+        let identity = req.extensions().get::<AuthenticatedIdentity>().cloned();
+        let matches = self
+            .sessions
+            .identity_matches(session_id, identity.as_ref())
+            .await;
+        if !matches {
+            tracing::warn!(%session_id, "Session used by a different identity than its creator");
+        }
+        matches
+    }
+
+    /// Resolves the session of a stateless request.
+    ///
+    /// Without session tokens, any `Mcp-Session-Id` is ignored. With them, `initialize`
+    /// requests are issued a new token, returned to be sent back, and other requests
+    /// must present a valid one. The decoded token is added to the request extensions.
+    #[cfg_attr(not(feature = "signed-session-tokens"), allow(unused_variables))]
+    fn stateless_session(
+        &self,
+        req: &HttpRequest,
+        message: &mut ClientJsonRpcMessage,
+    ) -> Result<Option<String>, HttpResponse> {
+        let session_id = self.request_session_id(req);
+
+        #[cfg(feature = "signed-session-tokens")]
+        if let Some(tokens) = self.session_tokens.as_ref() {
+            let identity = req.extensions().get::<AuthenticatedIdentity>().cloned();
+            let is_initialize = matches!(
+                message,
+                ClientJsonRpcMessage::Request(request_msg)
+                    if matches!(request_msg.request, ClientRequest::InitializeRequest(_))
+            );
+            let (issued, session) = if is_initialize {
+                let (token, session) = tokens.issue(identity);
+                tracing::debug!(session_id = %session.id, "Issued stateless session token");
+                (Some(token), session)
+            } else {
+                let Some(token) = session_id else {
+                    return Err(missing_session_id_response());
+                };
+                match tokens.verify(token) {
+                    Some(session) if session.identity == identity => (None, session),
+                    Some(session) => {
+                        tracing::warn!(session_id = %session.id, "Session token used by a different identity than its creator");
+                        return Err(session_not_found_response(None));
+                    }
+                    None => {
+                        tracing::warn!("Invalid or expired session token");
+                        return Err(session_not_found_response(None));
+                    }
+                }
+            };
+            if let ClientJsonRpcMessage::Request(request_msg) = message {
+                request_msg.request.extensions_mut().insert(session);
+            }
+            return Ok(issued);
+        }
+
+        if session_id.is_some() {
+            tracing::debug!("Mcp-Session-Id header ignored in stateless mode");
+        }
+        Ok(None)
+    }
+
+    /// Runs the session_extensions hook for an `initialize` request and stores the
+    /// result with the session.
+    ///
+    /// The captured extensions are also added to the `initialize` request itself.
+    async fn capture_session_extensions(
+        &self,
+        req: &HttpRequest,
+        session_id: &SessionId,
+        message: &mut ClientJsonRpcMessage,
+    ) {
+        if let Some(ref hook) = self.session_extensions
+            && let ClientJsonRpcMessage::Request(request_msg) = message
+        {
+            let mut sticky = rmcp::model::Extensions::new();
+            hook(req, &mut sticky);
+            request_msg.request.extensions_mut().extend(sticky.clone());
+            self.sessions.set_extensions(session_id, sticky).await;
+        }
+    }
+
+    /// Injects the negotiated [`SessionCapabilities`] and the sticky extensions of
+    /// a session into a JSON-RPC request.
+    async fn apply_session_extensions(
+        &self,
+        session_id: &SessionId,
+        extensions: &mut rmcp::model::Extensions,
+    ) {
+        if let Some(capabilities) = self.sessions.capabilities(session_id).await {
+            extensions.insert(capabilities);
+        }
+        if self.session_extensions.is_none() {
+            return;
+        }
+        if let Some(sticky) = self.sessions.extensions(session_id).await {
+            extensions.extend(sticky);
+        }
+    }
+
+    /// Propagates HTTP-level context into the extensions of a JSON-RPC request.
+    ///
+    /// Inserts the [`ClientConnectionInfo`] and [`HeaderSnapshot`], copies the
+    /// allow-listed actix-web extensions, runs the `on_request` hook, then forwards the
+    /// Authorization header when the `authorization-token-passthrough` feature is
+    /// enabled. `context` describes the code path in log messages (e.g. "for new
+    /// session").
+    fn propagate_request_context(
+        &self,
+        req: &HttpRequest,
+        extensions: &mut rmcp::model::Extensions,
+        context: &str,
+    ) {
+        let mut connection = ClientConnectionInfo::from_request(req);
+        if let Some(ref proxies) = self.trusted_proxies {
+            connection.realip_remote_addr = proxies.client_ip(req).map(|ip| ip.to_string());
+        }
+        tracing::debug!(client = ?connection.realip_remote_addr, "Request received {context}");
+        extensions.insert(connection);
+        if !self.snapshot_headers.is_empty() {
+            extensions.insert(HeaderSnapshot::capture(req, &self.snapshot_headers));
+        }
+        self.propagate_extensions.copy(req, extensions);
+
+        // Call on_request hook to propagate extensions from HttpRequest
+        if let Some(ref hook) = self.on_request {
+            hook(req, extensions);
+        }
+
+        // Extract and inject Authorization header if present.
+        //
+        // SECURITY: This transport forwards Authorization headers to MCP services.
+        //
+        // MCP-COMPLIANT USAGE: MCP services MUST validate these tokens as intended for themselves
+        // and MUST NOT forward them to upstream APIs (per MCP specification).
+        //
+        // NON-COMPLIANT USAGE: Some implementations (e.g., rmcp-openapi-server) use these tokens
+        // for upstream API authentication. This violates MCP specifications but may be necessary
+        // for certain proxy architectures. Use with caution and ensure proper token audience validation.
+        // See SECURITY.md for details.
+        //
+        // Supports OAuth 2.1 token rotation patterns by forwarding each request's
+        // Authorization independently. This enables:
+        // - Token rotation within sessions (security best practice)
+        // - Token refresh when access tokens expire
+        // - Scope changes for different operations within the same session
+        //
+        // The proxy does NOT cache or reuse tokens from session initialization.
+        // Each request must provide its own valid Authorization header. The only
+        // exception is the legacy SSE transport, where the token presented when
+        // opening the event stream covers messages posted without one.
+        #[cfg(feature = "authorization-token-passthrough")]
+        if let Some(authorization) = bearer_authorization(req, context) {
+            extensions.insert(authorization);
+        }
+
+        #[cfg(not(feature = "authorization-token-passthrough"))]
+        if req.headers().get(header::AUTHORIZATION).is_some() {
+            tracing::warn!(
+                "Authorization header present but not forwarded {context}. \
+                 Enable 'authorization-token-passthrough' feature to forward tokens to MCP services. \
+                 Note: Token passthrough violates MCP specifications. See SECURITY.md for details."
+            );
+        }
+    }
+}
+
+impl<S, M> AppData<S, M>
+where
+    S: rmcp::Service<RoleServer> + Send + 'static,
+    M: SessionManager + 'static,
+{
+    /// Spawns the task serving a newly created session.
+    ///
+    /// The task runs the MCP service over the session transport until either side
+    /// terminates, then closes the session in the session manager. Closing the
+    /// session cancels the service, and with it every in-flight handler.
+    async fn spawn_session(
+        &self,
+        req: &HttpRequest,
+        session_id: SessionId,
+        transport: M::Transport,
+        service_instance: lazy_service::SessionService<S>,
+    ) {
+        let identity = self
+            .bind_session_identity
+            .then(|| req.extensions().get::<AuthenticatedIdentity>().cloned())
+            .flatten();
+        let tenant = req.extensions().get::<TenantId>().cloned();
+        let cancellation = self
+            .sessions
+            .insert(session_id.clone(), identity, tenant.clone())
+            .await;
+        session_log::created(&session_id, tenant.as_ref());
+        self.events.emit(|| TransportEvent::SessionCreated {
+            session_id: session_id.clone(),
+        });
+        if let Some(timeout) = self.initialize_timeout {
+            self.close_if_never_initialized(session_id.clone(), timeout);
+        }
+        let cache_tenant = self.request_tenant(req);
+        let app_data = self.clone();
+        tokio::spawn(async move {
+            let transport =
+                session_cancellation::CancelOnClose::new(transport, cancellation.clone());
+            let transport = response_cache::InvalidateOnChange::new(
+                transport,
+                app_data.response_cache.clone(),
+                cache_tenant,
+            );
+            let transport =
+                tool_toggles::HideDisabledTools::new(transport, app_data.tool_toggles.clone());
+            let service_instance = tenant_quotas::ToolCallQuota::new(
+                error_reports::ReportErrors::new(
+                    handler_runtime::OnHandlerRuntime::new(
+                        service_instance,
+                        app_data.handler_runtime.clone(),
+                    ),
+                    Some(session_id.clone()),
+                    app_data.on_internal_error.clone(),
+                ),
+                tenant,
+                app_data.tenant_quotas.as_deref(),
+                &app_data.tenant_counters,
+            );
+            let service = serve_server_with_ct::<_, _, _, TransportAdapterIdentity>(
+                service_instance,
+                transport,
+                cancellation,
+            )
+            .await;
+            let reason = match service {
+                Ok(service) => {
+                    app_data
+                        .sessions
+                        .set_peer(&session_id, service.peer().clone())
+                        .await;
+                    match service.waiting().await {
+                        Ok(rmcp::service::QuitReason::Closed) => {
+                            SessionCloseReason::TransportClosed
+                        }
+                        Ok(_) | Err(_) => SessionCloseReason::ServiceStopped,
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to create service: {e}");
+                    SessionCloseReason::InitializationFailed
+                }
+            };
+            let _ = app_data
+                .session_manager
+                .close_session(&session_id)
+                .await
+                .inspect_err(|e| {
+                    tracing::error!("Failed to close session {session_id}: {e}");
+                });
+            app_data.legacy_sse.remove(&session_id).await;
+            app_data.session_closed(&session_id, reason).await;
+        });
+    }
+
+    /// Closes the session if its client has not followed up on `initialize` after
+    /// `timeout`.
+    fn close_if_never_initialized(&self, session_id: SessionId, timeout: Duration) {
+        let app_data = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(timeout).await;
+            if !app_data.sessions.is_half_open(&session_id).await {
+                return;
+            }
+            tracing::info!(%session_id, ?timeout, "Closing session that never completed initialization");
+            app_data
+                .close_session(&session_id, SessionCloseReason::InitializeTimeout)
+                .await;
+        });
+    }
+
+    /// Closes a session on the transport's own initiative.
+    async fn close_session(&self, session_id: &SessionId, reason: SessionCloseReason) {
+        self.session_closed(session_id, reason).await;
+        self.legacy_sse.remove(session_id).await;
+        let _ = self
+            .session_manager
+            .close_session(session_id)
+            .await
+            .inspect_err(|e| {
+                tracing::error!("Failed to close session {session_id}: {e}");
+            });
+    }
+
+    /// Interleaves liveness pings into the standalone stream of a session.
+    ///
+    /// The stream ends once the session is closed for missing too many pings.
+    fn ping_standalone<St>(
+        &self,
+        session_id: SessionId,
+        stream: St,
+    ) -> BoxStream<'static, ServerSseMessage>
+    where
+        St: Stream<Item = ServerSseMessage> + Send + 'static,
+    {
+        let Some(interval) = self.ping_interval else {
+            return stream.boxed();
+        };
+        let app_data = self.clone();
+        async_stream::stream! {
+            let mut stream = Box::pin(stream);
+            let mut timer = tokio::time::interval(interval);
+            // Consume the immediate first tick
+            timer.tick().await;
+            loop {
+                tokio::select! {
+                    item = stream.next() => match item {
+                        Some(item) => yield item,
+                        None => break,
+                    },
+                    _ = timer.tick() => {
+                        match app_data.sessions.next_ping(&session_id, app_data.ping_max_missed).await {
+                            liveness::PingOutcome::Send(id) => {
+                                yield ServerSseMessage::from_message(liveness::ping_request(id));
+                            }
+                            liveness::PingOutcome::Unresponsive => {
+                                tracing::info!(%session_id, "Closing session that stopped answering pings");
+                                app_data
+                                    .close_session(&session_id, SessionCloseReason::Unresponsive)
+                                    .await;
+                                break;
+                            }
+                            liveness::PingOutcome::Closed => break,
+                        }
+                    }
+                }
+            }
+        }
+        .boxed()
+    }
+
+    /// Records the events written to a stream of a session in the event store, if
+    /// one is configured.
+    ///
+    /// Messages without an event id cannot be resumed from and are not recorded.
+    fn record_events<St>(
+        &self,
+        session_id: SessionId,
+        stream_id: String,
+        stream: St,
+    ) -> BoxStream<'static, ServerSseMessage>
+    where
+        St: Stream<Item = ServerSseMessage> + Send + 'static,
+    {
+        let Some(store) = self.event_store.clone() else {
+            return stream.boxed();
+        };
+        stream
+            .then(move |msg| {
+                let store = store.clone();
+                let session_id = session_id.clone();
+                let stream_id = stream_id.clone();
+                async move {
+                    if let (Some(event_id), Some(message)) = (&msg.event_id, &msg.message) {
+                        let event = StoredEvent::new(stream_id, event_id.clone(), message.clone());
+                        if let Err(e) = store.append(&session_id, event).await {
+                            tracing::warn!(%session_id, %event_id, "Failed to record event: {e}");
+                        }
+                    }
+                    msg
+                }
+            })
+            .boxed()
+    }
+
+    /// Replaces the event ids of a stream of a session with generated ones, if an
+    /// event id generator is configured.
+    fn rename_events<St>(
+        &self,
+        session_id: &SessionId,
+        stream: St,
+    ) -> BoxStream<'static, ServerSseMessage>
+    where
+        St: Stream<Item = ServerSseMessage> + Send + 'static,
+    {
+        match self.event_ids {
+            Some(ref ids) => ids.rename(session_id.clone(), stream),
+            None => stream.boxed(),
+        }
+    }
+
+    /// Resumes a stream of a session after `last_event_id`.
+    ///
+    /// With an event store that knows `last_event_id`, the missed events are
+    /// replayed from the store and the session manager only provides the live
+    /// remainder of the stream; if it can no longer resume the stream (e.g. the
+    /// request has completed), the replay alone is sent. Otherwise the session
+    /// manager answers the resumption on its own.
+    async fn resume_stream(
+        &self,
+        session_id: &SessionId,
+        last_event_id: String,
+    ) -> Result<BoxStream<'static, ServerSseMessage>> {
+        let replay = match self.event_store {
+            Some(ref store) => store
+                .replay(session_id, &last_event_id)
+                .await
+                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?,
+            None => None,
+        };
+        let Some(replay) = replay else {
+            let stream = self
+                .session_manager
+                .resume(session_id, last_event_id)
+                .await
+                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            return Ok(stream.boxed());
+        };
+
+        tracing::debug!(
+            %session_id,
+            stream_id = %replay.stream_id,
+            events = replay.events.len(),
+            "Replaying events from the event store"
+        );
+        // The session manager may send events the client already has again, so
+        // these are dropped from the live stream
+        let delivered: HashSet<String> = replay
+            .events
+            .iter()
+            .map(|event| event.event_id.clone())
+            .chain([last_event_id.clone()])
+            .collect();
+        let resume_from = replay
+            .events
+            .last()
+            .map_or(last_event_id, |event| event.event_id.clone());
+        let live = match self.session_manager.resume(session_id, resume_from).await {
+            Ok(stream) => {
+                let stream = stream.filter(move |msg| {
+                    let duplicate = msg
+                        .event_id
+                        .as_ref()
+                        .is_some_and(|id| delivered.contains(id));
+                    std::future::ready(!duplicate)
+                });
+                self.record_events(session_id.clone(), replay.stream_id, stream)
+            }
+            Err(e) => {
+                tracing::debug!(%session_id, "Stream cannot be resumed live, sending the replay only: {e}");
+                futures::stream::empty().boxed()
+            }
+        };
+        let replayed = futures::stream::iter(replay.events.into_iter().map(|event| {
+            let mut msg = ServerSseMessage::default();
+            msg.event_id = Some(event.event_id);
+            msg.message = Some(event.message);
+            msg
+        }));
+        Ok(replayed.chain(live).boxed())
+    }
+
+    /// Answers a duplicate delivery of `request_id` with the response of the
+    /// original request, once it exists.
+    async fn answer_duplicate(
+        &self,
+        req: &HttpRequest,
+        session_id: &SessionId,
+        response: tokio::sync::watch::Receiver<idempotency::ResponseSlot>,
+        request_id: RequestId,
+        in_flight: shutdown::InFlight,
+    ) -> HttpResponse {
+        let outbound = self.outbound(Some(session_id)).await;
+        let encoding = PayloadEncoding::negotiate(req, self.msgpack);
+        let named_events = self.named_events;
+        let formatted_stream = idempotency::replay(response, request_id).map(move |msg| {
+            let message = msg.message.as_deref().map(|m| outbound.apply(m));
+            Ok::<_, actix_web::Error>(format_sse_event(
+                encoding,
+                named_events,
+                None,
+                message.as_deref(),
+            ))
+        });
+        let sse_stream = wrap_with_sse_keepalive(
+            in_flight.hold(formatted_stream),
+            self.keep_alive_for(req),
+            self.keep_alive_frame.clone(),
+        );
+        let mut http_response = HttpResponse::Ok();
+        http_response
+            .content_type(EVENT_STREAM_MIME_TYPE)
+            .append_header((CACHE_CONTROL, "no-cache"))
+            .append_header((HEADER_X_ACCEL_BUFFERING, "no"));
+        encoding.announce(&mut http_response);
+        http_response.streaming(sse_stream)
+    }
+
+    /// Looks up a request of `req` in the cache, if it is a discovery request and
+    /// discovery caching is enabled, or a call of one of the `cacheable_tools`.
+    ///
+    /// Results are kept per tenant when handlers are per tenant.
+    fn lookup_cached(
+        &self,
+        req: &HttpRequest,
+        request: &ClientRequest,
+        request_id: &RequestId,
+    ) -> Option<response_cache::Lookup> {
+        let tenant = self.request_tenant(req);
+        let (key, ttl) = match request {
+            ClientRequest::CallToolRequest(call) => (
+                response_cache::CacheKey::tool_call(tenant, &call.params),
+                self.cacheable_tools.ttl(&call.params.name)?,
+            ),
+            request => (
+                response_cache::CacheKey::discovery(tenant, request)?,
+                self.discovery_cache_ttl?,
+            ),
+        };
+        Some(self.response_cache.lookup(key, request_id.clone(), ttl))
+    }
+
+    /// Answers an `initialize` request sent within the existing session `session_id`,
+    /// with the original result under [`ReinitializePolicy::Replay`] and an error
+    /// otherwise.
+    async fn answer_reinitialize(
+        &self,
+        req: &HttpRequest,
+        session_id: &SessionId,
+        request_id: RequestId,
+        in_flight: shutdown::InFlight,
+    ) -> HttpResponse {
+        if self.reinitialize_policy == ReinitializePolicy::Replay
+            && let Some(result) = self.sessions.initialize_result(session_id).await
+        {
+            tracing::debug!(%session_id, "Replaying initialize result of existing session");
+            let response =
+                ServerJsonRpcMessage::response(ServerResult::InitializeResult(result), request_id);
+            return self
+                .answer_from_cache(req, Some(session_id), None, &response, in_flight)
+                .await;
+        }
+        tracing::warn!(%session_id, "Refusing initialize request in existing session");
+        reinitialize::already_initialized_response(request_id)
+    }
+
+    /// Closes the session an `initialize` request was sent within, before a new one is
+    /// created for it under [`ReinitializePolicy::Recreate`].
+    ///
+    /// Sessions that do not exist or belong to another identity are left alone.
+    async fn close_reinitialized_session(
+        &self,
+        req: &HttpRequest,
+        session_id: &SessionId,
+    ) -> Result<()> {
+        let has_session = self
+            .session_manager
+            .has_session(session_id)
+            .await
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        if !has_session || !self.session_identity_matches(req, session_id).await {
+            return Ok(());
+        }
+        tracing::info!(%session_id, "Closing session re-initialized by its client");
+        self.session_closed(session_id, SessionCloseReason::Reinitialized)
+            .await;
+        self.session_manager
+            .close_session(session_id)
+            .await
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+        Ok(())
+    }
+
+    /// Answers a request with its cached `response`.
+    ///
+    /// `issued_token` is the session token issued to a stateless client, if any.
+    async fn answer_from_cache(
+        &self,
+        req: &HttpRequest,
+        session_id: Option<&SessionId>,
+        issued_token: Option<String>,
+        response: &ServerJsonRpcMessage,
+        in_flight: shutdown::InFlight,
+    ) -> HttpResponse {
+        let outbound = self.outbound(session_id).await;
+        let encoding = PayloadEncoding::negotiate(req, self.msgpack);
+        let event = format_sse_event(
+            encoding,
+            self.named_events,
+            None,
+            Some(&outbound.apply(response)),
+        );
+        let sse_stream = in_flight
+            .hold(futures::stream::once(std::future::ready(event)))
+            .map(Ok::<_, actix_web::Error>);
+        let mut http_response = HttpResponse::Ok();
+        http_response
+            .content_type(EVENT_STREAM_MIME_TYPE)
+            .append_header((CACHE_CONTROL, "no-cache"))
+            .append_header((HEADER_X_ACCEL_BUFFERING, "no"));
+        if let Some(token) = issued_token {
+            self.announce_session_id(req, &mut http_response, &token);
+        }
+        encoding.announce(&mut http_response);
+        http_response.streaming(sse_stream)
+    }
+
+    /// Applies `timeout` to the stream answering `request_id` in a session.
+    ///
+    /// On timeout, the request is cancelled in the session and the stream ends with
+    /// a timeout error. The request is also cancelled if the stream is dropped early
+    /// because the client disconnected, unless it can be resumed. Either way,
+    /// `cancellation` is cancelled as well.
+    fn limit_session_request<St>(
+        &self,
+        session_id: SessionId,
+        request_id: RequestId,
+        cancellation: RequestCancellation,
+        timeout: Option<Duration>,
+        stream: St,
+    ) -> BoxStream<'static, ServerSseMessage>
+    where
+        St: Stream<Item = ServerSseMessage> + Send + 'static,
+    {
+        let session_manager = self.session_manager.clone();
+        let stream = request_timeout::with_timeout(stream, timeout, {
+            let session_id = session_id.clone();
+            let request_id = request_id.clone();
+            let cancellation = cancellation.clone();
+            move |timeout| async move {
+                cancellation.cancel();
+                let _ = session_manager
+                    .accept_message(
+                        &session_id,
+                        request_timeout::cancellation(request_id.clone(), "Request timed out"),
+                    )
+                    .await
+                    .inspect_err(|e| {
+                        tracing::error!("Failed to cancel timed out request in {session_id}: {e}");
+                    });
+                ServerSseMessage::from_message(request_timeout::timeout_error(request_id, timeout))
+            }
+        });
+
+        let session_manager = self.session_manager.clone();
+        disconnect::on_disconnect(
+            stream,
+            |msg| msg.event_id.is_some(),
+            move || {
+                tracing::debug!(%session_id, %request_id, "Client disconnected, cancelling request");
+                cancellation.cancel();
+                tokio::spawn(async move {
+                    let _ = session_manager
+                        .accept_message(
+                            &session_id,
+                            request_timeout::cancellation(request_id, "Client disconnected"),
+                        )
+                        .await
+                        .inspect_err(|e| {
+                            tracing::debug!(
+                                "Failed to cancel abandoned request in {session_id}: {e}"
+                            );
+                        });
+                });
+            },
+        )
+    }
+}
+
+/// Returns the non-empty `Mcp-Session-Id` of `req`.
+fn header_session_id(req: &HttpRequest) -> Option<&str> {
+    req.headers()
+        .get(HEADER_SESSION_ID)
+        .and_then(|v| v.to_str().ok())
+        .filter(|s| !s.is_empty())
+}
+
+/// Returns the parameters of `message` if it is an `initialize` request.
+fn initialize_params(message: &ClientJsonRpcMessage) -> Option<&InitializeRequestParams> {
+    match message {
+        ClientJsonRpcMessage::Request(request_msg) => match &request_msg.request {
+            ClientRequest::InitializeRequest(initialize) => Some(&initialize.params),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Extracts a Bearer token from the Authorization header of `req`.
+///
+/// `context` describes the code path in log messages.
+#[cfg(feature = "authorization-token-passthrough")]
+fn bearer_authorization(req: &HttpRequest, context: &str) -> Option<AuthorizationHeader> {
+    let auth_value = req.headers().get(header::AUTHORIZATION)?;
+    match auth_value.to_str() {
+        Ok(auth_str) if auth_str.starts_with("Bearer ") && auth_str.len() > 7 => {
+            tracing::debug!(
+                "Forwarding Authorization header to MCP service {context}. \
+                 Note: MCP services must not pass this token to upstream APIs per MCP spec. \
+                 See SECURITY.md for details."
+            );
+            Some(AuthorizationHeader(auth_str.to_string()))
+        }
+        Ok(auth_str) if auth_str == "Bearer" || auth_str == "Bearer " => {
+            tracing::debug!("Malformed Bearer token {context}: missing token value");
+            None
+        }
+        Ok(auth_str) => {
+            let auth_type = auth_str.split_whitespace().next().unwrap_or("unknown");
+            tracing::warn!("Non-Bearer authorization header ignored {context}: {auth_type}");
+            None
+        }
+        Err(e) => {
+            tracing::debug!("Invalid Authorization header encoding {context}: {e}");
+            None
+        }
+    }
+}
+
+// SSE Stream Helper Functions
+//
+// These functions provide reusable SSE keep-alive functionality to avoid code duplication.
+
+/// Serialize a `ServerSseMessage` as a single SSE event.
+///
+/// Priming events ([SEP-1699](https://github.com/modelcontextprotocol/modelcontextprotocol/issues/1699))
+/// carry no JSON-RPC payload (`message == None`) and MUST be emitted with an empty `data` field
+/// (`data:\n\n`), not the JSON literal `null`.
+///
+/// With `named_events`, message events carry an `event: message` line, or
+/// `event: error` for JSON-RPC errors. Priming events stay unnamed.
+fn format_sse_event(
+    encoding: PayloadEncoding,
+    named_events: bool,
+    event_id: Option<&str>,
+    message: Option<&rmcp::model::ServerJsonRpcMessage>,
+) -> Bytes {
+    let mut output = String::new();
+    if let Some(id) = event_id {
+        output.push_str(&format!("id: {id}\n"));
+    }
+    match message {
+        Some(message) => {
+            if named_events {
+                let name = match message {
+                    ServerJsonRpcMessage::Error(_) => "error",
+                    _ => "message",
+                };
+                output.push_str(&format!("event: {name}\n"));
+            }
+            let data = encoding.encode(message);
+            output.push_str(&format!("data: {data}\n\n"));
+        }
+        None => output.push_str("data:\n\n"),
+    }
+    Bytes::from(output)
+}
+
+/// [`format_sse_event`], recording the size and serialization time of message
+/// events in `payload` when set.
+fn format_recorded_sse_event(
+    payload: Option<&payload_stats::PayloadSource>,
+    encoding: PayloadEncoding,
+    named_events: bool,
+    event_id: Option<&str>,
+    message: Option<&rmcp::model::ServerJsonRpcMessage>,
+) -> Bytes {
+    let Some(payload) = payload.filter(|_| message.is_some()) else {
+        return format_sse_event(encoding, named_events, event_id, message);
+    };
+    let started_at = Instant::now();
+    let event = format_sse_event(encoding, named_events, event_id, message);
+    payload.record(event.len(), started_at.elapsed());
+    event
+}
+
+/// Applies the transform_outbound hook to a server message, cloning it only when a hook
+/// is configured.
+fn transform_outbound<'a>(
+    hook: Option<&OutboundMessageHook>,
+    message: &'a ServerJsonRpcMessage,
+) -> Cow<'a, ServerJsonRpcMessage> {
+    match hook {
+        Some(hook) => {
+            let mut message = message.clone();
+            hook(&mut message);
+            Cow::Owned(message)
+        }
+        None => Cow::Borrowed(message),
+    }
+}
+
+/// Prepares server messages for the wire: applies the transform_outbound hook,
+/// counts forwarded progress notifications and mirrors logging notifications.
+#[derive(Clone)]
+struct Outbound {
+    hook: Option<Arc<OutboundMessageHook>>,
+    forwarded_progress: Arc<AtomicU64>,
+    mirror_logging: bool,
+    /// Session the messages belong to; `None` in stateless mode
+    session_id: Option<SessionId>,
+    /// Protocol version negotiated by the session, once known
+    protocol_version: Option<Arc<OnceLock<ProtocolVersion>>>,
+}
+
+impl Outbound {
+    fn apply<'a>(&self, message: &'a ServerJsonRpcMessage) -> Cow<'a, ServerJsonRpcMessage> {
+        if let ServerJsonRpcMessage::Notification(notification) = message {
+            match &notification.notification {
+                ServerNotification::ProgressNotification(_) => {
+                    self.forwarded_progress.fetch_add(1, Ordering::Relaxed);
+                }
+                ServerNotification::LoggingMessageNotification(log) if self.mirror_logging => {
+                    mirror_log(self.session_id.as_ref(), &log.params);
+                }
+                _ => {}
+            }
+        }
+        let mut message = match self.protocol_version.as_deref().and_then(OnceLock::get) {
+            Some(version) => protocol_version::downgrade(version, message),
+            None => Cow::Borrowed(message),
+        };
+        if let Some(hook) = &self.hook {
+            hook(message.to_mut());
+        }
+        message
+    }
+}
+
+/// Emits an MCP logging notification as a `tracing` event.
+fn mirror_log(session_id: Option<&SessionId>, log: &LoggingMessageNotificationParam) {
+    let session_id = session_id.map(|id| id.as_ref()).unwrap_or_default();
+    let logger = log.logger.as_deref().unwrap_or_default();
+    let data = &log.data;
+    match log.level {
+        LoggingLevel::Debug => {
+            tracing::debug!(target: "rmcp_actix_web::client_log", session_id, logger, %data, "Logged to client")
+        }
+        LoggingLevel::Info | LoggingLevel::Notice => {
+            tracing::info!(target: "rmcp_actix_web::client_log", session_id, logger, %data, "Logged to client")
+        }
+        LoggingLevel::Warning => {
+            tracing::warn!(target: "rmcp_actix_web::client_log", session_id, logger, %data, "Logged to client")
+        }
+        LoggingLevel::Error
+        | LoggingLevel::Critical
+        | LoggingLevel::Alert
+        | LoggingLevel::Emergency => {
+            tracing::error!(target: "rmcp_actix_web::client_log", session_id, logger, %data, "Logged to client")
+        }
+    }
+}
+
+/// Buffers `stream` up to its first item satisfying `ready`.
+///
+/// Used to hold back the HTTP response head until the handler produced its first
+/// message, so [`ResponseHeaders`] set until then can still be applied. The returned
+/// stream yields the buffered items, then the rest of `stream`.
+async fn buffer_until<St, T>(
+    stream: St,
+    ready: impl Fn(&T) -> bool,
+) -> futures::stream::BoxStream<'static, T>
+where
+    St: Stream<Item = T> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut stream = Box::pin(stream);
+    let mut buffered = Vec::new();
+    while let Some(item) = stream.next().await {
+        let done = ready(&item);
+        buffered.push(item);
+        if done {
+            break;
+        }
+    }
+    futures::stream::iter(buffered).chain(stream).boxed()
+}
+
+/// Adds the headers set by a handler to an HTTP response.
+fn apply_response_headers(
+    builder: &mut actix_web::HttpResponseBuilder,
+    headers: Option<&ResponseHeaders>,
+) {
+    for (name, value) in headers.map(ResponseHeaders::take).unwrap_or_default() {
+        match (
+            header::HeaderName::try_from(name.as_str()),
+            header::HeaderValue::try_from(value.as_str()),
+        ) {
+            (Ok(name), Ok(value)) => {
+                builder.append_header((name, value));
+            }
+            _ => tracing::warn!(%name, "Ignoring invalid response header set by handler"),
+        }
+    }
+}
+
+/// Answers `DELETE` to a route serving `methods` when clients may not terminate
+/// their sessions.
+async fn handle_delete_disabled(methods: &'static str) -> HttpResponse {
+    tracing::debug!("DELETE request refused: session termination by clients is disabled");
+    let mut response = Problem::new(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "session_delete_disabled",
+        "Sessions cannot be terminated by clients",
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::ALLOW, header::HeaderValue::from_static(methods));
+    response
+}
+
+/// Answers a request without the session id it requires.
+fn missing_session_id_response() -> HttpResponse {
+    Problem::new(
+        StatusCode::BAD_REQUEST,
+        "missing_session_id",
+        MISSING_SESSION_ID_MESSAGE,
+    )
+    .into_response()
+}
+
+/// Answers a request for a session that does not exist or belongs to another
+/// identity.
+///
+/// `session_id` is echoed in the problem when it is a plain session id, not a
+/// session token.
+fn session_not_found_response(session_id: Option<&SessionId>) -> HttpResponse {
+    let problem = Problem::new(
+        StatusCode::NOT_FOUND,
+        "session_not_found",
+        SESSION_NOT_FOUND_MESSAGE,
+    );
+    match session_id {
+        Some(session_id) => problem.with_session_id(session_id.to_string()),
+        None => problem,
+    }
+    .into_response()
+}
+
+/// Wraps any SSE-formatted stream with keep-alive ping support.
+///
+/// Adds periodic keep-alive frames (`:ping\n\n` by default) during silent periods to prevent
+/// connection timeouts.
+/// The wrapper automatically stops when the underlying stream ends, allowing POST responses
+/// to close properly per MCP spec.
+///
+/// # Arguments
+///
+/// * `stream` - A stream of SSE-formatted bytes (already formatted as `data: ...\n\n`)
+/// * `keep_alive` - Optional keep-alive interval. If `Some`, sends `frame` at this interval
+///   during silent periods. If `None`, no pings are sent.
+/// * `frame` - The serialized keep-alive frame
+///
+/// # Returns
+///
+/// A stream that multiplexes the input stream with keep-alive pings, ending when the input ends.
+fn wrap_with_sse_keepalive<S>(
+    stream: S,
+    keep_alive: Option<Duration>,
+    frame: Bytes,
+) -> impl Stream<Item = Result<Bytes, actix_web::Error>>
+where
+    S: Stream<Item = Result<Bytes, actix_web::Error>> + Send + 'static,
+{
+    async_stream::stream! {
+        let mut stream = Box::pin(stream);
+        let mut keep_alive_timer = keep_alive.map(|duration| tokio::time::interval(duration));
+
+        // Consume the immediate first tick if keep-alive is enabled
+        if let Some(ref mut timer) = keep_alive_timer {
+            timer.tick().await;
+        }
+
+        loop {
+            tokio::select! {
+                result = stream.next() => {
+                    match result {
+                        Some(msg) => yield msg,
+                        None => break, // Stream ended, stop sending pings
+                    }
+                }
+                _ = async {
+                    match keep_alive_timer.as_mut() {
+                        Some(timer) => {
+                            timer.tick().await;
+                        }
+                        None => {
+                            std::future::pending::<()>().await;
+                        }
+                    }
+                } => {
+                    yield Ok(frame.clone());
+                }
+            }
         }
     }
 }
 
+/// Builder returned by [`StreamableHttpService::from_config`], with the settings
+/// of a [`TransportConfig`] applied.
+///
+/// The settings taken from the configuration cannot be set again on it.
+pub type ConfiguredServiceBuilder<S, M> = StreamableHttpServiceBuilder<
+    S,
+    M,
+    streamable_http_service_builder::SetCors<
+        streamable_http_service_builder::SetLegacySse<
+            streamable_http_service_builder::SetAllowedOrigins<
+                streamable_http_service_builder::SetMaxBodySize<
+                    streamable_http_service_builder::SetSseKeepAlive<
+                        streamable_http_service_builder::SetStatefulMode,
+                    >,
+                >,
+            >,
+        >,
+    >,
+>;
+
+impl<S, M> StreamableHttpService<S, M> {
+    /// Creates a builder pre-filled from a [`TransportConfig`].
+    ///
+    /// The service factory, session manager and code-level options such as hooks,
+    /// authentication or filters are set on the returned builder as usual.
+    /// `bind_address` is not used by the service and should be passed to
+    /// `HttpServer::bind`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let config = TransportConfig::from_env()?;
+    /// let service = StreamableHttpService::from_config(&config)
+    ///     .service_factory(Arc::new(|| Ok(MyService::new())))
+    ///     .session_manager(Arc::new(LocalSessionManager::default()))
+    ///     .message_filter(MessageFilter::new().deny_tools(["delete_file"]))
+    ///     .build();
+    /// ```
+    pub fn from_config(config: &TransportConfig) -> ConfiguredServiceBuilder<S, M> {
+        Self::builder()
+            .stateful_mode(config.stateful_mode)
+            .maybe_sse_keep_alive(config.sse_keep_alive())
+            .maybe_max_body_size(config.max_body_size)
+            .allowed_origins(config.allowed_origins.clone())
+            .legacy_sse(config.legacy_sse)
+            .cors(config.cors)
+    }
+}
+
 impl<S, M> StreamableHttpService<S, M>
 where
-    S: Clone + rmcp::ServerHandler + Send + 'static,
+    S: rmcp::Service<RoleServer> + Send + 'static,
     M: SessionManager + 'static,
 {
     /// Creates a new scope configured with this service for framework-level composition.
     ///
-    /// This method provides framework-level composition aligned with RMCP patterns,
-    /// similar to how `SseService::scope()` works. This allows mounting the
-    /// streamable HTTP service at custom paths using actix-web's routing.
+    /// This method provides framework-level composition aligned with RMCP patterns,
+    /// similar to how `SseService::scope()` works. This allows mounting the
+    /// streamable HTTP service at custom paths using actix-web's routing.
+    ///
+    /// The method consumes `self`, so you can call it directly on the service instance.
+    /// If you need to use the service multiple times, wrap it in an `Arc` and clone it.
+    ///
+    /// This method is equivalent to `scope_with_path("")`.
+    ///
+    /// # Returns
+    ///
+    /// Returns an actix-web `Scope` configured with the streamable HTTP routes
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use rmcp_actix_web::transport::StreamableHttpService;
+    /// use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+    /// use actix_web::{App, HttpServer, web};
+    /// use std::sync::Arc;
+    ///
+    /// # use rmcp::{ServerHandler, model::ServerInfo};
+    /// # #[derive(Clone)]
+    /// # struct MyService;
+    /// # impl ServerHandler for MyService {
+    /// #     fn get_info(&self) -> ServerInfo { ServerInfo::default() }
+    /// # }
+    /// # impl MyService { fn new() -> Self { Self } }
+    /// #[actix_web::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     // Create service OUTSIDE HttpServer::new() to share across workers
+    ///     let service = StreamableHttpService::builder()
+    ///         .service_factory(Arc::new(|| Ok(MyService::new())))
+    ///         .session_manager(Arc::new(LocalSessionManager::default()))
+    ///         .build();
+    ///
+    ///     HttpServer::new(move || {
+    ///         App::new()
+    ///             // Clone service for each worker (shares the same LocalSessionManager)
+    ///             .service(web::scope("/api/v1/mcp").service(service.clone().scope()))
+    ///     })
+    ///     .bind("127.0.0.1:8080")?
+    ///     .run();
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn scope(
+        self,
+    ) -> Scope<
+        impl actix_web::dev::ServiceFactory<
+            actix_web::dev::ServiceRequest,
+            Config = (),
+            Response = actix_web::dev::ServiceResponse,
+            Error = actix_web::Error,
+            InitError = (),
+        >,
+    > {
+        self.scope_with_path("")
+    }
+
+    /// Gracefully shuts the service down.
+    ///
+    /// New sessions and new JSON-RPC requests are refused with `503 Service
+    /// Unavailable` from now on, while notifications and client responses are still
+    /// accepted so running requests can complete. Once no request is in flight, or
+    /// after `grace` at the latest, open legacy SSE streams receive a final
+    /// `shutdown` event and every remaining session is closed through the session
+    /// manager (the `on_session_closed` hook sees
+    /// [`SessionCloseReason::ServerShutdown`]).
+    ///
+    /// The shutdown state is shared by all clones of the service, so one call covers
+    /// every worker. Await it before stopping the `HttpServer`, so Ctrl+C does not
+    /// abandon running tool calls.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let server = HttpServer::new(move || {
+    ///     App::new().service(service.clone().scope_with_path("/mcp"))
+    /// })
+    /// .disable_signals()
+    /// .bind("127.0.0.1:8080")?
+    /// .run();
+    /// let handle = server.handle();
+    ///
+    /// tokio::spawn(async move {
+    ///     tokio::signal::ctrl_c().await.ok();
+    ///     shutdown_service.shutdown(Duration::from_secs(30)).await;
+    ///     handle.stop(true).await;
+    /// });
+    /// server.await
+    /// ```
+    pub async fn shutdown(&self, grace: Duration) {
+        tracing::info!(?grace, "Shutting down, draining in-flight requests");
+        if !self.shutdown.drain(grace).await {
+            tracing::warn!("Shutdown grace period elapsed with requests still in flight");
+        }
+
+        let session_ids = self.sessions.ids().await;
+        for session_id in &session_ids {
+            self.sessions
+                .close(
+                    session_id,
+                    SessionCloseReason::ServerShutdown,
+                    self.on_session_closed.as_deref(),
+                    &self.events,
+                )
+                .await;
+        }
+        self.shutdown.stop();
+        for session_id in &session_ids {
+            let _ = self
+                .session_manager
+                .close_session(session_id)
+                .await
+                .inspect_err(|e| {
+                    tracing::error!("Failed to close session {session_id}: {e}");
+                });
+        }
+        tracing::info!(sessions = session_ids.len(), "Shutdown complete");
+    }
+
+    /// Creates the opt-in readiness probe resource.
+    ///
+    /// The returned resource answers `GET` at `path` with `200 OK` while the
+    /// service accepts new sessions, and with `503 Service Unavailable` and a
+    /// `Retry-After` header once [`shutdown`](Self::shutdown) has started, so load
+    /// balancers stop routing new MCP clients to an instance that is about to
+    /// terminate. Register it at the application root, next to the MCP scope.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// App::new()
+    ///     .service(service.readiness_probe("/readyz"))
+    ///     .service(service.clone().scope_with_path("/mcp"))
+    /// ```
+    pub fn readiness_probe(&self, path: &str) -> actix_web::Resource {
+        shutdown::readiness_resource(path, self.shutdown.clone())
+    }
+
+    /// Returns the liveness measurements of a session, or `None` if the session is
+    /// not open.
+    ///
+    /// Measurements are only taken while
+    /// [`ping_interval`](StreamableHttpServiceBuilder::ping_interval) is set and the
+    /// client keeps its standalone stream open.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// if let Some(liveness) = service.session_liveness(&session_id).await {
+    ///     metrics::histogram!("mcp_ping_rtt_seconds")
+    ///         .record(liveness.last_round_trip.unwrap_or_default().as_secs_f64());
+    /// }
+    /// ```
+    pub async fn session_liveness(&self, session_id: &SessionId) -> Option<SessionLiveness> {
+        self.sessions.liveness(session_id).await
+    }
+
+    /// Returns the protocol version negotiated by a session, or `None` if the
+    /// session is not open or has not completed `initialize`.
+    pub async fn session_protocol_version(
+        &self,
+        session_id: &SessionId,
+    ) -> Option<ProtocolVersion> {
+        self.sessions.protocol_version(session_id).await
+    }
+
+    /// Returns the capabilities the client and the server announced during
+    /// `initialize`, or `None` if the session is not open or has not completed
+    /// `initialize`.
+    ///
+    /// The same value is inserted into the extensions of every later request of
+    /// the session, so the `on_request` hook and MCP handlers can adapt to the
+    /// client without a lookup.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let supports_sampling = service
+    ///     .session_capabilities(&session_id)
+    ///     .await
+    ///     .is_some_and(|capabilities| capabilities.client.sampling.is_some());
+    /// ```
+    pub async fn session_capabilities(
+        &self,
+        session_id: &SessionId,
+    ) -> Option<SessionCapabilities> {
+        self.sessions.capabilities(session_id).await
+    }
+
+    /// Returns the number of `notifications/progress` messages written to clients.
+    ///
+    /// The counter is shared by all clones of the service and covers every
+    /// transport: request streams, the standalone stream, stateless responses and
+    /// legacy SSE connections.
+    pub fn forwarded_progress_notifications(&self) -> u64 {
+        self.forwarded_progress.load(Ordering::Relaxed)
+    }
+
+    /// Returns a snapshot of the transport's live state.
+    ///
+    /// The state is shared by all clones of the service, so the snapshot covers
+    /// every worker.
+    pub async fn runtime_stats(&self) -> RuntimeStats {
+        self.stats_sources().snapshot().await
+    }
+
+    /// Returns the payload size and serialization time histograms recorded so far.
+    ///
+    /// Empty unless [`payload_stats`](StreamableHttpServiceBuilder::payload_stats)
+    /// is enabled. The histograms are shared by all clones of the service.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// for (source, stats) in service.payload_stats().sources {
+    ///     tracing::info!(%source, messages = stats.messages, max_bytes = stats.size_bytes.max);
+    /// }
+    /// ```
+    pub fn payload_stats(&self) -> PayloadStats {
+        self.payload_recorder.snapshot()
+    }
+
+    /// Subscribes to the [`TransportEvent`]s of this service and all its clones.
+    ///
+    /// Only events sent after subscribing are received. Events are dropped for
+    /// subscribers lagging more than
+    /// [`event_channel_capacity`](StreamableHttpServiceBuilder::event_channel_capacity)
+    /// behind, which then receive
+    /// [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use rmcp_actix_web::transport::TransportEvent;
+    ///
+    /// let mut events = service.subscribe_events();
+    /// tokio::spawn(async move {
+    ///     while let Ok(event) = events.recv().await {
+    ///         if let TransportEvent::RequestCompleted { method, duration, .. } = event {
+    ///             tracing::info!(%method, ?duration, "request completed");
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<TransportEvent> {
+        self.events.subscribe()
+    }
+
+    /// Returns the usage of `tenant` and how often it hit its quotas.
+    ///
+    /// Usage is counted only for tenants with a quota in
+    /// [`tenant_quotas`](StreamableHttpServiceBuilder::tenant_quotas), except for
+    /// open sessions, which are counted for every tenant.
+    pub async fn tenant_usage(&self, tenant: &TenantId) -> TenantUsage {
+        let sessions = self.sessions.tenant_sessions(tenant).await;
+        self.tenant_counters.usage(tenant, sessions)
+    }
+
+    /// Creates the opt-in statistics resource.
+    ///
+    /// The returned resource answers `GET` at `path` with the
+    /// [`runtime_stats`](Self::runtime_stats) as JSON, for dashboards that do not
+    /// scrape Prometheus. It is not authenticated; register it on a path that is
+    /// not exposed publicly, or behind an authentication middleware.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// App::new()
+    ///     .service(service.stats_route("/internal/stats"))
+    ///     .service(service.clone().scope_with_path("/mcp"))
+    /// ```
+    pub fn stats_route(&self, path: &str) -> actix_web::Resource {
+        runtime_stats::stats_resource(path, self.stats_sources())
+    }
+
+    fn stats_sources(&self) -> runtime_stats::StatsSources {
+        runtime_stats::StatsSources {
+            started_at: self.started_at,
+            sessions: self.sessions.clone(),
+            sse_connections: self.sse_connections.clone(),
+            legacy_sse: self.legacy_connections.clone(),
+            pending_requests: self.pending_requests.clone(),
+            shutdown: self.shutdown.clone(),
+            forwarded_progress: self.forwarded_progress.clone(),
+        }
+    }
+
+    /// Returns the `ServerInfo` provider of the discovery routes.
+    ///
+    /// The info is read from the first handler the provider constructs and served
+    /// from memory afterwards, by every clone of the service, so discovery
+    /// requests do not construct a handler each. A factory failure is not
+    /// remembered.
+    fn discovery_info(&self) -> discovery::ServerInfoProvider {
+        let service_factory = self.service_factory.clone();
+        let info = self.discovery_info.clone();
+        Arc::new(move || {
+            if let Some(info) = info.get() {
+                return Ok(info.clone());
+            }
+            let fresh = service_factory()?.get_info();
+            Ok(info.get_or_init(|| fresh).clone())
+        })
+    }
+
+    /// Creates the opt-in discovery manifest resource.
+    ///
+    /// The returned resource answers `GET /.well-known/mcp.json` with a
+    /// [`DiscoveryManifest`](super::DiscoveryManifest) built from the handler's
+    /// `ServerInfo`, which is read from a handler only once. `endpoint` is the
+    /// full path at which the MCP scope is mounted, as clients should use it.
+    /// Register the resource at the application root, since well-known URIs are
+    /// not relative to the scope.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// App::new()
+    ///     .service(service.well_known_manifest("/api/v1/mcp"))
+    ///     .service(web::scope("/api/v1/mcp").service(service.clone().scope()))
+    /// ```
+    pub fn well_known_manifest(&self, endpoint: &str) -> actix_web::Resource {
+        discovery::manifest_resource(
+            discovery::WELL_KNOWN_MANIFEST_PATH,
+            self.discovery_info(),
+            endpoint.to_string(),
+            self.stateful_mode,
+            self.discovery_auth.clone(),
+        )
+    }
+
+    /// Creates the entry describing this service in a
+    /// [`ServiceDirectory`](super::ServiceDirectory).
+    ///
+    /// `endpoint` is the full path at which the MCP scope is mounted. The entry
+    /// describes the service like [`well_known_manifest`](Self::well_known_manifest)
+    /// and adds the tools of a fresh handler instance, leaving out those denied by
+    /// the `message_filter` or disabled with [`disable_tool`](Self::disable_tool).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let directory = ServiceDirectory::new()
+    ///     .service(calculator.directory_entry("/api/v1/calculator"))
+    ///     .service(weather.directory_entry("/api/v1/weather"));
+    /// ```
+    pub fn directory_entry(&self, endpoint: &str) -> DirectoryEntry {
+        let tools_factory = self.service_factory.clone();
+        let message_filter = self.message_filter.clone();
+        let tool_toggles = self.tool_toggles.clone();
+        DirectoryEntry::new(
+            self.discovery_info(),
+            Arc::new(move || {
+                let handler = tools_factory().map(DynServerHandler::new);
+                Box::pin(async move { discovery::list_tools(handler?).await })
+            }),
+            Arc::new(move |tool: &str| {
+                message_filter.offers_tool(tool) && !tool_toggles.is_disabled(tool)
+            }),
+            endpoint.to_string(),
+            self.stateful_mode,
+            self.discovery_auth.clone(),
+        )
+    }
+
+    /// Disables the tool `name` for every session, returning `false` if it was
+    /// already disabled.
+    ///
+    /// The tool disappears from `tools/list` results and its calls are rejected
+    /// by the transport until [`enable_tool`](Self::enable_tool) is called. Running
+    /// sessions receive `notifications/tools/list_changed`. The toggles are shared
+    /// by all clones of the service.
+    pub async fn disable_tool(&self, name: &str) -> bool {
+        self.tool_admin_state().set_enabled(name, false).await
+    }
+
+    /// Enables a tool disabled with [`disable_tool`](Self::disable_tool), returning
+    /// `false` if it was not disabled.
+    pub async fn enable_tool(&self, name: &str) -> bool {
+        self.tool_admin_state().set_enabled(name, true).await
+    }
+
+    /// Returns the names of the tools disabled at runtime, sorted.
+    pub fn disabled_tools(&self) -> Vec<String> {
+        self.tool_toggles.disabled()
+    }
+
+    /// Creates the opt-in admin scope for toggling tools at runtime.
+    ///
+    /// Every route requires one of the API keys accepted by `auth`:
+    ///
+    /// - `GET {path}/tools` lists the disabled tools.
+    /// - `POST {path}/tools/{name}/disable` disables a tool, like
+    ///   [`disable_tool`](Self::disable_tool).
+    /// - `POST {path}/tools/{name}/enable` enables it again.
+    ///
+    /// Register the scope next to the MCP scope, on a path that is not exposed to
+    /// MCP clients.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// App::new()
+    ///     .service(service.tool_admin("/admin", ["operator-key"]))
+    ///     .service(service.clone().scope_with_path("/mcp"))
+    /// ```
+    pub fn tool_admin(&self, path: &str, auth: impl Into<ApiKeyAuth>) -> Scope {
+        tool_toggles::admin_scope(path, self.tool_admin_state(), auth.into())
+    }
+
+    fn tool_admin_state(&self) -> tool_toggles::ToolAdmin {
+        tool_toggles::ToolAdmin {
+            toggles: self.tool_toggles.clone(),
+            sessions: self.sessions.clone(),
+            response_cache: self.response_cache.clone(),
+        }
+    }
+
+    /// Sends `notification` to every running session, returning how many sessions
+    /// it reached.
+    ///
+    /// The notification is delivered on each session's standalone stream, or its
+    /// legacy SSE stream, like notifications sent by handlers. Sessions that have
+    /// not completed `initialize` are skipped.
     ///
-    /// The method consumes `self`, so you can call it directly on the service instance.
-    /// If you need to use the service multiple times, wrap it in an `Arc` and clone it.
+    /// # Example
     ///
-    /// This method is equivalent to `scope_with_path("")`.
+    /// ```rust,ignore
+    /// use rmcp::model::{CustomNotification, ServerNotification};
     ///
-    /// # Returns
+    /// let reached = service
+    ///     .broadcast(ServerNotification::CustomNotification(CustomNotification::new(
+    ///         "notifications/maintenance",
+    ///         Some(json!({ "startsInMinutes": 10 })),
+    ///     )))
+    ///     .await;
+    /// ```
+    pub async fn broadcast(&self, notification: ServerNotification) -> usize {
+        session_push::broadcast(&self.sessions, notification).await
+    }
+
+    /// Sends `notification` to the session `session_id`, returning `false` if the
+    /// session is not running or did not accept it.
     ///
-    /// Returns an actix-web `Scope` configured with the streamable HTTP routes
+    /// Lets code outside MCP handlers, such as webhook handlers or background jobs,
+    /// push to one particular client. The notification is delivered on the
+    /// session's standalone stream, or its legacy SSE stream, like notifications
+    /// sent by its handler.
     ///
     /// # Example
     ///
-    /// ```rust,no_run
-    /// use rmcp_actix_web::transport::StreamableHttpService;
-    /// use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
-    /// use actix_web::{App, HttpServer, web};
-    /// use std::sync::Arc;
+    /// ```rust,ignore
+    /// use rmcp::model::{
+    ///     ResourceUpdatedNotification, ResourceUpdatedNotificationParam, ServerNotification,
+    /// };
     ///
-    /// # use rmcp::{ServerHandler, model::ServerInfo};
-    /// # #[derive(Clone)]
-    /// # struct MyService;
-    /// # impl ServerHandler for MyService {
-    /// #     fn get_info(&self) -> ServerInfo { ServerInfo::default() }
-    /// # }
-    /// # impl MyService { fn new() -> Self { Self } }
-    /// #[actix_web::main]
-    /// async fn main() -> std::io::Result<()> {
-    ///     // Create service OUTSIDE HttpServer::new() to share across workers
-    ///     let service = StreamableHttpService::builder()
-    ///         .service_factory(Arc::new(|| Ok(MyService::new())))
-    ///         .session_manager(Arc::new(LocalSessionManager::default()))
-    ///         .build();
+    /// // In a webhook handler, after the document of a session changed upstream
+    /// service
+    ///     .send_to_session(
+    ///         &session_id,
+    ///         ServerNotification::ResourceUpdatedNotification(
+    ///             ResourceUpdatedNotification::new(ResourceUpdatedNotificationParam::new(
+    ///                 "file:///report.md",
+    ///             )),
+    ///         ),
+    ///     )
+    ///     .await;
+    /// ```
+    pub async fn send_to_session(
+        &self,
+        session_id: &SessionId,
+        notification: ServerNotification,
+    ) -> bool {
+        session_push::send_to(&self.sessions, session_id, notification).await
+    }
+
+    /// Creates the opt-in broadcast resource.
     ///
-    ///     HttpServer::new(move || {
-    ///         App::new()
-    ///             // Clone service for each worker (shares the same LocalSessionManager)
-    ///             .service(web::scope("/api/v1/mcp").service(service.clone().scope()))
-    ///     })
-    ///     .bind("127.0.0.1:8080")?
-    ///     .run();
+    /// The returned resource answers `POST` at `path` with a JSON body holding a
+    /// server notification (`method` and `params`) by sending it to every running
+    /// session, like [`broadcast`](Self::broadcast), and responds with the number
+    /// of sessions reached. Every request requires one of the API keys accepted by
+    /// `auth`. Register it before any scope whose path is a prefix of `path`.
     ///
-    ///     Ok(())
-    /// }
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// App::new()
+    ///     .service(service.broadcast_route("/ops/broadcast", ["operator-key"]))
+    ///     .service(service.clone().scope_with_path("/mcp"))
     /// ```
-    pub fn scope(
-        self,
-    ) -> Scope<
-        impl actix_web::dev::ServiceFactory<
-            actix_web::dev::ServiceRequest,
-            Config = (),
-            Response = actix_web::dev::ServiceResponse,
-            Error = actix_web::Error,
-            InitError = (),
-        >,
-    > {
-        self.scope_with_path("")
+    pub fn broadcast_route(&self, path: &str, auth: impl Into<ApiKeyAuth>) -> actix_web::Resource {
+        session_push::broadcast_resource(path, self.sessions.clone(), auth.into())
     }
 
     /// Creates a new scope configured with this service for framework-level composition.
@@ -496,23 +4476,235 @@ where
             InitError = (),
         >,
     > {
+        let allowed_origins: Arc<[String]> = self.allowed_origins.into();
+        let cors_origins = self.cors.then(|| allowed_origins.clone());
+        // Browsers only send the session cookie cross-origin with credentials allowed
+        let cors_credentials = self.session_cookie.is_some();
         let app_data = AppData {
-            service_factory: self.service_factory,
+            service_factory: self.warm_pool.serve(self.service_factory),
+            tenant_service_factory: self.tenant_service_factory,
             session_manager: self.session_manager,
             stateful_mode: self.stateful_mode,
+            hybrid_mode: self.hybrid_mode,
+            shared_service: self.shared_service,
+            reinitialize_policy: self.reinitialize_policy,
+            session_cookie: self
+                .session_cookie
+                .map(|name| Arc::new(session_cookie::SessionCookie::new(name))),
+            session_id_query: self.session_id_query,
             sse_keep_alive: self.sse_keep_alive,
+            sse_keep_alive_bounds: self.sse_keep_alive_bounds,
+            keep_alive_frame: self.keep_alive_frame.to_bytes(),
+            request_timeout: self.request_timeout,
+            request_deadline_headers: self.request_deadline_headers,
+            initialize_timeout: self.initialize_timeout,
+            ping_interval: self.ping_interval,
+            ping_max_missed: self.ping_max_missed,
+            event_store: self.event_store.clone(),
+            event_ids: self.event_id_generator.map(event_ids::EventIds::new),
+            idempotency_window: self.idempotency_window,
+            discovery_cache_ttl: self.discovery_cache_ttl,
+            cacheable_tools: Arc::new(self.cacheable_tools),
+            response_cache: self.response_cache,
+            tool_toggles: self.tool_toggles,
+            #[cfg(feature = "msgpack")]
+            msgpack: self.msgpack,
+            #[cfg(not(feature = "msgpack"))]
+            msgpack: false,
+            named_events: self.named_events,
+            payload_recorder: self.payload_recorder,
+            slow_consumer_policy: self.slow_consumer_policy,
+            slow_consumer_buffer: self.slow_consumer_buffer,
+            log_redaction: self.log_redaction,
             on_request: self.on_request,
+            propagate_extensions: Arc::new(self.propagate_extensions),
+            snapshot_headers: self.snapshot_headers.into(),
+            response_headers: self.response_headers,
+            blob_ttl: self.blob_ttl,
+            upload_ttl: self.upload_ttl,
+            session_extensions: self.session_extensions,
+            request_guard: self.request_guard,
+            require_api_key: self.require_api_key,
+            bind_session_identity: self.bind_session_identity,
+            #[cfg(feature = "signed-session-tokens")]
+            session_tokens: self.session_tokens,
+            allowed_origins: allowed_origins.clone(),
+            cors: self.cors,
+            trusted_proxies: self.trusted_proxies.map(Arc::new),
+            ip_filter: Arc::new(self.ip_filter),
+            session_creation_limit: self.session_creation_limit,
+            tenant_quotas: self.tenant_quotas.map(Arc::new),
+            tenant_counters: self.tenant_counters,
+            session_creations: self.session_creations,
+            max_sse_streams_per_client: self.max_sse_streams_per_client,
+            sse_connections: self.sse_connections,
+            max_pending_requests_per_session: self.max_pending_requests_per_session,
+            lazy_service_info: self.lazy_service_info,
+            handler_runtime: self.handler_runtime,
+            pending_requests: self.pending_requests,
+            transform_inbound: self.transform_inbound,
+            transform_outbound: self.transform_outbound,
+            mirror_logging: self.mirror_logging,
+            message_filter: Arc::new(self.message_filter),
+            tool_scopes: Arc::new(self.tool_scopes),
+            authorize: self.authorize,
+            on_session_created: self.on_session_created,
+            on_session_closed: self.on_session_closed,
+            on_internal_error: self.on_internal_error,
+            sessions: self.sessions,
+            events: self.events,
+            idempotency: self.idempotency,
+            blob_store: self.blob_store,
+            upload_store: self.upload_store,
+            legacy_sse: self.legacy_connections,
+            shutdown: self.shutdown,
+            forwarded_progress: self.forwarded_progress,
+        };
+
+        let mut scope = web::scope(path).app_data(Data::new(app_data));
+        if let Some(limit) = self.max_body_size {
+            scope = scope.app_data(web::PayloadConfig::new(limit));
+        }
+        let route_name = self.route_name;
+        let query_token_param = self.query_token_param;
+        let body_read_timeout = self.body_read_timeout;
+        let (endpoint_methods, message_methods) = if self.session_delete {
+            ("GET, POST, DELETE, HEAD, OPTIONS", "POST, DELETE, OPTIONS")
+        } else {
+            ("GET, POST, HEAD, OPTIONS", "POST, OPTIONS")
         };
+        let mut scope = scope
+            .wrap(self.path_normalization.middleware())
+            // Also keeps the response type independent of the normalization
+            .wrap_fn(move |req, srv| {
+                let origin = cors_origins
+                    .as_deref()
+                    .and_then(|allowed| cors::response_origin(req.request(), allowed));
+                srv.call(req).map(move |res| {
+                    res.map(|res| {
+                        let mut res = problem::render_error(res);
+                        if let Some(origin) = origin {
+                            cors::add_response_headers(origin, cors_credentials, res.headers_mut());
+                        }
+                        res
+                    })
+                })
+            })
+            .wrap_fn(move |mut req, srv| {
+                if let Some(param) = query_token_param.as_deref() {
+                    query_token::promote(&mut req, param);
+                }
+                srv.call(req)
+            })
+            .wrap_fn(move |mut req, srv| {
+                let timed_out = body_read_timeout
+                    .map(|timeout| (body_timeout::limit(&mut req, timeout), timeout));
+                srv.call(req).map(move |res| match timed_out {
+                    Some((timed_out, timeout)) => {
+                        res.map(|res| body_timeout::answer_timed_out(res, &timed_out, timeout))
+                    }
+                    None => res,
+                })
+            })
+            .service(
+                web::resource("")
+                    .name(&route_name)
+                    .route(web::get().to(Self::handle_get))
+                    .route(web::post().to(Self::handle_post))
+                    .route(if self.session_delete {
+                        web::delete().to(Self::handle_delete)
+                    } else {
+                        web::delete().to(move || handle_delete_disabled(endpoint_methods))
+                    })
+                    .route(web::head().to(Self::handle_head))
+                    .route(web::method(Method::OPTIONS).to(move |req, service| {
+                        Self::handle_options(req, service, endpoint_methods)
+                    })),
+            );
+
+        if self.blob_ttl.is_some() {
+            scope = scope.service(
+                web::resource(BLOB_PATH)
+                    .name(&format!("{route_name}_blob"))
+                    .route(web::get().to(Self::handle_blob)),
+            );
+        }
+        if self.upload_ttl.is_some() {
+            scope = scope.service(
+                web::resource(UPLOAD_PATH)
+                    .name(&format!("{route_name}_upload"))
+                    .app_data(web::PayloadConfig::new(self.max_upload_size))
+                    .route(web::post().to(Self::handle_upload)),
+            );
+        }
+
+        if self.legacy_sse {
+            if self.stateful_mode {
+                scope = scope
+                    .service(
+                        web::resource(legacy_sse::LEGACY_SSE_PATH)
+                            .name(&format!("{route_name}_sse"))
+                            .route(web::get().to(Self::handle_legacy_sse))
+                            .route(web::method(Method::OPTIONS).to(|req, service| {
+                                Self::handle_options(req, service, "GET, OPTIONS")
+                            })),
+                    )
+                    .service(
+                        web::resource(legacy_sse::LEGACY_MESSAGE_PATH)
+                            .name(&format!("{route_name}_message"))
+                            .route(web::post().to(Self::handle_legacy_message))
+                            .route(if self.session_delete {
+                                web::delete().to(Self::handle_legacy_delete)
+                            } else {
+                                web::delete().to(move || handle_delete_disabled(message_methods))
+                            })
+                            .route(web::method(Method::OPTIONS).to(move |req, service| {
+                                Self::handle_options(req, service, message_methods)
+                            })),
+                    );
+            } else {
+                tracing::warn!("Legacy SSE endpoints require stateful mode and were not mounted");
+            }
+        }
+
+        scope
+    }
+
+    /// Answers `HEAD` on the MCP endpoint, for probes: `200 OK` unless the request
+    /// is rejected or the service is shutting down.
+    async fn handle_head(req: HttpRequest, service: Data<AppData<S, M>>) -> HttpResponse {
+        if let Some(response) = service.check_request_guard(&req) {
+            return response;
+        }
+        if service.shutdown.is_draining() {
+            return HttpResponse::ServiceUnavailable().finish();
+        }
+        HttpResponse::Ok().finish()
+    }
 
-        web::scope(path)
-            .app_data(Data::new(app_data))
-            .wrap(middleware::NormalizePath::trim())
-            .route("", web::get().to(Self::handle_get))
-            .route("", web::post().to(Self::handle_post))
-            .route("", web::delete().to(Self::handle_delete))
+    /// Answers `OPTIONS` on a route serving `methods`, including CORS preflights.
+    ///
+    /// Preflights carry no credentials, so only the client address and the origin
+    /// are checked.
+    async fn handle_options(
+        req: HttpRequest,
+        service: Data<AppData<S, M>>,
+        methods: &'static str,
+    ) -> HttpResponse {
+        if let Some(response) = service
+            .check_client_ip(&req)
+            .or_else(|| service.check_origin(&req))
+        {
+            return response;
+        }
+        cors::options_response(&req, methods, service.cors)
     }
 
     async fn handle_get(req: HttpRequest, service: Data<AppData<S, M>>) -> Result<HttpResponse> {
+        if let Some(response) = service.check_request_guard(&req) {
+            return Ok(response);
+        }
+
         // Check accept header
         let accept = req
             .headers()
@@ -520,20 +4712,23 @@ where
             .and_then(|h| h.to_str().ok());
 
         if !accept.is_some_and(|header| header.contains(EVENT_STREAM_MIME_TYPE)) {
-            return Ok(HttpResponse::NotAcceptable()
-                .body("Not Acceptable: Client must accept text/event-stream"));
+            return Ok(Problem::new(
+                StatusCode::NOT_ACCEPTABLE,
+                "not_acceptable",
+                "Client must accept text/event-stream",
+            )
+            .into_response());
         }
 
         // Check session id
-        let session_id = req
-            .headers()
-            .get(HEADER_SESSION_ID)
-            .and_then(|v| v.to_str().ok())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_owned().into());
+        let session_id: Option<SessionId> = header_session_id(&req)
+            .map(str::to_owned)
+            .or_else(|| service.query_session_id(&req))
+            .or_else(|| service.cookie_session_id(&req).map(str::to_owned))
+            .map(Into::into);
 
         let Some(session_id) = session_id else {
-            return Ok(HttpResponse::BadRequest().body(MISSING_SESSION_ID_BODY));
+            return Ok(missing_session_id_response());
         };
 
         tracing::debug!(%session_id, "GET request for SSE stream");
@@ -545,11 +4740,22 @@ where
             .await
             .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-        if !has_session {
+        if !has_session || !service.session_identity_matches(&req, &session_id).await {
             tracing::warn!(%session_id, "Session not found");
-            return Ok(HttpResponse::NotFound().body(SESSION_NOT_FOUND_BODY));
+            return Ok(session_not_found_response(Some(&session_id)));
+        }
+        if let Some(response) = service
+            .check_protocol_version(&req, Some(&session_id))
+            .await
+        {
+            return Ok(response);
         }
 
+        let connection = match service.acquire_sse_connection(&req) {
+            Ok(connection) => connection,
+            Err(response) => return Ok(response),
+        };
+
         // Check if last event id is provided
         let last_event_id = req
             .headers()
@@ -558,41 +4764,63 @@ where
             .map(|s| s.to_owned());
 
         // Get the appropriate stream
-        let sse_stream: std::pin::Pin<Box<dyn Stream<Item = _> + Send>> =
-            if let Some(last_event_id) = last_event_id {
-                tracing::debug!(%session_id, %last_event_id, "Resuming stream from last event");
-                Box::pin(
-                    service
-                        .session_manager
-                        .resume(&session_id, last_event_id)
-                        .await
-                        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?,
-                )
-            } else {
-                tracing::debug!(%session_id, "Creating standalone stream");
-                Box::pin(
-                    service
-                        .session_manager
-                        .create_standalone_stream(&session_id)
-                        .await
-                        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?,
-                )
+        let sse_stream = if let Some(last_event_id) = last_event_id {
+            session_log::resumed(&session_id, &last_event_id);
+            let last_event_id = match service.event_ids {
+                Some(ref ids) => ids.resolve(&session_id, last_event_id),
+                None => last_event_id,
             };
+            service.resume_stream(&session_id, last_event_id).await?
+        } else {
+            tracing::debug!(%session_id, "Creating standalone stream");
+            let stream = service
+                .session_manager
+                .create_standalone_stream(&session_id)
+                .await
+                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            let stream =
+                service.record_events(session_id.clone(), STANDALONE_STREAM_ID.to_owned(), stream);
+            service.ping_standalone(session_id.clone(), stream)
+        };
+        let sse_stream = service.rename_events(&session_id, sse_stream);
+        let sse_stream = service.pace_for_consumer(sse_stream);
 
         // Convert to SSE format and add keep-alive
-        let formatted_stream = sse_stream.map(|msg| {
-            Ok::<_, actix_web::Error>(format_sse_event(
+        let outbound = service.outbound(Some(&session_id)).await;
+        let encoding = PayloadEncoding::negotiate(&req, service.msgpack);
+        let named_events = service.named_events;
+        let payload = service
+            .payload_recorder
+            .source(|| payload_stats::STANDALONE_SOURCE.to_owned());
+        let formatted_stream = sse_stream.map(move |msg| {
+            let message = msg.message.as_deref().map(|m| outbound.apply(m));
+            Ok::<_, actix_web::Error>(format_recorded_sse_event(
+                payload.as_ref(),
+                encoding,
+                named_events,
                 msg.event_id.as_deref(),
-                msg.message.as_deref(),
+                message.as_deref(),
             ))
         });
-        let sse_stream = wrap_with_sse_keepalive(formatted_stream, service.sse_keep_alive);
+        let open_stream = service.events.stream_opened(&session_id);
+        let sse_stream = stream_end::track(
+            wrap_with_sse_keepalive(
+                connection.hold(formatted_stream),
+                service.keep_alive_for(&req),
+                service.keep_alive_frame.clone(),
+            ),
+            service.keep_alive_frame.clone(),
+            service.shutdown.clone(),
+            move |reason| open_stream.ended(reason),
+        );
 
-        Ok(HttpResponse::Ok()
+        let mut response = HttpResponse::Ok();
+        response
             .content_type(EVENT_STREAM_MIME_TYPE)
             .append_header((CACHE_CONTROL, "no-cache"))
-            .append_header((HEADER_X_ACCEL_BUFFERING, "no"))
-            .streaming(sse_stream))
+            .append_header((HEADER_X_ACCEL_BUFFERING, "no"));
+        encoding.announce(&mut response);
+        Ok(response.streaming(sse_stream))
     }
 
     async fn handle_post(
@@ -600,18 +4828,30 @@ where
         body: Bytes,
         service: Data<AppData<S, M>>,
     ) -> Result<HttpResponse> {
+        if let Some(response) = service.check_request_guard(&req) {
+            return Ok(response);
+        }
+
         // Check accept header
         let accept = req
             .headers()
             .get(header::ACCEPT)
             .and_then(|h| h.to_str().ok());
 
+        // Binary clients may accept their encoding instead of JSON
         if !accept.is_some_and(|header| {
-            header.contains(JSON_MIME_TYPE) && header.contains(EVENT_STREAM_MIME_TYPE)
+            PayloadEncoding::accepts(header, service.msgpack)
+                && header.contains(EVENT_STREAM_MIME_TYPE)
         }) {
-            return Ok(HttpResponse::NotAcceptable().body(
-                "Not Acceptable: Client must accept both application/json and text/event-stream",
-            ));
+            return Ok(Problem::new(
+                StatusCode::NOT_ACCEPTABLE,
+                "not_acceptable",
+                format!(
+                    "Client must accept both {} and text/event-stream",
+                    PayloadEncoding::content_types(service.msgpack)
+                ),
+            )
+            .into_response());
         }
 
         // Check content type
@@ -620,24 +4860,82 @@ where
             .get(header::CONTENT_TYPE)
             .and_then(|h| h.to_str().ok());
 
-        if !content_type.is_some_and(|header| header.starts_with(JSON_MIME_TYPE)) {
-            return Ok(HttpResponse::UnsupportedMediaType()
-                .body("Unsupported Media Type: Content-Type must be application/json"));
-        }
+        let Some(body_encoding) = PayloadEncoding::from_content_type(content_type, service.msgpack)
+        else {
+            return Ok(Problem::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "unsupported_media_type",
+                format!(
+                    "Content-Type must be {}",
+                    PayloadEncoding::content_types(service.msgpack)
+                ),
+            )
+            .into_response());
+        };
 
         // Deserialize the message
-        let mut message: ClientJsonRpcMessage = serde_json::from_slice(&body)
+        let mut message = body_encoding
+            .decode(&body)
             .map_err(|e| InternalError::new(e, StatusCode::BAD_REQUEST))?;
+        service.transform_inbound(&req, &mut message);
+
+        if let Some(response) = service.shutdown.reject(&message) {
+            return Ok(response);
+        }
+        if let Some(response) = service.admit_tenant_request(&req, &message) {
+            return Ok(response);
+        }
+        let rejection = match service
+            .message_filter
+            .reject(&message)
+            .or_else(|| service.tool_toggles.reject(&message))
+            .or_else(|| service.tool_scopes.reject(&req, &message))
+        {
+            Some(error) => Some(error),
+            None => {
+                request_authorization::reject(service.authorize.as_deref(), &req, &message).await
+            }
+        };
+        if let Some(error) = rejection {
+            let error = transform_outbound(service.transform_outbound.as_deref(), &error);
+            return Ok(HttpResponse::Ok()
+                .content_type(JSON_MIME_TYPE)
+                .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string())));
+        }
+        // `initialize` and stateless requests are not sent within a session
+        let event_session_id = (service.stateful_mode && initialize_params(&message).is_none())
+            .then(|| service.request_session_id(&req))
+            .flatten()
+            .map(SessionId::from);
+        let in_flight = service.shutdown.in_flight().with_request(
+            service
+                .events
+                .request_started(event_session_id.as_ref(), &message),
+        );
 
-        tracing::debug!(?message, "POST request with message");
+        tracing::debug!(
+            message = %service.log_redaction.display(&message),
+            "POST request with message"
+        );
 
         if service.stateful_mode {
             // Check session id
-            let session_id = req
-                .headers()
-                .get(HEADER_SESSION_ID)
-                .and_then(|v| v.to_str().ok())
-                .filter(|s| !s.is_empty());
+            // A client starting over must not be held to the session of its cookie
+            let mut session_id = header_session_id(&req).or_else(|| {
+                initialize_params(&message)
+                    .is_none()
+                    .then(|| service.cookie_session_id(&req))
+                    .flatten()
+            });
+            if let Some(id) = session_id
+                && initialize_params(&message).is_some()
+                && service.reinitialize_policy == ReinitializePolicy::Recreate
+            {
+                service
+                    .close_reinitialized_session(&req, &id.to_owned().into())
+                    .await?;
+                session_id = None;
+            }
 
             if let Some(session_id) = session_id {
                 let session_id = session_id.to_owned().into();
@@ -649,10 +4947,24 @@ where
                     .await
                     .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-                if !has_session {
+                if !has_session || !service.session_identity_matches(&req, &session_id).await {
                     tracing::warn!(%session_id, "Session not found");
-                    return Ok(HttpResponse::NotFound().body(SESSION_NOT_FOUND_BODY));
+                    return Ok(session_not_found_response(Some(&session_id)));
                 }
+                if let Some(response) = service
+                    .check_protocol_version(&req, Some(&session_id))
+                    .await
+                {
+                    return Ok(response);
+                }
+                if let ClientJsonRpcMessage::Request(request_msg) = &message
+                    && matches!(request_msg.request, ClientRequest::InitializeRequest(_))
+                {
+                    return Ok(service
+                        .answer_reinitialize(&req, &session_id, request_msg.id.clone(), in_flight)
+                        .await);
+                }
+                service.sessions.mark_initialized(&session_id).await;
 
                 // Note: In actix-web we can't inject request parts like in tower,
                 // but session_id is already available through headers
@@ -660,78 +4972,82 @@ where
                 match message {
                     #[allow(unused_mut)]
                     ClientJsonRpcMessage::Request(mut request_msg) => {
-                        // Call on_request hook to propagate extensions from HttpRequest
-                        if let Some(ref hook) = service.on_request {
-                            hook(&req, request_msg.request.extensions_mut());
-                        }
-
-                        // Extract and inject Authorization header for existing sessions.
-                        //
-                        // SECURITY: This transport forwards Authorization headers to MCP services.
-                        //
-                        // MCP-COMPLIANT USAGE: MCP services MUST validate these tokens as intended for themselves
-                        // and MUST NOT forward them to upstream APIs (per MCP specification).
-                        //
-                        // NON-COMPLIANT USAGE: Some implementations (e.g., rmcp-openapi-server) use these tokens
-                        // for upstream API authentication. This violates MCP specifications but may be necessary
-                        // for certain proxy architectures. Use with caution and ensure proper token audience validation.
-                        // See SECURITY.md for details.
-                        //
-                        // Supports OAuth 2.1 token rotation patterns by forwarding each request's
-                        // Authorization independently. This enables:
-                        // - Token rotation within sessions (security best practice)
-                        // - Token refresh when access tokens expire
-                        // - Scope changes for different operations within the same session
-                        //
-                        // The proxy does NOT cache or reuse tokens from session initialization.
-                        // Each request must provide its own valid Authorization header.
-                        #[cfg(feature = "authorization-token-passthrough")]
-                        if let Some(auth_value) = req.headers().get(header::AUTHORIZATION) {
-                            match auth_value.to_str() {
-                                Ok(auth_str)
-                                    if auth_str.starts_with("Bearer ") && auth_str.len() > 7 =>
-                                {
-                                    tracing::debug!(
-                                        "Forwarding Authorization header to MCP service for existing session. \
-                                         Note: MCP services must not pass this token to upstream APIs per MCP spec. \
-                                         See SECURITY.md for details."
-                                    );
-                                    request_msg
-                                        .request
-                                        .extensions_mut()
-                                        .insert(AuthorizationHeader(auth_str.to_string()));
-                                }
-                                Ok(auth_str) if auth_str == "Bearer" || auth_str == "Bearer " => {
-                                    tracing::debug!(
-                                        "Malformed Bearer token in existing session: missing token value"
-                                    );
-                                }
-                                Ok(auth_str) if !auth_str.starts_with("Bearer ") => {
-                                    let auth_type =
-                                        auth_str.split_whitespace().next().unwrap_or("unknown");
-                                    tracing::warn!(
-                                        "Non-Bearer authorization header ignored for existing session: {}",
-                                        auth_type
-                                    );
-                                }
-                                Err(e) => {
-                                    tracing::debug!(
-                                        "Invalid Authorization header encoding in existing session: {}",
-                                        e
-                                    );
-                                }
-                                _ => {}
+                        let cache_miss = match service.lookup_cached(
+                            &req,
+                            &request_msg.request,
+                            &request_msg.id,
+                        ) {
+                            Some(response_cache::Lookup::Hit(response)) => {
+                                return Ok(service
+                                    .answer_from_cache(
+                                        &req,
+                                        Some(&session_id),
+                                        None,
+                                        &response,
+                                        in_flight,
+                                    )
+                                    .await);
                             }
-                        }
-
-                        #[cfg(not(feature = "authorization-token-passthrough"))]
-                        if req.headers().get(header::AUTHORIZATION).is_some() {
-                            tracing::warn!(
-                                "Authorization header present but not forwarded. \
-                                 Enable 'authorization-token-passthrough' feature to forward tokens to MCP services. \
-                                 Note: Token passthrough violates MCP specifications. See SECURITY.md for details."
-                            );
-                        }
+                            Some(response_cache::Lookup::Miss(miss)) => Some(miss),
+                            None => None,
+                        };
+                        let pending =
+                            match service.acquire_pending_request(&session_id, &request_msg.id) {
+                                Ok(pending) => pending,
+                                Err(response) => return Ok(response),
+                            };
+                        let recorder = match service.idempotency_window {
+                            Some(window) => match service.idempotency.claim(
+                                &req,
+                                &session_id,
+                                &request_msg.id,
+                                window,
+                            ) {
+                                idempotency::Claim::Original(recorder) => Some(recorder),
+                                idempotency::Claim::Duplicate(response) => {
+                                    return Ok(service
+                                        .answer_duplicate(
+                                            &req,
+                                            &session_id,
+                                            response,
+                                            request_msg.id,
+                                            in_flight,
+                                        )
+                                        .await);
+                                }
+                            },
+                            None => None,
+                        };
+                        service
+                            .apply_session_extensions(
+                                &session_id,
+                                request_msg.request.extensions_mut(),
+                            )
+                            .await;
+                        service.propagate_request_context(
+                            &req,
+                            request_msg.request.extensions_mut(),
+                            "for existing session",
+                        );
+                        let response_headers =
+                            service.attach_response_headers(request_msg.request.extensions_mut());
+                        service.attach_blob_handles(
+                            &req,
+                            Some(&session_id),
+                            request_msg.request.extensions_mut(),
+                        );
+                        let cancellation = service
+                            .attach_request_cancellation(
+                                Some(&session_id),
+                                request_msg.request.extensions_mut(),
+                            )
+                            .await;
+                        let timeout = service
+                            .attach_request_deadline(&req, request_msg.request.extensions_mut());
+                        let request_id = request_msg.id.clone();
+                        let payload = service
+                            .payload_recorder
+                            .request_source(&request_msg.request);
 
                         let stream = service
                             .session_manager
@@ -740,28 +5056,80 @@ where
                             .map_err(|e| {
                                 InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR)
                             })?;
+                        let outbound = service.outbound(Some(&session_id)).await;
+                        let stream = service.record_events(
+                            session_id.clone(),
+                            request_id.to_string(),
+                            stream,
+                        );
+                        let stream = service.rename_events(&session_id, stream);
+                        let stream = service.limit_session_request(
+                            session_id,
+                            request_id,
+                            cancellation,
+                            timeout,
+                            stream,
+                        );
+                        let stream = match recorder {
+                            Some(recorder) => idempotency::record(stream, recorder),
+                            None => stream,
+                        };
+                        let stream = match cache_miss {
+                            Some(miss) => stream
+                                .inspect(move |msg| {
+                                    if let Some(message) = &msg.message {
+                                        miss.fill(message);
+                                    }
+                                })
+                                .boxed(),
+                            None => stream,
+                        };
+                        let stream = service.pace_for_consumer(stream);
+                        let stream = if response_headers.is_some() {
+                            buffer_until(stream, |msg| msg.message.is_some()).await
+                        } else {
+                            stream
+                        };
 
                         // Convert to SSE format with keep-alive
                         // Keep-alive prevents timeouts during long tool execution with no progress updates
                         // Stream closes automatically after final response (keep-alive stops when stream ends)
-                        let formatted_stream = stream.map(|msg| {
-                            Ok::<_, actix_web::Error>(format_sse_event(
+                        let encoding = PayloadEncoding::negotiate(&req, service.msgpack);
+                        let named_events = service.named_events;
+                        let formatted_stream = stream.map(move |msg| {
+                            let message = msg.message.as_deref().map(|m| outbound.apply(m));
+                            Ok::<_, actix_web::Error>(format_recorded_sse_event(
+                                payload.as_ref(),
+                                encoding,
+                                named_events,
                                 msg.event_id.as_deref(),
-                                msg.message.as_deref(),
+                                message.as_deref(),
                             ))
                         });
-                        let sse_stream =
-                            wrap_with_sse_keepalive(formatted_stream, service.sse_keep_alive);
+                        let sse_stream = wrap_with_sse_keepalive(
+                            pending.hold(in_flight.hold(formatted_stream)),
+                            service.keep_alive_for(&req),
+                            service.keep_alive_frame.clone(),
+                        );
 
-                        Ok(HttpResponse::Ok()
+                        let mut response = HttpResponse::Ok();
+                        response
                             .content_type(EVENT_STREAM_MIME_TYPE)
                             .append_header((CACHE_CONTROL, "no-cache"))
-                            .append_header((HEADER_X_ACCEL_BUFFERING, "no"))
-                            .streaming(sse_stream))
+                            .append_header((HEADER_X_ACCEL_BUFFERING, "no"));
+                        encoding.announce(&mut response);
+                        apply_response_headers(&mut response, response_headers.as_ref());
+                        Ok(response.streaming(sse_stream))
                     }
                     ClientJsonRpcMessage::Notification(_)
                     | ClientJsonRpcMessage::Response(_)
                     | ClientJsonRpcMessage::Error(_) => {
+                        // Answers to the transport's own pings stop here
+                        if let Some(ping_id) = liveness::answered_ping(&message) {
+                            service.sessions.ping_answered(&session_id, ping_id).await;
+                            return Ok(HttpResponse::Accepted().finish());
+                        }
+
                         // Handle notification
                         service
                             .session_manager
@@ -779,6 +5147,9 @@ where
                 // a session id is a 400 Bad Request per MCP 2025-03-26 Streamable
                 // HTTP Session Management. The check happens before create_session
                 // so a rejected request never leaves a stranded session behind.
+                if let Some(response) = service.check_protocol_version(&req, None).await {
+                    return Ok(response);
+                }
                 let is_initialize_request = matches!(
                     &message,
                     ClientJsonRpcMessage::Request(request_msg)
@@ -786,134 +5157,103 @@ where
                 );
 
                 if !is_initialize_request {
+                    if service.hybrid_mode {
+                        tracing::debug!("POST request without session, handling statelessly");
+                        return Self::handle_stateless_request(
+                            req, service, message, None, in_flight, true,
+                        )
+                        .await;
+                    }
                     tracing::warn!("Mcp-Session-Id missing for non-initialize request");
-                    return Ok(HttpResponse::BadRequest().body(MISSING_SESSION_ID_BODY));
+                    return Ok(missing_session_id_response());
                 }
 
                 tracing::debug!("POST request without session, creating new session");
 
+                // Created first, so a failing factory does not leave a session behind
+                let request_id = match &message {
+                    ClientJsonRpcMessage::Request(request_msg) => Some(request_msg.id.clone()),
+                    _ => None,
+                };
+                service.admit_session_creation(&req, request_id.clone())?;
+                service
+                    .admit_tenant_session(&req, request_id.clone())
+                    .await?;
+                let service_instance = service.session_service(&req, request_id)?;
+
                 let (session_id, transport) = service
                     .session_manager
                     .create_session()
                     .await
                     .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-                tracing::info!(%session_id, "Created new session");
+                tracing::debug!(%session_id, "Created new session");
 
+                let mut response_headers = None;
                 if let ClientJsonRpcMessage::Request(request_msg) = &mut message {
-                    // Call on_request hook to propagate extensions from HttpRequest
-                    if let Some(ref hook) = service.on_request {
-                        hook(&req, request_msg.request.extensions_mut());
-                    }
-
-                    // Extract and inject Authorization header if present
-                    //
-                    // SECURITY: This transport forwards Authorization headers to MCP services.
-                    //
-                    // MCP-COMPLIANT USAGE: MCP services MUST validate these tokens as intended for themselves
-                    // and MUST NOT forward them to upstream APIs (per MCP specification).
-                    //
-                    // NON-COMPLIANT USAGE: Some implementations (e.g., rmcp-openapi-server) use these tokens
-                    // for upstream API authentication. This violates MCP specifications but may be necessary
-                    // for certain proxy architectures. Use with caution and ensure proper token audience validation.
-                    // See SECURITY.md for details.
-                    #[cfg(feature = "authorization-token-passthrough")]
-                    if let Some(auth_value) = req.headers().get(header::AUTHORIZATION) {
-                        match auth_value.to_str() {
-                            Ok(auth_str)
-                                if auth_str.starts_with("Bearer ") && auth_str.len() > 7 =>
-                            {
-                                tracing::debug!(
-                                    "Forwarding Authorization header to MCP service for new session. \
-                                     Note: MCP services must not pass this token to upstream APIs per MCP spec. \
-                                     See SECURITY.md for details."
-                                );
-                                request_msg
-                                    .request
-                                    .extensions_mut()
-                                    .insert(AuthorizationHeader(auth_str.to_string()));
-                            }
-                            Ok(auth_str) if auth_str == "Bearer" || auth_str == "Bearer " => {
-                                tracing::debug!(
-                                    "Malformed Bearer token in new session: missing token value"
-                                );
-                            }
-                            Ok(auth_str) if !auth_str.starts_with("Bearer ") => {
-                                let auth_type =
-                                    auth_str.split_whitespace().next().unwrap_or("unknown");
-                                tracing::warn!(
-                                    "Non-Bearer authorization header ignored for new session: {}",
-                                    auth_type
-                                );
-                            }
-                            Err(e) => {
-                                tracing::debug!(
-                                    "Invalid Authorization header encoding in new session: {}",
-                                    e
-                                );
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    #[cfg(not(feature = "authorization-token-passthrough"))]
-                    if req.headers().get(header::AUTHORIZATION).is_some() {
-                        tracing::warn!(
-                            "Authorization header present but not forwarded for new session. \
-                             Enable 'authorization-token-passthrough' feature to forward tokens to MCP services. \
-                             Note: Token passthrough violates MCP specifications. See SECURITY.md for details."
-                        );
-                    }
+                    service.propagate_request_context(
+                        &req,
+                        request_msg.request.extensions_mut(),
+                        "for new session",
+                    );
+                    response_headers =
+                        service.attach_response_headers(request_msg.request.extensions_mut());
+                    service.attach_blob_handles(
+                        &req,
+                        Some(&session_id),
+                        request_msg.request.extensions_mut(),
+                    );
                 }
 
-                let service_instance = service
-                    .get_service()
-                    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
-
-                // Spawn a task to serve the session
-                tokio::spawn({
-                    let session_manager = service.session_manager.clone();
-                    let session_id = session_id.clone();
-                    async move {
-                        let service = serve_server::<S, M::Transport, _, TransportAdapterIdentity>(
-                            service_instance,
-                            transport,
+                service
+                    .spawn_session(&req, session_id.clone(), transport, service_instance)
+                    .await;
+                if let ClientJsonRpcMessage::Request(request_msg) = &mut message {
+                    service
+                        .attach_request_cancellation(
+                            Some(&session_id),
+                            request_msg.request.extensions_mut(),
                         )
                         .await;
-                        match service {
-                            Ok(service) => {
-                                let _ = service.waiting().await;
-                            }
-                            Err(e) => {
-                                tracing::error!("Failed to create service: {e}");
-                            }
-                        }
-                        let _ = session_manager
-                            .close_session(&session_id)
-                            .await
-                            .inspect_err(|e| {
-                                tracing::error!("Failed to close session {session_id}: {e}");
-                            });
-                    }
-                });
+                }
+                service
+                    .capture_session_extensions(&req, &session_id, &mut message)
+                    .await;
+                service.notify_session_created(&req, &session_id, &message);
+                let client_capabilities =
+                    initialize_params(&message).map(|params| params.capabilities.clone());
 
                 // Get initialize response
-                let response = service
+                let mut response = service
                     .session_manager
                     .initialize_session(&session_id, message)
                     .await
                     .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                service
+                    .record_negotiation(&session_id, client_capabilities, &response)
+                    .await;
+                if let Some(ref hook) = service.transform_outbound {
+                    hook(&mut response);
+                }
 
                 tracing::debug!(?response, "Initialization complete, creating SSE stream");
 
                 // Return SSE stream with initialization response (no keep-alive)
                 // Per MCP spec: "After the JSON-RPC response has been sent, the server SHOULD close the SSE stream"
                 // Initialization completes with a single response, so no keep-alive needed
+                let encoding = PayloadEncoding::negotiate(&req, service.msgpack);
+                let event = format_recorded_sse_event(
+                    service
+                        .payload_recorder
+                        .source(|| "initialize".to_owned())
+                        .as_ref(),
+                    encoding,
+                    service.named_events,
+                    None,
+                    Some(&response),
+                );
                 let sse_stream = async_stream::stream! {
-                    yield Ok::<_, actix_web::Error>(Bytes::from(format!(
-                        "data: {}\n\n",
-                        serde_json::to_string(&response).unwrap_or_else(|_| "{}".to_string())
-                    )));
+                    yield Ok::<_, actix_web::Error>(event);
                 };
                 tracing::debug!("Created initialization response stream (closes after response)");
 
@@ -921,12 +5261,15 @@ where
                     ?session_id,
                     "Returning SSE streaming response for initialization"
                 );
-                Ok(HttpResponse::Ok()
+                let mut http_response = HttpResponse::Ok();
+                http_response
                     .content_type(EVENT_STREAM_MIME_TYPE)
                     .append_header((CACHE_CONTROL, "no-cache"))
-                    .append_header((HEADER_X_ACCEL_BUFFERING, "no"))
-                    .append_header((HEADER_SESSION_ID, session_id.as_ref()))
-                    .streaming(sse_stream))
+                    .append_header((HEADER_X_ACCEL_BUFFERING, "no"));
+                service.announce_session_id(&req, &mut http_response, session_id.as_ref());
+                encoding.announce(&mut http_response);
+                apply_response_headers(&mut http_response, response_headers.as_ref());
+                Ok(http_response.streaming(sse_stream))
             }
         } else {
             // Stateless mode: MCP 2025-03-26 Streamable HTTP Session Management
@@ -935,133 +5278,280 @@ where
             // accepted, logged for observability, and otherwise ignored. The
             // Python and TypeScript reference SDKs make the same interpretation.
             tracing::debug!("POST request in stateless mode");
-            if req
-                .headers()
-                .get(HEADER_SESSION_ID)
-                .and_then(|v| v.to_str().ok())
-                .filter(|s| !s.is_empty())
-                .is_some()
-            {
-                tracing::debug!("Mcp-Session-Id header ignored in stateless mode");
+            if let Some(response) = service.check_protocol_version(&req, None).await {
+                return Ok(response);
             }
+            let issued_token = match service.stateless_session(&req, &mut message) {
+                Ok(issued_token) => issued_token,
+                Err(response) => return Ok(response),
+            };
 
-            match message {
-                #[allow(unused_mut)]
-                ClientJsonRpcMessage::Request(mut request) => {
-                    tracing::debug!(?request, "Processing request in stateless mode");
+            Self::handle_stateless_request(req, service, message, issued_token, in_flight, false)
+                .await
+        }
+    }
 
-                    // Call on_request hook to propagate extensions from HttpRequest
-                    if let Some(ref hook) = service.on_request {
-                        hook(&req, request.request.extensions_mut());
+    /// Handles a message without a session, like in stateless mode.
+    ///
+    /// With `shared`, requests are served by the handler shared by all of them
+    /// instead of a handler created for each.
+    async fn handle_stateless_request(
+        req: HttpRequest,
+        service: Data<AppData<S, M>>,
+        message: ClientJsonRpcMessage,
+        issued_token: Option<String>,
+        in_flight: shutdown::InFlight,
+        shared: bool,
+    ) -> Result<HttpResponse> {
+        match message {
+            #[allow(unused_mut)]
+            ClientJsonRpcMessage::Request(mut request) => {
+                tracing::debug!(
+                    request = %service.log_redaction.display(&request),
+                    "Processing request in stateless mode"
+                );
+                let cache_miss = match service.lookup_cached(&req, &request.request, &request.id) {
+                    Some(response_cache::Lookup::Hit(response)) => {
+                        return Ok(service
+                            .answer_from_cache(&req, None, issued_token, &response, in_flight)
+                            .await);
                     }
+                    Some(response_cache::Lookup::Miss(miss)) => Some(miss),
+                    None => None,
+                };
 
-                    // Extract and inject Authorization header if present
-                    //
-                    // SECURITY: This transport forwards Authorization headers to MCP services.
-                    //
-                    // MCP-COMPLIANT USAGE: MCP services MUST validate these tokens as intended for themselves
-                    // and MUST NOT forward them to upstream APIs (per MCP specification).
-                    //
-                    // NON-COMPLIANT USAGE: Some implementations (e.g., rmcp-openapi-server) use these tokens
-                    // for upstream API authentication. This violates MCP specifications but may be necessary
-                    // for certain proxy architectures. Use with caution and ensure proper token audience validation.
-                    // See SECURITY.md for details.
-                    #[cfg(feature = "authorization-token-passthrough")]
-                    if let Some(auth_value) = req.headers().get(header::AUTHORIZATION) {
-                        match auth_value.to_str() {
-                            Ok(auth_str)
-                                if auth_str.starts_with("Bearer ") && auth_str.len() > 7 =>
-                            {
-                                tracing::debug!(
-                                    "Forwarding Authorization header to MCP service in stateless mode. \
-                                     Note: MCP services must not pass this token to upstream APIs per MCP spec. \
-                                     See SECURITY.md for details."
-                                );
-                                request
-                                    .request
-                                    .extensions_mut()
-                                    .insert(AuthorizationHeader(auth_str.to_string()));
-                            }
-                            Ok(auth_str) if auth_str == "Bearer" || auth_str == "Bearer " => {
-                                tracing::debug!(
-                                    "Malformed Bearer token in stateless mode: missing token value"
-                                );
-                            }
-                            Ok(auth_str) if !auth_str.starts_with("Bearer ") => {
-                                let auth_type =
-                                    auth_str.split_whitespace().next().unwrap_or("unknown");
-                                tracing::warn!(
-                                    "Non-Bearer authorization header ignored in stateless mode: {}",
-                                    auth_type
-                                );
-                            }
-                            Err(e) => {
-                                tracing::debug!(
-                                    "Invalid Authorization header encoding in stateless mode: {}",
-                                    e
-                                );
-                            }
-                            _ => {}
-                        }
-                    }
+                service.propagate_request_context(
+                    &req,
+                    request.request.extensions_mut(),
+                    "in stateless mode",
+                );
+                let response_headers =
+                    service.attach_response_headers(request.request.extensions_mut());
+                service.attach_blob_handles(&req, None, request.request.extensions_mut());
+                let cancellation = service
+                    .attach_request_cancellation(None, request.request.extensions_mut())
+                    .await;
+                let timeout =
+                    service.attach_request_deadline(&req, request.request.extensions_mut());
+                let request_id = request.id.clone();
+                let payload = service.payload_recorder.request_source(&request.request);
 
-                    #[cfg(not(feature = "authorization-token-passthrough"))]
-                    if req.headers().get(header::AUTHORIZATION).is_some() {
-                        tracing::warn!(
-                            "Authorization header present but not forwarded in stateless mode. \
-                             Enable 'authorization-token-passthrough' feature to forward tokens to MCP services. \
-                             Note: Token passthrough violates MCP specifications. See SECURITY.md for details."
-                        );
-                    }
+                // In stateless mode, handle the request directly
+                let service_instance = if shared && service.request_tenant(&req).is_none() {
+                    let instance = service
+                        .shared_service
+                        .get_or_try_init(|| service.get_service(&req, Some(request_id.clone())))
+                        .await?;
+                    handler_runtime::OnHandlerRuntime::shared(
+                        instance,
+                        service.handler_runtime.clone(),
+                    )
+                } else {
+                    handler_runtime::OnHandlerRuntime::new(
+                        service.get_service(&req, Some(request_id.clone()))?,
+                        service.handler_runtime.clone(),
+                    )
+                };
+                let service_instance = error_reports::ReportErrors::new(
+                    service_instance,
+                    None,
+                    service.on_internal_error.clone(),
+                );
 
-                    // In stateless mode, handle the request directly
-                    let service_instance = service
-                        .get_service()
-                        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                let (transport, receiver) =
+                    OneshotTransport::<RoleServer>::new(ClientJsonRpcMessage::Request(request));
+                let transport = response_cache::InvalidateOnChange::new(
+                    transport,
+                    service.response_cache.clone(),
+                    service.request_tenant(&req),
+                );
+                let transport =
+                    tool_toggles::HideDisabledTools::new(transport, service.tool_toggles.clone());
+                let service_instance = tenant_quotas::ToolCallQuota::new(
+                    service_instance,
+                    req.extensions().get::<TenantId>().cloned(),
+                    service.tenant_quotas.as_deref(),
+                    &service.tenant_counters,
+                );
+                let service_handle = serve_directly(service_instance, transport, None);
 
-                    let (transport, receiver) =
-                        OneshotTransport::<RoleServer>::new(ClientJsonRpcMessage::Request(request));
-                    let service_handle = serve_directly(service_instance, transport, None);
+                let task = tokio::spawn(async move {
+                    // Let the service process the request
+                    let _ = service_handle.waiting().await;
+                });
+                let abort_task = task.abort_handle();
+                let timeout_cancellation = cancellation.clone();
+                let receiver = request_timeout::with_timeout(
+                    ReceiverStream::new(receiver),
+                    timeout,
+                    move |timeout| async move {
+                        timeout_cancellation.cancel();
+                        task.abort();
+                        request_timeout::timeout_error(request_id, timeout)
+                    },
+                );
+                let receiver = disconnect::on_disconnect(
+                    receiver,
+                    |_| false,
+                    move || {
+                        tracing::debug!("Client disconnected, aborting stateless request");
+                        cancellation.cancel();
+                        abort_task.abort();
+                    },
+                );
 
-                    tokio::spawn(async move {
-                        // Let the service process the request
-                        let _ = service_handle.waiting().await;
-                    });
+                // Convert receiver stream to SSE format with keep-alive
+                // Keep-alive prevents timeouts during long tool execution with no progress updates
+                // Stream closes automatically after final response (keep-alive stops when stream ends)
+                let receiver = match cache_miss {
+                    Some(miss) => receiver.inspect(move |message| miss.fill(message)).boxed(),
+                    None => receiver,
+                };
+                let receiver = service.pace_for_consumer(receiver);
+                let receiver = if response_headers.is_some() {
+                    buffer_until(receiver, |_| true).await
+                } else {
+                    receiver
+                };
+                let outbound = service.outbound(None).await;
+                let encoding = PayloadEncoding::negotiate(&req, service.msgpack);
+                let named_events = service.named_events;
+                let log_redaction = service.log_redaction.clone();
+                let formatted_stream = receiver.map(move |message| {
+                    tracing::info!(message = %log_redaction.display(&message));
+                    let message = outbound.apply(&message);
+                    Ok::<_, actix_web::Error>(format_recorded_sse_event(
+                        payload.as_ref(),
+                        encoding,
+                        named_events,
+                        None,
+                        Some(&message),
+                    ))
+                });
+                let sse_stream = wrap_with_sse_keepalive(
+                    in_flight.hold(formatted_stream),
+                    service.keep_alive_for(&req),
+                    service.keep_alive_frame.clone(),
+                );
 
-                    // Convert receiver stream to SSE format with keep-alive
-                    // Keep-alive prevents timeouts during long tool execution with no progress updates
-                    // Stream closes automatically after final response (keep-alive stops when stream ends)
-                    let formatted_stream = ReceiverStream::new(receiver).map(|message| {
-                        tracing::info!(?message);
-                        let data =
-                            serde_json::to_string(&message).unwrap_or_else(|_| "{}".to_string());
-                        Ok::<_, actix_web::Error>(Bytes::from(format!("data: {data}\n\n")))
-                    });
-                    let sse_stream =
-                        wrap_with_sse_keepalive(formatted_stream, service.sse_keep_alive);
-
-                    Ok(HttpResponse::Ok()
-                        .content_type(EVENT_STREAM_MIME_TYPE)
-                        .append_header((CACHE_CONTROL, "no-cache"))
-                        .append_header((HEADER_X_ACCEL_BUFFERING, "no"))
-                        .streaming(sse_stream))
+                let mut response = HttpResponse::Ok();
+                response
+                    .content_type(EVENT_STREAM_MIME_TYPE)
+                    .append_header((CACHE_CONTROL, "no-cache"))
+                    .append_header((HEADER_X_ACCEL_BUFFERING, "no"));
+                if let Some(token) = issued_token {
+                    service.announce_session_id(&req, &mut response, &token);
                 }
-                _ => Ok(HttpResponse::UnprocessableEntity().body("Unexpected message type")),
+                encoding.announce(&mut response);
+                apply_response_headers(&mut response, response_headers.as_ref());
+                Ok(response.streaming(sse_stream))
             }
+            _ => Ok(Problem::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "unexpected_message",
+                "Unexpected message type",
+            )
+            .into_response()),
         }
     }
 
-    async fn handle_delete(req: HttpRequest, service: Data<AppData<S, M>>) -> Result<HttpResponse> {
-        // Check session id
-        let session_id = req
+    async fn handle_blob(
+        req: HttpRequest,
+        blob_id: web::Path<String>,
+        service: Data<AppData<S, M>>,
+    ) -> HttpResponse {
+        if let Some(response) = service.check_request_guard(&req) {
+            return response;
+        }
+        let mut session_id = service.request_session_id(&req);
+        if let Some(id) = session_id
+            && !service
+                .session_identity_matches(&req, &id.to_owned().into())
+                .await
+        {
+            session_id = None;
+        }
+
+        // Blobs of other sessions are reported as missing, not as forbidden
+        match service.blob_store.get(&blob_id, session_id) {
+            Some((mime_type, data)) => HttpResponse::Ok()
+                .content_type(mime_type)
+                .append_header((CACHE_CONTROL, "private, no-store"))
+                .body(data),
+            None => {
+                tracing::debug!(blob_id = %blob_id, "Blob not found or expired");
+                Problem::new(
+                    StatusCode::NOT_FOUND,
+                    "blob_not_found",
+                    BLOB_NOT_FOUND_MESSAGE,
+                )
+                .into_response()
+            }
+        }
+    }
+
+    async fn handle_upload(
+        req: HttpRequest,
+        body: Bytes,
+        service: Data<AppData<S, M>>,
+    ) -> Result<HttpResponse> {
+        if let Some(response) = service.check_request_guard(&req) {
+            return Ok(response);
+        }
+        let Some(ttl) = service.upload_ttl else {
+            return Ok(HttpResponse::NotFound().finish());
+        };
+
+        let session_id: Option<SessionId> = if service.stateful_mode {
+            let Some(session_id) = service
+                .request_session_id(&req)
+                .map(|s| s.to_owned().into())
+            else {
+                return Ok(missing_session_id_response());
+            };
+            let has_session = service
+                .session_manager
+                .has_session(&session_id)
+                .await
+                .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            if !has_session || !service.session_identity_matches(&req, &session_id).await {
+                tracing::warn!(%session_id, "Session not found");
+                return Ok(session_not_found_response(Some(&session_id)));
+            }
+            Some(session_id)
+        } else {
+            None
+        };
+
+        let mime_type = req
             .headers()
-            .get(HEADER_SESSION_ID)
+            .get(header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok())
-            .filter(|s| !s.is_empty())
+            .unwrap_or("application/octet-stream")
+            .to_owned();
+        let size = body.len();
+        let handle = service
+            .upload_store
+            .insert(session_id.clone(), mime_type, body, ttl);
+        tracing::debug!(?session_id, %handle, size, "Stored upload");
+
+        Ok(HttpResponse::Created()
+            .content_type(JSON_MIME_TYPE)
+            .body(serde_json::json!({ "handle": handle }).to_string()))
+    }
+
+    async fn handle_delete(req: HttpRequest, service: Data<AppData<S, M>>) -> Result<HttpResponse> {
+        if let Some(response) = service.check_request_guard(&req) {
+            return Ok(response);
+        }
+
+        // Check session id
+        let session_id = service
+            .request_session_id(&req)
             .map(|s| s.to_owned().into());
 
         let Some(session_id) = session_id else {
-            return Ok(HttpResponse::BadRequest().body(MISSING_SESSION_ID_BODY));
+            return Ok(missing_session_id_response());
         };
 
         tracing::debug!(%session_id, "DELETE request to close session");
@@ -1072,10 +5562,20 @@ where
             .await
             .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-        if !has_session {
+        if !has_session || !service.session_identity_matches(&req, &session_id).await {
             tracing::warn!(%session_id, "Session not found");
-            return Ok(HttpResponse::NotFound().body(SESSION_NOT_FOUND_BODY));
+            return Ok(session_not_found_response(Some(&session_id)));
         }
+        if let Some(response) = service
+            .check_protocol_version(&req, Some(&session_id))
+            .await
+        {
+            return Ok(response);
+        }
+
+        service
+            .session_closed(&session_id, SessionCloseReason::ClientDelete)
+            .await;
 
         // Close session
         service
@@ -1084,20 +5584,24 @@ where
             .await
             .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
 
-        tracing::info!(%session_id, "Session closed");
+        tracing::debug!(%session_id, "Session deleted by client");
 
-        Ok(HttpResponse::NoContent().finish())
+        let mut response = HttpResponse::NoContent();
+        if let Some(cookie) = service.session_cookie.as_deref() {
+            cookie.clear(&req, &mut response);
+        }
+        Ok(response.finish())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use rmcp::model::{
-        EmptyResult, JsonRpcResponse, JsonRpcVersion2_0, RequestId, ServerJsonRpcMessage,
-        ServerResult,
+        EmptyResult, ErrorData, JsonRpcResponse, JsonRpcVersion2_0, RequestId,
+        ServerJsonRpcMessage, ServerResult,
     };
 
-    use super::format_sse_event;
+    use super::{PayloadEncoding, format_sse_event};
 
     fn dummy_message() -> ServerJsonRpcMessage {
         ServerJsonRpcMessage::Response(JsonRpcResponse {
@@ -1115,7 +5619,7 @@ mod tests {
     /// empty `data` field instead.
     #[test]
     fn priming_event_emits_empty_data_not_null() {
-        let bytes = format_sse_event(Some("0/0"), None);
+        let bytes = format_sse_event(PayloadEncoding::Json, false, Some("0/0"), None);
         let wire = std::str::from_utf8(&bytes).expect("utf-8");
 
         assert_eq!(wire, "id: 0/0\ndata:\n\n");
@@ -1128,7 +5632,7 @@ mod tests {
     #[test]
     fn message_event_serializes_payload_as_json() {
         let message = dummy_message();
-        let bytes = format_sse_event(Some("1/0"), Some(&message));
+        let bytes = format_sse_event(PayloadEncoding::Json, false, Some("1/0"), Some(&message));
         let wire = std::str::from_utf8(&bytes).expect("utf-8");
 
         assert_eq!(
@@ -1140,7 +5644,7 @@ mod tests {
     #[test]
     fn message_event_without_event_id_omits_id_line() {
         let message = dummy_message();
-        let bytes = format_sse_event(None, Some(&message));
+        let bytes = format_sse_event(PayloadEncoding::Json, false, None, Some(&message));
         let wire = std::str::from_utf8(&bytes).expect("utf-8");
 
         assert_eq!(
@@ -1148,4 +5652,26 @@ mod tests {
             "data: {\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{}}\n\n"
         );
     }
+
+    #[test]
+    fn named_events_label_messages_and_errors() {
+        let message = dummy_message();
+        let bytes = format_sse_event(PayloadEncoding::Json, true, Some("1/0"), Some(&message));
+        let wire = std::str::from_utf8(&bytes).expect("utf-8");
+        assert!(
+            wire.starts_with("id: 1/0\nevent: message\ndata: {"),
+            "{wire:?}"
+        );
+
+        let error = ServerJsonRpcMessage::error(
+            ErrorData::invalid_params("bad", None),
+            Some(RequestId::Number(1)),
+        );
+        let bytes = format_sse_event(PayloadEncoding::Json, true, None, Some(&error));
+        let wire = std::str::from_utf8(&bytes).expect("utf-8");
+        assert!(wire.starts_with("event: error\ndata: {"), "{wire:?}");
+
+        let bytes = format_sse_event(PayloadEncoding::Json, true, Some("0/0"), None);
+        assert_eq!(&bytes[..], b"id: 0/0\ndata:\n\n");
+    }
 }
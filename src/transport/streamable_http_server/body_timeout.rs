@@ -0,0 +1,79 @@
+//! Timeout on receiving request bodies.
+//!
+//! With [`body_read_timeout`](super::StreamableHttpServiceBuilder::body_read_timeout)
+//! set, the payload of every request to the service's scope must be received
+//! in full within the timeout, counted from when the request reaches the
+//! scope. A client dripping its body slower than that is answered with
+//! `408 Request Timeout` and its connection is closed, whatever actix-web's
+//! `client_request_timeout`, which only covers the request head, is set to.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use actix_web::{
+    HttpMessage, HttpResponse,
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    error::PayloadError,
+    http::{ConnectionType, StatusCode},
+};
+use futures::StreamExt;
+
+use crate::transport::Problem;
+
+/// Limits the time left to receive the body of `req` to `timeout`.
+///
+/// Returns a flag set once the timeout has elapsed before the end of the body,
+/// to be passed to [`answer_timed_out`] with the response.
+pub(super) fn limit(req: &mut ServiceRequest, timeout: Duration) -> Arc<AtomicBool> {
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let flag = timed_out.clone();
+    let mut payload = req.take_payload();
+    let stream = async_stream::stream! {
+        let deadline = tokio::time::sleep(timeout);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                chunk = payload.next() => match chunk {
+                    Some(chunk) => yield chunk,
+                    None => break,
+                },
+                _ = &mut deadline => {
+                    tracing::warn!(?timeout, "Request body not received in time");
+                    flag.store(true, Ordering::Relaxed);
+                    yield Err(PayloadError::Io(std::io::ErrorKind::TimedOut.into()));
+                    break;
+                }
+            }
+        }
+    };
+    req.set_payload(Payload::from(stream.boxed_local()));
+    timed_out
+}
+
+/// Replaces `res` with a `408 Request Timeout` problem closing the connection
+/// if the body of its request timed out.
+pub(super) fn answer_timed_out(
+    res: ServiceResponse,
+    timed_out: &AtomicBool,
+    timeout: Duration,
+) -> ServiceResponse {
+    if !timed_out.load(Ordering::Relaxed) {
+        return res;
+    }
+    let mut response: HttpResponse = Problem::new(
+        StatusCode::REQUEST_TIMEOUT,
+        "body_read_timeout",
+        format!("Request body not received within {}ms", timeout.as_millis()),
+    )
+    .into();
+    // The rest of the body may still be on its way
+    response
+        .head_mut()
+        .set_connection_type(ConnectionType::Close);
+    res.into_response(response)
+}
@@ -0,0 +1,84 @@
+//! CORS preflight and response headers.
+//!
+//! Browser clients calling the MCP endpoints from another origin first send an
+//! `OPTIONS` preflight, then expect `Access-Control-*` headers on every
+//! response. With [`cors`](super::StreamableHttpServiceBuilder::cors) enabled,
+//! the transport answers both following `allowed_origins`, so the scope does
+//! not need to be wrapped in a separate CORS middleware whose policy could
+//! drift from the origin check.
+
+use actix_web::{
+    HttpRequest, HttpResponse,
+    http::header::{self, HeaderMap, HeaderValue},
+};
+
+/// Response headers browser scripts may read.
+const EXPOSED_HEADERS: &str = "Mcp-Session-Id, Mcp-Protocol-Version";
+/// How long browsers may cache a preflight answer, in seconds.
+const PREFLIGHT_MAX_AGE_SECS: u32 = 24 * 60 * 60;
+
+/// Returns whether `origin` is in `allowed_origins`, an empty list allowing all.
+pub(super) fn origin_allowed(allowed_origins: &[String], origin: &str) -> bool {
+    allowed_origins.is_empty()
+        || allowed_origins
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(origin))
+}
+
+/// Answers an `OPTIONS` request to a route serving `methods`.
+///
+/// A CORS preflight gets the allowed methods and headers when `cors` is
+/// enabled; the origin has been checked by the caller and is echoed by
+/// [`add_response_headers`]. Any other `OPTIONS` request only gets `Allow`.
+pub(super) fn options_response(req: &HttpRequest, methods: &str, cors: bool) -> HttpResponse {
+    let mut response = HttpResponse::NoContent();
+    response.insert_header((header::ALLOW, methods));
+    let is_preflight = req.headers().contains_key(header::ORIGIN)
+        && req
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+    if cors && is_preflight {
+        response
+            .insert_header((header::ACCESS_CONTROL_ALLOW_METHODS, methods))
+            .insert_header((header::ACCESS_CONTROL_MAX_AGE, PREFLIGHT_MAX_AGE_SECS));
+        // Whatever the client asks for; unknown headers are ignored by the handlers
+        if let Some(headers) = req.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS) {
+            response.insert_header((header::ACCESS_CONTROL_ALLOW_HEADERS, headers.clone()));
+        }
+    }
+    response.finish()
+}
+
+/// Returns the origin of `req` to echo in the CORS headers, if it is allowed.
+pub(super) fn response_origin(
+    req: &HttpRequest,
+    allowed_origins: &[String],
+) -> Option<HeaderValue> {
+    let origin = req.headers().get(header::ORIGIN)?;
+    let allowed = origin
+        .to_str()
+        .is_ok_and(|origin| origin_allowed(allowed_origins, origin));
+    allowed.then(|| origin.clone())
+}
+
+/// Adds the CORS headers to a response to a request from `origin`.
+///
+/// `credentials` allows requests sent with cookies.
+pub(super) fn add_response_headers(
+    origin: HeaderValue,
+    credentials: bool,
+    headers: &mut HeaderMap,
+) {
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    headers.insert(
+        header::ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static(EXPOSED_HEADERS),
+    );
+    if credentials {
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+            HeaderValue::from_static("true"),
+        );
+    }
+    headers.append(header::VARY, HeaderValue::from_static("Origin"));
+}
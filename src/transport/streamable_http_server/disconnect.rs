@@ -0,0 +1,65 @@
+//! Cleanup after SSE consumers disconnect.
+//!
+//! actix-web drops the body of a streaming response as soon as writing to the
+//! connection fails, so a dropped response stream means the client is gone.
+//! Without cleanup, the handler of a POSTed request would keep running and the
+//! session manager would keep its response channel until the next send to it
+//! fails. Instead, when the stream answering a request is dropped before it
+//! ended, the request is cancelled right away: through a
+//! `notifications/cancelled` sent to the session in stateful mode, by aborting
+//! the request task in stateless mode.
+//!
+//! Streams that delivered an event id are left alone, since the client may
+//! resume them with `Last-Event-ID`. The standalone `GET` stream needs no
+//! cleanup of its own: the session manager replaces its channel when the
+//! client opens a new one, and the session itself expires on inactivity. Disconnects are noticed on the next write
+//! to the connection, so the SSE keep-alive interval bounds the delay; servers
+//! built with `HttpServer::h1_allow_half_closed(false)` also notice clients
+//! closing their end of the connection.
+
+use futures::{Stream, StreamExt, stream::BoxStream};
+
+/// Runs its callback when dropped, unless disarmed.
+struct DisconnectGuard<F: FnOnce()>(Option<F>);
+
+impl<F: FnOnce()> DisconnectGuard<F> {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl<F: FnOnce()> Drop for DisconnectGuard<F> {
+    fn drop(&mut self) {
+        if let Some(on_disconnect) = self.0.take() {
+            on_disconnect();
+        }
+    }
+}
+
+/// Calls `on_disconnect` if `stream` is dropped before it ended.
+///
+/// The callback is disarmed once an item matching `disarm` is yielded.
+pub(super) fn on_disconnect<St, D, F>(
+    stream: St,
+    disarm: D,
+    on_disconnect: F,
+) -> BoxStream<'static, St::Item>
+where
+    St: Stream + Send + 'static,
+    St::Item: Send + 'static,
+    D: Fn(&St::Item) -> bool + Send + 'static,
+    F: FnOnce() + Send + 'static,
+{
+    let mut guard = DisconnectGuard(Some(on_disconnect));
+    async_stream::stream! {
+        let mut stream = Box::pin(stream);
+        while let Some(item) = stream.next().await {
+            if disarm(&item) {
+                guard.disarm();
+            }
+            yield item;
+        }
+        guard.disarm();
+    }
+    .boxed()
+}
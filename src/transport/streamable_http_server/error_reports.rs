@@ -0,0 +1,194 @@
+//! Reporting of handler panics and internal errors.
+//!
+//! A panic inside a tool would otherwise unwind through the session's service
+//! task, leaving the client without a response and the failure visible only in
+//! the logs. Every handler invocation is run behind a panic-catching wrapper:
+//! a panic is answered with a JSON-RPC internal error and, like a handler
+//! returning an internal error itself, passed to the
+//! [`on_internal_error`](super::StreamableHttpServiceBuilder::on_internal_error)
+//! hook as an [`ErrorReport`], ready to be forwarded to an error tracker.
+
+use std::{any::Any, panic::AssertUnwindSafe, sync::Arc};
+
+use futures::FutureExt;
+use rmcp::{
+    ErrorData, RoleServer, Service,
+    model::{ClientNotification, ClientRequest, ErrorCode, RequestId, ServerInfo, ServerResult},
+    service::{NotificationContext, RequestContext},
+    transport::common::server_side_http::SessionId,
+};
+
+use super::OnInternalErrorHook;
+use crate::transport::ClientConnectionInfo;
+
+const HANDLER_PANICKED_MESSAGE: &str = "Handler panicked";
+
+/// What went wrong in an [`ErrorReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorReportKind {
+    /// The handler panicked; the client received an internal error.
+    Panic,
+    /// The handler returned a JSON-RPC internal error.
+    InternalError,
+}
+
+/// A handler failure passed to the `on_internal_error` hook.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ErrorReport {
+    /// Whether the handler panicked or returned an internal error.
+    pub kind: ErrorReportKind,
+    /// The panic message, or the message of the returned error.
+    pub message: String,
+    /// Session the failing message was sent in, if any.
+    pub session_id: Option<SessionId>,
+    /// Id of the failing request; `None` for notifications.
+    pub request_id: Option<RequestId>,
+    /// Method of the failing message, e.g. `tools/call`.
+    pub method: String,
+    /// Connection details of the HTTP request that carried the message.
+    pub connection: Option<ClientConnectionInfo>,
+}
+
+/// MCP service catching the panics of its handlers and reporting them, and the
+/// internal errors they return, to the `on_internal_error` hook.
+pub(super) struct ReportErrors<S> {
+    inner: S,
+    session_id: Option<SessionId>,
+    hook: Option<Arc<OnInternalErrorHook>>,
+}
+
+impl<S> ReportErrors<S> {
+    pub(super) fn new(
+        inner: S,
+        session_id: Option<SessionId>,
+        hook: Option<Arc<OnInternalErrorHook>>,
+    ) -> Self {
+        Self {
+            inner,
+            session_id,
+            hook,
+        }
+    }
+
+    fn report(
+        &self,
+        kind: ErrorReportKind,
+        message: String,
+        request_id: Option<RequestId>,
+        method: &str,
+        connection: Option<ClientConnectionInfo>,
+    ) {
+        match kind {
+            ErrorReportKind::Panic => tracing::error!(
+                session_id = ?self.session_id,
+                ?request_id,
+                method,
+                "Handler panicked: {message}"
+            ),
+            ErrorReportKind::InternalError => tracing::debug!(
+                session_id = ?self.session_id,
+                ?request_id,
+                method,
+                "Handler returned an internal error: {message}"
+            ),
+        }
+        if let Some(hook) = &self.hook {
+            hook(&ErrorReport {
+                kind,
+                message,
+                session_id: self.session_id.clone(),
+                request_id,
+                method: method.to_owned(),
+                connection,
+            });
+        }
+    }
+}
+
+impl<S> Service<RoleServer> for ReportErrors<S>
+where
+    S: Service<RoleServer>,
+{
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, ErrorData> {
+        let method = request.method().to_owned();
+        let request_id = context.id.clone();
+        let connection = context.extensions.get::<ClientConnectionInfo>().cloned();
+        match AssertUnwindSafe(self.inner.handle_request(request, context))
+            .catch_unwind()
+            .await
+        {
+            Ok(Err(error)) if error.code == ErrorCode::INTERNAL_ERROR => {
+                self.report(
+                    ErrorReportKind::InternalError,
+                    error.message.to_string(),
+                    Some(request_id),
+                    &method,
+                    connection,
+                );
+                Err(error)
+            }
+            Ok(result) => result,
+            Err(panic) => {
+                self.report(
+                    ErrorReportKind::Panic,
+                    panic_message(&*panic),
+                    Some(request_id),
+                    &method,
+                    connection,
+                );
+                Err(ErrorData::internal_error(HANDLER_PANICKED_MESSAGE, None))
+            }
+        }
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        let method = notification_method(&notification);
+        let connection = context.extensions.get::<ClientConnectionInfo>().cloned();
+        AssertUnwindSafe(self.inner.handle_notification(notification, context))
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|panic| {
+                self.report(
+                    ErrorReportKind::Panic,
+                    panic_message(&*panic),
+                    None,
+                    &method,
+                    connection,
+                );
+                Err(ErrorData::internal_error(HANDLER_PANICKED_MESSAGE, None))
+            })
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        self.inner.get_info()
+    }
+}
+
+/// Returns the method of a notification, which rmcp only exposes serialized.
+fn notification_method(notification: &ClientNotification) -> String {
+    serde_json::to_value(notification)
+        .ok()
+        .and_then(|value| value.get("method")?.as_str().map(str::to_owned))
+        .unwrap_or_default()
+}
+
+/// Returns the message a panic was raised with.
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
@@ -0,0 +1,104 @@
+//! Custom SSE event ids.
+//!
+//! The session manager assigns the event ids of a session's streams and parses
+//! them back on `Last-Event-ID` resumption. With an
+//! [`event_id_generator`](super::StreamableHttpServiceBuilder::event_id_generator)
+//! configured, the transport sends the generated id in place of the native
+//! one and remembers which native id it stands for, so a client resuming from
+//! a generated id is resumed from the right event.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use futures::{Stream, StreamExt, stream::BoxStream};
+use rmcp::transport::streamable_http_server::session::{ServerSseMessage, SessionId};
+
+use super::EventIdHook;
+
+/// Number of generated ids remembered per session for resumption.
+const EVENT_IDS_CAPACITY: usize = 1024;
+
+/// Generated ids of a session, oldest first, with the native ids they replace.
+#[derive(Default)]
+struct Recent {
+    order: VecDeque<String>,
+    native: HashMap<String, String>,
+}
+
+/// Generates the event ids sent to clients and maps them back.
+#[derive(Clone)]
+pub(super) struct EventIds {
+    hook: Arc<EventIdHook>,
+    sessions: Arc<Mutex<HashMap<SessionId, Recent>>>,
+}
+
+impl EventIds {
+    pub(super) fn new(hook: Arc<EventIdHook>) -> Self {
+        Self {
+            hook,
+            sessions: Arc::default(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<SessionId, Recent>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Generates the id sent in place of the native id `native`.
+    fn assign(&self, session_id: &SessionId, native: &str) -> String {
+        let generated = (self.hook)(session_id, native);
+        let mut sessions = self.lock();
+        let recent = sessions.entry(session_id.clone()).or_default();
+        if recent
+            .native
+            .insert(generated.clone(), native.to_owned())
+            .is_none()
+        {
+            recent.order.push_back(generated.clone());
+            if recent.order.len() > EVENT_IDS_CAPACITY
+                && let Some(oldest) = recent.order.pop_front()
+            {
+                recent.native.remove(&oldest);
+            }
+        }
+        generated
+    }
+
+    /// Returns the native id a `Last-Event-ID` stands for.
+    ///
+    /// Ids the transport did not generate (or no longer remembers) are returned
+    /// unchanged, for the session manager to judge.
+    pub(super) fn resolve(&self, session_id: &SessionId, last_event_id: String) -> String {
+        self.lock()
+            .get(session_id)
+            .and_then(|recent| recent.native.get(&last_event_id).cloned())
+            .unwrap_or(last_event_id)
+    }
+
+    /// Replaces the native event ids of a stream of `session_id`.
+    pub(super) fn rename<St>(
+        &self,
+        session_id: SessionId,
+        stream: St,
+    ) -> BoxStream<'static, ServerSseMessage>
+    where
+        St: Stream<Item = ServerSseMessage> + Send + 'static,
+    {
+        let ids = self.clone();
+        stream
+            .map(move |mut msg| {
+                if let Some(native) = msg.event_id.take() {
+                    msg.event_id = Some(ids.assign(&session_id, &native));
+                }
+                msg
+            })
+            .boxed()
+    }
+
+    /// Forgets the ids of a closed session.
+    pub(super) fn remove_session(&self, session_id: &SessionId) {
+        self.lock().remove(session_id);
+    }
+}
@@ -0,0 +1,91 @@
+//! Execution of MCP handlers on a dedicated runtime.
+//!
+//! rmcp runs every request handler on the runtime of the session, which for
+//! this transport is the actix-web worker that created it. A CPU-heavy tool
+//! then competes with the SSE writes and connection handling of that worker.
+//! With a handler runtime configured, each request is handed to that runtime
+//! instead and only its result travels back to the worker.
+//!
+//! Requests abandoned by rmcp, e.g. because the session closed, are aborted on
+//! the handler runtime as well. Notifications are cheap and stay on the worker.
+
+use std::sync::Arc;
+
+use futures::future::Either;
+use rmcp::{
+    ErrorData, RoleServer, Service,
+    model::{ClientNotification, ClientRequest, ServerInfo, ServerResult},
+    service::{NotificationContext, RequestContext},
+};
+use tokio::{runtime::Handle, task::JoinHandle};
+
+/// MCP service whose requests run on the handler runtime, if one is configured.
+pub(super) struct OnHandlerRuntime<S> {
+    inner: Arc<S>,
+    runtime: Option<Handle>,
+}
+
+impl<S> OnHandlerRuntime<S> {
+    pub(super) fn new(inner: S, runtime: Option<Handle>) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            runtime,
+        }
+    }
+
+    /// Wraps a handler shared with other services.
+    pub(super) fn shared(inner: Arc<S>, runtime: Option<Handle>) -> Self {
+        Self { inner, runtime }
+    }
+}
+
+impl<S> Service<RoleServer> for OnHandlerRuntime<S>
+where
+    S: Service<RoleServer>,
+{
+    fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<ServerResult, ErrorData>> + Send + '_ {
+        let Some(runtime) = &self.runtime else {
+            return Either::Left(self.inner.handle_request(request, context));
+        };
+        let inner = self.inner.clone();
+        let task =
+            AbortOnDrop(runtime.spawn(async move { inner.handle_request(request, context).await }));
+        Either::Right(async move {
+            let mut task = task;
+            match (&mut task.0).await {
+                Ok(result) => result,
+                // Handed back to the caller, which reports the panic
+                Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                Err(e) => {
+                    tracing::error!("Handler task failed: {e}");
+                    Err(ErrorData::internal_error("Handler task failed", None))
+                }
+            }
+        })
+    }
+
+    fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        self.inner.handle_notification(notification, context)
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        self.inner.get_info()
+    }
+}
+
+/// Aborts the handler task when its result is no longer awaited.
+struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
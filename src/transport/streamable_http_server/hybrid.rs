@@ -0,0 +1,63 @@
+//! Session-less requests next to sessions.
+//!
+//! In hybrid mode, a stateful service also answers requests that carry no
+//! `Mcp-Session-Id`, as a stateless service would: simple one-shot clients call
+//! tools right away while full clients initialize sessions on the same
+//! endpoint. Session-less requests share a single handler, created on the first
+//! of them, rather than paying for a new one each.
+
+use std::{any::Any, marker::PhantomData, sync::Arc};
+
+use tokio::sync::OnceCell;
+
+/// Handler shared by the session-less requests, created on first use.
+///
+/// Stored type-erased, so the service state stays `Send` whatever the handler
+/// type; the handler itself is only touched where it is known to be
+/// `Send + Sync`.
+pub(super) struct SharedService<S> {
+    instance: Arc<OnceCell<Arc<dyn Any + Send + Sync>>>,
+    _service: PhantomData<fn() -> S>,
+}
+
+impl<S> SharedService<S>
+where
+    S: Send + Sync + 'static,
+{
+    /// Returns the shared handler, creating it with `factory` if needed.
+    ///
+    /// A failing factory is retried on the next request.
+    pub(super) async fn get_or_try_init<E>(
+        &self,
+        factory: impl FnOnce() -> Result<S, E>,
+    ) -> Result<Arc<S>, E> {
+        let instance = self
+            .instance
+            .get_or_try_init(|| async {
+                factory().map(|service| Arc::new(service) as Arc<dyn Any + Send + Sync>)
+            })
+            .await?;
+        Ok(instance
+            .clone()
+            .downcast()
+            .unwrap_or_else(|_| unreachable!("the shared handler is always an S")))
+    }
+}
+
+impl<S> Default for SharedService<S> {
+    fn default() -> Self {
+        Self {
+            instance: Arc::new(OnceCell::new()),
+            _service: PhantomData,
+        }
+    }
+}
+
+impl<S> Clone for SharedService<S> {
+    fn clone(&self) -> Self {
+        Self {
+            instance: self.instance.clone(),
+            _service: PhantomData,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Deduplication of retried POST requests.
+//!
+//! Clients behind retry middleware may deliver the same request twice, e.g.
+//! when the connection dropped before the response arrived. With an
+//! idempotency window configured, the transport remembers the response of each
+//! request of a session, keyed by its `Idempotency-Key` header or, without the
+//! header, by its JSON-RPC id. A duplicate delivery does not reach the handler
+//! again: it is answered with the response of the original request, once that
+//! response exists.
+//!
+//! The stream of the original request is driven by a task of its own, so its
+//! response is recorded even if the client disconnected before receiving it.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::HttpRequest;
+use futures::{Stream, StreamExt, stream::BoxStream};
+use rmcp::{
+    model::{RequestId, ServerJsonRpcMessage},
+    transport::streamable_http_server::session::{ServerSseMessage, SessionId},
+};
+use tokio::sync::{mpsc, watch};
+
+/// Header carrying the client-chosen idempotency key of a request.
+const HEADER_IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+
+/// Buffer between the task driving an original request and its HTTP response.
+const FORWARD_CAPACITY: usize = 16;
+
+/// The response of a request, once known.
+pub(super) type ResponseSlot = Option<Arc<ServerJsonRpcMessage>>;
+
+struct Entry {
+    response: watch::Receiver<ResponseSlot>,
+    created_at: Instant,
+}
+
+/// Result of looking up a request in the [`IdempotencyCache`].
+pub(super) enum Claim {
+    /// First delivery of the request; its response is recorded with the recorder.
+    Original(Recorder),
+    /// Duplicate delivery; the receiver yields the response of the original.
+    Duplicate(watch::Receiver<ResponseSlot>),
+}
+
+/// Records the response of an original request.
+///
+/// Dropped without a response (e.g. because the session closed), it forgets the
+/// request so a later retry is handled afresh.
+pub(super) struct Recorder {
+    cache: IdempotencyCache,
+    key: (SessionId, String),
+    response: watch::Sender<ResponseSlot>,
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if self.response.borrow().is_none() {
+            self.cache.lock().remove(&self.key);
+        }
+    }
+}
+
+/// Responses of recent requests, per session and idempotency key.
+#[derive(Clone, Default)]
+pub(super) struct IdempotencyCache {
+    entries: Arc<Mutex<HashMap<(SessionId, String), Entry>>>,
+}
+
+impl IdempotencyCache {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(SessionId, String), Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Looks up the request `request_id` of `req` in a session.
+    ///
+    /// Answered requests older than `window` are forgotten first.
+    pub(super) fn claim(
+        &self,
+        req: &HttpRequest,
+        session_id: &SessionId,
+        request_id: &RequestId,
+        window: Duration,
+    ) -> Claim {
+        let key = match req
+            .headers()
+            .get(HEADER_IDEMPOTENCY_KEY)
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(key) => format!("key:{key}"),
+            None => format!("id:{request_id}"),
+        };
+        let key = (session_id.clone(), key);
+
+        let mut entries = self.lock();
+        entries.retain(|_, entry| {
+            entry.response.borrow().is_none() || entry.created_at.elapsed() < window
+        });
+        if let Some(entry) = entries.get(&key) {
+            tracing::debug!(%session_id, key = %key.1, "Duplicate request delivery");
+            return Claim::Duplicate(entry.response.clone());
+        }
+        let (response, receiver) = watch::channel(None);
+        entries.insert(
+            key.clone(),
+            Entry {
+                response: receiver,
+                created_at: Instant::now(),
+            },
+        );
+        Claim::Original(Recorder {
+            cache: self.clone(),
+            key,
+            response,
+        })
+    }
+
+    /// Forgets every request of a closed session.
+    pub(super) fn remove_session(&self, session_id: &SessionId) {
+        self.lock().retain(|(id, _), _| id != session_id);
+    }
+}
+
+/// Drives the stream of an original request in a task of its own and records
+/// its response.
+///
+/// The returned stream forwards the messages for as long as the client reads
+/// them; the task keeps going after the client disconnects.
+pub(super) fn record<St>(stream: St, recorder: Recorder) -> BoxStream<'static, ServerSseMessage>
+where
+    St: Stream<Item = ServerSseMessage> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(FORWARD_CAPACITY);
+    tokio::spawn(async move {
+        let mut stream = std::pin::pin!(stream);
+        let mut forwarding = true;
+        while let Some(msg) = stream.next().await {
+            if let Some(message) = &msg.message
+                && matches!(
+                    message.as_ref(),
+                    ServerJsonRpcMessage::Response(_) | ServerJsonRpcMessage::Error(_)
+                )
+            {
+                recorder.response.send_replace(Some(message.clone()));
+            }
+            if forwarding && tx.send(msg).await.is_err() {
+                forwarding = false;
+            }
+        }
+    });
+    tokio_stream::wrappers::ReceiverStream::new(rx).boxed()
+}
+
+/// Answers a duplicate delivery of `request_id` with the response of the
+/// original request, addressed to `request_id`.
+///
+/// The stream ends without a message if the original request never completes.
+pub(super) fn replay(
+    mut response: watch::Receiver<ResponseSlot>,
+    request_id: RequestId,
+) -> BoxStream<'static, ServerSseMessage> {
+    futures::stream::once(async move {
+        let message = response
+            .wait_for(Option::is_some)
+            .await
+            .ok()
+            .and_then(|slot| slot.clone())?;
+        let mut message = Arc::unwrap_or_clone(message);
+        match &mut message {
+            ServerJsonRpcMessage::Response(response) => response.id = request_id,
+            ServerJsonRpcMessage::Error(error) => error.id = Some(request_id),
+            _ => {}
+        }
+        Some(ServerSseMessage::from_message(message))
+    })
+    .filter_map(std::future::ready)
+    .boxed()
+}
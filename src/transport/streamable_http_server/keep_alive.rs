@@ -0,0 +1,70 @@
+//! Frames sent on idle SSE streams.
+
+use actix_web::web::Bytes;
+
+/// Frame sent on an SSE stream during silent periods, see
+/// [`keep_alive_frame`](super::StreamableHttpServiceBuilder::keep_alive_frame).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeepAliveFrame {
+    /// An SSE comment (`:<text>`), ignored by clients.
+    Comment(String),
+    /// A named SSE event, for intermediaries that strip comments.
+    ///
+    /// The event carries no id, so it never moves a client's `Last-Event-ID`.
+    Event {
+        /// Event name, sent as the `event` field.
+        name: String,
+        /// Event payload, sent as the `data` field.
+        data: String,
+    },
+}
+
+impl Default for KeepAliveFrame {
+    /// The `:ping` comment.
+    fn default() -> Self {
+        Self::Comment("ping".to_string())
+    }
+}
+
+impl KeepAliveFrame {
+    /// Creates a comment frame.
+    pub fn comment(text: impl Into<String>) -> Self {
+        Self::Comment(text.into())
+    }
+
+    /// Creates a named event frame.
+    pub fn event(name: impl Into<String>, data: impl Into<String>) -> Self {
+        Self::Event {
+            name: name.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Serializes the frame, splitting multi-line text into one field per line.
+    pub(super) fn to_bytes(&self) -> Bytes {
+        let mut output = String::new();
+        match self {
+            Self::Comment(text) => {
+                for line in text.lines() {
+                    output.push_str(&format!(":{line}\n"));
+                }
+                if output.is_empty() {
+                    output.push_str(":\n");
+                }
+            }
+            Self::Event { name, data } => {
+                let name = name.lines().next().unwrap_or_default();
+                output.push_str(&format!("event: {name}\n"));
+                for line in data.lines() {
+                    output.push_str(&format!("data: {line}\n"));
+                }
+                if data.is_empty() {
+                    output.push_str("data:\n");
+                }
+            }
+        }
+        output.push('\n');
+        Bytes::from(output)
+    }
+}
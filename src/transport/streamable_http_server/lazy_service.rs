@@ -0,0 +1,146 @@
+//! Deferred construction of session handlers.
+//!
+//! A session normally calls the service factory when it is created, i.e. on the
+//! `initialize` request. Clients that initialize and disappear right away, such
+//! as health probes and capability scanners, then pay for a full handler each.
+//! With a lazy server info configured, `initialize` is answered from that info
+//! and the factory is only called on the first other request or notification.
+//!
+//! The `notifications/initialized` notification is part of the handshake: it is
+//! held back and delivered to the handler once it exists. A failing factory
+//! fails the request that triggered it and is retried on the next one.
+
+use std::sync::Mutex;
+
+use rmcp::{
+    ErrorData, RoleServer, Service,
+    model::{ClientNotification, ClientRequest, ErrorCode, ServerInfo, ServerResult},
+    service::{NotificationContext, RequestContext},
+};
+use tokio::sync::OnceCell;
+
+use super::{SERVICE_UNAVAILABLE_MESSAGE, ServiceFactory};
+
+/// Handler of one session, constructed up front or on first use.
+pub(super) struct SessionService<S> {
+    instance: OnceCell<S>,
+    deferred: Option<Deferred<S>>,
+}
+
+/// What a lazily constructed handler needs until it exists.
+struct Deferred<S> {
+    factory: ServiceFactory<S>,
+    info: ServerInfo,
+    /// Held back `initialized` notification
+    initialized: Mutex<Option<(ClientNotification, NotificationContext<RoleServer>)>>,
+}
+
+impl<S> SessionService<S> {
+    /// Session served by an existing handler.
+    pub(super) fn ready(service: S) -> Self {
+        Self {
+            instance: OnceCell::new_with(Some(service)),
+            deferred: None,
+        }
+    }
+
+    /// Session answering `initialize` with `info` and calling `factory` on first use.
+    pub(super) fn deferred(factory: ServiceFactory<S>, info: ServerInfo) -> Self {
+        Self {
+            instance: OnceCell::new(),
+            deferred: Some(Deferred {
+                factory,
+                info,
+                initialized: Mutex::new(None),
+            }),
+        }
+    }
+}
+
+impl<S> SessionService<S>
+where
+    S: Service<RoleServer>,
+{
+    /// Returns the handler, constructing it if needed.
+    async fn instance(&self) -> Result<&S, ErrorData> {
+        let Some(deferred) = &self.deferred else {
+            return Ok(self.instance.get().expect("ready sessions have a handler"));
+        };
+        let instance = self
+            .instance
+            .get_or_try_init(|| async {
+                (deferred.factory)().map_err(|e| {
+                    tracing::error!("Service factory failed: {e}");
+                    ErrorData::new(ErrorCode::INTERNAL_ERROR, SERVICE_UNAVAILABLE_MESSAGE, None)
+                })
+            })
+            .await?;
+        // Checked under the lock by `handle_notification`, so the held back
+        // notification is either taken here or never held back
+        let initialized = deferred
+            .initialized
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+        if let Some((notification, context)) = initialized {
+            instance.handle_notification(notification, context).await?;
+        }
+        Ok(instance)
+    }
+}
+
+impl<S> Service<RoleServer> for SessionService<S>
+where
+    S: Service<RoleServer>,
+{
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, ErrorData> {
+        if let (Some(deferred), ClientRequest::InitializeRequest(initialize)) =
+            (&self.deferred, &request)
+            && self.instance.get().is_none()
+        {
+            if context.peer.peer_info().is_none() {
+                context.peer.set_peer_info(initialize.params.clone());
+            }
+            return Ok(ServerResult::InitializeResult(deferred.info.clone()));
+        }
+        self.instance()
+            .await?
+            .handle_request(request, context)
+            .await
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        if let (Some(deferred), ClientNotification::InitializedNotification(_)) =
+            (&self.deferred, &notification)
+        {
+            let mut initialized = deferred
+                .initialized
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if self.instance.get().is_none() {
+                *initialized = Some((notification, context));
+                return Ok(());
+            }
+        }
+        self.instance()
+            .await?
+            .handle_notification(notification, context)
+            .await
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        match (self.instance.get(), &self.deferred) {
+            (Some(instance), _) => instance.get_info(),
+            (None, Some(deferred)) => deferred.info.clone(),
+            (None, None) => unreachable!("ready sessions have a handler"),
+        }
+    }
+}
@@ -0,0 +1,854 @@
+//! Legacy HTTP+SSE endpoints sharing sessions with the Streamable HTTP transport.
+//!
+//! The MCP 2024-11-05 HTTP+SSE transport uses two endpoints:
+//! - **GET `/sse`**: Opens an event stream. The first event is an `endpoint`
+//!   event carrying the URL to POST messages to (`/message?sessionId=...`);
+//!   every server message is then delivered as a `message` event.
+//! - **POST `/message?sessionId=...`**: Sends a JSON-RPC message and returns
+//!   `202 Accepted`; the response arrives on the event stream.
+//!
+//! As an extension, **DELETE `/message?sessionId=...`** lets a client that
+//! exits cleanly end its session right away (`204 No Content`) instead of
+//! leaving it to be reaped when the event stream drops.
+//!
+//! When enabled with
+//! [`legacy_sse`](super::StreamableHttpServiceBuilder::legacy_sse), these
+//! endpoints are served next to the Streamable HTTP endpoint, against the same
+//! service factory and session manager. A legacy connection is a regular
+//! session of the session manager, so both transports share one session space
+//! and one set of handlers.
+//!
+//! ## Errors on the message endpoint
+//!
+//! Session errors are answered with a JSON-RPC error body (`application/json`),
+//! carrying the request id when the body could be read:
+//!
+//! | Status | JSON-RPC code | Meaning | Client reaction |
+//! |--------|---------------|---------|-----------------|
+//! | `400 Bad Request` | `-32600` | `sessionId` query parameter missing | Fix the request |
+//! | `404 Not Found` | `-32010` ([`LEGACY_SESSION_NOT_FOUND_CODE`]) | Session id was never issued (or forgotten) | Open a new `/sse` stream and re-initialize |
+//! | `410 Gone` | `-32011` ([`LEGACY_SESSION_CLOSED_CODE`]) | Session existed but its event stream was closed | Reconnect to `/sse`; the old session cannot be resumed |
+//! | `503 Service Unavailable` | `-32003` | Server is shutting down (requests only) | Reconnect with backoff |
+//!
+//! Requests exceeding the
+//! [`request_timeout`](super::StreamableHttpServiceBuilder::request_timeout)
+//! are answered on the event stream with code `-32008`; the session stays
+//! valid, so clients must not re-initialize on it.
+//!
+//! ## Event ids
+//!
+//! Every `message` event carries an id of the form `<session id>/<sequence>`,
+//! where the sequence starts at 1 and increases by one per event of the
+//! session, across reconnects. Clients can use it to drop duplicates.
+//!
+//! ## Resumption
+//!
+//! With a
+//! [`legacy_sse_resume_window`](super::StreamableHttpServiceBuilder::legacy_sse_resume_window),
+//! the session of a client whose event stream drops is kept for that long.
+//! Reconnecting to `/sse` with a `Last-Event-ID` header resumes it: the
+//! `endpoint` event is sent again, followed by the recent events the client
+//! missed and the messages queued while it was away. The server only notices
+//! a dropped stream when writing to it, so resumption relies on keep-alives
+//! being enabled; a reconnect that arrives before the old stream is detected
+//! as gone starts a new session.
+//!
+//! ## Authorization
+//!
+//! Some clients only send credentials on the `EventSource` request. With the
+//! `authorization-token-passthrough` feature, the Bearer token presented when
+//! opening `/sse` is remembered for the connection and forwarded as
+//! [`AuthorizationHeader`](crate::transport::AuthorizationHeader) with every
+//! message posted without an Authorization header of its own.
+//!
+//! ## Shutdown
+//!
+//! Once [`StreamableHttpService::shutdown`] is called, new `/sse` connections
+//! and requests are refused with `503 Service Unavailable`. After in-flight
+//! requests have drained, every open event stream ends with a final
+//! `shutdown` event, so clients can tell an orderly shutdown from a network
+//! failure.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        Arc, OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use actix_web::{
+    HttpRequest, HttpResponse, Result,
+    error::InternalError,
+    http::{StatusCode, header::CACHE_CONTROL},
+    web::{self, Bytes, Data},
+};
+use futures::{Stream, StreamExt};
+use rmcp::{
+    model::{
+        ClientJsonRpcMessage, ClientRequest, ErrorCode, ErrorData, Extensions, GetExtensions,
+        ServerJsonRpcMessage,
+    },
+    transport::streamable_http_server::session::{ServerSseMessage, SessionId, SessionManager},
+};
+use tokio::sync::{Notify, RwLock, mpsc};
+
+use super::{
+    AppData, EVENT_STREAM_MIME_TYPE, HEADER_LAST_EVENT_ID, HEADER_X_ACCEL_BUFFERING,
+    JSON_MIME_TYPE, PayloadEncoding, SESSION_NOT_FOUND_MESSAGE, SessionCloseReason,
+    StreamableHttpService, initialize_params, session_log, shutdown,
+    stream_end::{self, StreamEndReason},
+    wrap_with_sse_keepalive,
+};
+
+/// Path of the legacy event stream endpoint, relative to the service scope.
+pub(super) const LEGACY_SSE_PATH: &str = "/sse";
+/// Path of the legacy message endpoint, relative to the service scope.
+pub(super) const LEGACY_MESSAGE_PATH: &str = "/message";
+/// Default capacity of the per-connection outbound message queue.
+pub(super) const DEFAULT_LEGACY_CHANNEL_CAPACITY: usize = 64;
+const MISSING_LEGACY_SESSION_ID_MESSAGE: &str = "sessionId query parameter is required";
+const SESSION_CLOSED_MESSAGE: &str = "Session closed";
+/// JSON-RPC error code answering a legacy message for a session id that was
+/// never issued, or has been forgotten.
+///
+/// Clients should open a new `/sse` stream and re-initialize.
+pub const LEGACY_SESSION_NOT_FOUND_CODE: ErrorCode = ErrorCode(-32010);
+/// JSON-RPC error code answering a legacy message for a session whose event
+/// stream has been closed.
+///
+/// The session cannot be resumed; clients should reconnect to `/sse`.
+pub const LEGACY_SESSION_CLOSED_CODE: ErrorCode = ErrorCode(-32011);
+/// Final event sent on open event streams when the server shuts down.
+const SHUTDOWN_EVENT: &str = "event: shutdown\ndata: {}\n\n";
+/// Number of recently closed session ids remembered to answer `410 Gone`.
+const CLOSED_SESSIONS_CAPACITY: usize = 1024;
+
+/// What to do when a legacy connection's outbound queue is full.
+///
+/// The queue fills up when the client reads its event stream slower than the
+/// service produces messages (typically bursts of notifications).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SseOverflowPolicy {
+    /// Wait for room in the queue. Nothing is lost, but the producer of the
+    /// message (the service task) is stalled until the client catches up.
+    #[default]
+    Block,
+    /// Drop the message that does not fit and log a warning.
+    DropNewest,
+    /// Close the event stream, and with it the session; the client has to
+    /// reconnect.
+    Disconnect,
+}
+
+/// Sending half of a legacy connection's outbound queue.
+#[derive(Clone)]
+struct LegacySender {
+    sender: mpsc::Sender<Arc<ServerJsonRpcMessage>>,
+    policy: SseOverflowPolicy,
+    /// Notified to end the event stream when the queue overflows under
+    /// [`SseOverflowPolicy::Disconnect`].
+    overflowed: Arc<Notify>,
+    /// Extensions captured when the event stream was opened, applied to every
+    /// request posted on the connection.
+    connection_extensions: Extensions,
+}
+
+impl LegacySender {
+    /// Queues a message according to the overflow policy.
+    ///
+    /// Returns `false` once the connection is gone and forwarding should stop.
+    async fn send(&self, message: Arc<ServerJsonRpcMessage>) -> bool {
+        use mpsc::error::TrySendError;
+
+        if self.policy == SseOverflowPolicy::Block {
+            return self.sender.send(message).await.is_ok();
+        }
+        match self.sender.try_send(message) {
+            Ok(()) => true,
+            Err(TrySendError::Closed(_)) => false,
+            Err(TrySendError::Full(_)) if self.policy == SseOverflowPolicy::DropNewest => {
+                tracing::warn!("Legacy SSE queue full, dropping message");
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                tracing::warn!("Legacy SSE queue full, disconnecting client");
+                self.overflowed.notify_one();
+                false
+            }
+        }
+    }
+}
+
+/// Receiving side of a legacy connection.
+///
+/// When resumption is enabled, it is parked between a disconnect and the
+/// client's reconnect, so queued messages and the replay history survive.
+struct LegacyStream {
+    receiver: mpsc::Receiver<Arc<ServerJsonRpcMessage>>,
+    overflowed: Arc<Notify>,
+    /// Recently sent events with their sequence number, oldest first.
+    history: VecDeque<(u64, Bytes)>,
+    history_capacity: usize,
+    next_event_id: u64,
+}
+
+impl LegacyStream {
+    /// Formats `message` as the next event of the stream, recording it for replay.
+    fn next_event(&mut self, session_id: &SessionId, message: &ServerJsonRpcMessage) -> Bytes {
+        let event_id = self.next_event_id;
+        self.next_event_id += 1;
+        let event = format_legacy_message(&format!("{session_id}/{event_id}"), message);
+        if self.history_capacity > 0 {
+            if self.history.len() == self.history_capacity {
+                self.history.pop_front();
+            }
+            self.history.push_back((event_id, event.clone()));
+        }
+        event
+    }
+
+    /// Recorded events sent after `last_event_id`.
+    fn replay_after(&self, last_event_id: u64) -> Vec<Bytes> {
+        self.history
+            .iter()
+            .filter(|(event_id, _)| *event_id > last_event_id)
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+}
+
+/// State of a legacy connection, as seen by the message endpoint.
+enum LegacyConnection {
+    Open(LegacySender),
+    Closed,
+    Unknown,
+}
+
+/// Open legacy SSE connections, keyed by session id, plus the ids of recently
+/// closed ones.
+#[derive(Clone)]
+pub(super) struct LegacySseConnections {
+    senders: Arc<RwLock<HashMap<SessionId, LegacySender>>>,
+    closed: Arc<RwLock<VecDeque<SessionId>>>,
+    /// Streams of disconnected clients awaiting a reconnect, with their park token.
+    parked: Arc<RwLock<HashMap<SessionId, (u64, LegacyStream)>>>,
+    next_park_token: Arc<AtomicU64>,
+    channel_capacity: usize,
+    overflow_policy: SseOverflowPolicy,
+    resume_window: Option<Duration>,
+}
+
+impl LegacySseConnections {
+    pub(super) fn new(
+        channel_capacity: usize,
+        overflow_policy: SseOverflowPolicy,
+        resume_window: Option<Duration>,
+    ) -> Self {
+        Self {
+            senders: Default::default(),
+            closed: Default::default(),
+            parked: Default::default(),
+            next_park_token: Default::default(),
+            // tokio channels cannot have a zero capacity
+            channel_capacity: channel_capacity.max(1),
+            overflow_policy,
+            resume_window,
+        }
+    }
+
+    /// Registers a new connection and returns the receiving side of its queue.
+    async fn open(&self, session_id: SessionId, connection_extensions: Extensions) -> LegacyStream {
+        let (sender, receiver) = mpsc::channel(self.channel_capacity);
+        let overflowed = Arc::new(Notify::new());
+        let sender = LegacySender {
+            sender,
+            policy: self.overflow_policy,
+            overflowed: overflowed.clone(),
+            connection_extensions,
+        };
+        self.senders.write().await.insert(session_id, sender);
+        LegacyStream {
+            receiver,
+            overflowed,
+            history: VecDeque::new(),
+            history_capacity: if self.resume_window.is_some() {
+                self.channel_capacity
+            } else {
+                0
+            },
+            next_event_id: 1,
+        }
+    }
+
+    /// Keeps the stream of a disconnected client for a reconnect, returning the
+    /// token to pass to [`expire`](Self::expire).
+    async fn park(&self, session_id: SessionId, stream: LegacyStream) -> u64 {
+        let token = self.next_park_token.fetch_add(1, Ordering::Relaxed);
+        self.parked
+            .write()
+            .await
+            .insert(session_id, (token, stream));
+        token
+    }
+
+    /// Takes back the parked stream of a reconnecting client.
+    async fn resume(&self, session_id: &SessionId) -> Option<LegacyStream> {
+        let (_, stream) = self.parked.write().await.remove(session_id)?;
+        Some(stream)
+    }
+
+    /// Drops a parked stream once the resume window is over.
+    ///
+    /// Returns `false` if the stream was resumed in the meantime.
+    async fn expire(&self, session_id: &SessionId, token: u64) -> bool {
+        let mut parked = self.parked.write().await;
+        if parked
+            .get(session_id)
+            .is_some_and(|(parked_token, _)| *parked_token == token)
+        {
+            parked.remove(session_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    async fn get(&self, session_id: &SessionId) -> LegacyConnection {
+        if let Some(sender) = self.senders.read().await.get(session_id) {
+            return LegacyConnection::Open(sender.clone());
+        }
+        if self.closed.read().await.contains(session_id) {
+            LegacyConnection::Closed
+        } else {
+            LegacyConnection::Unknown
+        }
+    }
+
+    /// Returns the number of open legacy SSE connections.
+    pub(super) async fn open_count(&self) -> usize {
+        self.senders.read().await.len()
+    }
+
+    /// Drops the connection entry, ending the event stream once every
+    /// in-flight forwarder is done.
+    pub(super) async fn remove(&self, session_id: &SessionId) {
+        self.parked.write().await.remove(session_id);
+        if self.senders.write().await.remove(session_id).is_some() {
+            let mut closed = self.closed.write().await;
+            if closed.len() == CLOSED_SESSIONS_CAPACITY {
+                closed.pop_front();
+            }
+            closed.push_back(session_id.clone());
+        }
+    }
+}
+
+/// Builds a JSON-RPC error response for the message endpoint.
+///
+/// The request id is echoed when `body` holds a readable request.
+fn legacy_error_response(
+    status: StatusCode,
+    code: ErrorCode,
+    message: &'static str,
+    body: &[u8],
+) -> HttpResponse {
+    let id = match serde_json::from_slice::<ClientJsonRpcMessage>(body) {
+        Ok(ClientJsonRpcMessage::Request(request)) => Some(request.id),
+        _ => None,
+    };
+    let error = ServerJsonRpcMessage::error(ErrorData::new(code, message, None), id);
+    HttpResponse::build(status)
+        .content_type(JSON_MIME_TYPE)
+        .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()))
+}
+
+/// Item of a legacy event stream.
+enum LegacyEvent {
+    Message(Arc<ServerJsonRpcMessage>),
+    Shutdown,
+    Closed,
+}
+
+/// Closes the backing session once the legacy event stream is dropped, after
+/// the resume window if one is configured.
+struct LegacyConnectionGuard<S: 'static, M: SessionManager> {
+    app_data: AppData<S, M>,
+    session_id: SessionId,
+    /// Receiving side of the connection; `None` once it must not be resumed.
+    stream: Option<LegacyStream>,
+    /// Why the event stream ended, set once it has.
+    end_reason: Arc<OnceLock<StreamEndReason>>,
+}
+
+impl<S: 'static, M: SessionManager> Drop for LegacyConnectionGuard<S, M> {
+    fn drop(&mut self) {
+        let app_data = self.app_data.clone();
+        let session_id = self.session_id.clone();
+        let stream = self.stream.take();
+        let end_reason = self.end_reason.clone();
+        tokio::spawn(async move {
+            if let Some(stream) = stream
+                && let Some(window) = app_data.legacy_sse.resume_window
+            {
+                let token = app_data.legacy_sse.park(session_id.clone(), stream).await;
+                tracing::debug!(%session_id, "Legacy SSE stream dropped, waiting for reconnect");
+                tokio::time::sleep(window).await;
+                if !app_data.legacy_sse.expire(&session_id, token).await {
+                    return;
+                }
+            }
+            app_data.legacy_sse.remove(&session_id).await;
+            tracing::debug!(%session_id, "Legacy SSE stream closed, closing session");
+            let end_reason = end_reason
+                .get()
+                .copied()
+                .unwrap_or(StreamEndReason::ClientDisconnect);
+            app_data
+                .session_closed(
+                    &session_id,
+                    SessionCloseReason::StreamDisconnected(end_reason),
+                )
+                .await;
+            let _ = app_data
+                .session_manager
+                .close_session(&session_id)
+                .await
+                .inspect_err(|e| {
+                    tracing::error!("Failed to close session {session_id}: {e}");
+                });
+        });
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(super) struct LegacyMessageQuery {
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+}
+
+/// Formats a server message as a legacy `message` event.
+fn format_legacy_message(event_id: &str, message: &ServerJsonRpcMessage) -> Bytes {
+    let data = PayloadEncoding::Json.encode(message);
+    Bytes::from(format!("id: {event_id}\nevent: message\ndata: {data}\n\n"))
+}
+
+/// Parses a legacy `Last-Event-ID` header (`<session id>/<sequence>`).
+fn parse_last_event_id(req: &HttpRequest) -> Option<(SessionId, u64)> {
+    let value = req.headers().get(HEADER_LAST_EVENT_ID)?.to_str().ok()?;
+    let (session_id, event_id) = value.rsplit_once('/')?;
+    Some((session_id.to_owned().into(), event_id.parse().ok()?))
+}
+
+/// Forwards every message of a session stream onto a legacy connection.
+fn forward_to_legacy<St>(stream: St, sender: LegacySender)
+where
+    St: Stream<Item = ServerSseMessage> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut stream = Box::pin(stream);
+        while let Some(msg) = stream.next().await {
+            // Priming events carry no payload and have no legacy equivalent
+            if let Some(message) = msg.message
+                && !sender.send(message).await
+            {
+                break;
+            }
+        }
+    });
+}
+
+impl<S, M> StreamableHttpService<S, M>
+where
+    S: rmcp::Service<rmcp::RoleServer> + Send + 'static,
+    M: SessionManager + 'static,
+{
+    pub(super) async fn handle_legacy_sse(
+        req: HttpRequest,
+        service: Data<AppData<S, M>>,
+    ) -> Result<HttpResponse> {
+        if let Some(response) = service.check_request_guard(&req) {
+            return Ok(response);
+        }
+        if service.shutdown.is_draining() {
+            return Ok(shutdown::shutting_down_response(None));
+        }
+        let connection = match service.acquire_sse_connection(&req) {
+            Ok(connection) => connection,
+            Err(response) => return Ok(response),
+        };
+
+        let mut resumed = None;
+        if let Some((session_id, last_event_id)) = parse_last_event_id(&req)
+            && let Some(stream) = service.legacy_sse.resume(&session_id).await
+        {
+            session_log::resumed(&session_id, &last_event_id.to_string());
+            let replay = stream.replay_after(last_event_id);
+            resumed = Some((session_id, stream, replay));
+        }
+        let (session_id, stream, replay) = match resumed {
+            Some(resumed) => resumed,
+            None => {
+                let (session_id, stream) = Self::open_legacy_session(&req, &service).await?;
+                (session_id, stream, Vec::new())
+            }
+        };
+
+        let base_path = req.path().strip_suffix(LEGACY_SSE_PATH).unwrap_or_default();
+        let endpoint = format!("{base_path}{LEGACY_MESSAGE_PATH}?sessionId={session_id}");
+
+        let end_reason = Arc::new(OnceLock::new());
+        let guard = LegacyConnectionGuard {
+            app_data: service.get_ref().clone(),
+            session_id: session_id.clone(),
+            stream: Some(stream),
+            end_reason: end_reason.clone(),
+        };
+        let outbound = service.outbound(Some(&session_id)).await;
+        let shutdown = service.shutdown.clone();
+        let open_stream = service.events.stream_opened(&session_id);
+        let stream = async_stream::stream! {
+            let mut guard = guard;
+            yield Bytes::from(format!("event: endpoint\ndata: {endpoint}\n\n"));
+            for event in replay {
+                yield event;
+            }
+            while let Some(stream) = guard.stream.as_mut() {
+                let event = tokio::select! {
+                    message = stream.receiver.recv() => message.map_or(LegacyEvent::Closed, LegacyEvent::Message),
+                    _ = stream.overflowed.notified() => LegacyEvent::Closed,
+                    _ = shutdown.stopped() => LegacyEvent::Shutdown,
+                };
+                match event {
+                    LegacyEvent::Message(message) => {
+                        yield stream.next_event(&session_id, &outbound.apply(&message));
+                    }
+                    LegacyEvent::Shutdown => {
+                        guard.stream = None;
+                        yield Bytes::from_static(SHUTDOWN_EVENT.as_bytes());
+                    }
+                    LegacyEvent::Closed => guard.stream = None,
+                }
+            }
+        };
+        let sse_stream = stream_end::track(
+            wrap_with_sse_keepalive(
+                connection.hold(stream.map(Ok::<_, actix_web::Error>)),
+                service.keep_alive_for(&req),
+                service.keep_alive_frame.clone(),
+            ),
+            service.keep_alive_frame.clone(),
+            service.shutdown.clone(),
+            move |reason| {
+                let _ = end_reason.set(reason);
+                open_stream.ended(reason);
+            },
+        );
+
+        Ok(HttpResponse::Ok()
+            .content_type(EVENT_STREAM_MIME_TYPE)
+            .append_header((CACHE_CONTROL, "no-cache"))
+            .append_header((HEADER_X_ACCEL_BUFFERING, "no"))
+            .streaming(sse_stream))
+    }
+
+    /// Creates the session backing a new legacy connection.
+    #[cfg_attr(
+        not(feature = "authorization-token-passthrough"),
+        allow(unused_variables)
+    )]
+    async fn open_legacy_session(
+        req: &HttpRequest,
+        service: &AppData<S, M>,
+    ) -> Result<(SessionId, LegacyStream)> {
+        service.admit_session_creation(req, None)?;
+        service.admit_tenant_session(req, None).await?;
+        // Created first, so a failing factory does not leave a session behind
+        let service_instance = service.session_service(req, None)?;
+        let (session_id, transport) = service
+            .session_manager
+            .create_session()
+            .await
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+        service
+            .spawn_session(req, session_id.clone(), transport, service_instance)
+            .await;
+
+        tracing::debug!(%session_id, "Created new legacy SSE session");
+
+        #[cfg_attr(not(feature = "authorization-token-passthrough"), allow(unused_mut))]
+        let mut connection_extensions = Extensions::new();
+        #[cfg(feature = "authorization-token-passthrough")]
+        if let Some(authorization) = super::bearer_authorization(req, "on legacy SSE connection") {
+            connection_extensions.insert(authorization);
+        }
+        let stream = service
+            .legacy_sse
+            .open(session_id.clone(), connection_extensions)
+            .await;
+        Ok((session_id, stream))
+    }
+
+    pub(super) async fn handle_legacy_delete(
+        req: HttpRequest,
+        query: web::Query<LegacyMessageQuery>,
+        service: Data<AppData<S, M>>,
+    ) -> Result<HttpResponse> {
+        if let Some(response) = service.check_request_guard(&req) {
+            return Ok(response);
+        }
+
+        let Some(session_id) = query
+            .into_inner()
+            .session_id
+            .filter(|s| !s.is_empty())
+            .map(SessionId::from)
+        else {
+            return Ok(legacy_error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::INVALID_REQUEST,
+                MISSING_LEGACY_SESSION_ID_MESSAGE,
+                &[],
+            ));
+        };
+
+        if !service.session_identity_matches(&req, &session_id).await {
+            return Ok(legacy_error_response(
+                StatusCode::NOT_FOUND,
+                LEGACY_SESSION_NOT_FOUND_CODE,
+                SESSION_NOT_FOUND_MESSAGE,
+                &[],
+            ));
+        }
+        match service.legacy_sse.get(&session_id).await {
+            LegacyConnection::Open(_) => {}
+            LegacyConnection::Closed => {
+                return Ok(legacy_error_response(
+                    StatusCode::GONE,
+                    LEGACY_SESSION_CLOSED_CODE,
+                    SESSION_CLOSED_MESSAGE,
+                    &[],
+                ));
+            }
+            LegacyConnection::Unknown => {
+                return Ok(legacy_error_response(
+                    StatusCode::NOT_FOUND,
+                    LEGACY_SESSION_NOT_FOUND_CODE,
+                    SESSION_NOT_FOUND_MESSAGE,
+                    &[],
+                ));
+            }
+        }
+
+        tracing::debug!(%session_id, "DELETE request to close legacy SSE session");
+
+        service.legacy_sse.remove(&session_id).await;
+        service
+            .session_closed(&session_id, SessionCloseReason::ClientDelete)
+            .await;
+        service
+            .session_manager
+            .close_session(&session_id)
+            .await
+            .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+
+        tracing::debug!(%session_id, "Legacy SSE session deleted by client");
+
+        Ok(HttpResponse::NoContent().finish())
+    }
+
+    pub(super) async fn handle_legacy_message(
+        req: HttpRequest,
+        query: web::Query<LegacyMessageQuery>,
+        body: Bytes,
+        service: Data<AppData<S, M>>,
+    ) -> Result<HttpResponse> {
+        if let Some(response) = service.check_request_guard(&req) {
+            return Ok(response);
+        }
+
+        let Some(session_id) = query
+            .into_inner()
+            .session_id
+            .filter(|s| !s.is_empty())
+            .map(SessionId::from)
+        else {
+            return Ok(legacy_error_response(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::INVALID_REQUEST,
+                MISSING_LEGACY_SESSION_ID_MESSAGE,
+                &body,
+            ));
+        };
+
+        if !service.session_identity_matches(&req, &session_id).await {
+            return Ok(legacy_error_response(
+                StatusCode::NOT_FOUND,
+                LEGACY_SESSION_NOT_FOUND_CODE,
+                SESSION_NOT_FOUND_MESSAGE,
+                &body,
+            ));
+        }
+        let sender = match service.legacy_sse.get(&session_id).await {
+            LegacyConnection::Open(sender) => sender,
+            LegacyConnection::Closed => {
+                tracing::warn!(%session_id, "Legacy SSE session closed");
+                return Ok(legacy_error_response(
+                    StatusCode::GONE,
+                    LEGACY_SESSION_CLOSED_CODE,
+                    SESSION_CLOSED_MESSAGE,
+                    &body,
+                ));
+            }
+            LegacyConnection::Unknown => {
+                tracing::warn!(%session_id, "Legacy SSE session not found");
+                return Ok(legacy_error_response(
+                    StatusCode::NOT_FOUND,
+                    LEGACY_SESSION_NOT_FOUND_CODE,
+                    SESSION_NOT_FOUND_MESSAGE,
+                    &body,
+                ));
+            }
+        };
+
+        let mut message = PayloadEncoding::Json
+            .decode(&body)
+            .map_err(|e| InternalError::new(e, StatusCode::BAD_REQUEST))?;
+        service.transform_inbound(&req, &mut message);
+
+        if let Some(response) = service.shutdown.reject(&message) {
+            return Ok(response);
+        }
+        if let Some(response) = service.admit_tenant_request(&req, &message) {
+            return Ok(response);
+        }
+        let rejection = match service
+            .message_filter
+            .reject(&message)
+            .or_else(|| service.tool_toggles.reject(&message))
+            .or_else(|| service.tool_scopes.reject(&req, &message))
+        {
+            Some(error) => Some(error),
+            None => {
+                crate::transport::request_authorization::reject(
+                    service.authorize.as_deref(),
+                    &req,
+                    &message,
+                )
+                .await
+            }
+        };
+        if let Some(error) = rejection {
+            sender.send(Arc::new(error)).await;
+            return Ok(HttpResponse::Accepted().finish());
+        }
+        let in_flight = service.shutdown.in_flight();
+
+        tracing::debug!(
+            %session_id,
+            message = %service.log_redaction.display(&message),
+            "Legacy SSE message"
+        );
+
+        let is_initialize = matches!(
+            &message,
+            ClientJsonRpcMessage::Request(request_msg)
+                if matches!(request_msg.request, ClientRequest::InitializeRequest(_))
+        );
+        if !is_initialize {
+            service.sessions.mark_initialized(&session_id).await;
+        }
+
+        match message {
+            #[allow(unused_mut)]
+            ClientJsonRpcMessage::Request(mut request_msg) => {
+                if !is_initialize {
+                    service
+                        .apply_session_extensions(&session_id, request_msg.request.extensions_mut())
+                        .await;
+                }
+                service.propagate_request_context(
+                    &req,
+                    request_msg.request.extensions_mut(),
+                    "for legacy SSE session",
+                );
+                let cancellation = service
+                    .attach_request_cancellation(
+                        Some(&session_id),
+                        request_msg.request.extensions_mut(),
+                    )
+                    .await;
+                let timeout =
+                    service.attach_request_deadline(&req, request_msg.request.extensions_mut());
+                // Values from the request itself take precedence over the ones
+                // captured when the connection was opened
+                if !sender.connection_extensions.is_empty() {
+                    let extensions = request_msg.request.extensions_mut();
+                    let request_extensions = std::mem::take(extensions);
+                    extensions.extend(sender.connection_extensions.clone());
+                    extensions.extend(request_extensions);
+                }
+
+                if is_initialize {
+                    let mut message = ClientJsonRpcMessage::Request(request_msg);
+                    service
+                        .capture_session_extensions(&req, &session_id, &mut message)
+                        .await;
+                    service.notify_session_created(&req, &session_id, &message);
+                    let client_capabilities =
+                        initialize_params(&message).map(|params| params.capabilities.clone());
+                    let response = service
+                        .session_manager
+                        .initialize_session(&session_id, message)
+                        .await
+                        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                    service
+                        .record_negotiation(&session_id, client_capabilities, &response)
+                        .await;
+                    sender.send(Arc::new(response)).await;
+
+                    // Server-initiated messages have no request stream to travel on;
+                    // route the session's standalone stream onto the connection.
+                    let standalone = service
+                        .session_manager
+                        .create_standalone_stream(&session_id)
+                        .await
+                        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                    forward_to_legacy(standalone, sender);
+                } else {
+                    let pending =
+                        match service.acquire_pending_request(&session_id, &request_msg.id) {
+                            Ok(pending) => pending,
+                            Err(response) => return Ok(response),
+                        };
+                    let request_id = request_msg.id.clone();
+                    let stream = service
+                        .session_manager
+                        .create_stream(&session_id, ClientJsonRpcMessage::Request(request_msg))
+                        .await
+                        .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+                    let stream = service.limit_session_request(
+                        session_id,
+                        request_id,
+                        cancellation,
+                        timeout,
+                        stream,
+                    );
+                    forward_to_legacy(pending.hold(in_flight.hold(stream)), sender);
+                }
+            }
+            message => {
+                service
+                    .session_manager
+                    .accept_message(&session_id, message)
+                    .await
+                    .map_err(|e| InternalError::new(e, StatusCode::INTERNAL_SERVER_ERROR))?;
+            }
+        }
+
+        Ok(HttpResponse::Accepted().finish())
+    }
+}
@@ -0,0 +1,107 @@
+//! Server-initiated pings measuring session liveness.
+//!
+//! While a client keeps its standalone `GET` stream open, the transport sends it
+//! an MCP `ping` request at the configured interval. The client answers with a
+//! POST, which the transport intercepts to record the round-trip time instead of
+//! forwarding it to the session. A ping still unanswered when the next one is
+//! due counts as missed; after too many consecutive misses the session is
+//! closed as unresponsive.
+
+use std::time::{Duration, Instant};
+
+use rmcp::model::{
+    ClientJsonRpcMessage, NumberOrString, PingRequest, ServerJsonRpcMessage, ServerRequest,
+};
+
+/// Prefix of the ids of transport-generated pings, so their answers can be told
+/// apart from answers to requests of the handler.
+const PING_ID_PREFIX: &str = "rmcp-actix-web-ping-";
+
+/// Default number of consecutive missed pings after which a session is closed.
+pub(super) const DEFAULT_PING_MAX_MISSED: u32 = 3;
+
+/// Liveness measurements of a session.
+///
+/// Returned by [`StreamableHttpService::session_liveness`](super::StreamableHttpService::session_liveness).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SessionLiveness {
+    /// Number of pings sent to the client.
+    pub pings_sent: u64,
+    /// Round-trip time of the last answered ping.
+    pub last_round_trip: Option<Duration>,
+    /// Number of consecutive pings the client did not answer in time.
+    pub missed_pings: u32,
+}
+
+/// What to do when a ping is due.
+pub(super) enum PingOutcome {
+    /// Send a ping with this id.
+    Send(String),
+    /// The client missed too many pings; close the session.
+    Unresponsive,
+    /// The session is gone.
+    Closed,
+}
+
+/// Ping state of a session.
+#[derive(Debug, Default)]
+pub(super) struct Liveness {
+    stats: SessionLiveness,
+    /// Id and send time of the ping awaiting an answer
+    outstanding: Option<(String, Instant)>,
+}
+
+impl Liveness {
+    pub(super) fn stats(&self) -> SessionLiveness {
+        self.stats
+    }
+
+    /// Accounts for a missed previous ping, then prepares the next one.
+    ///
+    /// `max_missed` of zero never gives up on the client.
+    pub(super) fn next_ping(&mut self, max_missed: u32) -> PingOutcome {
+        if self.outstanding.take().is_some() {
+            self.stats.missed_pings += 1;
+            if max_missed > 0 && self.stats.missed_pings >= max_missed {
+                return PingOutcome::Unresponsive;
+            }
+        }
+        self.stats.pings_sent += 1;
+        let id = format!("{PING_ID_PREFIX}{}", self.stats.pings_sent);
+        self.outstanding = Some((id.clone(), Instant::now()));
+        PingOutcome::Send(id)
+    }
+
+    /// Records the answer to the ping `id`; answers to stale pings are ignored.
+    pub(super) fn answered(&mut self, id: &str) -> Option<Duration> {
+        let (_, sent_at) = self
+            .outstanding
+            .take_if(|(outstanding, _)| outstanding == id)?;
+        let round_trip = sent_at.elapsed();
+        self.stats.last_round_trip = Some(round_trip);
+        self.stats.missed_pings = 0;
+        Some(round_trip)
+    }
+}
+
+/// Builds the ping request with the given id.
+pub(super) fn ping_request(id: String) -> ServerJsonRpcMessage {
+    ServerJsonRpcMessage::request(
+        ServerRequest::PingRequest(PingRequest::default()),
+        NumberOrString::String(id.into()),
+    )
+}
+
+/// Returns the ping id answered by `message`, if it answers a transport ping.
+pub(super) fn answered_ping(message: &ClientJsonRpcMessage) -> Option<&str> {
+    let id = match message {
+        ClientJsonRpcMessage::Response(response) => &response.id,
+        ClientJsonRpcMessage::Error(error) => error.id.as_ref()?,
+        _ => return None,
+    };
+    match id {
+        NumberOrString::String(id) if id.starts_with(PING_ID_PREFIX) => Some(id),
+        _ => None,
+    }
+}
@@ -0,0 +1,33 @@
+//! Normalization of request paths before routing.
+
+use actix_web::middleware::{Condition, NormalizePath, TrailingSlash};
+
+/// How the service scope normalizes request paths, see
+/// [`path_normalization`](super::StreamableHttpServiceBuilder::path_normalization).
+///
+/// Every variant but `Disabled` also merges repeated slashes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathNormalization {
+    /// Trims trailing slashes, so `/mcp/` reaches `/mcp`.
+    #[default]
+    Trim,
+    /// Only merges repeated slashes, keeping trailing slashes.
+    MergeOnly,
+    /// Appends a trailing slash to every path.
+    Always,
+    /// Routes request paths as they are, leaving normalization to the app.
+    Disabled,
+}
+
+impl PathNormalization {
+    /// Builds the middleware applying this normalization.
+    pub(super) fn middleware(self) -> Condition<NormalizePath> {
+        let trailing_slash = match self {
+            Self::Trim | Self::Disabled => TrailingSlash::Trim,
+            Self::MergeOnly => TrailingSlash::MergeOnly,
+            Self::Always => TrailingSlash::Always,
+        };
+        Condition::new(self != Self::Disabled, NormalizePath::new(trailing_slash))
+    }
+}
@@ -0,0 +1,206 @@
+//! Binary payload encodings for machine-to-machine clients.
+//!
+//! Besides JSON, clients may exchange MessagePack (with the `msgpack` feature
+//! and the `msgpack` builder option enabled) or CBOR (with the `cbor` feature
+//! enabled):
+//!
+//! - POST bodies may be sent as `application/msgpack` or `application/cbor`.
+//! - A client listing one of these types in its `Accept` header receives SSE
+//!   events whose `data` is the base64-encoded binary form of each message. Such
+//!   responses carry an `X-MCP-Payload-Encoding` header naming the encoding
+//!   (`msgpack` or `cbor`). Priming events carry no data and are unchanged.
+//!
+//! SSE `data` is text, so base64 makes event payloads a third larger than the
+//! binary form, which for typical messages outweighs what the binary encoding
+//! saves over JSON. The gains on the response side are in parsing cost, and
+//! in size only for numeric-heavy payloads; request bodies are sent as raw
+//! binary and keep the full saving.
+//!
+//! Plain JSON clients are unaffected.
+//!
+//! Every event payload and request body of both transports is serialized
+//! through [`PayloadEncoding`], so the JSON codec lives in a single place. With
+//! the `simd-json` feature, JSON messages are encoded and decoded by
+//! `simd-json` instead of `serde_json`; the request body is copied once, as
+//! `simd-json` parses in place.
+
+use actix_web::{HttpRequest, http::header};
+use rmcp::model::ClientJsonRpcMessage;
+
+use super::JSON_MIME_TYPE;
+
+/// Response header announcing the encoding of event payloads.
+const HEADER_PAYLOAD_ENCODING: &str = "X-MCP-Payload-Encoding";
+
+/// Encoding of request bodies and of the `data` of SSE events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PayloadEncoding {
+    /// JSON text, the MCP default.
+    Json,
+    /// MessagePack.
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    /// CBOR.
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+/// Binary encodings, in order of preference.
+const BINARY: &[PayloadEncoding] = &[
+    #[cfg(feature = "cbor")]
+    PayloadEncoding::Cbor,
+    #[cfg(feature = "msgpack")]
+    PayloadEncoding::MsgPack,
+];
+
+impl PayloadEncoding {
+    fn mime_type(self) -> &'static str {
+        match self {
+            Self::Json => JSON_MIME_TYPE,
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => "application/msgpack",
+            #[cfg(feature = "cbor")]
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    /// Returns `true` if the encoding may be used; MessagePack only when
+    /// `msgpack` is enabled.
+    #[cfg_attr(not(feature = "msgpack"), allow(unused_variables))]
+    fn is_enabled(self, msgpack: bool) -> bool {
+        match self {
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => msgpack,
+            _ => true,
+        }
+    }
+
+    /// Returns the content types accepted for request bodies, e.g. for error
+    /// messages.
+    pub(super) fn content_types(msgpack: bool) -> String {
+        [Self::Json]
+            .iter()
+            .chain(BINARY)
+            .filter(|encoding| encoding.is_enabled(msgpack))
+            .map(|encoding| encoding.mime_type())
+            .collect::<Vec<_>>()
+            .join(" or ")
+    }
+
+    /// Returns `true` if `accept` lists JSON or an enabled binary encoding.
+    pub(super) fn accepts(accept: &str, msgpack: bool) -> bool {
+        accept.contains(JSON_MIME_TYPE)
+            || BINARY.iter().any(|encoding| {
+                encoding.is_enabled(msgpack) && accept.contains(encoding.mime_type())
+            })
+    }
+
+    /// Returns the encoding of a request body, or `None` if its content type is
+    /// not supported.
+    pub(super) fn from_content_type(content_type: Option<&str>, msgpack: bool) -> Option<Self> {
+        let content_type = content_type?;
+        [Self::Json].iter().chain(BINARY).copied().find(|encoding| {
+            encoding.is_enabled(msgpack) && content_type.starts_with(encoding.mime_type())
+        })
+    }
+
+    /// Picks the encoding of the events answering `req`: the preferred binary
+    /// encoding listed in its `Accept` header, JSON otherwise.
+    pub(super) fn negotiate(req: &HttpRequest, msgpack: bool) -> Self {
+        let Some(accept) = req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|h| h.to_str().ok())
+        else {
+            return Self::Json;
+        };
+        BINARY
+            .iter()
+            .copied()
+            .find(|encoding| encoding.is_enabled(msgpack) && accept.contains(encoding.mime_type()))
+            .unwrap_or(Self::Json)
+    }
+
+    /// Announces a binary encoding on an SSE response.
+    pub(super) fn announce(self, builder: &mut actix_web::HttpResponseBuilder) {
+        let name: Option<&'static str> = match self {
+            Self::Json => None,
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => Some("msgpack"),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => Some("cbor"),
+        };
+        if let Some(name) = name {
+            builder.append_header((HEADER_PAYLOAD_ENCODING, name));
+        }
+    }
+
+    /// Serializes an event payload.
+    pub(super) fn encode<T: serde::Serialize>(self, message: &T) -> String {
+        match self {
+            #[cfg(not(feature = "simd-json"))]
+            Self::Json => serde_json::to_string(message).unwrap_or_else(|_| "{}".to_string()),
+            #[cfg(feature = "simd-json")]
+            Self::Json => simd_json::to_string(message).unwrap_or_else(|_| "{}".to_string()),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => base64_payload(rmp_serde::to_vec_named(message).unwrap_or_default()),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                // Flattened fields would be written as indefinite-length maps;
+                // `Value` maps have a known length, which every decoder accepts.
+                let value = serde_json::to_value(message).unwrap_or_default();
+                let mut bytes = Vec::new();
+                if ciborium::into_writer(&value, &mut bytes).is_err() {
+                    bytes.clear();
+                }
+                base64_payload(bytes)
+            }
+        }
+    }
+
+    /// Deserializes a request body.
+    pub(super) fn decode(
+        self,
+        body: &[u8],
+    ) -> Result<ClientJsonRpcMessage, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            #[cfg(not(feature = "simd-json"))]
+            Self::Json => Ok(serde_json::from_slice(body)?),
+            #[cfg(feature = "simd-json")]
+            Self::Json => Ok(simd_json::from_slice(&mut body.to_vec())?),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => {
+                let mut reader = body;
+                let message = rmp_serde::from_read(&mut reader)?;
+                whole_body(reader, message, "MessagePack")
+            }
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut reader = body;
+                let message = ciborium::from_reader(&mut reader)?;
+                whole_body(reader, message, "CBOR")
+            }
+        }
+    }
+}
+
+/// Encodes a binary event payload for the text `data` of an SSE event.
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+fn base64_payload(bytes: Vec<u8>) -> String {
+    use base64::Engine;
+
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Returns `message` if decoding it consumed the whole body, leaving `rest`.
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+fn whole_body(
+    rest: &[u8],
+    message: ClientJsonRpcMessage,
+    encoding: &str,
+) -> Result<ClientJsonRpcMessage, Box<dyn std::error::Error + Send + Sync>> {
+    if !rest.is_empty() {
+        return Err(format!("Invalid {encoding} body: trailing bytes").into());
+    }
+    Ok(message)
+}
@@ -0,0 +1,232 @@
+//! Payload size and serialization time statistics.
+//!
+//! With [`payload_stats`](super::StreamableHttpServiceBuilder::payload_stats)
+//! enabled, every message event written to a Streamable HTTP response or
+//! standalone stream is timed while it is serialized, and its size and
+//! serialization time are counted in fixed-bucket histograms. The histograms
+//! are kept per source of the message, so tools returning pathological result
+//! sizes stand out:
+//!
+//! - `tools/call:<tool>` for the stream of a call to `<tool>`,
+//! - the method, e.g. `resources/read`, for the streams of other requests,
+//! - `standalone` for standalone `GET` streams.
+//!
+//! Progress and other notifications sent on the stream of a request are
+//! counted with its response. Sizes are those of the whole SSE event, with
+//! its framing and, for binary encodings, its base64 form. Priming events,
+//! keep-alive frames, responses replayed from the idempotency or response
+//! caches and legacy SSE connections are not counted.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rmcp::model::ClientRequest;
+use serde::Serialize;
+
+/// Upper bounds of the payload size buckets, in bytes.
+pub const PAYLOAD_SIZE_BUCKETS: [u64; 8] = [
+    256,
+    1024,
+    4 * 1024,
+    16 * 1024,
+    64 * 1024,
+    256 * 1024,
+    1024 * 1024,
+    4 * 1024 * 1024,
+];
+
+/// Upper bounds of the serialization time buckets, in microseconds.
+pub const SERIALIZATION_TIME_BUCKETS: [u64; 8] = [10, 50, 100, 500, 1_000, 5_000, 10_000, 50_000];
+
+/// Source label of standalone `GET` streams.
+pub(super) const STANDALONE_SOURCE: &str = "standalone";
+
+/// Payload statistics of every message source seen so far.
+///
+/// Returned by
+/// [`StreamableHttpService::payload_stats`](super::StreamableHttpService::payload_stats).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PayloadStats {
+    /// Statistics keyed by message source, e.g. `tools/call:search`.
+    pub sources: BTreeMap<String, SourcePayloadStats>,
+}
+
+/// Payload statistics of one message source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct SourcePayloadStats {
+    /// Message events written.
+    pub messages: u64,
+    /// Serialized sizes, in bytes, over [`PAYLOAD_SIZE_BUCKETS`].
+    pub size_bytes: Histogram,
+    /// Serialization times, in microseconds, over [`SERIALIZATION_TIME_BUCKETS`].
+    pub serialization_micros: Histogram,
+}
+
+/// A fixed-bucket histogram.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Histogram {
+    /// Cumulative bucket counts, Prometheus style: each bucket counts the
+    /// observations up to its bound. The last bucket has no bound and counts
+    /// every observation.
+    pub buckets: Vec<HistogramBucket>,
+    /// Sum of the observations.
+    pub sum: u64,
+    /// Largest observation.
+    pub max: u64,
+}
+
+/// A bucket of a [`Histogram`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct HistogramBucket {
+    /// Inclusive upper bound of the bucket; `None` for the last, unbounded one.
+    pub le: Option<u64>,
+    /// Observations up to the bound.
+    pub count: u64,
+}
+
+/// Observations of one histogram, one count per bucket and one for overflows.
+struct Observations<const N: usize> {
+    counts: [u64; N],
+    overflow: u64,
+    sum: u64,
+    max: u64,
+}
+
+impl<const N: usize> Observations<N> {
+    fn new() -> Self {
+        Self {
+            counts: [0; N],
+            overflow: 0,
+            sum: 0,
+            max: 0,
+        }
+    }
+
+    fn record(&mut self, bounds: &[u64; N], value: u64) {
+        match bounds.iter().position(|bound| value <= *bound) {
+            Some(bucket) => self.counts[bucket] += 1,
+            None => self.overflow += 1,
+        }
+        self.sum = self.sum.saturating_add(value);
+        self.max = self.max.max(value);
+    }
+
+    fn histogram(&self, bounds: &[u64; N]) -> Histogram {
+        let mut cumulative = 0;
+        let mut buckets: Vec<_> = bounds
+            .iter()
+            .zip(self.counts)
+            .map(|(bound, count)| {
+                cumulative += count;
+                HistogramBucket {
+                    le: Some(*bound),
+                    count: cumulative,
+                }
+            })
+            .collect();
+        buckets.push(HistogramBucket {
+            le: None,
+            count: cumulative + self.overflow,
+        });
+        Histogram {
+            buckets,
+            sum: self.sum,
+            max: self.max,
+        }
+    }
+}
+
+struct SourceObservations {
+    messages: u64,
+    sizes: Observations<{ PAYLOAD_SIZE_BUCKETS.len() }>,
+    times: Observations<{ SERIALIZATION_TIME_BUCKETS.len() }>,
+}
+
+type Sources = Arc<Mutex<HashMap<Arc<str>, SourceObservations>>>;
+
+/// Histograms shared by every clone of the service; disabled unless
+/// [`payload_stats`](super::StreamableHttpServiceBuilder::payload_stats) is set.
+#[derive(Clone, Default)]
+pub(super) struct PayloadRecorder(Option<Sources>);
+
+impl PayloadRecorder {
+    pub(super) fn new(enabled: bool) -> Self {
+        Self(enabled.then(Sources::default))
+    }
+
+    /// Returns the recorder of the messages of `source`, if recording is enabled.
+    pub(super) fn source(&self, source: impl FnOnce() -> String) -> Option<PayloadSource> {
+        Some(PayloadSource {
+            sources: self.0.clone()?,
+            label: source().into(),
+        })
+    }
+
+    /// Returns the recorder of the messages answering `request`, if recording is
+    /// enabled.
+    pub(super) fn request_source(&self, request: &ClientRequest) -> Option<PayloadSource> {
+        self.source(|| match request {
+            ClientRequest::CallToolRequest(call) => format!("tools/call:{}", call.params.name),
+            request => request.method().to_owned(),
+        })
+    }
+
+    pub(super) fn snapshot(&self) -> PayloadStats {
+        let Some(sources) = &self.0 else {
+            return PayloadStats::default();
+        };
+        let sources = sources.lock().unwrap_or_else(|e| e.into_inner());
+        PayloadStats {
+            sources: sources
+                .iter()
+                .map(|(label, observed)| {
+                    let stats = SourcePayloadStats {
+                        messages: observed.messages,
+                        size_bytes: observed.sizes.histogram(&PAYLOAD_SIZE_BUCKETS),
+                        serialization_micros: observed.times.histogram(&SERIALIZATION_TIME_BUCKETS),
+                    };
+                    (label.to_string(), stats)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Records the messages of one source.
+#[derive(Clone)]
+pub(super) struct PayloadSource {
+    sources: Sources,
+    label: Arc<str>,
+}
+
+impl PayloadSource {
+    /// Records a message event of `size` bytes serialized in `elapsed`.
+    pub(super) fn record(&self, size: usize, elapsed: Duration) {
+        let mut sources = self.sources.lock().unwrap_or_else(|e| e.into_inner());
+        let observed = sources
+            .entry(self.label.clone())
+            .or_insert_with(|| SourceObservations {
+                messages: 0,
+                sizes: Observations::new(),
+                times: Observations::new(),
+            });
+        observed.messages += 1;
+        observed.sizes.record(
+            &PAYLOAD_SIZE_BUCKETS,
+            u64::try_from(size).unwrap_or(u64::MAX),
+        );
+        observed.times.record(
+            &SERIALIZATION_TIME_BUCKETS,
+            u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX),
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Per-session protocol version handling.
+//!
+//! The protocol version negotiated by `initialize` is recorded with the session,
+//! so one deployment can serve clients pinned to different spec revisions:
+//!
+//! - The `MCP-Protocol-Version` header, which clients send on every request
+//!   from 2025-06-18 on, must name a known version and, within a session, the
+//!   negotiated one. Requests without the header are accepted, as older clients
+//!   never send it.
+//! - Tool results are downgraded for sessions negotiated before 2025-06-18,
+//!   which predate `structuredContent`: a structured result without any content
+//!   block is also serialized into a text content block, where those clients
+//!   can read it.
+
+use std::borrow::Cow;
+
+use actix_web::{HttpRequest, HttpResponse, http::StatusCode};
+use rmcp::{
+    model::{Content, ProtocolVersion, ServerJsonRpcMessage, ServerResult},
+    transport::common::http_header::HEADER_MCP_PROTOCOL_VERSION,
+};
+
+use crate::transport::Problem;
+
+/// Checks the `MCP-Protocol-Version` header of `req` against the known versions
+/// and the version negotiated by the session, if any.
+///
+/// Returns the `400 Bad Request` response to send when the header is rejected.
+pub(super) fn check_header(
+    req: &HttpRequest,
+    negotiated: Option<&ProtocolVersion>,
+) -> Option<HttpResponse> {
+    let value = req.headers().get(HEADER_MCP_PROTOCOL_VERSION)?;
+    let Ok(version) = value.to_str() else {
+        return Some(
+            Problem::new(
+                StatusCode::BAD_REQUEST,
+                "invalid_protocol_version",
+                "Invalid MCP-Protocol-Version header encoding",
+            )
+            .into_response(),
+        );
+    };
+    if !ProtocolVersion::KNOWN_VERSIONS
+        .iter()
+        .any(|known| known.as_str() == version)
+    {
+        return Some(
+            Problem::new(
+                StatusCode::BAD_REQUEST,
+                "unsupported_protocol_version",
+                format!("Unsupported MCP-Protocol-Version: {version}"),
+            )
+            .into_response(),
+        );
+    }
+    match negotiated {
+        Some(negotiated) if negotiated.as_str() != version => {
+            Some(
+                Problem::new(
+                    StatusCode::BAD_REQUEST,
+                    "protocol_version_mismatch",
+                    format!(
+                        "MCP-Protocol-Version {version} does not match the negotiated version {negotiated}"
+                    ),
+                )
+                .into_response(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Adapts a server message to what clients of `version` understand.
+pub(super) fn downgrade<'a>(
+    version: &ProtocolVersion,
+    message: &'a ServerJsonRpcMessage,
+) -> Cow<'a, ServerJsonRpcMessage> {
+    // Versions are dates, so they order lexicographically
+    if version.as_str() >= ProtocolVersion::V_2025_06_18.as_str() {
+        return Cow::Borrowed(message);
+    }
+    let ServerJsonRpcMessage::Response(response) = message else {
+        return Cow::Borrowed(message);
+    };
+    let ServerResult::CallToolResult(result) = &response.result else {
+        return Cow::Borrowed(message);
+    };
+    let Some(structured) = &result.structured_content else {
+        return Cow::Borrowed(message);
+    };
+    if !result.content.is_empty() {
+        return Cow::Borrowed(message);
+    }
+
+    let text = Content::text(structured.to_string());
+    let mut message = message.clone();
+    if let ServerJsonRpcMessage::Response(response) = &mut message
+        && let ServerResult::CallToolResult(result) = &mut response.result
+    {
+        result.content.push(text);
+    }
+    Cow::Owned(message)
+}
@@ -0,0 +1,78 @@
+//! Access tokens passed in the query string of `GET` requests.
+//!
+//! Browsers' `EventSource` cannot send an `Authorization` header, so with
+//! [`query_token_param`](super::StreamableHttpServiceBuilder::query_token_param)
+//! set, a `GET` request may carry its token in the query string instead. The
+//! token is moved into an `Authorization: Bearer` header before the request
+//! reaches the guards and handlers, and removed from the URI, so it shows up
+//! neither in the URIs seen past this point nor in the transport's logs.
+
+use actix_web::{
+    dev::ServiceRequest,
+    http::{
+        Method, Uri,
+        header::{self, HeaderValue},
+    },
+    web,
+};
+
+/// Moves the token in the `param` query parameter of a `GET` request into its
+/// `Authorization` header.
+///
+/// An `Authorization` header already sent takes precedence; the query parameter
+/// is removed from the URI either way.
+pub(super) fn promote(req: &mut ServiceRequest, param: &str) {
+    if req.method() != Method::GET {
+        return;
+    }
+    let Some(query) = req.uri().query() else {
+        return;
+    };
+    let (tokens, kept): (Vec<&str>, Vec<&str>) = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .partition(|pair| pair.split_once('=').map_or(*pair, |(name, _)| name) == param);
+    if tokens.is_empty() {
+        return;
+    }
+    let token = tokens.iter().find_map(|pair| decode_value(pair, param));
+    let kept = kept.join("&");
+
+    if let Some(token) = token
+        && !req.headers().contains_key(header::AUTHORIZATION)
+    {
+        match HeaderValue::try_from(format!("Bearer {token}")) {
+            Ok(value) => {
+                req.headers_mut().insert(header::AUTHORIZATION, value);
+            }
+            Err(_) => tracing::debug!("Ignoring query token that is not a valid header value"),
+        }
+    }
+    strip_query(req, &kept);
+}
+
+/// Returns the percent-decoded, non-empty value of a `param=value` pair.
+fn decode_value(pair: &str, param: &str) -> Option<String> {
+    web::Query::<std::collections::HashMap<String, String>>::from_query(pair)
+        .ok()?
+        .remove(param)
+        .filter(|token| !token.is_empty())
+}
+
+/// Replaces the query string of `req` with `query`.
+fn strip_query(req: &mut ServiceRequest, query: &str) {
+    let path_and_query = if query.is_empty() {
+        req.path().to_owned()
+    } else {
+        format!("{}?{query}", req.path())
+    };
+    let mut parts = req.uri().clone().into_parts();
+    let Ok(path_and_query) = path_and_query.parse() else {
+        return;
+    };
+    parts.path_and_query = Some(path_and_query);
+    if let Ok(uri) = Uri::from_parts(parts) {
+        req.match_info_mut().get_mut().update(&uri);
+        req.head_mut().uri = uri;
+    }
+}
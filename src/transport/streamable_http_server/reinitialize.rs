@@ -0,0 +1,44 @@
+//! `initialize` requests sent within an existing session.
+//!
+//! A client that lost track of its handshake, for instance after a page reload
+//! that kept the session cookie, may send `initialize` again with its
+//! `Mcp-Session-Id`. A [`ReinitializePolicy`] decides what the transport does
+//! with it instead of handing it to the session's service, which has already
+//! been initialized.
+
+use actix_web::HttpResponse;
+use rmcp::model::{ErrorCode, ErrorData, RequestId, ServerJsonRpcMessage};
+
+use super::JSON_MIME_TYPE;
+
+const ALREADY_INITIALIZED_MESSAGE: &str = "Session already initialized";
+/// JSON-RPC error code for `initialize` requests refused in an existing session.
+const ALREADY_INITIALIZED_CODE: ErrorCode = ErrorCode(-32007);
+
+/// What to do with an `initialize` request sent within an existing session, see
+/// [`reinitialize_policy`](super::StreamableHttpServiceBuilder::reinitialize_policy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReinitializePolicy {
+    /// Refuses the request with `400 Bad Request` and a JSON-RPC error, leaving
+    /// the session as it is.
+    #[default]
+    Reject,
+    /// Closes the session and creates a new one for the request, as if it had
+    /// been sent without `Mcp-Session-Id`.
+    Recreate,
+    /// Answers with the result of the session's original `initialize` request,
+    /// leaving the session as it is.
+    Replay,
+}
+
+/// Builds the `400 Bad Request` response refusing the `initialize` request `id`.
+pub(super) fn already_initialized_response(id: RequestId) -> HttpResponse {
+    let error = ServerJsonRpcMessage::error(
+        ErrorData::new(ALREADY_INITIALIZED_CODE, ALREADY_INITIALIZED_MESSAGE, None),
+        Some(id),
+    );
+    HttpResponse::BadRequest()
+        .content_type(JSON_MIME_TYPE)
+        .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()))
+}
@@ -0,0 +1,48 @@
+//! Request deadlines announced by clients.
+//!
+//! Clients and MCP proxies that have a deadline of their own announce it with
+//! either header:
+//!
+//! - `Request-Timeout`: the remaining budget in seconds, e.g. `30` or `2.5`,
+//!   counted from the moment the request is received.
+//! - `X-Request-Deadline`: the deadline as Unix time in milliseconds.
+//!
+//! A request may only shorten the service's own request timeout, never extend
+//! it. Malformed values are ignored.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use actix_web::HttpRequest;
+
+use crate::transport::RequestDeadline;
+
+/// Returns the budget announced by the headers of `req`, if any.
+pub(super) fn announced_budget(req: &HttpRequest) -> Option<Duration> {
+    let header = |name: &str| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+    };
+    let timeout = header(RequestDeadline::TIMEOUT_HEADER).and_then(|value| {
+        let secs = value.parse::<f64>().ok();
+        let budget = secs.and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+        if budget.is_none() {
+            tracing::debug!(value, "Ignoring malformed Request-Timeout header");
+        }
+        budget
+    });
+    let deadline = header(RequestDeadline::DEADLINE_HEADER).and_then(|value| {
+        let Ok(millis) = value.parse::<u64>() else {
+            tracing::debug!(value, "Ignoring malformed X-Request-Deadline header");
+            return None;
+        };
+        let deadline = UNIX_EPOCH + Duration::from_millis(millis);
+        Some(
+            deadline
+                .duration_since(SystemTime::now())
+                .unwrap_or_default(),
+        )
+    });
+    timeout.into_iter().chain(deadline).min()
+}
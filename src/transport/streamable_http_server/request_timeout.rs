@@ -0,0 +1,82 @@
+//! Overall timeout for POSTed requests.
+//!
+//! When a request has not produced its final response within the configured
+//! timeout, the transport answers it with a JSON-RPC error on the request's
+//! stream, ends the stream, and cancels the handler: through a
+//! `notifications/cancelled` sent to the session in stateful mode, by aborting
+//! the request task in stateless mode.
+
+use std::{future::Future, time::Duration};
+
+use futures::{Stream, StreamExt, stream::BoxStream};
+use rmcp::model::{
+    CancelledNotification, CancelledNotificationParam, ClientJsonRpcMessage, ClientNotification,
+    ErrorCode, ErrorData, RequestId, ServerJsonRpcMessage,
+};
+
+/// JSON-RPC error code for requests that exceeded the request timeout.
+///
+/// Distinct from [`LEGACY_SESSION_NOT_FOUND_CODE`](super::LEGACY_SESSION_NOT_FOUND_CODE),
+/// on which clients re-initialize their session.
+const REQUEST_TIMEOUT_CODE: ErrorCode = ErrorCode(-32008);
+
+/// Builds the error answering a request that timed out.
+pub(super) fn timeout_error(id: RequestId, timeout: Duration) -> ServerJsonRpcMessage {
+    ServerJsonRpcMessage::error(
+        ErrorData::new(
+            REQUEST_TIMEOUT_CODE,
+            format!("Request timed out after {}ms", timeout.as_millis()),
+            None,
+        ),
+        Some(id),
+    )
+}
+
+/// Builds the notification cancelling a request for `reason`.
+pub(super) fn cancellation(id: RequestId, reason: &str) -> ClientJsonRpcMessage {
+    ClientJsonRpcMessage::notification(ClientNotification::CancelledNotification(
+        CancelledNotification::new(CancelledNotificationParam {
+            request_id: id,
+            reason: Some(reason.to_string()),
+        }),
+    ))
+}
+
+/// Ends `stream` with the item produced by `on_timeout` if it has not finished
+/// within `timeout`.
+///
+/// `on_timeout` receives the elapsed timeout.
+pub(super) fn with_timeout<St, F, Fut>(
+    stream: St,
+    timeout: Option<Duration>,
+    on_timeout: F,
+) -> BoxStream<'static, St::Item>
+where
+    St: Stream + Send + 'static,
+    St::Item: Send + 'static,
+    F: FnOnce(Duration) -> Fut + Send + 'static,
+    Fut: Future<Output = St::Item> + Send,
+{
+    let Some(timeout) = timeout else {
+        return stream.boxed();
+    };
+    async_stream::stream! {
+        let mut stream = Box::pin(stream);
+        let deadline = tokio::time::sleep(timeout);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                item = stream.next() => match item {
+                    Some(item) => yield item,
+                    None => break,
+                },
+                _ = &mut deadline => {
+                    tracing::warn!(?timeout, "Request timed out");
+                    yield on_timeout(timeout).await;
+                    break;
+                }
+            }
+        }
+    }
+    .boxed()
+}
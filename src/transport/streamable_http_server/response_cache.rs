@@ -0,0 +1,288 @@
+//! Caching of discovery results and of idempotent tool results.
+//!
+//! Fleets of clients tend to poll the discovery methods, and every poll reaches
+//! the handler although the lists rarely change. With a TTL configured, the
+//! transport keeps the result of each discovery request, keyed by method and
+//! pagination cursor, and answers the same request from any session with it
+//! until the TTL expires. Results of tools listed in
+//! [`CacheableTools`](crate::transport::CacheableTools) are kept the same way,
+//! keyed by tool name and canonical arguments. The cache belongs to the service
+//! instance, so every worker shares it. With a
+//! [`tenant_service_factory`](super::StreamableHttpServiceBuilder::tenant_service_factory),
+//! results are also keyed by tenant, since the handler of each tenant has its own
+//! tools and data.
+//!
+//! A `notifications/tools/list_changed` or `notifications/prompts/list_changed`
+//! sent by the service of any session drops the cached pages of that list for
+//! the tenant of the session,
+//! whether or not a client is listening for it. Responses of requests that were
+//! running when the notification went out are not cached, since they may
+//! predate the change.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use rmcp::{
+    RoleServer,
+    model::{
+        CallToolRequestParams, ClientRequest, RequestId, ServerJsonRpcMessage, ServerNotification,
+        ServerResult,
+    },
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::Transport,
+};
+use serde_json::Value;
+
+use super::TenantId;
+
+/// Discovery method of a cached list.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum List {
+    Tools,
+    Prompts,
+}
+
+/// Cache key of a cacheable request.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub(super) struct CacheKey {
+    /// Tenant whose handler answers the request, when handlers are per tenant
+    tenant: Option<TenantId>,
+    request: RequestKey,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum RequestKey {
+    /// Discovery request: the listed kind and the requested page
+    List { list: List, cursor: Option<String> },
+    /// Tool call: the tool name and its arguments in canonical JSON form
+    ToolCall { name: String, arguments: String },
+}
+
+impl CacheKey {
+    /// Returns the key of `request` answered for `tenant`, if it is a discovery
+    /// request.
+    pub(super) fn discovery(tenant: Option<TenantId>, request: &ClientRequest) -> Option<Self> {
+        let (list, params) = match request {
+            ClientRequest::ListToolsRequest(request) => (List::Tools, &request.params),
+            ClientRequest::ListPromptsRequest(request) => (List::Prompts, &request.params),
+            _ => return None,
+        };
+        Some(Self {
+            tenant,
+            request: RequestKey::List {
+                list,
+                cursor: params.as_ref().and_then(|params| params.cursor.clone()),
+            },
+        })
+    }
+
+    /// Returns the key of a call of a tool answered for `tenant`.
+    pub(super) fn tool_call(tenant: Option<TenantId>, params: &CallToolRequestParams) -> Self {
+        let arguments = params
+            .arguments
+            .as_ref()
+            .map(|arguments| canonical(&Value::Object(arguments.clone())).to_string())
+            .unwrap_or_default();
+        Self {
+            tenant,
+            request: RequestKey::ToolCall {
+                name: params.name.to_string(),
+                arguments,
+            },
+        }
+    }
+}
+
+/// Returns `value` with the keys of every object sorted.
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonical(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        value => value.clone(),
+    }
+}
+
+struct Entry {
+    result: ServerResult,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct Cache {
+    entries: HashMap<CacheKey, Entry>,
+    /// Bumped by every invalidation, so results computed before it are dropped
+    generation: u64,
+}
+
+/// Cached results, shared by every worker.
+#[derive(Clone, Default)]
+pub(super) struct ResponseCache {
+    cache: Arc<Mutex<Cache>>,
+}
+
+/// A cache miss, to be filled with the response of the request.
+pub(super) struct Miss {
+    cache: ResponseCache,
+    key: CacheKey,
+    ttl: Duration,
+    generation: u64,
+}
+
+/// Result of looking up a request in the [`ResponseCache`].
+pub(super) enum Lookup {
+    /// The cached response, addressed to the request.
+    Hit(Box<ServerJsonRpcMessage>),
+    /// No fresh result; the response of the handler fills the cache.
+    Miss(Miss),
+}
+
+impl ResponseCache {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Looks up the result of the request `key`, answering `request_id`.
+    ///
+    /// On a miss, the result is kept for `ttl` once known. Expired results are
+    /// forgotten first.
+    pub(super) fn lookup(&self, key: CacheKey, request_id: RequestId, ttl: Duration) -> Lookup {
+        let mut cache = self.lock();
+        let now = Instant::now();
+        cache.entries.retain(|_, entry| entry.expires_at > now);
+        if let Some(entry) = cache.entries.get(&key) {
+            tracing::debug!(%request_id, "Answering request from cache");
+            return Lookup::Hit(Box::new(ServerJsonRpcMessage::response(
+                entry.result.clone(),
+                request_id,
+            )));
+        }
+        Lookup::Miss(Miss {
+            cache: self.clone(),
+            key,
+            ttl,
+            generation: cache.generation,
+        })
+    }
+
+    /// Drops the cached pages of `tenant` of a list whose change `message`
+    /// announces.
+    pub(super) fn invalidate(&self, tenant: Option<&TenantId>, message: &ServerJsonRpcMessage) {
+        if let Some(list) = changed_list(message) {
+            self.drop_list(list, |cached| cached == tenant);
+        }
+    }
+
+    /// Drops the cached pages of every tenant of a list whose change `message`
+    /// announces.
+    pub(super) fn invalidate_all_tenants(&self, message: &ServerJsonRpcMessage) {
+        if let Some(list) = changed_list(message) {
+            self.drop_list(list, |_| true);
+        }
+    }
+
+    fn drop_list(&self, list: List, of_tenant: impl Fn(Option<&TenantId>) -> bool) {
+        let mut cache = self.lock();
+        cache.generation += 1;
+        cache.entries.retain(|key, _| {
+            !of_tenant(key.tenant.as_ref())
+                || !matches!(key.request, RequestKey::List { list: cached, .. } if cached == list)
+        });
+    }
+}
+
+/// Returns the list whose change `message` announces, if any.
+fn changed_list(message: &ServerJsonRpcMessage) -> Option<List> {
+    let ServerJsonRpcMessage::Notification(notification) = message else {
+        return None;
+    };
+    match notification.notification {
+        ServerNotification::ToolListChangedNotification(_) => Some(List::Tools),
+        ServerNotification::PromptListChangedNotification(_) => Some(List::Prompts),
+        _ => None,
+    }
+}
+
+impl Miss {
+    /// Stores the successful response among the messages of the request.
+    ///
+    /// Tool results flagged as errors are not stored.
+    pub(super) fn fill(&self, message: &ServerJsonRpcMessage) {
+        let ServerJsonRpcMessage::Response(response) = message else {
+            return;
+        };
+        if let ServerResult::CallToolResult(result) = &response.result
+            && result.is_error == Some(true)
+        {
+            return;
+        }
+        let mut cache = self.cache.lock();
+        if cache.generation != self.generation {
+            return;
+        }
+        cache.entries.insert(
+            self.key.clone(),
+            Entry {
+                result: response.result.clone(),
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+    }
+}
+
+/// Service transport that invalidates the cache on list-changed notifications.
+pub(super) struct InvalidateOnChange<T> {
+    inner: T,
+    cache: ResponseCache,
+    /// Tenant whose handler sends the notifications, when handlers are per tenant
+    tenant: Option<TenantId>,
+}
+
+impl<T> InvalidateOnChange<T> {
+    pub(super) fn new(inner: T, cache: ResponseCache, tenant: Option<TenantId>) -> Self {
+        Self {
+            inner,
+            cache,
+            tenant,
+        }
+    }
+}
+
+impl<T> Transport<RoleServer> for InvalidateOnChange<T>
+where
+    T: Transport<RoleServer>,
+{
+    type Error = T::Error;
+
+    fn name() -> Cow<'static, str> {
+        T::name()
+    }
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleServer>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.cache.invalidate(self.tenant.as_ref(), &item);
+        self.inner.send(item)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleServer>>> + Send {
+        self.inner.receive()
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
@@ -0,0 +1,117 @@
+//! Live transport statistics.
+//!
+//! A snapshot of what the transport is holding right now: sessions, open
+//! streams, request queues and progress counters, plus uptime and build
+//! information. Meant for dashboards and ad-hoc inspection that do not scrape
+//! Prometheus; every value is read from state the transport keeps anyway.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+use actix_web::{HttpResponse, Resource, web};
+use serde::Serialize;
+
+use super::{
+    legacy_sse::LegacySseConnections, session_backpressure::PendingRequests,
+    sessions::SessionRegistry, shutdown::ShutdownSignal, sse_connections::SseConnections,
+};
+
+/// Snapshot of the transport's live state.
+///
+/// Returned by [`StreamableHttpService::runtime_stats`](super::StreamableHttpService::runtime_stats)
+/// and served as JSON by [`stats_route`](super::StreamableHttpService::stats_route).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct RuntimeStats {
+    /// Open sessions.
+    pub sessions: usize,
+    /// Open sessions whose client has not followed up on `initialize` yet.
+    pub half_open_sessions: usize,
+    /// Open standalone `GET` and legacy SSE streams.
+    pub sse_streams: usize,
+    /// Open legacy SSE connections, a subset of `sse_streams`.
+    pub legacy_sse_connections: usize,
+    /// Requests posted to sessions whose response stream has not ended.
+    pub pending_requests: usize,
+    /// Largest number of pending requests in a single session.
+    pub deepest_session_queue: usize,
+    /// JSON-RPC requests being handled, in every mode.
+    pub in_flight_requests: usize,
+    /// `notifications/progress` messages written to clients so far.
+    pub forwarded_progress_notifications: u64,
+    /// Whether [`shutdown`](super::StreamableHttpService::shutdown) has started.
+    pub draining: bool,
+    /// Whole seconds since the service was built.
+    pub uptime_seconds: u64,
+    /// Build of the transport.
+    pub build: BuildInfo,
+}
+
+/// Build information of the transport crate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct BuildInfo {
+    /// Crate name.
+    pub name: &'static str,
+    /// Crate version.
+    pub version: &'static str,
+}
+
+impl Default for BuildInfo {
+    fn default() -> Self {
+        Self {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        }
+    }
+}
+
+/// Shared state the statistics are read from.
+#[derive(Clone)]
+pub(super) struct StatsSources {
+    pub(super) started_at: Instant,
+    pub(super) sessions: SessionRegistry,
+    pub(super) sse_connections: SseConnections,
+    pub(super) legacy_sse: LegacySseConnections,
+    pub(super) pending_requests: PendingRequests,
+    pub(super) shutdown: ShutdownSignal,
+    pub(super) forwarded_progress: Arc<AtomicU64>,
+}
+
+impl StatsSources {
+    /// Takes a snapshot of the current state.
+    pub(super) async fn snapshot(&self) -> RuntimeStats {
+        let (sessions, half_open_sessions) = self.sessions.counts().await;
+        let (pending_requests, deepest_session_queue) = self.pending_requests.depths();
+        RuntimeStats {
+            sessions,
+            half_open_sessions,
+            sse_streams: self.sse_connections.total(),
+            legacy_sse_connections: self.legacy_sse.open_count().await,
+            pending_requests,
+            deepest_session_queue,
+            in_flight_requests: self.shutdown.in_flight_count(),
+            forwarded_progress_notifications: self.forwarded_progress.load(Ordering::Relaxed),
+            draining: self.shutdown.is_draining(),
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+            build: BuildInfo::default(),
+        }
+    }
+}
+
+/// Creates the statistics resource at `path`.
+pub(super) fn stats_resource(path: &str, sources: StatsSources) -> Resource {
+    web::resource(path)
+        .app_data(web::Data::new(sources))
+        .route(web::get().to(handle_stats))
+}
+
+async fn handle_stats(sources: web::Data<StatsSources>) -> HttpResponse {
+    HttpResponse::Ok().json(sources.snapshot().await)
+}
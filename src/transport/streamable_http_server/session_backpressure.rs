@@ -0,0 +1,110 @@
+//! Per-session cap on pending requests.
+//!
+//! Every JSON-RPC request posted to a session waits in the session manager's
+//! queue until the service task picks it up, then runs until its response
+//! stream ends. Without a cap, a client sending faster than the service
+//! answers makes that backlog grow without bound. With a cap configured, a
+//! session may only have that many requests pending at once; further requests
+//! are refused with `429 Too Many Requests` and a `Retry-After` header, so
+//! well-behaved clients back off instead of piling up more work.
+//!
+//! Notifications and client responses are never refused, since pending
+//! requests may be waiting for them.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use actix_web::{
+    HttpResponse,
+    http::header::{HeaderValue, RETRY_AFTER},
+};
+use futures::{Stream, StreamExt};
+use rmcp::{
+    model::{ErrorCode, ErrorData, RequestId, ServerJsonRpcMessage},
+    transport::streamable_http_server::session::SessionId,
+};
+
+use super::JSON_MIME_TYPE;
+
+const SESSION_BUSY_MESSAGE: &str = "Too many pending requests in this session";
+/// JSON-RPC error code for requests refused because their session is saturated.
+const SESSION_BUSY_CODE: ErrorCode = ErrorCode(-32005);
+/// Delay suggested to clients, in seconds, before retrying a refused request.
+const SESSION_BUSY_RETRY_AFTER_SECS: u32 = 1;
+
+/// Pending requests per session, shared by every worker.
+#[derive(Clone, Default)]
+pub(super) struct PendingRequests {
+    pending: Arc<Mutex<HashMap<SessionId, usize>>>,
+}
+
+impl PendingRequests {
+    /// Counts a new request of `session_id`, unless it already has `max` pending.
+    pub(super) fn acquire(
+        &self,
+        session_id: &SessionId,
+        max: Option<usize>,
+    ) -> Option<PendingRequest> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let count = pending.entry(session_id.clone()).or_default();
+        if max.is_some_and(|max| *count >= max) {
+            return None;
+        }
+        *count += 1;
+        Some(PendingRequest(self.clone(), session_id.clone()))
+    }
+
+    /// Returns the number of pending requests of all sessions, and the largest
+    /// number pending in a single session.
+    pub(super) fn depths(&self) -> (usize, usize) {
+        let pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let total = pending.values().sum();
+        let deepest = pending.values().copied().max().unwrap_or_default();
+        (total, deepest)
+    }
+}
+
+/// A pending request, counted until dropped.
+pub(super) struct PendingRequest(PendingRequests, SessionId);
+
+impl PendingRequest {
+    /// Keeps the request counted until `stream` ends or is dropped.
+    pub(super) fn hold<St>(self, stream: St) -> impl Stream<Item = St::Item> + Send + 'static
+    where
+        St: Stream + Send + 'static,
+    {
+        stream.map(move |item| {
+            let _pending = &self;
+            item
+        })
+    }
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        let mut pending = self.0.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = pending.get_mut(&self.1) {
+            *count -= 1;
+            if *count == 0 {
+                pending.remove(&self.1);
+            }
+        }
+    }
+}
+
+/// Builds the `429 Too Many Requests` response refusing the request `id`.
+pub(super) fn session_busy_response(id: RequestId) -> HttpResponse {
+    let error = ServerJsonRpcMessage::error(
+        ErrorData::new(SESSION_BUSY_CODE, SESSION_BUSY_MESSAGE, None),
+        Some(id),
+    );
+    HttpResponse::TooManyRequests()
+        .content_type(JSON_MIME_TYPE)
+        .insert_header((
+            RETRY_AFTER,
+            HeaderValue::from(SESSION_BUSY_RETRY_AFTER_SECS),
+        ))
+        .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()))
+}
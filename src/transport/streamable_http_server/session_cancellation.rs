@@ -0,0 +1,68 @@
+//! Cancellation of the work in progress of closed sessions.
+//!
+//! Every session is served by a task running the MCP service, whose handlers run
+//! in tasks of their own. Closing the session in the session manager only ends
+//! the session transport: rmcp then waits for in-flight handlers to answer
+//! before the service stops, so a handler stuck on a slow upstream would keep
+//! running long after its client is gone. Instead, each session owns a
+//! cancellation token, passed to rmcp as the service's cancellation token. rmcp
+//! derives the token of every request context from it, so cancelling it
+//! cancels all handlers of the session at once.
+//!
+//! The token is cancelled when the transport closes the session (`DELETE`,
+//! initialize timeout, liveness, shutdown) and when the session transport ends
+//! on its own, e.g. because the session manager expired an idle session.
+
+use std::borrow::Cow;
+
+use rmcp::{
+    RoleServer,
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::Transport,
+};
+use tokio_util::sync::CancellationToken;
+
+/// Session transport that cancels its session's token once it ends.
+pub(super) struct CancelOnClose<T> {
+    inner: T,
+    cancellation: CancellationToken,
+}
+
+impl<T> CancelOnClose<T> {
+    pub(super) fn new(inner: T, cancellation: CancellationToken) -> Self {
+        Self {
+            inner,
+            cancellation,
+        }
+    }
+}
+
+impl<T> Transport<RoleServer> for CancelOnClose<T>
+where
+    T: Transport<RoleServer>,
+{
+    type Error = T::Error;
+
+    fn name() -> Cow<'static, str> {
+        T::name()
+    }
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleServer>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.inner.send(item)
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<RoleServer>> {
+        let message = self.inner.receive().await;
+        if message.is_none() {
+            self.cancellation.cancel();
+        }
+        message
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
@@ -0,0 +1,72 @@
+//! Session ids carried in a cookie.
+//!
+//! Browser clients whose `fetch` or `EventSource` wrappers cannot set custom
+//! headers get the session id in an `HttpOnly` cookie when the session is
+//! created, and the browser sends it back on every request. `Mcp-Session-Id`
+//! still takes precedence when a request carries both.
+
+use actix_web::{HttpRequest, HttpResponseBuilder, http::header};
+
+/// The cookie carrying the session id.
+///
+/// The cookie is scoped to the path of the endpoint the session was created on,
+/// which the other routes of the service are nested under.
+#[derive(Debug)]
+pub(super) struct SessionCookie {
+    name: String,
+}
+
+impl SessionCookie {
+    pub(super) fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    /// Returns the session id of the cookie sent with `req`, if any.
+    pub(super) fn read<'a>(&self, req: &'a HttpRequest) -> Option<&'a str> {
+        req.headers()
+            .get_all(header::COOKIE)
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|cookies| cookies.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .find(|(name, _)| *name == self.name)
+            .map(|(_, value)| value.trim_matches('"'))
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Sets the cookie to `session_id` on `response`.
+    ///
+    /// The cookie is `Secure` when the request came over HTTPS, as seen through
+    /// the connection info.
+    pub(super) fn set(
+        &self,
+        req: &HttpRequest,
+        response: &mut HttpResponseBuilder,
+        session_id: &str,
+    ) {
+        let secure = if req.connection_info().scheme() == "https" {
+            "; Secure"
+        } else {
+            ""
+        };
+        response.append_header((
+            header::SET_COOKIE,
+            format!(
+                "{}={session_id}; Path={}; HttpOnly; SameSite=Strict{secure}",
+                self.name,
+                req.path()
+            ),
+        ));
+    }
+
+    /// Removes the cookie from the client, once its session is closed.
+    pub(super) fn clear(&self, req: &HttpRequest, response: &mut HttpResponseBuilder) {
+        response.append_header((
+            header::SET_COOKIE,
+            format!(
+                "{}=; Path={}; HttpOnly; SameSite=Strict; Max-Age=0",
+                self.name,
+                req.path()
+            ),
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+//! Structured session lifecycle logging.
+//!
+//! Every session start, resumption and end is logged at `info` level under the
+//! `rmcp_actix_web::session` target, with an `event` field naming what happened
+//! and stable field names, so log pipelines can build session analytics
+//! without parsing messages:
+//!
+//! | `event`           | Fields                                         |
+//! |-------------------|------------------------------------------------|
+//! | `session.created` | `session_id`, `tenant` (if any)                |
+//! | `session.resumed` | `session_id`, `last_event_id`                  |
+//! | `session.closed`  | `session_id`, `reason`, `duration_ms`          |
+//!
+//! `reason` is one of the [`SessionCloseReason::as_str`] values.
+
+use std::time::Duration;
+
+use rmcp::transport::common::server_side_http::SessionId;
+
+use super::{SessionCloseReason, TenantId};
+
+/// Logs the creation of `session_id`.
+pub(super) fn created(session_id: &SessionId, tenant: Option<&TenantId>) {
+    match tenant {
+        Some(tenant) => tracing::info!(
+            target: "rmcp_actix_web::session",
+            event = "session.created",
+            %session_id,
+            tenant = %tenant.0,
+            "Session created"
+        ),
+        None => tracing::info!(
+            target: "rmcp_actix_web::session",
+            event = "session.created",
+            %session_id,
+            "Session created"
+        ),
+    }
+}
+
+/// Logs a client picking `session_id` up again after `last_event_id`.
+pub(super) fn resumed(session_id: &SessionId, last_event_id: &str) {
+    tracing::info!(
+        target: "rmcp_actix_web::session",
+        event = "session.resumed",
+        %session_id,
+        last_event_id,
+        "Session resumed"
+    );
+}
+
+/// Logs the end of `session_id` after `duration`.
+pub(super) fn closed(session_id: &SessionId, reason: SessionCloseReason, duration: Duration) {
+    tracing::info!(
+        target: "rmcp_actix_web::session",
+        event = "session.closed",
+        %session_id,
+        reason = reason.as_str(),
+        duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        "Session closed"
+    );
+}
@@ -0,0 +1,89 @@
+//! Notifications sent to sessions from outside MCP handlers.
+//!
+//! Handlers reach their own client through the `Peer` of their request
+//! context. Other parts of the application, such as maintenance tasks, have no
+//! such handle: they send through the service instead, which keeps the peer of
+//! every running session. Notifications go out on each session's standalone
+//! stream (or legacy SSE stream), like those sent by handlers.
+//!
+//! Sessions are only reachable once their `initialize` handshake completed.
+
+use actix_web::{HttpRequest, HttpResponse, Resource, http::StatusCode, web};
+use futures::future::join_all;
+use rmcp::{model::ServerNotification, transport::streamable_http_server::session::SessionId};
+
+use super::sessions::SessionRegistry;
+use crate::transport::{ApiKeyAuth, Problem};
+
+/// Sends `notification` to every running session, returning how many
+/// accepted it.
+pub(super) async fn broadcast(
+    sessions: &SessionRegistry,
+    notification: ServerNotification,
+) -> usize {
+    let peers = sessions.peers().await;
+    let sent = join_all(peers.into_iter().map(|(session_id, peer)| {
+        let notification = notification.clone();
+        async move {
+            peer.send_notification(notification)
+                .await
+                .inspect_err(|e| tracing::debug!(%session_id, "Failed to send notification: {e}"))
+                .is_ok()
+        }
+    }))
+    .await;
+    sent.into_iter().filter(|sent| *sent).count()
+}
+
+/// Sends `notification` to the session `session_id`, returning whether it
+/// accepted it.
+pub(super) async fn send_to(
+    sessions: &SessionRegistry,
+    session_id: &SessionId,
+    notification: ServerNotification,
+) -> bool {
+    let Some(peer) = sessions.peer(session_id).await else {
+        tracing::debug!(%session_id, "Cannot send notification: session not running");
+        return false;
+    };
+    peer.send_notification(notification)
+        .await
+        .inspect_err(|e| tracing::debug!(%session_id, "Failed to send notification: {e}"))
+        .is_ok()
+}
+
+/// Creates the broadcast resource at `path`, guarded by `auth`.
+pub(super) fn broadcast_resource(
+    path: &str,
+    sessions: SessionRegistry,
+    auth: ApiKeyAuth,
+) -> Resource {
+    web::resource(path)
+        .app_data(web::Data::new(sessions))
+        .app_data(web::Data::new(auth))
+        .route(web::post().to(handle_broadcast))
+}
+
+async fn handle_broadcast(
+    req: HttpRequest,
+    body: web::Bytes,
+    sessions: web::Data<SessionRegistry>,
+    auth: web::Data<ApiKeyAuth>,
+) -> HttpResponse {
+    if let Some(response) = auth.check(&req) {
+        return response;
+    }
+    let notification = match serde_json::from_slice::<ServerNotification>(&body) {
+        Ok(notification) => notification,
+        Err(e) => {
+            return Problem::new(
+                StatusCode::BAD_REQUEST,
+                "invalid_notification",
+                e.to_string(),
+            )
+            .into_response();
+        }
+    };
+    let sessions = broadcast(&sessions, notification).await;
+    HttpResponse::Ok().json(serde_json::json!({ "sessions": sessions }))
+}
@@ -0,0 +1,81 @@
+//! Rate limiting of session creation.
+//!
+//! Creating a session spawns a service task and allocates its channels, which
+//! is far more expensive than serving a request on an existing session. A
+//! [`SessionCreationLimit`] caps how many sessions each client address may
+//! create per time window, independently of any limit on ordinary requests.
+//! Requests over the limit get `429 Too Many Requests` with a `Retry-After`
+//! header telling when the window resets.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Number of tracked addresses above which expired windows are pruned.
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Maximum number of sessions a client address may create per window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionCreationLimit {
+    max: u32,
+    window: Duration,
+}
+
+impl SessionCreationLimit {
+    /// Allows `max` new sessions per `window` and client address.
+    pub fn new(max: u32, window: Duration) -> Self {
+        Self { max, window }
+    }
+
+    /// Allows `max` new sessions per minute and client address.
+    pub fn per_minute(max: u32) -> Self {
+        Self::new(max, Duration::from_secs(60))
+    }
+}
+
+/// Session creations counted in the current window of a client address.
+struct Window {
+    started_at: Instant,
+    count: u32,
+}
+
+/// Session creation counters, shared by every worker.
+#[derive(Clone, Default)]
+pub(super) struct SessionCreations {
+    windows: Arc<Mutex<HashMap<Option<IpAddr>, Window>>>,
+}
+
+impl SessionCreations {
+    /// Counts a session creation by `client`, unless it would exceed `limit`.
+    ///
+    /// Returns the time until the client's window resets when it is over the limit.
+    pub(super) fn admit(
+        &self,
+        client: Option<IpAddr>,
+        limit: &SessionCreationLimit,
+    ) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() >= PRUNE_THRESHOLD {
+            windows.retain(|_, window| now.duration_since(window.started_at) < limit.window);
+        }
+        let window = windows.entry(client).or_insert(Window {
+            started_at: now,
+            count: 0,
+        });
+        if now.duration_since(window.started_at) >= limit.window {
+            *window = Window {
+                started_at: now,
+                count: 0,
+            };
+        }
+        if window.count >= limit.max {
+            return Err(limit.window - now.duration_since(window.started_at));
+        }
+        window.count += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,447 @@
+//! Transport-side bookkeeping for live sessions.
+//!
+//! The session manager owns the MCP state of a session; this registry tracks
+//! what the HTTP transport itself needs to know about each session (when it
+//! was created, whether its close has already been reported) so lifecycle
+//! hooks fire exactly once regardless of which code path closes the session.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock},
+    time::Instant,
+};
+
+use rmcp::{
+    Peer, RoleServer,
+    model::{
+        ClientCapabilities, Extensions, InitializeResult, ProtocolVersion, ServerCapabilities,
+    },
+    transport::streamable_http_server::session::SessionId,
+};
+
+use super::{
+    OnSessionClosedHook,
+    liveness::{Liveness, PingOutcome, SessionLiveness},
+    session_log,
+    stream_end::StreamEndReason,
+    transport_events::{TransportEvent, TransportEvents},
+};
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// Why a session was closed.
+///
+/// Passed to the [`on_session_closed`](super::StreamableHttpServiceBuilder::on_session_closed)
+/// hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SessionCloseReason {
+    /// The client terminated the session with an HTTP `DELETE`.
+    ClientDelete,
+    /// The legacy SSE event stream backing the session ended, for the given
+    /// reason.
+    StreamDisconnected(StreamEndReason),
+    /// The session transport closed, e.g. because the session manager expired
+    /// an idle session.
+    TransportClosed,
+    /// The MCP service stopped on its own (cancelled or panicked).
+    ServiceStopped,
+    /// The MCP service failed to complete the initialize handshake.
+    InitializationFailed,
+    /// The service was shut down with
+    /// [`StreamableHttpService::shutdown`](super::StreamableHttpService::shutdown).
+    ServerShutdown,
+    /// The client sent nothing after `initialize` within the configured
+    /// [`initialize_timeout`](super::StreamableHttpServiceBuilder::initialize_timeout).
+    InitializeTimeout,
+    /// The client stopped answering the pings enabled by
+    /// [`ping_interval`](super::StreamableHttpServiceBuilder::ping_interval).
+    Unresponsive,
+    /// The client sent `initialize` again within the session, and the
+    /// [`reinitialize_policy`](super::StreamableHttpServiceBuilder::reinitialize_policy)
+    /// replaced it with a new session.
+    Reinitialized,
+}
+
+impl SessionCloseReason {
+    /// Returns the stable, snake_case name of the reason, as logged in the
+    /// `reason` field of `session.closed` events.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ClientDelete => "client_delete",
+            Self::StreamDisconnected(_) => "stream_disconnected",
+            Self::TransportClosed => "transport_closed",
+            Self::ServiceStopped => "service_stopped",
+            Self::InitializationFailed => "initialization_failed",
+            Self::ServerShutdown => "server_shutdown",
+            Self::InitializeTimeout => "initialize_timeout",
+            Self::Unresponsive => "unresponsive",
+            Self::Reinitialized => "reinitialized",
+        }
+    }
+}
+
+/// Capabilities the client and the server announced during `initialize`.
+///
+/// Returned by [`StreamableHttpService::session_capabilities`](super::StreamableHttpService::session_capabilities)
+/// and inserted into the extensions of every later request of the session, where
+/// the `on_request` hook and MCP handlers can read it.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct SessionCapabilities {
+    /// Capabilities declared in the client's `initialize` request.
+    pub client: ClientCapabilities,
+    /// Capabilities declared in the server's `initialize` result.
+    pub server: ServerCapabilities,
+}
+
+/// Identity of the authenticated caller of a request.
+///
+/// Inserted into the request extensions by the authentication middleware, e.g.
+/// with the subject of the validated access token. With
+/// [`bind_session_identity`](super::StreamableHttpServiceBuilder::bind_session_identity)
+/// enabled, the identity that creates a session is the only one allowed to use
+/// it afterwards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuthenticatedIdentity(pub String);
+
+/// Tenant a request belongs to.
+///
+/// Inserted into the request extensions by the middleware resolving tenants, e.g.
+/// from a token claim or the host name. With a
+/// [`tenant_service_factory`](super::StreamableHttpServiceBuilder::tenant_service_factory)
+/// configured, the handler of a session is constructed for the tenant of its
+/// `initialize` request, and the session is only usable by that tenant afterwards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TenantId(pub String);
+
+/// Per-session state kept by the transport.
+#[derive(Debug)]
+pub(super) struct SessionEntry {
+    /// When the session was created.
+    pub(super) created_at: Instant,
+    /// Extensions captured at initialize time and injected into every later request.
+    pub(super) extensions: Extensions,
+    /// Whether the client sent anything after its `initialize` request.
+    initialized: bool,
+    /// Pings sent on the standalone stream and their answers.
+    liveness: Liveness,
+    /// Protocol version negotiated by `initialize`, shared with the session's
+    /// outbound streams.
+    protocol_version: Arc<OnceLock<ProtocolVersion>>,
+    /// Capabilities negotiated by `initialize`.
+    capabilities: Option<SessionCapabilities>,
+    /// Result the service answered `initialize` with.
+    initialize_result: Option<InitializeResult>,
+    /// Identity that created the session, when sessions are bound to identities.
+    identity: Option<AuthenticatedIdentity>,
+    /// Tenant of the request that created the session.
+    tenant: Option<TenantId>,
+    /// Cancels the session's service and its in-flight handlers.
+    cancellation: CancellationToken,
+    /// Handle for sending to the client, once the session's service is running.
+    peer: Option<Peer<RoleServer>>,
+}
+
+impl SessionEntry {
+    fn new(identity: Option<AuthenticatedIdentity>, tenant: Option<TenantId>) -> Self {
+        Self {
+            created_at: Instant::now(),
+            extensions: Extensions::new(),
+            initialized: false,
+            liveness: Liveness::default(),
+            protocol_version: Arc::default(),
+            capabilities: None,
+            initialize_result: None,
+            identity,
+            tenant,
+            cancellation: CancellationToken::new(),
+            peer: None,
+        }
+    }
+}
+
+/// Live sessions created through this service, keyed by session id.
+#[derive(Clone, Default)]
+pub(super) struct SessionRegistry {
+    entries: Arc<RwLock<HashMap<SessionId, SessionEntry>>>,
+}
+
+impl SessionRegistry {
+    /// Registers a newly created session, bound to `identity` and `tenant` if given.
+    ///
+    /// Returns the token cancelled when the session is closed.
+    pub(super) async fn insert(
+        &self,
+        session_id: SessionId,
+        identity: Option<AuthenticatedIdentity>,
+        tenant: Option<TenantId>,
+    ) -> CancellationToken {
+        let entry = SessionEntry::new(identity, tenant);
+        let cancellation = entry.cancellation.clone();
+        self.entries.write().await.insert(session_id, entry);
+        cancellation
+    }
+
+    /// Returns a token cancelled when the session is closed, if the session is
+    /// registered.
+    pub(super) async fn child_token(&self, session_id: &SessionId) -> Option<CancellationToken> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .map(|entry| entry.cancellation.child_token())
+    }
+
+    /// Returns `false` if the session was created by a different identity than
+    /// `identity`.
+    ///
+    /// Sessions unknown to the registry are left to the session manager.
+    pub(super) async fn identity_matches(
+        &self,
+        session_id: &SessionId,
+        identity: Option<&AuthenticatedIdentity>,
+    ) -> bool {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .is_none_or(|entry| entry.identity.as_ref() == identity)
+    }
+
+    /// Returns `false` if the session was created for a different tenant than
+    /// `tenant`.
+    ///
+    /// Sessions unknown to the registry are left to the session manager.
+    pub(super) async fn tenant_matches(
+        &self,
+        session_id: &SessionId,
+        tenant: Option<&TenantId>,
+    ) -> bool {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .is_none_or(|entry| entry.tenant.as_ref() == tenant)
+    }
+
+    /// Returns the number of registered sessions of `tenant`.
+    pub(super) async fn tenant_sessions(&self, tenant: &TenantId) -> usize {
+        self.entries
+            .read()
+            .await
+            .values()
+            .filter(|entry| entry.tenant.as_ref() == Some(tenant))
+            .count()
+    }
+
+    /// Stores the sticky extensions of a session.
+    pub(super) async fn set_extensions(&self, session_id: &SessionId, extensions: Extensions) {
+        if let Some(entry) = self.entries.write().await.get_mut(session_id) {
+            entry.extensions = extensions;
+        }
+    }
+
+    /// Returns a copy of the sticky extensions of a session.
+    pub(super) async fn extensions(&self, session_id: &SessionId) -> Option<Extensions> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .map(|entry| entry.extensions.clone())
+    }
+
+    /// Records that the client followed up on its `initialize` request.
+    pub(super) async fn mark_initialized(&self, session_id: &SessionId) {
+        // Checked under the read lock first, so established sessions never contend
+        // for the write lock
+        if self.is_half_open(session_id).await
+            && let Some(entry) = self.entries.write().await.get_mut(session_id)
+        {
+            entry.initialized = true;
+        }
+    }
+
+    /// Returns `true` if the session is still open but its client never followed up
+    /// on `initialize`.
+    pub(super) async fn is_half_open(&self, session_id: &SessionId) -> bool {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .is_some_and(|entry| !entry.initialized)
+    }
+
+    /// Records the protocol version negotiated by a session.
+    pub(super) async fn set_protocol_version(
+        &self,
+        session_id: &SessionId,
+        version: ProtocolVersion,
+    ) {
+        if let Some(entry) = self.entries.read().await.get(session_id) {
+            let _ = entry.protocol_version.set(version);
+        }
+    }
+
+    /// Returns the protocol version negotiated by a session.
+    pub(super) async fn protocol_version(&self, session_id: &SessionId) -> Option<ProtocolVersion> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .and_then(|entry| entry.protocol_version.get().cloned())
+    }
+
+    /// Returns the cell the protocol version of a session is recorded in, so
+    /// streams opened before `initialize` see it once negotiated.
+    pub(super) async fn protocol_version_cell(
+        &self,
+        session_id: &SessionId,
+    ) -> Option<Arc<OnceLock<ProtocolVersion>>> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .map(|entry| entry.protocol_version.clone())
+    }
+
+    /// Records the capabilities negotiated by a session.
+    pub(super) async fn set_capabilities(
+        &self,
+        session_id: &SessionId,
+        capabilities: SessionCapabilities,
+    ) {
+        if let Some(entry) = self.entries.write().await.get_mut(session_id) {
+            entry.capabilities = Some(capabilities);
+        }
+    }
+
+    /// Records the result a session answered `initialize` with.
+    pub(super) async fn set_initialize_result(
+        &self,
+        session_id: &SessionId,
+        result: InitializeResult,
+    ) {
+        if let Some(entry) = self.entries.write().await.get_mut(session_id) {
+            entry.initialize_result = Some(result);
+        }
+    }
+
+    /// Returns the result a session answered `initialize` with.
+    pub(super) async fn initialize_result(
+        &self,
+        session_id: &SessionId,
+    ) -> Option<InitializeResult> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .and_then(|entry| entry.initialize_result.clone())
+    }
+
+    /// Returns the capabilities negotiated by a session.
+    pub(super) async fn capabilities(&self, session_id: &SessionId) -> Option<SessionCapabilities> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .and_then(|entry| entry.capabilities.clone())
+    }
+
+    /// Prepares the next liveness ping of a session.
+    pub(super) async fn next_ping(&self, session_id: &SessionId, max_missed: u32) -> PingOutcome {
+        match self.entries.write().await.get_mut(session_id) {
+            Some(entry) => entry.liveness.next_ping(max_missed),
+            None => PingOutcome::Closed,
+        }
+    }
+
+    /// Records the client's answer to the liveness ping `ping_id`.
+    pub(super) async fn ping_answered(&self, session_id: &SessionId, ping_id: &str) {
+        let round_trip = self
+            .entries
+            .write()
+            .await
+            .get_mut(session_id)
+            .and_then(|entry| entry.liveness.answered(ping_id));
+        if let Some(round_trip) = round_trip {
+            tracing::debug!(%session_id, ?round_trip, "Ping answered");
+        }
+    }
+
+    /// Returns the liveness measurements of a session.
+    pub(super) async fn liveness(&self, session_id: &SessionId) -> Option<SessionLiveness> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .map(|entry| entry.liveness.stats())
+    }
+
+    /// Records the handle for sending to the client of a session.
+    pub(super) async fn set_peer(&self, session_id: &SessionId, peer: Peer<RoleServer>) {
+        if let Some(entry) = self.entries.write().await.get_mut(session_id) {
+            entry.peer = Some(peer);
+        }
+    }
+
+    /// Returns the handle for sending to the client of a session, if it is running.
+    pub(super) async fn peer(&self, session_id: &SessionId) -> Option<Peer<RoleServer>> {
+        self.entries
+            .read()
+            .await
+            .get(session_id)
+            .and_then(|entry| entry.peer.clone())
+    }
+
+    /// Returns the handles for sending to the clients of all running sessions.
+    pub(super) async fn peers(&self) -> Vec<(SessionId, Peer<RoleServer>)> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .filter_map(|(id, entry)| Some((id.clone(), entry.peer.clone()?)))
+            .collect()
+    }
+
+    /// Returns the number of registered sessions, and how many of them have not
+    /// completed initialization.
+    pub(super) async fn counts(&self) -> (usize, usize) {
+        let entries = self.entries.read().await;
+        let half_open = entries.values().filter(|entry| !entry.initialized).count();
+        (entries.len(), half_open)
+    }
+
+    /// Returns the ids of all registered sessions.
+    pub(super) async fn ids(&self) -> Vec<SessionId> {
+        self.entries.read().await.keys().cloned().collect()
+    }
+
+    /// Records that a session has been closed, cancels its in-flight handlers and
+    /// runs the on_session_closed hook.
+    ///
+    /// Safe to call from every code path that may close a session: only the first
+    /// call for a given session has any effect.
+    pub(super) async fn close(
+        &self,
+        session_id: &SessionId,
+        reason: SessionCloseReason,
+        hook: Option<&OnSessionClosedHook>,
+        events: &TransportEvents,
+    ) {
+        let Some(entry) = self.entries.write().await.remove(session_id) else {
+            return;
+        };
+        entry.cancellation.cancel();
+        let duration = entry.created_at.elapsed();
+        session_log::closed(session_id, reason, duration);
+        if let Some(hook) = hook {
+            hook(session_id, reason);
+        }
+        events.emit(|| TransportEvent::SessionClosed {
+            session_id: session_id.clone(),
+            reason,
+            duration,
+        });
+    }
+}
@@ -0,0 +1,192 @@
+//! Graceful shutdown of the service.
+//!
+//! A shutdown goes through two phases. While draining, the transport refuses
+//! new sessions and new JSON-RPC requests with `503 Service Unavailable`, but
+//! still accepts notifications and client responses, which in-flight requests
+//! may be waiting for. Once no request is in flight (or the grace period is
+//! over), the service stops: legacy event streams receive a final `shutdown`
+//! event and every remaining session is closed.
+//!
+//! Stopping also cancels the [`RequestCancellation`](crate::transport::RequestCancellation)
+//! of requests still running in stateless mode; those of sessions are cancelled
+//! when their session is closed.
+//!
+//! The optional readiness probe reports the same state, so load balancers stop
+//! routing new clients to an instance as soon as it starts draining.
+
+use std::{sync::Arc, time::Duration};
+
+use actix_web::{
+    HttpResponse, Resource,
+    http::{
+        StatusCode,
+        header::{HeaderValue, RETRY_AFTER},
+    },
+    web::{self, Data},
+};
+use futures::{Stream, StreamExt};
+use rmcp::model::{ClientJsonRpcMessage, ErrorCode, ErrorData, RequestId, ServerJsonRpcMessage};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use super::{JSON_MIME_TYPE, transport_events::RequestInFlight};
+
+const SHUTTING_DOWN_MESSAGE: &str = "Server is shutting down";
+/// JSON-RPC error code for requests received after shutdown started.
+const SHUTTING_DOWN_CODE: ErrorCode = ErrorCode(-32003);
+/// Delay suggested to clients, in seconds, before reconnecting after shutdown.
+const SHUTDOWN_RETRY_AFTER_SECS: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Running,
+    Draining,
+    Stopped,
+}
+
+struct Inner {
+    phase: watch::Sender<Phase>,
+    in_flight: watch::Sender<usize>,
+    cancellation: CancellationToken,
+}
+
+/// Service-wide shutdown state.
+///
+/// Shared by every clone of the service, so all workers observe it.
+#[derive(Clone)]
+pub(super) struct ShutdownSignal(Arc<Inner>);
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self(Arc::new(Inner {
+            phase: watch::Sender::new(Phase::Running),
+            in_flight: watch::Sender::new(0),
+            cancellation: CancellationToken::new(),
+        }))
+    }
+}
+
+impl ShutdownSignal {
+    /// Returns `true` once shutdown has started.
+    pub(super) fn is_draining(&self) -> bool {
+        *self.0.phase.borrow() != Phase::Running
+    }
+
+    /// Resolves once the service has stopped.
+    pub(super) async fn stopped(&self) {
+        let _ = self
+            .0
+            .phase
+            .subscribe()
+            .wait_for(|phase| *phase == Phase::Stopped)
+            .await;
+    }
+
+    /// Stops accepting new work and waits up to `grace` for in-flight requests.
+    ///
+    /// Returns `false` if requests were still in flight when the grace period
+    /// ended.
+    pub(super) async fn drain(&self, grace: Duration) -> bool {
+        self.0.phase.send_if_modified(|phase| {
+            let running = *phase == Phase::Running;
+            if running {
+                *phase = Phase::Draining;
+            }
+            running
+        });
+        let mut in_flight = self.0.in_flight.subscribe();
+        tokio::time::timeout(grace, in_flight.wait_for(|count| *count == 0))
+            .await
+            .is_ok()
+    }
+
+    pub(super) fn stop(&self) {
+        self.0.phase.send_replace(Phase::Stopped);
+        self.0.cancellation.cancel();
+    }
+
+    /// Returns a token cancelled once the service has stopped.
+    pub(super) fn child_token(&self) -> CancellationToken {
+        self.0.cancellation.child_token()
+    }
+
+    /// Returns the number of requests in flight.
+    pub(super) fn in_flight_count(&self) -> usize {
+        *self.0.in_flight.borrow()
+    }
+
+    /// Counts a request as in flight until the returned guard is dropped.
+    pub(super) fn in_flight(&self) -> InFlight {
+        self.0.in_flight.send_modify(|count| *count += 1);
+        InFlight(self.clone(), None)
+    }
+
+    /// Returns the response refusing `message` if it is a request received
+    /// during shutdown.
+    pub(super) fn reject(&self, message: &ClientJsonRpcMessage) -> Option<HttpResponse> {
+        match message {
+            ClientJsonRpcMessage::Request(request) if self.is_draining() => {
+                tracing::debug!("Request refused during shutdown");
+                Some(shutting_down_response(Some(request.id.clone())))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Marks a request as in flight for as long as it is alive.
+pub(super) struct InFlight(ShutdownSignal, Option<RequestInFlight>);
+
+impl InFlight {
+    /// Announces the completion of `request` along with the end of this one.
+    pub(super) fn with_request(mut self, request: Option<RequestInFlight>) -> Self {
+        self.1 = request;
+        self
+    }
+
+    /// Keeps the request in flight until `stream` ends or is dropped.
+    pub(super) fn hold<St>(self, stream: St) -> impl Stream<Item = St::Item> + Send + 'static
+    where
+        St: Stream + Send + 'static,
+    {
+        stream.map(move |item| {
+            let _in_flight = &self;
+            item
+        })
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.0.in_flight.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Builds the `503 Service Unavailable` response sent during shutdown.
+pub(super) fn shutting_down_response(id: Option<RequestId>) -> HttpResponse {
+    let error = ServerJsonRpcMessage::error(
+        ErrorData::new(SHUTTING_DOWN_CODE, SHUTTING_DOWN_MESSAGE, None),
+        id,
+    );
+    HttpResponse::build(StatusCode::SERVICE_UNAVAILABLE)
+        .content_type(JSON_MIME_TYPE)
+        .insert_header((RETRY_AFTER, HeaderValue::from(SHUTDOWN_RETRY_AFTER_SECS)))
+        .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()))
+}
+
+/// Builds the readiness probe resource answering at `path`.
+pub(super) fn readiness_resource(path: &str, signal: ShutdownSignal) -> Resource {
+    web::resource(path)
+        .app_data(Data::new(signal))
+        .route(web::get().to(handle_readiness))
+}
+
+async fn handle_readiness(signal: Data<ShutdownSignal>) -> HttpResponse {
+    if signal.is_draining() {
+        HttpResponse::build(StatusCode::SERVICE_UNAVAILABLE)
+            .insert_header((RETRY_AFTER, HeaderValue::from(SHUTDOWN_RETRY_AFTER_SECS)))
+            .json(serde_json::json!({ "status": "draining" }))
+    } else {
+        HttpResponse::Ok().json(serde_json::json!({ "status": "ready" }))
+    }
+}
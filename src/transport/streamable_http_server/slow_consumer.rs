@@ -0,0 +1,292 @@
+//! Policies for SSE consumers that read slower than the service writes.
+//!
+//! Without a policy, a response stream is read straight from the session
+//! manager, so a client that does not keep up eventually fills the session
+//! manager's channel and stalls the service task producing the messages.
+//! The other policies decouple the two: a pump task drains the session
+//! manager's stream into a bounded buffer the response reads from, and the
+//! policy decides what happens when that buffer is full.
+//!
+//! Responses and server requests are never dropped: with
+//! [`SlowConsumerPolicy::DropOldestNotifications`], a full buffer of them
+//! grows past its capacity rather than losing one.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use futures::{Stream, StreamExt, stream::BoxStream};
+use rmcp::{
+    model::{ErrorCode, ErrorData, JsonRpcMessage, ServerJsonRpcMessage},
+    transport::streamable_http_server::session::ServerSseMessage,
+};
+use tokio::sync::Notify;
+
+/// Default capacity of the buffer between the session manager and a slow consumer.
+pub(super) const DEFAULT_SLOW_CONSUMER_BUFFER: usize = 64;
+/// JSON-RPC error code of the event ending a stream whose consumer fell behind.
+const SLOW_CONSUMER_CODE: ErrorCode = ErrorCode(-32004);
+const SLOW_CONSUMER_MESSAGE: &str = "Stream closed: client is not reading fast enough";
+
+/// What to do when a client reads an SSE stream slower than the service writes it.
+///
+/// Applies to the Streamable HTTP streams: responses to `POST` requests and the
+/// standalone `GET` stream. Legacy SSE connections have their own
+/// [`SseOverflowPolicy`](super::SseOverflowPolicy).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SlowConsumerPolicy {
+    /// Let the session manager's channel fill up and stall the service task until
+    /// the client catches up. Nothing is lost.
+    #[default]
+    Block,
+    /// Drop the oldest buffered notification to make room, and log a warning.
+    /// Responses and server requests are always delivered.
+    DropOldestNotifications,
+    /// End the stream with a terminal JSON-RPC error event (code `-32004`) and
+    /// discard what was buffered.
+    Disconnect,
+}
+
+/// Messages a slow consumer policy can be applied to.
+pub(super) trait StreamItem: Send + 'static {
+    /// Whether the message is a notification, which may be dropped.
+    fn is_notification(&self) -> bool;
+
+    /// The terminal event ending a stream under [`SlowConsumerPolicy::Disconnect`].
+    fn overflow_error() -> Self;
+}
+
+impl StreamItem for ServerJsonRpcMessage {
+    fn is_notification(&self) -> bool {
+        matches!(self, JsonRpcMessage::Notification(_))
+    }
+
+    fn overflow_error() -> Self {
+        ServerJsonRpcMessage::error(
+            ErrorData::new(SLOW_CONSUMER_CODE, SLOW_CONSUMER_MESSAGE, None),
+            None,
+        )
+    }
+}
+
+impl StreamItem for ServerSseMessage {
+    fn is_notification(&self) -> bool {
+        self.message
+            .as_deref()
+            .is_some_and(StreamItem::is_notification)
+    }
+
+    fn overflow_error() -> Self {
+        ServerSseMessage::from_message(ServerJsonRpcMessage::overflow_error())
+    }
+}
+
+struct Buffer<T> {
+    queue: VecDeque<T>,
+    ended: bool,
+    overflowed: bool,
+}
+
+/// Shared state of the pump task and the consumer.
+struct Shared<T> {
+    buffer: Mutex<Buffer<T>>,
+    changed: Notify,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Buffer<T>> {
+        self.buffer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Aborts the pump task once the consumer is gone.
+struct PumpGuard(tokio::task::JoinHandle<()>);
+
+impl Drop for PumpGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+enum Next<T> {
+    Item(T),
+    Overflowed,
+    Ended,
+    Wait,
+}
+
+/// Applies `policy` to `stream`, buffering up to `capacity` messages.
+pub(super) fn apply<St>(
+    stream: St,
+    policy: SlowConsumerPolicy,
+    capacity: usize,
+) -> BoxStream<'static, St::Item>
+where
+    St: Stream + Send + 'static,
+    St::Item: StreamItem,
+{
+    if policy == SlowConsumerPolicy::Block {
+        return stream.boxed();
+    }
+    let capacity = capacity.max(1);
+    let shared = Arc::new(Shared {
+        buffer: Mutex::new(Buffer {
+            queue: VecDeque::new(),
+            ended: false,
+            overflowed: false,
+        }),
+        changed: Notify::new(),
+    });
+    let pump = tokio::spawn(pump(stream, policy, capacity, shared.clone()));
+    async_stream::stream! {
+        let _pump = PumpGuard(pump);
+        loop {
+            let next = {
+                let mut buffer = shared.lock();
+                if buffer.overflowed {
+                    Next::Overflowed
+                } else if let Some(item) = buffer.queue.pop_front() {
+                    Next::Item(item)
+                } else if buffer.ended {
+                    Next::Ended
+                } else {
+                    Next::Wait
+                }
+            };
+            match next {
+                Next::Item(item) => yield item,
+                Next::Overflowed => {
+                    yield St::Item::overflow_error();
+                    break;
+                }
+                Next::Ended => break,
+                // The pump stores a permit if it notifies before we wait
+                Next::Wait => shared.changed.notified().await,
+            }
+        }
+    }
+    .boxed()
+}
+
+/// Moves the messages of `stream` into the shared buffer as soon as they are produced.
+async fn pump<St>(
+    stream: St,
+    policy: SlowConsumerPolicy,
+    capacity: usize,
+    shared: Arc<Shared<St::Item>>,
+) where
+    St: Stream + Send + 'static,
+    St::Item: StreamItem,
+{
+    let mut stream = Box::pin(stream);
+    while let Some(item) = stream.next().await {
+        {
+            let mut buffer = shared.lock();
+            if buffer.queue.len() >= capacity {
+                if policy == SlowConsumerPolicy::Disconnect {
+                    tracing::warn!(capacity, "SSE consumer too slow, closing the stream");
+                    buffer.queue.clear();
+                    buffer.overflowed = true;
+                    drop(buffer);
+                    shared.changed.notify_one();
+                    return;
+                }
+                match buffer.queue.iter().position(StreamItem::is_notification) {
+                    Some(oldest) => {
+                        buffer.queue.remove(oldest);
+                        tracing::warn!(capacity, "SSE consumer too slow, dropping a notification");
+                    }
+                    None if item.is_notification() => {
+                        tracing::warn!(capacity, "SSE consumer too slow, dropping a notification");
+                        continue;
+                    }
+                    None => {}
+                }
+            }
+            buffer.queue.push_back(item);
+        }
+        shared.changed.notify_one();
+    }
+    shared.lock().ended = true;
+    shared.changed.notify_one();
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{StreamExt, stream};
+    use rmcp::model::{
+        EmptyResult, JsonRpcResponse, JsonRpcVersion2_0, LoggingLevel,
+        LoggingMessageNotificationParam, RequestId, ServerNotification, ServerResult,
+    };
+
+    use super::*;
+
+    fn response(id: i64) -> ServerJsonRpcMessage {
+        ServerJsonRpcMessage::Response(JsonRpcResponse {
+            jsonrpc: JsonRpcVersion2_0,
+            id: RequestId::Number(id),
+            result: ServerResult::EmptyResult(EmptyResult {}),
+        })
+    }
+
+    fn notification(data: i64) -> ServerJsonRpcMessage {
+        ServerJsonRpcMessage::notification(ServerNotification::LoggingMessageNotification(
+            rmcp::model::LoggingMessageNotification::new(LoggingMessageNotificationParam {
+                level: LoggingLevel::Info,
+                logger: None,
+                data: data.into(),
+            }),
+        ))
+    }
+
+    fn notification_data(message: &ServerJsonRpcMessage) -> Option<i64> {
+        let value = serde_json::to_value(message).ok()?;
+        value["params"]["data"].as_i64()
+    }
+
+    /// Lets the pump drain `messages` before anything is consumed.
+    async fn pumped(
+        messages: Vec<ServerJsonRpcMessage>,
+        policy: SlowConsumerPolicy,
+        capacity: usize,
+    ) -> Vec<ServerJsonRpcMessage> {
+        let stream = apply(stream::iter(messages), policy, capacity);
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        stream.collect().await
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_keeps_latest_notifications() {
+        let messages = (1..=5).map(notification).collect();
+        let delivered = pumped(messages, SlowConsumerPolicy::DropOldestNotifications, 2).await;
+        let data: Vec<_> = delivered.iter().filter_map(notification_data).collect();
+        assert_eq!(data, vec![4, 5]);
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_never_drops_responses() {
+        let messages = vec![response(1), response(2), notification(1), response(3)];
+        let delivered = pumped(messages, SlowConsumerPolicy::DropOldestNotifications, 2).await;
+        assert_eq!(delivered.len(), 3);
+        assert!(delivered.iter().all(|m| !m.is_notification()));
+    }
+
+    #[tokio::test]
+    async fn disconnect_ends_with_error_event() {
+        let messages = (1..=5).map(notification).collect();
+        let delivered = pumped(messages, SlowConsumerPolicy::Disconnect, 2).await;
+        assert_eq!(delivered.len(), 1);
+        let value = serde_json::to_value(&delivered[0]).unwrap();
+        assert_eq!(value["error"]["code"], -32004);
+    }
+
+    #[tokio::test]
+    async fn stream_within_capacity_is_untouched() {
+        let messages = (1..=3).map(notification).collect();
+        let delivered = pumped(messages, SlowConsumerPolicy::Disconnect, 8).await;
+        let data: Vec<_> = delivered.iter().filter_map(notification_data).collect();
+        assert_eq!(data, vec![1, 2, 3]);
+    }
+}
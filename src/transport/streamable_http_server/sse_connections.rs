@@ -0,0 +1,76 @@
+//! Per-client cap on long-lived SSE streams.
+//!
+//! Every standalone `GET` stream and legacy SSE connection holds an HTTP
+//! connection, and with it a worker connection slot, for as long as it is
+//! open. With a cap configured, each client address may only keep that many
+//! such streams open at once; further ones are refused with `429 Too Many
+//! Requests` until one closes. The short-lived SSE responses to `POST`
+//! requests are not counted.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use futures::{Stream, StreamExt};
+
+/// Open SSE streams per client address, shared by every worker.
+#[derive(Clone, Default)]
+pub(super) struct SseConnections {
+    open: Arc<Mutex<HashMap<Option<IpAddr>, usize>>>,
+}
+
+impl SseConnections {
+    /// Counts a new stream of `client`, unless it already has `max` open.
+    pub(super) fn acquire(
+        &self,
+        client: Option<IpAddr>,
+        max: Option<usize>,
+    ) -> Option<SseConnection> {
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let count = open.entry(client).or_default();
+        if max.is_some_and(|max| *count >= max) {
+            return None;
+        }
+        *count += 1;
+        Some(SseConnection(self.clone(), client))
+    }
+
+    /// Returns the number of open streams of all clients.
+    pub(super) fn total(&self) -> usize {
+        self.open
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .sum()
+    }
+}
+
+/// An open SSE stream, counted until dropped.
+pub(super) struct SseConnection(SseConnections, Option<IpAddr>);
+
+impl SseConnection {
+    /// Keeps the stream counted until `stream` ends or is dropped.
+    pub(super) fn hold<St>(self, stream: St) -> impl Stream<Item = St::Item> + Send + 'static
+    where
+        St: Stream + Send + 'static,
+    {
+        stream.map(move |item| {
+            let _connection = &self;
+            item
+        })
+    }
+}
+
+impl Drop for SseConnection {
+    fn drop(&mut self) {
+        let mut open = self.0.open.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = open.get_mut(&self.1) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.1);
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Why long-lived SSE streams end.
+//!
+//! Standalone `GET` streams and legacy SSE connections are tracked until they
+//! end, and the way they ended is classified into a [`StreamEndReason`]. It is
+//! carried by [`TransportEvent::StreamDropped`](super::TransportEvent::StreamDropped)
+//! and, for legacy sessions, which end with their stream, by
+//! [`SessionCloseReason::StreamDisconnected`](super::SessionCloseReason::StreamDisconnected).
+//!
+//! actix-web drops a response stream when the connection fails, without saying
+//! why, so the classification is a best effort: a stream dropped while one of
+//! its events was being written is counted as a write error (a keep-alive
+//! failure for keep-alive frames), one dropped while waiting for its next event
+//! as a client disconnect.
+
+use actix_web::web::Bytes;
+use futures::{Stream, StreamExt};
+
+use super::shutdown::ShutdownSignal;
+
+/// Why a long-lived SSE stream ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StreamEndReason {
+    /// The client went away while the stream was idle.
+    ClientDisconnect,
+    /// The server ended the stream, e.g. because its session closed.
+    ServerClose,
+    /// The connection failed while an event was being written.
+    WriteError,
+    /// The connection failed while a keep-alive frame was being written.
+    KeepAliveFailure,
+    /// The stream ended because the service is shutting down.
+    Shutdown,
+}
+
+impl StreamEndReason {
+    /// Returns the stable, snake_case name of the reason, e.g. for metrics labels.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ClientDisconnect => "client_disconnect",
+            Self::ServerClose => "server_close",
+            Self::WriteError => "write_error",
+            Self::KeepAliveFailure => "keep_alive_failure",
+            Self::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// Item of the stream being handed to actix-web when the stream ends.
+#[derive(Clone, Copy)]
+enum Pending {
+    Event,
+    KeepAlive,
+}
+
+/// Calls `on_end` with the reason once `stream` ends or is dropped.
+struct EndGuard<F: FnOnce(StreamEndReason)> {
+    on_end: Option<F>,
+    pending: Option<Pending>,
+    shutdown: ShutdownSignal,
+}
+
+impl<F: FnOnce(StreamEndReason)> EndGuard<F> {
+    fn end(&mut self, reason: StreamEndReason) {
+        let reason = if self.shutdown.is_draining() {
+            StreamEndReason::Shutdown
+        } else {
+            reason
+        };
+        if let Some(on_end) = self.on_end.take() {
+            on_end(reason);
+        }
+    }
+}
+
+impl<F: FnOnce(StreamEndReason)> Drop for EndGuard<F> {
+    fn drop(&mut self) {
+        let reason = match self.pending {
+            None => StreamEndReason::ClientDisconnect,
+            Some(Pending::Event) => StreamEndReason::WriteError,
+            Some(Pending::KeepAlive) => StreamEndReason::KeepAliveFailure,
+        };
+        self.end(reason);
+    }
+}
+
+/// Tracks `stream`, whose keep-alive frames are `keep_alive_frame`, calling
+/// `on_end` with the reason it ended.
+pub(super) fn track<St, E, F>(
+    stream: St,
+    keep_alive_frame: Bytes,
+    shutdown: ShutdownSignal,
+    on_end: F,
+) -> impl Stream<Item = Result<Bytes, E>> + 'static
+where
+    St: Stream<Item = Result<Bytes, E>> + 'static,
+    E: 'static,
+    F: FnOnce(StreamEndReason) + 'static,
+{
+    let mut guard = EndGuard {
+        on_end: Some(on_end),
+        pending: None,
+        shutdown,
+    };
+    async_stream::stream! {
+        let mut stream = Box::pin(stream);
+        while let Some(item) = stream.next().await {
+            guard.pending = Some(match &item {
+                Ok(bytes) if *bytes == keep_alive_frame => Pending::KeepAlive,
+                _ => Pending::Event,
+            });
+            yield item;
+            // Polled again, so the item was accepted
+            guard.pending = None;
+        }
+        guard.end(StreamEndReason::ServerClose);
+    }
+}
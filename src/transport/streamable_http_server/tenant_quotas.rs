@@ -0,0 +1,347 @@
+//! Quotas per tenant.
+//!
+//! In a multi-tenant deployment, one tenant's clients must not exhaust the
+//! capacity shared with every other tenant. [`TenantQuotas`] caps, per
+//! [`TenantId`], the open sessions, the JSON-RPC requests per minute and the
+//! concurrently running tool calls:
+//!
+//! - A new session over the session cap is refused with `403 Forbidden` until
+//!   one of the tenant's sessions closes.
+//! - A request over the rate is refused with `429 Too Many Requests` and a
+//!   `Retry-After` header telling when the minute window resets.
+//! - A tool call over the concurrency cap is answered with a JSON-RPC error
+//!   without reaching the handler.
+//!
+//! Every refusal carries a JSON-RPC error with code [`QUOTA_EXCEEDED_CODE`].
+//!
+//! Requests without a `TenantId` extension are not subject to any quota. The
+//! usage of each tenant, including how often its quotas were hit, is returned
+//! by [`StreamableHttpService::tenant_usage`](super::StreamableHttpService::tenant_usage).
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    HttpResponse,
+    http::header::{HeaderValue, RETRY_AFTER},
+};
+use rmcp::{
+    ErrorData, RoleServer, Service,
+    model::{
+        ClientNotification, ClientRequest, ErrorCode, RequestId, ServerInfo, ServerJsonRpcMessage,
+        ServerResult,
+    },
+    service::{NotificationContext, RequestContext},
+};
+
+use super::{JSON_MIME_TYPE, TenantId};
+
+/// JSON-RPC error code for requests refused by a tenant quota.
+pub const QUOTA_EXCEEDED_CODE: ErrorCode = ErrorCode(-32009);
+
+/// Window of the request rate.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Limits applying to one tenant.
+///
+/// The default quota limits nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TenantQuota {
+    max_sessions: Option<usize>,
+    requests_per_minute: Option<u32>,
+    max_concurrent_tool_calls: Option<usize>,
+}
+
+impl TenantQuota {
+    /// Creates a quota that limits nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Caps the sessions the tenant may have open at once.
+    pub fn max_sessions(mut self, max: usize) -> Self {
+        self.max_sessions = Some(max);
+        self
+    }
+
+    /// Caps the JSON-RPC requests the tenant may send per minute.
+    pub fn requests_per_minute(mut self, max: u32) -> Self {
+        self.requests_per_minute = Some(max);
+        self
+    }
+
+    /// Caps the tool calls of the tenant running at once.
+    pub fn max_concurrent_tool_calls(mut self, max: usize) -> Self {
+        self.max_concurrent_tool_calls = Some(max);
+        self
+    }
+}
+
+/// Quotas keyed by tenant, with a fallback for unlisted tenants.
+///
+/// ## Example
+///
+/// ```rust
+/// use rmcp_actix_web::transport::{TenantQuota, TenantQuotas};
+///
+/// let quotas = TenantQuotas::new()
+///     .default_quota(TenantQuota::new().max_sessions(10).requests_per_minute(600))
+///     .tenant("acme", TenantQuota::new().max_sessions(100));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TenantQuotas {
+    default: Option<TenantQuota>,
+    tenants: HashMap<String, TenantQuota>,
+}
+
+impl TenantQuotas {
+    /// Creates an empty set of quotas, limiting no tenant.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the quota of tenants without a quota of their own.
+    pub fn default_quota(mut self, quota: TenantQuota) -> Self {
+        self.default = Some(quota);
+        self
+    }
+
+    /// Sets the quota of the tenant `tenant`.
+    pub fn tenant(mut self, tenant: impl Into<String>, quota: TenantQuota) -> Self {
+        self.tenants.insert(tenant.into(), quota);
+        self
+    }
+
+    /// Returns the quota applying to `tenant`, if any.
+    pub(super) fn quota(&self, tenant: &TenantId) -> Option<&TenantQuota> {
+        self.tenants.get(&tenant.0).or(self.default.as_ref())
+    }
+}
+
+/// Usage and quota hits of a tenant.
+///
+/// Returned by [`StreamableHttpService::tenant_usage`](super::StreamableHttpService::tenant_usage).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TenantUsage {
+    /// Open sessions.
+    pub sessions: usize,
+    /// Requests counted in the current minute window.
+    pub requests_in_window: u32,
+    /// Tool calls running.
+    pub concurrent_tool_calls: usize,
+    /// New sessions refused by the session cap.
+    pub rejected_sessions: u64,
+    /// Requests refused by the request rate.
+    pub rejected_requests: u64,
+    /// Tool calls refused by the concurrency cap.
+    pub rejected_tool_calls: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    window_started_at: Option<Instant>,
+    usage: TenantUsage,
+}
+
+/// Usage counters of every tenant, shared by every worker.
+#[derive(Clone, Default)]
+pub(super) struct TenantCounters {
+    tenants: Arc<Mutex<HashMap<TenantId, Counters>>>,
+}
+
+impl TenantCounters {
+    fn update<T>(&self, tenant: &TenantId, f: impl FnOnce(&mut Counters) -> T) -> T {
+        let mut tenants = self.tenants.lock().unwrap_or_else(|e| e.into_inner());
+        f(tenants.entry(tenant.clone()).or_default())
+    }
+
+    /// Checks a new session of `tenant`, which already has `open` sessions.
+    pub(super) fn admit_session(
+        &self,
+        tenant: &TenantId,
+        quota: &TenantQuota,
+        open: usize,
+    ) -> bool {
+        let admitted = quota.max_sessions.is_none_or(|max| open < max);
+        if !admitted {
+            self.update(tenant, |counters| counters.usage.rejected_sessions += 1);
+        }
+        admitted
+    }
+
+    /// Counts a request of `tenant`, unless it would exceed its rate.
+    ///
+    /// Returns the time until the window resets when the tenant is over its rate.
+    pub(super) fn admit_request(
+        &self,
+        tenant: &TenantId,
+        quota: &TenantQuota,
+    ) -> Result<(), Duration> {
+        let Some(max) = quota.requests_per_minute else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        self.update(tenant, |counters| {
+            let started_at = match counters.window_started_at {
+                Some(started_at) if now.duration_since(started_at) < RATE_WINDOW => started_at,
+                _ => {
+                    counters.usage.requests_in_window = 0;
+                    *counters.window_started_at.insert(now)
+                }
+            };
+            if counters.usage.requests_in_window >= max {
+                counters.usage.rejected_requests += 1;
+                return Err(RATE_WINDOW - now.duration_since(started_at));
+            }
+            counters.usage.requests_in_window += 1;
+            Ok(())
+        })
+    }
+
+    /// Counts a running tool call of `tenant`, unless it already has `max` running.
+    fn acquire_tool_call(&self, tenant: &TenantId, max: usize) -> Option<ToolCall> {
+        self.update(tenant, |counters| {
+            if counters.usage.concurrent_tool_calls >= max {
+                counters.usage.rejected_tool_calls += 1;
+                return None;
+            }
+            counters.usage.concurrent_tool_calls += 1;
+            Some(())
+        })?;
+        Some(ToolCall(self.clone(), tenant.clone()))
+    }
+
+    /// Returns the usage of `tenant`, which has `sessions` open sessions.
+    pub(super) fn usage(&self, tenant: &TenantId, sessions: usize) -> TenantUsage {
+        let tenants = self.tenants.lock().unwrap_or_else(|e| e.into_inner());
+        let mut usage = tenants
+            .get(tenant)
+            .map(|counters| {
+                let mut usage = counters.usage;
+                if counters
+                    .window_started_at
+                    .is_none_or(|started_at| started_at.elapsed() >= RATE_WINDOW)
+                {
+                    usage.requests_in_window = 0;
+                }
+                usage
+            })
+            .unwrap_or_default();
+        usage.sessions = sessions;
+        usage
+    }
+}
+
+/// A running tool call, counted until dropped.
+struct ToolCall(TenantCounters, TenantId);
+
+impl Drop for ToolCall {
+    fn drop(&mut self) {
+        self.0.update(&self.1, |counters| {
+            counters.usage.concurrent_tool_calls -= 1
+        });
+    }
+}
+
+/// Builds the `429 Too Many Requests` response refusing the request `id` of
+/// `tenant`.
+pub(super) fn rate_limited_response(
+    tenant: &TenantId,
+    id: RequestId,
+    retry_after: Duration,
+) -> HttpResponse {
+    let error = ServerJsonRpcMessage::error(
+        ErrorData::new(
+            QUOTA_EXCEEDED_CODE,
+            format!("Request rate of tenant {} exceeded", tenant.0),
+            None,
+        ),
+        Some(id),
+    );
+    // Rounded up, so clients do not retry before the window resets
+    let retry_after_secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    HttpResponse::TooManyRequests()
+        .content_type(JSON_MIME_TYPE)
+        .insert_header((RETRY_AFTER, HeaderValue::from(retry_after_secs.max(1))))
+        .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()))
+}
+
+/// Builds the `403 Forbidden` response refusing a new session of `tenant`.
+pub(super) fn session_quota_response(tenant: &TenantId, id: Option<RequestId>) -> HttpResponse {
+    let error = ServerJsonRpcMessage::error(
+        ErrorData::new(
+            QUOTA_EXCEEDED_CODE,
+            format!("Session quota of tenant {} exhausted", tenant.0),
+            None,
+        ),
+        id,
+    );
+    HttpResponse::Forbidden()
+        .content_type(JSON_MIME_TYPE)
+        .body(serde_json::to_string(&error).unwrap_or_else(|_| "{}".to_string()))
+}
+
+/// MCP service refusing tool calls over the concurrency cap of its tenant.
+pub(super) struct ToolCallQuota<S> {
+    inner: S,
+    limit: Option<(TenantCounters, TenantId, usize)>,
+}
+
+impl<S> ToolCallQuota<S> {
+    /// Wraps the service serving `tenant`, if any.
+    pub(super) fn new(
+        inner: S,
+        tenant: Option<TenantId>,
+        quotas: Option<&TenantQuotas>,
+        counters: &TenantCounters,
+    ) -> Self {
+        let limit = tenant.and_then(|tenant| {
+            let max = quotas?.quota(&tenant)?.max_concurrent_tool_calls?;
+            Some((counters.clone(), tenant, max))
+        });
+        Self { inner, limit }
+    }
+}
+
+impl<S> Service<RoleServer> for ToolCallQuota<S>
+where
+    S: Service<RoleServer>,
+{
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, ErrorData> {
+        let _tool_call = match (&self.limit, &request) {
+            (Some((counters, tenant, max)), ClientRequest::CallToolRequest(_)) => {
+                let Some(tool_call) = counters.acquire_tool_call(tenant, *max) else {
+                    tracing::warn!(tenant = %tenant.0, "Too many concurrent tool calls for tenant");
+                    return Err(ErrorData::new(
+                        QUOTA_EXCEEDED_CODE,
+                        format!("Too many concurrent tool calls for tenant {}", tenant.0),
+                        None,
+                    ));
+                };
+                Some(tool_call)
+            }
+            _ => None,
+        };
+        self.inner.handle_request(request, context).await
+    }
+
+    fn handle_notification(
+        &self,
+        notification: ClientNotification,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = Result<(), ErrorData>> + Send + '_ {
+        self.inner.handle_notification(notification, context)
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        self.inner.get_info()
+    }
+}
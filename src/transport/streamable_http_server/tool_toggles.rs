@@ -0,0 +1,231 @@
+//! Switching individual tools off and on at runtime.
+//!
+//! The [`MessageFilter`](crate::transport::MessageFilter) is fixed when the
+//! service is built. When a tool misbehaves in production, operators need to
+//! pull it without a redeploy: a disabled tool is removed from `tools/list`
+//! results and its calls are answered by the transport with a JSON-RPC error,
+//! for every session at once. The handler is not involved and keeps listing
+//! the tool.
+//!
+//! Every change sends `notifications/tools/list_changed` to all running
+//! sessions, so clients refresh their tool lists, and drops cached tool lists.
+//!
+//! The admin scope exposes the toggles over HTTP, behind an API key:
+//!
+//! - `GET {path}/tools` lists the disabled tools.
+//! - `POST {path}/tools/{name}/disable` disables a tool.
+//! - `POST {path}/tools/{name}/enable` enables it again.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use actix_web::{HttpRequest, HttpResponse, Scope, web};
+use rmcp::{
+    RoleServer,
+    model::{
+        ClientJsonRpcMessage, ClientRequest, ErrorData, JsonRpcRequest, ServerJsonRpcMessage,
+        ServerNotification, ServerResult,
+    },
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::Transport,
+};
+
+use super::{response_cache::ResponseCache, session_push, sessions::SessionRegistry};
+use crate::transport::ApiKeyAuth;
+
+/// Names of the disabled tools, shared by every worker.
+#[derive(Clone, Default)]
+pub(super) struct ToolToggles {
+    disabled: Arc<RwLock<HashSet<String>>>,
+}
+
+impl ToolToggles {
+    /// Returns whether the tool `name` is disabled.
+    pub(super) fn is_disabled(&self, name: &str) -> bool {
+        self.disabled
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(name)
+    }
+
+    /// Disables or enables the tool `name`, returning whether that changed anything.
+    fn set_enabled(&self, name: &str, enabled: bool) -> bool {
+        let mut disabled = self.disabled.write().unwrap_or_else(|e| e.into_inner());
+        if enabled {
+            disabled.remove(name)
+        } else {
+            disabled.insert(name.to_string())
+        }
+    }
+
+    /// Returns the names of the disabled tools, sorted.
+    pub(super) fn disabled(&self) -> Vec<String> {
+        let disabled = self.disabled.read().unwrap_or_else(|e| e.into_inner());
+        disabled
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Checks a client message, returning the error response to send back if
+    /// it calls a disabled tool.
+    pub(super) fn reject(&self, message: &ClientJsonRpcMessage) -> Option<ServerJsonRpcMessage> {
+        let ClientJsonRpcMessage::Request(JsonRpcRequest {
+            id,
+            request: ClientRequest::CallToolRequest(call),
+            ..
+        }) = message
+        else {
+            return None;
+        };
+        let tool = call.params.name.as_ref();
+        if !self.is_disabled(tool) {
+            return None;
+        }
+        tracing::info!(tool, "Call of disabled tool rejected");
+        Some(ServerJsonRpcMessage::error(
+            ErrorData::invalid_params(format!("Tool disabled: {tool}"), None),
+            Some(id.clone()),
+        ))
+    }
+
+    /// Removes the disabled tools from a `tools/list` response.
+    fn hide_disabled(&self, message: &mut ServerJsonRpcMessage) {
+        if let ServerJsonRpcMessage::Response(response) = message
+            && let ServerResult::ListToolsResult(result) = &mut response.result
+        {
+            let disabled = self.disabled.read().unwrap_or_else(|e| e.into_inner());
+            if !disabled.is_empty() {
+                result
+                    .tools
+                    .retain(|tool| !disabled.contains(tool.name.as_ref()));
+            }
+        }
+    }
+}
+
+/// Tool toggles together with what a change has to reach.
+#[derive(Clone)]
+pub(super) struct ToolAdmin {
+    pub(super) toggles: ToolToggles,
+    pub(super) sessions: SessionRegistry,
+    pub(super) response_cache: ResponseCache,
+}
+
+impl ToolAdmin {
+    /// Disables or enables the tool `name`, returning whether that changed anything.
+    ///
+    /// A change drops the cached tool lists and notifies every running session.
+    pub(super) async fn set_enabled(&self, name: &str, enabled: bool) -> bool {
+        if !self.toggles.set_enabled(name, enabled) {
+            return false;
+        }
+        tracing::info!(tool = name, enabled, "Tool availability changed");
+        let notification = ServerNotification::ToolListChangedNotification(Default::default());
+        self.response_cache
+            .invalidate_all_tenants(&ServerJsonRpcMessage::notification(notification.clone()));
+        session_push::broadcast(&self.sessions, notification).await;
+        true
+    }
+}
+
+/// Service transport that hides disabled tools from `tools/list` results.
+pub(super) struct HideDisabledTools<T> {
+    inner: T,
+    toggles: ToolToggles,
+}
+
+impl<T> HideDisabledTools<T> {
+    pub(super) fn new(inner: T, toggles: ToolToggles) -> Self {
+        Self { inner, toggles }
+    }
+}
+
+impl<T> Transport<RoleServer> for HideDisabledTools<T>
+where
+    T: Transport<RoleServer>,
+{
+    type Error = T::Error;
+
+    fn name() -> Cow<'static, str> {
+        T::name()
+    }
+
+    fn send(
+        &mut self,
+        mut item: TxJsonRpcMessage<RoleServer>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.toggles.hide_disabled(&mut item);
+        self.inner.send(item)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleServer>>> + Send {
+        self.inner.receive()
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
+
+/// Creates the admin scope at `path`, guarded by `auth`.
+pub(super) fn admin_scope(path: &str, admin: ToolAdmin, auth: ApiKeyAuth) -> Scope {
+    web::scope(path)
+        .app_data(web::Data::new(admin))
+        .app_data(web::Data::new(auth))
+        .route("/tools", web::get().to(list_disabled))
+        .route("/tools/{name}/disable", web::post().to(disable))
+        .route("/tools/{name}/enable", web::post().to(enable))
+}
+
+async fn list_disabled(
+    req: HttpRequest,
+    admin: web::Data<ToolAdmin>,
+    auth: web::Data<ApiKeyAuth>,
+) -> HttpResponse {
+    if let Some(response) = auth.check(&req) {
+        return response;
+    }
+    HttpResponse::Ok().json(serde_json::json!({ "disabled": admin.toggles.disabled() }))
+}
+
+async fn disable(
+    req: HttpRequest,
+    name: web::Path<String>,
+    admin: web::Data<ToolAdmin>,
+    auth: web::Data<ApiKeyAuth>,
+) -> HttpResponse {
+    toggle(&req, &name, &admin, &auth, false).await
+}
+
+async fn enable(
+    req: HttpRequest,
+    name: web::Path<String>,
+    admin: web::Data<ToolAdmin>,
+    auth: web::Data<ApiKeyAuth>,
+) -> HttpResponse {
+    toggle(&req, &name, &admin, &auth, true).await
+}
+
+async fn toggle(
+    req: &HttpRequest,
+    name: &str,
+    admin: &ToolAdmin,
+    auth: &ApiKeyAuth,
+    enabled: bool,
+) -> HttpResponse {
+    if let Some(response) = auth.check(req) {
+        return response;
+    }
+    let changed = admin.set_enabled(name, enabled).await;
+    HttpResponse::Ok().json(serde_json::json!({
+        "tool": name,
+        "enabled": enabled,
+        "changed": changed,
+    }))
+}
@@ -0,0 +1,185 @@
+//! Broadcast of transport events.
+//!
+//! [`StreamableHttpService::subscribe_events`](super::StreamableHttpService::subscribe_events)
+//! hands out a [`tokio::sync::broadcast`] receiver of [`TransportEvent`]s:
+//! sessions created and closed, requests started and completed, standalone
+//! streams opened and dropped. Applications subscribe to it for custom
+//! monitoring, billing or side effects without wrapping the transport.
+//!
+//! Events are only built while someone is subscribed. A subscriber that falls
+//! more than
+//! [`event_channel_capacity`](super::StreamableHttpServiceBuilder::event_channel_capacity)
+//! events behind loses the oldest ones and is told how many with
+//! [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged); the
+//! transport itself never waits for subscribers.
+
+use std::time::{Duration, Instant};
+
+use rmcp::{
+    model::{ClientJsonRpcMessage, RequestId},
+    transport::common::server_side_http::SessionId,
+};
+use tokio::sync::broadcast;
+
+use super::{SessionCloseReason, StreamEndReason};
+
+/// Default number of events buffered for each subscriber.
+pub(super) const DEFAULT_EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Something that happened in the transport.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum TransportEvent {
+    /// A session was created, by an `initialize` request or a legacy SSE
+    /// connection.
+    SessionCreated {
+        /// The new session.
+        session_id: SessionId,
+    },
+    /// A session was closed.
+    SessionClosed {
+        /// The closed session.
+        session_id: SessionId,
+        /// Why it was closed.
+        reason: SessionCloseReason,
+        /// How long the session lived.
+        duration: Duration,
+    },
+    /// A JSON-RPC request was received on the Streamable HTTP endpoint.
+    RequestStarted {
+        /// Session the request was sent in; `None` for `initialize` and
+        /// stateless requests.
+        session_id: Option<SessionId>,
+        /// Id of the request.
+        request_id: RequestId,
+        /// Method of the request, e.g. `tools/call`.
+        method: String,
+    },
+    /// The response to a request was sent, or the client stopped waiting for it.
+    RequestCompleted {
+        /// Session the request was sent in, as in [`TransportEvent::RequestStarted`].
+        session_id: Option<SessionId>,
+        /// Id of the request.
+        request_id: RequestId,
+        /// Method of the request.
+        method: String,
+        /// Time from receiving the request to the end of its response.
+        duration: Duration,
+    },
+    /// A client opened a standalone `GET` stream or a legacy SSE connection.
+    StreamOpened {
+        /// Session the stream belongs to.
+        session_id: SessionId,
+    },
+    /// A standalone `GET` stream or legacy SSE connection ended.
+    StreamDropped {
+        /// Session the stream belonged to.
+        session_id: SessionId,
+        /// Why the stream ended.
+        reason: StreamEndReason,
+        /// How long the stream was open.
+        duration: Duration,
+    },
+}
+
+/// Sending half of the event broadcast, shared by every clone of the service.
+#[derive(Clone)]
+pub(super) struct TransportEvents(broadcast::Sender<TransportEvent>);
+
+impl TransportEvents {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self(broadcast::Sender::new(capacity.max(1)))
+    }
+
+    pub(super) fn subscribe(&self) -> broadcast::Receiver<TransportEvent> {
+        self.0.subscribe()
+    }
+
+    /// Sends the event built by `event`, if anyone is subscribed.
+    pub(super) fn emit(&self, event: impl FnOnce() -> TransportEvent) {
+        if self.0.receiver_count() > 0 {
+            let _ = self.0.send(event());
+        }
+    }
+
+    /// Announces `message` if it is a request, returning the guard announcing its
+    /// completion when dropped.
+    pub(super) fn request_started(
+        &self,
+        session_id: Option<&SessionId>,
+        message: &ClientJsonRpcMessage,
+    ) -> Option<RequestInFlight> {
+        let ClientJsonRpcMessage::Request(request) = message else {
+            return None;
+        };
+        if self.0.receiver_count() == 0 {
+            return None;
+        }
+        let request = RequestInFlight {
+            events: self.clone(),
+            session_id: session_id.cloned(),
+            request_id: request.id.clone(),
+            method: request.request.method().to_owned(),
+            started_at: Instant::now(),
+        };
+        self.emit(|| TransportEvent::RequestStarted {
+            session_id: request.session_id.clone(),
+            request_id: request.request_id.clone(),
+            method: request.method.clone(),
+        });
+        Some(request)
+    }
+
+    /// Announces a stream of `session_id`, returning the handle announcing its
+    /// end.
+    pub(super) fn stream_opened(&self, session_id: &SessionId) -> OpenStream {
+        self.emit(|| TransportEvent::StreamOpened {
+            session_id: session_id.clone(),
+        });
+        OpenStream {
+            events: self.clone(),
+            session_id: session_id.clone(),
+            opened_at: Instant::now(),
+        }
+    }
+}
+
+/// A request announced by [`TransportEvents::request_started`], completed when
+/// dropped.
+pub(super) struct RequestInFlight {
+    events: TransportEvents,
+    session_id: Option<SessionId>,
+    request_id: RequestId,
+    method: String,
+    started_at: Instant,
+}
+
+impl Drop for RequestInFlight {
+    fn drop(&mut self) {
+        self.events.emit(|| TransportEvent::RequestCompleted {
+            session_id: self.session_id.take(),
+            request_id: self.request_id.clone(),
+            method: std::mem::take(&mut self.method),
+            duration: self.started_at.elapsed(),
+        });
+    }
+}
+
+/// A stream announced by [`TransportEvents::stream_opened`].
+pub(super) struct OpenStream {
+    events: TransportEvents,
+    session_id: SessionId,
+    opened_at: Instant,
+}
+
+impl OpenStream {
+    /// Announces that the stream ended for `reason`.
+    pub(super) fn ended(self, reason: StreamEndReason) {
+        tracing::debug!(session_id = %self.session_id, reason = reason.as_str(), "SSE stream ended");
+        self.events.emit(|| TransportEvent::StreamDropped {
+            session_id: self.session_id,
+            reason,
+            duration: self.opened_at.elapsed(),
+        });
+    }
+}
@@ -0,0 +1,108 @@
+//! Pool of pre-constructed service instances.
+//!
+//! Handlers that load models or warm up connection pools make the service
+//! factory slow, and every new session waits for it. With a warm pool
+//! configured, instances are constructed ahead of time on tokio's blocking
+//! thread pool and handed to new sessions as they arrive. Each instance taken
+//! from the pool is replaced in the background; when the pool has run dry, the
+//! factory is called directly as without a pool.
+//!
+//! Filling starts when the service is mounted. Factory failures while filling
+//! are logged and leave the slot empty until the next instance is taken.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use super::ServiceFactory;
+
+/// Bounded pool of ready service instances, shared by every worker.
+pub(super) struct WarmPool<S> {
+    size: usize,
+    state: Arc<Mutex<PoolState<S>>>,
+}
+
+struct PoolState<S> {
+    ready: VecDeque<S>,
+    /// Instances being constructed in the background
+    constructing: usize,
+}
+
+impl<S> Clone for WarmPool<S> {
+    fn clone(&self) -> Self {
+        Self {
+            size: self.size,
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<S> WarmPool<S> {
+    /// Creates an empty pool holding up to `size` instances, if any.
+    pub(super) fn new(size: Option<usize>) -> Self {
+        Self {
+            size: size.unwrap_or_default(),
+            state: Arc::new(Mutex::new(PoolState {
+                ready: VecDeque::new(),
+                constructing: 0,
+            })),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PoolState<S>> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<S: Send + 'static> WarmPool<S> {
+    /// Starts filling the pool and returns a factory serving instances from it.
+    ///
+    /// Without a pool size, `factory` is returned as is.
+    pub(super) fn serve(&self, factory: ServiceFactory<S>) -> ServiceFactory<S> {
+        if self.size == 0 {
+            return factory;
+        }
+        self.fill(&factory);
+        let pool = self.clone();
+        Arc::new(move || {
+            let service = pool.lock().ready.pop_front();
+            pool.fill(&factory);
+            service.map_or_else(|| factory(), Ok)
+        })
+    }
+
+    /// Starts constructing instances until the pool is, or will be, full.
+    ///
+    /// Does nothing outside a tokio runtime.
+    fn fill(&self, factory: &ServiceFactory<S>) {
+        if tokio::runtime::Handle::try_current().is_err() {
+            return;
+        }
+        let missing = {
+            let mut state = self.lock();
+            let missing = self
+                .size
+                .saturating_sub(state.ready.len() + state.constructing);
+            state.constructing += missing;
+            missing
+        };
+        for _ in 0..missing {
+            let pool = self.clone();
+            let factory = factory.clone();
+            tokio::task::spawn_blocking(move || {
+                let service = factory();
+                let mut state = pool.lock();
+                state.constructing -= 1;
+                match service {
+                    Ok(service) => state.ready.push_back(service),
+                    Err(e) => {
+                        tracing::error!("Service factory failed while filling warm pool: {e}")
+                    }
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Serving over TLS with rustls.
+//!
+//! Almost every deployment reachable beyond localhost needs TLS, and setting
+//! up rustls for actix-web takes a fair amount of code. With the `rustls`
+//! feature, [`rustls_config`] builds a server configuration from PEM
+//! certificate and key files with sane defaults:
+//!
+//! - the `ring` crypto provider's default cipher suites, which only include
+//!   AEAD suites with forward secrecy,
+//! - TLS 1.2 and 1.3,
+//! - ALPN offering `h2` then `http/1.1`,
+//! - no client authentication.
+//!
+//! [`StreamableHttpService::serve_rustls`](super::StreamableHttpService::serve_rustls)
+//! mounts a service and serves it with that configuration. Applications with
+//! more routes pass the configuration to `HttpServer::bind_rustls_0_23`
+//! themselves.
+//!
+//! ## Example
+//!
+//! ```rust,ignore
+//! let service = StreamableHttpService::builder()
+//!     .service_factory(Arc::new(|| Ok(MyService::new())))
+//!     .session_manager(Arc::new(LocalSessionManager::default()))
+//!     .build();
+//!
+//! service
+//!     .serve_rustls("/mcp", "0.0.0.0:8443", "cert.pem", "key.pem")?
+//!     .await
+//! ```
+
+use std::{io, net::ToSocketAddrs, path::Path, sync::Arc};
+
+use actix_web::{App, HttpServer, dev::Server, web};
+use rmcp::{RoleServer, transport::streamable_http_server::SessionManager};
+use rustls::{
+    ServerConfig,
+    pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+};
+
+use super::StreamableHttpService;
+
+/// ALPN protocols offered, in order of preference.
+const ALPN_PROTOCOLS: [&[u8]; 2] = [b"h2", b"http/1.1"];
+
+/// Builds a rustls server configuration from PEM files.
+///
+/// `cert_path` holds the certificate chain, leaf first; `key_path` the private
+/// key, in PKCS#8, PKCS#1 or SEC1 form. Unreadable files, missing or malformed
+/// PEM sections and a key not matching the certificate are reported as
+/// [`io::Error`]s, like the other failures of binding a server.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let config = rustls_config("cert.pem", "key.pem")?;
+/// HttpServer::new(move || App::new().service(service.clone().scope_with_path("/mcp")))
+///     .bind_rustls_0_23("0.0.0.0:8443", config)?
+///     .run()
+///     .await
+/// ```
+pub fn rustls_config(
+    cert_path: impl AsRef<Path>,
+    key_path: impl AsRef<Path>,
+) -> io::Result<ServerConfig> {
+    let cert_path = cert_path.as_ref();
+    let key_path = key_path.as_ref();
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| pem_error(cert_path, e))?;
+    if certs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("no certificate found in {}", cert_path.display()),
+        ));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path).map_err(|e| pem_error(key_path, e))?;
+
+    let mut config =
+        ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(io::Error::other)?
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    config.alpn_protocols = ALPN_PROTOCOLS.iter().map(|p| p.to_vec()).collect();
+    Ok(config)
+}
+
+fn pem_error(path: &Path, error: rustls::pki_types::pem::Error) -> io::Error {
+    match error {
+        rustls::pki_types::pem::Error::Io(e) => {
+            io::Error::new(e.kind(), format!("failed to read {}: {e}", path.display()))
+        }
+        e => io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid PEM in {}: {e}", path.display()),
+        ),
+    }
+}
+
+impl<S, M> StreamableHttpService<S, M>
+where
+    S: rmcp::Service<RoleServer> + Send + 'static,
+    M: SessionManager + 'static,
+{
+    /// Serves this service at `path` over TLS on `addrs`, with the
+    /// [`rustls_config`] built from `cert_path` and `key_path`.
+    ///
+    /// The returned server runs once awaited; use `HttpServer` directly to tune
+    /// workers or mount other routes.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// service
+    ///     .serve_rustls("/mcp", "0.0.0.0:8443", "cert.pem", "key.pem")?
+    ///     .await
+    /// ```
+    pub fn serve_rustls(
+        self,
+        path: &str,
+        addrs: impl ToSocketAddrs,
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> io::Result<Server> {
+        let config = rustls_config(cert_path, key_path)?;
+        let path = path.to_owned();
+        let server = HttpServer::new(move || {
+            App::new().service(web::scope(&path).service(self.clone().scope()))
+        })
+        .bind_rustls_0_23(addrs, config)?
+        .run();
+        Ok(server)
+    }
+}
@@ -0,0 +1,155 @@
+//! OAuth scope requirements for tools.
+//!
+//! A [`ToolScopes`] map declares which OAuth scopes a tool requires, so that
+//! authorization is enforced once by the transport instead of being
+//! re-checked in every handler. `tools/call` requests for a mapped tool are
+//! answered with a JSON-RPC error unless the request was granted all the
+//! scopes the tool is listed under. Tools that appear under no scope are not
+//! restricted.
+//!
+//! The transport does not validate tokens itself: the authentication
+//! middleware in front of it validates the bearer token and inserts the
+//! scopes it grants into the request extensions as [`GrantedScopes`]. A
+//! request without `GrantedScopes` holds no scope.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rmcp_actix_web::transport::{GrantedScopes, ToolScopes};
+//!
+//! let scopes = ToolScopes::new()
+//!     .require("mcp:admin", ["delete_user", "reset_password"])
+//!     .require("mcp:write", ["create_user"]);
+//!
+//! // In the authentication middleware, after validating the token:
+//! // req.extensions_mut().insert(GrantedScopes::from_scope_claim(&claims.scope));
+//! let granted = GrantedScopes::from_scope_claim("mcp:read mcp:write");
+//! assert!(granted.contains("mcp:write"));
+//! ```
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use actix_web::{HttpMessage, HttpRequest};
+use rmcp::model::{
+    ClientJsonRpcMessage, ClientRequest, ErrorCode, ErrorData, JsonRpcRequest, ServerJsonRpcMessage,
+};
+
+/// Scopes granted to a request by the authentication layer.
+///
+/// Inserted into the request extensions after the access token is validated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GrantedScopes(pub HashSet<String>);
+
+impl GrantedScopes {
+    /// Creates the set from individual scopes.
+    pub fn new<I, T>(scopes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self(scopes.into_iter().map(Into::into).collect())
+    }
+
+    /// Parses a space-delimited OAuth `scope` claim.
+    pub fn from_scope_claim(claim: &str) -> Self {
+        Self::new(claim.split_ascii_whitespace())
+    }
+
+    /// Returns whether `scope` was granted.
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.contains(scope)
+    }
+}
+
+/// Mapping from OAuth scopes to the tools that require them.
+///
+/// The default map restricts nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolScopes {
+    required: HashMap<String, BTreeSet<String>>,
+}
+
+impl ToolScopes {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires `scope` for calling each of `tools`.
+    ///
+    /// A tool listed under several scopes requires all of them.
+    pub fn require<I, T>(mut self, scope: impl Into<String>, tools: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        let scope = scope.into();
+        for tool in tools {
+            self.required
+                .entry(tool.into())
+                .or_default()
+                .insert(scope.clone());
+        }
+        self
+    }
+
+    /// Returns whether no tool is restricted.
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty()
+    }
+
+    /// Checks a client request against the granted scopes.
+    ///
+    /// Returns the JSON-RPC error to answer with if a required scope is missing.
+    pub fn check_request(
+        &self,
+        request: &ClientRequest,
+        granted: Option<&GrantedScopes>,
+    ) -> Result<(), ErrorData> {
+        let ClientRequest::CallToolRequest(call) = request else {
+            return Ok(());
+        };
+        let tool = call.params.name.as_ref();
+        let Some(required) = self.required.get(tool) else {
+            return Ok(());
+        };
+        let missing: Vec<&str> = required
+            .iter()
+            .filter(|scope| !granted.is_some_and(|granted| granted.contains(scope)))
+            .map(String::as_str)
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(ErrorData::new(
+            ErrorCode::INVALID_REQUEST,
+            format!("Insufficient scope for tool {tool}"),
+            Some(serde_json::json!({ "required_scopes": missing })),
+        ))
+    }
+
+    /// Checks a client message, returning the error response to send back if
+    /// it is a request lacking a required scope.
+    pub(crate) fn reject(
+        &self,
+        req: &HttpRequest,
+        message: &ClientJsonRpcMessage,
+    ) -> Option<ServerJsonRpcMessage> {
+        if self.is_empty() {
+            return None;
+        }
+        let ClientJsonRpcMessage::Request(JsonRpcRequest { id, request, .. }) = message else {
+            return None;
+        };
+        let extensions = req.extensions();
+        let error = self
+            .check_request(request, extensions.get::<GrantedScopes>())
+            .err()?;
+        tracing::info!(
+            method = request.method(),
+            reason = %error.message,
+            "Request rejected for insufficient scope"
+        );
+        Some(ServerJsonRpcMessage::error(error, Some(id.clone())))
+    }
+}
@@ -0,0 +1,218 @@
+//! Compatibility shim for rmcp's tower-based Streamable HTTP service.
+//!
+//! rmcp ships its own Streamable HTTP server as a `tower::Service`, normally
+//! mounted inside an Axum router. This module wraps that service so it can be
+//! mounted inside an actix-web [`Scope`], letting applications migrate from the
+//! Axum transport incrementally: the existing `StreamableHttpServerConfig`,
+//! session manager and session store keep working unchanged while the HTTP
+//! layer moves to actix-web.
+//!
+//! Requests are translated field by field (method, URI, headers, body) into
+//! `http` 1.x requests, and the tower response is streamed back as-is, so the
+//! wire behavior is exactly the one of the rmcp implementation, including its
+//! `Host`/`Origin` validation.
+//!
+//! New deployments should prefer the native
+//! [`StreamableHttpService`](super::StreamableHttpService).
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use rmcp_actix_web::transport::TowerStreamableHttpService;
+//! use rmcp::transport::streamable_http_server::{
+//!     StreamableHttpServerConfig, StreamableHttpService,
+//!     session::local::LocalSessionManager,
+//! };
+//! use actix_web::{App, HttpServer};
+//! use std::sync::Arc;
+//!
+//! # use rmcp::{ServerHandler, model::ServerInfo};
+//! # #[derive(Clone)]
+//! # struct MyService;
+//! # impl ServerHandler for MyService {
+//! #     fn get_info(&self) -> ServerInfo { ServerInfo::default() }
+//! # }
+//! # impl MyService { fn new() -> Self { Self } }
+//! #[actix_web::main]
+//! async fn main() -> std::io::Result<()> {
+//!     // The same value you would have handed to `axum::Router::nest_service`
+//!     let tower_service = StreamableHttpService::new(
+//!         || Ok(MyService::new()),
+//!         Arc::new(LocalSessionManager::default()),
+//!         StreamableHttpServerConfig::default(),
+//!     );
+//!     let service = TowerStreamableHttpService::new(tower_service);
+//!
+//!     HttpServer::new(move || {
+//!         App::new().service(service.clone().scope_with_path("/mcp"))
+//!     })
+//!     .bind("127.0.0.1:8080")?
+//!     .run()
+//!     .await
+//! }
+//! ```
+
+use std::convert::Infallible;
+
+use actix_web::{
+    HttpRequest, HttpResponse, Scope,
+    http::StatusCode,
+    middleware,
+    web::{self, Bytes, Data},
+};
+use futures::StreamExt;
+use http_body_util::{BodyExt, Full, combinators::BoxBody};
+use rmcp::{
+    RoleServer,
+    transport::streamable_http_server::{
+        StreamableHttpService as RmcpStreamableHttpService, session::SessionManager,
+    },
+};
+
+use super::Problem;
+
+const EVENT_STREAM_MIME_TYPE: &str = "text/event-stream";
+
+/// Adapter mounting rmcp's tower Streamable HTTP service in actix-web.
+///
+/// # Type Parameters
+///
+/// * `S` - The MCP service type that handles protocol messages
+/// * `M` - The session manager type used by the wrapped tower service
+pub struct TowerStreamableHttpService<S, M> {
+    inner: RmcpStreamableHttpService<S, M>,
+}
+
+impl<S, M> Clone for TowerStreamableHttpService<S, M> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S, M> From<RmcpStreamableHttpService<S, M>> for TowerStreamableHttpService<S, M> {
+    fn from(inner: RmcpStreamableHttpService<S, M>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S, M> TowerStreamableHttpService<S, M>
+where
+    S: rmcp::Service<RoleServer> + Send + 'static,
+    M: SessionManager + 'static,
+{
+    /// Wraps an already configured rmcp tower service.
+    pub fn new(inner: RmcpStreamableHttpService<S, M>) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the wrapped rmcp tower service.
+    pub fn into_inner(self) -> RmcpStreamableHttpService<S, M> {
+        self.inner
+    }
+
+    /// Creates a new scope serving the wrapped tower service.
+    ///
+    /// This method is equivalent to `scope_with_path("")`.
+    pub fn scope(
+        self,
+    ) -> Scope<
+        impl actix_web::dev::ServiceFactory<
+            actix_web::dev::ServiceRequest,
+            Config = (),
+            Response = actix_web::dev::ServiceResponse,
+            Error = actix_web::Error,
+            InitError = (),
+        >,
+    > {
+        self.scope_with_path("")
+    }
+
+    /// Creates a new scope serving the wrapped tower service at `path`.
+    ///
+    /// Every HTTP method is forwarded to the tower service, which is
+    /// responsible for answering `405 Method Not Allowed` itself.
+    pub fn scope_with_path(
+        self,
+        path: &str,
+    ) -> Scope<
+        impl actix_web::dev::ServiceFactory<
+            actix_web::dev::ServiceRequest,
+            Config = (),
+            Response = actix_web::dev::ServiceResponse,
+            Error = actix_web::Error,
+            InitError = (),
+        >,
+    > {
+        web::scope(path)
+            .app_data(Data::new(self))
+            .wrap(middleware::NormalizePath::trim())
+            .route("", web::route().to(Self::handle))
+    }
+
+    async fn handle(
+        req: HttpRequest,
+        body: Bytes,
+        service: Data<TowerStreamableHttpService<S, M>>,
+    ) -> HttpResponse {
+        let request = match to_http_request(&req, body) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::warn!("Failed to translate request for tower service: {e}");
+                return Problem::new(StatusCode::BAD_REQUEST, "bad_request", e.to_string())
+                    .into_response();
+            }
+        };
+
+        let response = service.inner.handle(request).await;
+        from_http_response(response).await
+    }
+}
+
+/// Translates an actix-web request into the `http` 1.x request expected by rmcp.
+fn to_http_request(
+    req: &HttpRequest,
+    body: Bytes,
+) -> Result<http::Request<Full<Bytes>>, http::Error> {
+    let mut builder = http::Request::builder()
+        .method(req.method().as_str())
+        .uri(req.uri().to_string());
+    for (name, value) in req.headers() {
+        builder = builder.header(name.as_str(), value.as_bytes());
+    }
+    builder.body(Full::new(body))
+}
+
+/// Translates a tower response back into an actix-web response.
+///
+/// SSE bodies are streamed chunk by chunk; every other body is collected first
+/// so actix-web can emit a regular `Content-Length` response.
+async fn from_http_response(response: http::Response<BoxBody<Bytes, Infallible>>) -> HttpResponse {
+    let (parts, body) = response.into_parts();
+
+    let status =
+        StatusCode::from_u16(parts.status.as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut builder = HttpResponse::build(status);
+    for (name, value) in parts.headers.iter() {
+        builder.append_header((name.as_str(), value.as_bytes()));
+    }
+
+    let is_event_stream = parts
+        .headers
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with(EVENT_STREAM_MIME_TYPE));
+
+    if is_event_stream {
+        builder.streaming(
+            body.into_data_stream()
+                .map(|chunk| chunk.map_err(|never| -> actix_web::Error { match never {} })),
+        )
+    } else {
+        match body.collect().await {
+            Ok(collected) => builder.body(collected.to_bytes()),
+            Err(never) => match never {},
+        }
+    }
+}
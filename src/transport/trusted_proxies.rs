@@ -0,0 +1,178 @@
+//! Client address resolution behind reverse proxies.
+//!
+//! The `Forwarded` and `X-Forwarded-For` headers are set by whoever sends the
+//! request, so they can only be believed when they were written by a proxy the
+//! deployment controls. A [`TrustedProxies`] list names those proxies: the
+//! client address is read from the forwarding headers only when the socket
+//! peer is trusted, walking the chain of hops from the nearest one and
+//! stopping at the first address that is not a trusted proxy.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! use rmcp_actix_web::transport::TrustedProxies;
+//!
+//! // The load balancer, and the private network of the ingress controllers
+//! let proxies = TrustedProxies::new()
+//!     .trust(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 10)))
+//!     .trust_network(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8);
+//! ```
+
+use std::net::{IpAddr, SocketAddr};
+
+use actix_web::{HttpRequest, http::header};
+
+const HEADER_X_FORWARDED_FOR: &str = "X-Forwarded-For";
+
+/// Networks of the reverse proxies whose forwarding headers are believed.
+///
+/// The default list is empty and trusts no proxy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedProxies {
+    networks: Vec<(IpAddr, u8)>,
+}
+
+impl TrustedProxies {
+    /// Creates an empty list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trusts a single proxy address.
+    pub fn trust(self, addr: IpAddr) -> Self {
+        let prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+        self.trust_network(addr, prefix_len)
+    }
+
+    /// Trusts every address of a network given in CIDR form.
+    ///
+    /// `prefix_len` is capped to the length of the address. An IPv4-mapped IPv6
+    /// network such as `::ffff:10.0.0.0/104` is treated as the IPv4 network it
+    /// maps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an IPv4-mapped network has a prefix shorter than 96 bits.
+    pub fn trust_network(mut self, addr: IpAddr, prefix_len: u8) -> Self {
+        self.networks.push(canonical_network(addr, prefix_len));
+        self
+    }
+
+    /// Returns `true` if `ip` belongs to a trusted proxy.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.networks
+            .iter()
+            .any(|&(network, prefix_len)| in_network(ip, network, prefix_len))
+    }
+
+    /// Resolves the address of the client behind `req`.
+    ///
+    /// Returns the peer address unless the peer is a trusted proxy, in which case
+    /// the forwarding chain is walked back to the first untrusted hop. Returns
+    /// `None` only when the peer address is unknown.
+    pub fn client_ip(&self, req: &HttpRequest) -> Option<IpAddr> {
+        let mut client = req.peer_addr()?.ip();
+        if !self.contains(client) {
+            return Some(client);
+        }
+        for hop in forwarded_chain(req).iter().rev() {
+            let Some(ip) = parse_hop(hop) else {
+                break;
+            };
+            client = ip;
+            if !self.contains(ip) {
+                break;
+            }
+        }
+        Some(client)
+    }
+}
+
+/// Returns a CIDR network in the form [`in_network`] expects.
+///
+/// IPv4-mapped IPv6 networks become IPv4 networks, since client addresses are
+/// canonicalized before they are matched, and `prefix_len` is capped to the
+/// length of the address.
+///
+/// # Panics
+///
+/// Panics if an IPv4-mapped network has a prefix shorter than 96 bits: such a
+/// network reaches outside the mapped range and has no IPv4 equivalent.
+pub(super) fn canonical_network(addr: IpAddr, prefix_len: u8) -> (IpAddr, u8) {
+    match (addr, addr.to_canonical()) {
+        (IpAddr::V6(_), IpAddr::V4(mapped)) => {
+            let prefix_len = prefix_len.checked_sub(96).unwrap_or_else(|| {
+                panic!(
+                    "the IPv4-mapped network {addr}/{prefix_len} needs a prefix of at least 96 bits"
+                )
+            });
+            (IpAddr::V4(mapped), prefix_len.min(32))
+        }
+        (IpAddr::V4(_), _) => (addr, prefix_len.min(32)),
+        (IpAddr::V6(_), _) => (addr, prefix_len.min(128)),
+    }
+}
+
+/// Returns `true` if `ip` belongs to the network `network/prefix_len`.
+///
+/// A prefix longer than the address matches nothing.
+pub(super) fn in_network(ip: IpAddr, network: IpAddr, prefix_len: u8) -> bool {
+    match (ip, network.to_canonical()) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => {
+            let Some(shift) = 32u32.checked_sub(u32::from(prefix_len)) else {
+                return false;
+            };
+            let mask = u32::MAX.checked_shl(shift).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) => {
+            let Some(shift) = 128u32.checked_sub(u32::from(prefix_len)) else {
+                return false;
+            };
+            let mask = u128::MAX.checked_shl(shift).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+/// Returns the forwarded client addresses of `req`, from the original client to
+/// the nearest proxy.
+///
+/// `Forwarded` takes precedence over `X-Forwarded-For` when both are present.
+fn forwarded_chain(req: &HttpRequest) -> Vec<String> {
+    let forwarded: Vec<String> = req
+        .headers()
+        .get_all(header::FORWARDED)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|element| {
+            element.split(';').find_map(|pair| {
+                let (name, value) = pair.trim().split_once('=')?;
+                name.eq_ignore_ascii_case("for")
+                    .then(|| value.trim_matches('"').to_string())
+            })
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+    req.headers()
+        .get_all(HEADER_X_FORWARDED_FOR)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|hop| hop.trim().to_string())
+        .collect()
+}
+
+/// Parses a forwarded hop, which may carry a port and IPv6 brackets.
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    hop.parse::<IpAddr>()
+        .or_else(|_| hop.parse::<SocketAddr>().map(|addr| addr.ip()))
+        .or_else(|_| hop.trim_start_matches('[').trim_end_matches(']').parse())
+        .ok()
+        .map(|ip| ip.to_canonical())
+}
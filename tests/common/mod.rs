@@ -8,3 +8,15 @@ pub mod calculator;
 
 /// Test service for verifying Authorization header forwarding.
 pub mod headers_test_service;
+
+/// Service sending server-initiated messages through its client peer.
+pub mod peer_service;
+
+/// Service with slow tools for request lifecycle tests.
+pub mod slow_service;
+
+/// Service answering with structured content only.
+pub mod structured_service;
+
+/// Ephemeral HTTP server and MCP request helpers shared by integration tests.
+pub mod test_server;
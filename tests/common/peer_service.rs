@@ -0,0 +1,147 @@
+//! Test service sending server-initiated messages through its client peer.
+//!
+//! Used to verify that messages which do not answer a POSTed request travel on
+//! the standalone GET stream, and that server-initiated requests are answered
+//! by the client through POST.
+
+#![allow(dead_code)]
+use std::time::Duration;
+
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    model::*,
+    schemars,
+    service::RequestContext,
+    tool, tool_handler, tool_router,
+};
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct TouchRequest {
+    /// URI of the resource to report as updated
+    pub uri: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct LogRequest {
+    /// Message logged to the client at warning level
+    pub message: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct AskRequest {
+    /// Question forwarded to the client
+    pub question: String,
+}
+
+#[derive(Clone)]
+pub struct PeerService {
+    #[expect(
+        dead_code,
+        reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+    )]
+    tool_router: ToolRouter<PeerService>,
+}
+
+#[tool_router]
+impl PeerService {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    /// Reports a resource as updated once the tool call has completed
+    #[tool(description = "Report a resource update after answering")]
+    async fn touch(
+        &self,
+        Parameters(TouchRequest { uri }): Parameters<TouchRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let peer = context.peer.clone();
+        tokio::spawn(async move {
+            // Sent after the response, outside of any request
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let _ = peer
+                .notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+                .await;
+            let _ = peer.notify_resource_list_changed().await;
+        });
+        Ok(CallToolResult::success(vec![Content::text("scheduled")]))
+    }
+
+    /// Logs a warning to the client before answering
+    #[tool(description = "Log a warning to the client")]
+    async fn log(
+        &self,
+        Parameters(LogRequest { message }): Parameters<LogRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let _ = context
+            .peer
+            .notify_logging_message(
+                LoggingMessageNotificationParam::new(LoggingLevel::Warning, message.into())
+                    .with_logger("peer_service"),
+            )
+            .await;
+        Ok(CallToolResult::success(vec![Content::text("logged")]))
+    }
+
+    /// Asks the client to sample a completion and answers with its result
+    #[tool(description = "Ask the client's model a question")]
+    async fn ask_llm(
+        &self,
+        Parameters(AskRequest { question }): Parameters<AskRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let result = context
+            .peer
+            .create_message(CreateMessageRequestParams::new(
+                vec![SamplingMessage::user_text(question)],
+                100,
+            ))
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string(&result).expect("serializable result"),
+        )]))
+    }
+
+    /// Asks the client to elicit a name from the user and answers with the result
+    #[tool(description = "Ask the user for their name")]
+    async fn ask_user(
+        &self,
+        Parameters(AskRequest { question }): Parameters<AskRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let requested_schema = ElicitationSchema::builder()
+            .required_string("name")
+            .build()
+            .map_err(|e| McpError::internal_error(e, None))?;
+        let result = context
+            .peer
+            .create_elicitation(CreateElicitationRequestParams::FormElicitationParams {
+                meta: None,
+                message: question,
+                requested_schema,
+            })
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string(&result).expect("serializable result"),
+        )]))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for PeerService {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(
+            ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_logging()
+                .build(),
+        )
+    }
+}
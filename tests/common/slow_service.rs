@@ -0,0 +1,116 @@
+//! Test service whose tools take a while to answer.
+//!
+//! Used by tests of request lifecycles such as shutdown draining and request
+//! timeouts.
+
+#![allow(dead_code)]
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler, handler::server::router::tool::ToolRouter,
+    model::*, service::RequestContext, tool, tool_handler, tool_router,
+};
+use rmcp_actix_web::transport::RequestCancellation;
+
+#[derive(Clone)]
+pub struct SlowService {
+    /// Number of `hang` and `hang_until_transport_cancels` calls that observed
+    /// their cancellation
+    pub cancelled: Arc<AtomicUsize>,
+    /// Number of `count` calls executed
+    calls: Arc<AtomicUsize>,
+    #[expect(
+        dead_code,
+        reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+    )]
+    tool_router: ToolRouter<SlowService>,
+}
+
+#[tool_router]
+impl SlowService {
+    pub fn new() -> Self {
+        Self::with_cancel_counter(Default::default())
+    }
+
+    /// Creates a service recording cancelled `hang` calls in `cancelled`.
+    pub fn with_cancel_counter(cancelled: Arc<AtomicUsize>) -> Self {
+        Self {
+            cancelled,
+            calls: Default::default(),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    /// Takes a while to answer
+    #[tool(description = "Answer after a delay")]
+    async fn slow(&self) -> Result<CallToolResult, McpError> {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        Ok(CallToolResult::success(vec![Content::text("done")]))
+    }
+
+    /// Reports three progress steps before answering
+    #[tool(description = "Report progress, then answer")]
+    async fn progress(
+        &self,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if let Some(token) = context.meta.get_progress_token() {
+            for step in 1..=3 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                let _ = context
+                    .peer
+                    .notify_progress(
+                        ProgressNotificationParam::new(token.clone(), f64::from(step))
+                            .with_total(3.0),
+                    )
+                    .await;
+            }
+        }
+        Ok(CallToolResult::success(vec![Content::text("done")]))
+    }
+
+    /// Counts its executions, answering with the count after a delay
+    #[tool(description = "Count executions after a delay")]
+    async fn count(&self) -> Result<CallToolResult, McpError> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "call {call}"
+        ))]))
+    }
+
+    /// Never answers; waits until the request is cancelled
+    #[tool(description = "Wait until cancelled")]
+    async fn hang(&self, context: RequestContext<RoleServer>) -> Result<CallToolResult, McpError> {
+        context.ct.cancelled().await;
+        self.cancelled.fetch_add(1, Ordering::SeqCst);
+        Ok(CallToolResult::success(vec![Content::text("cancelled")]))
+    }
+
+    /// Never answers; waits until the transport's `RequestCancellation` fires
+    #[tool(description = "Wait until the transport cancels the request")]
+    async fn hang_until_transport_cancels(
+        &self,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(cancellation) = context.extensions.get::<RequestCancellation>().cloned() else {
+            return Err(McpError::internal_error("No RequestCancellation", None));
+        };
+        cancellation.cancelled().await;
+        self.cancelled.fetch_add(1, Ordering::SeqCst);
+        Ok(CallToolResult::success(vec![Content::text("cancelled")]))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for SlowService {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+    }
+}
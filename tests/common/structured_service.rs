@@ -0,0 +1,43 @@
+//! Test service whose tool answers with structured content only.
+//!
+//! Used to verify how tool results are adapted to the protocol version a
+//! session negotiated.
+
+#![allow(dead_code)]
+use rmcp::{
+    ErrorData as McpError, ServerHandler, handler::server::router::tool::ToolRouter, model::*,
+    tool, tool_handler, tool_router,
+};
+
+#[derive(Clone)]
+pub struct StructuredService {
+    #[expect(
+        dead_code,
+        reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+    )]
+    tool_router: ToolRouter<StructuredService>,
+}
+
+#[tool_router]
+impl StructuredService {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    /// Answers with a structured result and no content block
+    #[tool(description = "Report statistics as structured content")]
+    async fn stats(&self) -> Result<CallToolResult, McpError> {
+        let mut result = CallToolResult::structured(serde_json::json!({ "sessions": 3 }));
+        result.content.clear();
+        Ok(result)
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for StructuredService {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+    }
+}
@@ -0,0 +1,260 @@
+//! Minimal HTTP test server and MCP request helpers.
+//!
+//! Spawns a real actix-web server bound to an ephemeral port so tests exercise
+//! the full HTTP stack (headers, streaming bodies, connection handling) with a
+//! `reqwest` client, the same way MCP clients talk to the transport.
+
+#![allow(dead_code)]
+use std::time::Duration;
+
+use actix_web::{App, HttpServer, web};
+use futures::StreamExt;
+use serde_json::{Value, json};
+
+pub const ACCEPT_BOTH: &str = "application/json, text/event-stream";
+
+/// A running actix-web server that is stopped when dropped.
+pub struct TestServer {
+    pub addr: std::net::SocketAddr,
+    pub client: reqwest::Client,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl TestServer {
+    /// Starts a server whose app is configured by `configure` on every worker.
+    pub async fn spawn<F>(configure: F) -> Self
+    where
+        F: Fn(&mut web::ServiceConfig) + Clone + Send + 'static,
+    {
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter("rmcp_actix_web=debug")
+            .with_test_writer()
+            .try_init();
+
+        let server = HttpServer::new(move || App::new().configure(configure.clone()))
+            .workers(1)
+            .bind("127.0.0.1:0")
+            .expect("Failed to bind server");
+
+        let addr = *server.addrs().first().unwrap();
+        let server_handle = server.run();
+        let task = tokio::spawn(async move {
+            let _ = server_handle.await;
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        Self {
+            addr,
+            client: reqwest::Client::new(),
+            task,
+        }
+    }
+
+    /// Absolute URL for `path` on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// POSTs a JSON-RPC message, optionally on an existing session.
+    pub async fn post(
+        &self,
+        path: &str,
+        session_id: Option<&str>,
+        body: &Value,
+    ) -> reqwest::Response {
+        let mut request = self
+            .client
+            .post(self.url(path))
+            .header("Accept", ACCEPT_BOTH)
+            .header("Content-Type", "application/json")
+            .json(body);
+        if let Some(session_id) = session_id {
+            request = request.header("Mcp-Session-Id", session_id);
+        }
+        request.send().await.expect("Failed to send request")
+    }
+
+    /// Runs the initialize handshake and returns the new session id.
+    pub async fn initialize(&self, path: &str) -> String {
+        let response = self.post(path, None, &initialize_request(1)).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let session_id = response
+            .headers()
+            .get("Mcp-Session-Id")
+            .and_then(|v| v.to_str().ok())
+            .expect("initialize response must carry Mcp-Session-Id")
+            .to_string();
+        let _ = read_sse_messages(response).await;
+
+        let response = self
+            .post(
+                path,
+                Some(&session_id),
+                &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            )
+            .await;
+        assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+        session_id
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Builds an `initialize` request with the given JSON-RPC id.
+pub fn initialize_request(id: i64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": { "name": "test-client", "version": "1.0.0" }
+        },
+        "id": id
+    })
+}
+
+/// Builds a `tools/call` request with the given JSON-RPC id.
+pub fn tool_call_request(id: i64, name: &str, arguments: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "tools/call",
+        "params": { "name": name, "arguments": arguments },
+        "id": id
+    })
+}
+
+/// Extracts the JSON payloads of every `data:` line in an SSE body.
+///
+/// Priming events (empty `data:` lines) are skipped.
+pub fn parse_sse_messages(body: &str) -> Vec<Value> {
+    body.lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim)
+        .filter(|data| !data.is_empty())
+        .filter_map(|data| serde_json::from_str(data).ok())
+        .collect()
+}
+
+/// Reads an SSE response until it ends (or two seconds pass) and returns its
+/// JSON payloads.
+pub async fn read_sse_messages(response: reqwest::Response) -> Vec<Value> {
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    let _ = tokio::time::timeout(Duration::from_secs(2), async {
+        while let Some(Ok(chunk)) = stream.next().await {
+            body.extend_from_slice(&chunk);
+        }
+    })
+    .await;
+    parse_sse_messages(&String::from_utf8_lossy(&body))
+}
+
+/// Reads SSE payloads from a long-lived stream until `count` messages arrived
+/// or `timeout` elapsed.
+pub async fn read_sse_messages_until(
+    response: reqwest::Response,
+    count: usize,
+    timeout: Duration,
+) -> Vec<Value> {
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    let _ = tokio::time::timeout(timeout, async {
+        while let Some(Ok(chunk)) = stream.next().await {
+            body.extend_from_slice(&chunk);
+            if parse_sse_messages(&String::from_utf8_lossy(&body)).len() >= count {
+                break;
+            }
+        }
+    })
+    .await;
+    parse_sse_messages(&String::from_utf8_lossy(&body))
+}
+
+/// A single parsed SSE event.
+#[derive(Debug, Clone, Default)]
+pub struct SseEvent {
+    pub id: Option<String>,
+    pub event: Option<String>,
+    pub data: String,
+}
+
+impl SseEvent {
+    /// Parses `data` as JSON.
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.data).expect("SSE data must be JSON")
+    }
+}
+
+/// Incremental reader over a long-lived SSE response.
+///
+/// Comment lines (keep-alive pings) are skipped.
+pub struct SseEventReader {
+    stream: futures::stream::BoxStream<'static, reqwest::Result<actix_web::web::Bytes>>,
+    buffer: String,
+}
+
+impl SseEventReader {
+    pub fn new(response: reqwest::Response) -> Self {
+        Self {
+            stream: response.bytes_stream().boxed(),
+            buffer: String::new(),
+        }
+    }
+
+    /// Waits up to `timeout` for the next event.
+    pub async fn next_event(&mut self, timeout: Duration) -> Option<SseEvent> {
+        tokio::time::timeout(timeout, async {
+            loop {
+                if let Some(end) = self.buffer.find("\n\n") {
+                    let raw: String = self.buffer.drain(..end + 2).collect();
+                    let mut event = SseEvent::default();
+                    let mut has_field = false;
+                    for line in raw.lines() {
+                        if let Some(value) = line.strip_prefix("id:") {
+                            event.id = Some(value.trim().to_string());
+                            has_field = true;
+                        } else if let Some(value) = line.strip_prefix("event:") {
+                            event.event = Some(value.trim().to_string());
+                            has_field = true;
+                        } else if let Some(value) = line.strip_prefix("data:") {
+                            event.data.push_str(value.trim());
+                            has_field = true;
+                        }
+                    }
+                    if has_field {
+                        return Some(event);
+                    }
+                    continue;
+                }
+                match self.stream.next().await {
+                    Some(Ok(chunk)) => self.buffer.push_str(&String::from_utf8_lossy(&chunk)),
+                    _ => return None,
+                }
+            }
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// Waits for the next event whose data is a JSON-RPC message with the given id.
+    pub async fn next_response(&mut self, id: i64, timeout: Duration) -> Option<Value> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let event = self.next_event(remaining).await?;
+            if let Ok(message) = serde_json::from_str::<Value>(&event.data)
+                && message["id"] == id
+            {
+                return Some(message);
+            }
+        }
+    }
+}
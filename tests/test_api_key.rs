@@ -0,0 +1,74 @@
+//! Integration tests for API key authentication.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{ApiKeyAuth, StreamableHttpService};
+use serde_json::Value;
+
+async fn spawn(auth: impl Into<ApiKeyAuth>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .require_api_key(auth)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn post_with_key(server: &TestServer, header: &str, key: &str) -> reqwest::Response {
+    server
+        .client
+        .post(server.url("/mcp"))
+        .header("Content-Type", "application/json")
+        .header("Accept", "application/json, text/event-stream")
+        .header(header, key)
+        .json(&initialize_request(1))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+#[actix_web::test]
+async fn api_key_is_required_before_mcp_processing() {
+    let server = spawn(["key-1", "key-2"]).await;
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert!(response.headers().get("Mcp-Session-Id").is_none());
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "missing_api_key");
+
+    let response = post_with_key(&server, "X-Api-Key", "key-3").await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "invalid_api_key");
+
+    let response = post_with_key(&server, "X-Api-Key", "key-2").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert!(response.headers().get("Mcp-Session-Id").is_some());
+}
+
+#[actix_web::test]
+async fn custom_header_and_validator() {
+    let server =
+        spawn(ApiKeyAuth::validator(|key| key.starts_with("svc_")).header("X-Internal-Key")).await;
+
+    let response = post_with_key(&server, "X-Api-Key", "svc_reporting").await;
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let response = post_with_key(&server, "X-Internal-Key", "usr_alice").await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let response = post_with_key(&server, "X-Internal-Key", "svc_reporting").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
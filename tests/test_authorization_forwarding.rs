@@ -603,3 +603,81 @@ async fn test_missing_authorization_doesnt_break_service() {
 
     server_task.abort();
 }
+
+#[cfg(feature = "authorization-token-passthrough")]
+#[actix_web::test]
+async fn test_authorization_from_legacy_sse_connection_is_forwarded() {
+    use common::test_server::{SseEventReader, TestServer, initialize_request, tool_call_request};
+
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(HeadersTestService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    // Credentials are only presented on the EventSource request
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .header("Authorization", "Bearer connection-token")
+        .send()
+        .await
+        .expect("Failed to open SSE stream");
+    let mut events = SseEventReader::new(response);
+    let endpoint = events
+        .next_event(Duration::from_secs(2))
+        .await
+        .expect("endpoint event")
+        .data;
+
+    let post = |body: Value, authorization: Option<&'static str>| {
+        let mut request = server.client.post(server.url(&endpoint)).json(&body);
+        if let Some(authorization) = authorization {
+            request = request.header("Authorization", authorization);
+        }
+        request.send()
+    };
+
+    post(initialize_request(1), None).await.unwrap();
+    events
+        .next_response(1, Duration::from_secs(2))
+        .await
+        .expect("initialize result");
+    post(
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        None,
+    )
+    .await
+    .unwrap();
+
+    post(tool_call_request(2, "get_current_auth", json!({})), None)
+        .await
+        .unwrap();
+    let result = events
+        .next_response(2, Duration::from_secs(2))
+        .await
+        .expect("tool result");
+    let text = result["result"]["content"][0]["text"].as_str().unwrap();
+    let auth: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(auth["authorization"], "Bearer connection-token");
+
+    // A token sent with the message itself takes precedence
+    post(
+        tool_call_request(3, "get_current_auth", json!({})),
+        Some("Bearer rotated-token"),
+    )
+    .await
+    .unwrap();
+    let result = events
+        .next_response(3, Duration::from_secs(2))
+        .await
+        .expect("tool result");
+    let text = result["result"]["content"][0]["text"].as_str().unwrap();
+    let auth: Value = serde_json::from_str(text).unwrap();
+    assert_eq!(auth["authorization"], "Bearer rotated-token");
+}
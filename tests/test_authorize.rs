@@ -0,0 +1,97 @@
+//! Integration tests for the per-request authorize hook.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use actix_web::{HttpMessage, dev::Service};
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::{
+    model::ErrorData, transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+/// Identity inserted by the stand-in authentication middleware.
+#[derive(Clone)]
+struct User(String);
+
+#[actix_web::test]
+async fn authorize_hook_sees_identity_method_and_tool() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .authorize_fn({
+            let seen = seen.clone();
+            move |auth_req| {
+                let user = auth_req.http_request.extensions().get::<User>().cloned();
+                seen.lock()
+                    .unwrap()
+                    .push((auth_req.method.clone(), auth_req.target.clone()));
+                async move {
+                    tokio::task::yield_now().await;
+                    match (auth_req.target.as_deref(), user) {
+                        (Some("sub"), Some(User(name))) if name != "admin" => Err(
+                            ErrorData::invalid_request(format!("{name} may not call sub"), None),
+                        ),
+                        _ => Ok(()),
+                    }
+                }
+            }
+        })
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp").wrap_fn(|req, srv| {
+            if let Some(user) = req.headers().get("X-User").and_then(|v| v.to_str().ok()) {
+                let user = User(user.to_string());
+                req.extensions_mut().insert(user);
+            }
+            srv.call(req)
+        }));
+    })
+    .await;
+    let session_id = server.initialize("/mcp").await;
+
+    let call = |id: i64, user: &'static str| {
+        server
+            .client
+            .post(server.url("/mcp"))
+            .header("Accept", "application/json, text/event-stream")
+            .header("Content-Type", "application/json")
+            .header("Mcp-Session-Id", &session_id)
+            .header("X-User", user)
+            .json(&tool_call_request(id, "sub", json!({ "a": 5, "b": 3 })))
+            .send()
+    };
+
+    let response = call(2, "alice").await.expect("Failed to send request");
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 2);
+    assert_eq!(error["error"]["code"], -32600);
+    assert_eq!(error["error"]["message"], "alice may not call sub");
+
+    let response = call(3, "admin").await.expect("Failed to send request");
+    let messages = read_sse_messages(response).await;
+    let result = messages.iter().find(|m| m["id"] == 3).expect("sub result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 2);
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0], ("initialize".to_string(), None));
+    assert_eq!(
+        seen[1..],
+        [
+            ("tools/call".to_string(), Some("sub".to_string())),
+            ("tools/call".to_string(), Some("sub".to_string())),
+        ]
+    );
+}
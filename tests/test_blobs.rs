@@ -0,0 +1,148 @@
+//! Integration tests for side-channel blob downloads.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::test_server::{TestServer, read_sse_messages, tool_call_request};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+mod report_service {
+    use rmcp::{
+        ErrorData as McpError, RoleServer, ServerHandler,
+        handler::server::router::tool::ToolRouter, model::*, service::RequestContext, tool,
+        tool_handler, tool_router,
+    };
+    use rmcp_actix_web::transport::BlobLinks;
+
+    #[derive(Clone)]
+    pub struct ReportService {
+        #[expect(
+            dead_code,
+            reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+        )]
+        tool_router: ToolRouter<ReportService>,
+    }
+
+    #[tool_router]
+    impl ReportService {
+        pub fn new() -> Self {
+            Self {
+                tool_router: Self::tool_router(),
+            }
+        }
+
+        /// Publishes a binary report and returns its download URL
+        #[tool(description = "Render a report")]
+        async fn report(
+            &self,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            let links = context
+                .extensions
+                .get::<BlobLinks>()
+                .ok_or_else(|| McpError::internal_error("blob links unavailable", None))?;
+            let url = links.publish(vec![0u8, 1, 2, 255], "application/octet-stream");
+            Ok(CallToolResult::success(vec![Content::text(url)]))
+        }
+    }
+
+    #[tool_handler]
+    impl ServerHandler for ReportService {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        }
+    }
+}
+
+async fn spawn_server(ttl: Duration) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(report_service::ReportService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .blob_ttl(ttl)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Calls the `report` tool and returns the URL of the published blob.
+async fn publish_report(server: &TestServer, session_id: &str) -> String {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &tool_call_request(2, "report", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let url = messages[0]["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap_or_else(|| panic!("tool must return a URL: {messages:?}"))
+        .to_string();
+    assert!(url.starts_with(&server.url("/mcp/blobs/")), "{url}");
+    url
+}
+
+async fn download(server: &TestServer, url: &str, session_id: Option<&str>) -> reqwest::Response {
+    let mut request = server.client.get(url);
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to download blob")
+}
+
+#[actix_web::test]
+async fn published_blob_is_downloadable_by_its_session() {
+    let server = spawn_server(Duration::from_secs(60)).await;
+    let session_id = server.initialize("/mcp").await;
+    let url = publish_report(&server, &session_id).await;
+
+    let response = download(&server, &url, Some(&session_id)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/octet-stream"
+    );
+    assert_eq!(response.bytes().await.unwrap().as_ref(), [0, 1, 2, 255]);
+}
+
+#[actix_web::test]
+async fn blob_is_scoped_to_its_session() {
+    let server = spawn_server(Duration::from_secs(60)).await;
+    let session_id = server.initialize("/mcp").await;
+    let other_session_id = server.initialize("/mcp").await;
+    let url = publish_report(&server, &session_id).await;
+
+    let response = download(&server, &url, None).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    let response = download(&server, &url, Some(&other_session_id)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // Closing the session discards its blobs
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send DELETE");
+    assert!(response.status().is_success());
+    let response = download(&server, &url, Some(&session_id)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn blob_expires() {
+    let server = spawn_server(Duration::from_millis(100)).await;
+    let session_id = server.initialize("/mcp").await;
+    let url = publish_report(&server, &session_id).await;
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let response = download(&server, &url, Some(&session_id)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,115 @@
+//! Integration tests for serving blocking handler code.
+
+mod common;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use common::test_server::{TestServer, read_sse_messages};
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    model::{
+        ListResourceTemplatesResult, ListResourcesResult, PaginatedRequestParams, RawResource,
+        Resource, ServerCapabilities, ServerInfo,
+    },
+    service::RequestContext,
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::{BlockingHandler, StreamableHttpService, run_blocking};
+use serde_json::{Value, json};
+
+const BLOCKING_TIME: Duration = Duration::from_millis(500);
+
+/// Handler blocking its thread while listing resources.
+struct LegacyInventory;
+
+impl ServerHandler for LegacyInventory {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_resources().build())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        std::thread::sleep(BLOCKING_TIME);
+        Ok(ListResourcesResult::with_all_items(vec![Resource::new(
+            RawResource::new("inventory://items", "items"),
+            None,
+        )]))
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        run_blocking(|| -> Result<ListResourceTemplatesResult, McpError> {
+            panic!("inventory corrupted")
+        })
+        .await
+    }
+}
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(BlockingHandler::new(LegacyInventory))))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn request(server: &TestServer, session_id: &str, id: i64, method: &str) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+    let response = server.post("/mcp", Some(session_id), &request).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response)
+        .await
+        .into_iter()
+        .find(|m| m["id"] == id)
+        .expect("request must be answered")
+}
+
+#[actix_web::test]
+async fn blocking_handler_does_not_freeze_worker() {
+    let server = Arc::new(spawn_server().await);
+    let session_id = server.initialize("/mcp").await;
+
+    let listing = tokio::spawn({
+        let server = server.clone();
+        let session_id = session_id.clone();
+        async move { request(&server, &session_id, 2, "resources/list").await }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // The single worker keeps serving while the listing blocks its thread
+    let started = Instant::now();
+    let ping = request(&server, &session_id, 3, "ping").await;
+    assert!(
+        ping.get("result").is_some(),
+        "unexpected ping response: {ping}"
+    );
+    assert!(started.elapsed() < BLOCKING_TIME / 2);
+
+    let listing = listing.await.unwrap();
+    assert_eq!(
+        listing["result"]["resources"][0]["uri"],
+        "inventory://items"
+    );
+}
+
+#[actix_web::test]
+async fn panic_in_blocking_code_is_internal_error() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = request(&server, &session_id, 2, "resources/templates/list").await;
+    assert_eq!(response["error"]["code"], -32603);
+    assert_eq!(response["error"]["message"], "Blocking task failed");
+}
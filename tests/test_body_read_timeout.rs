@@ -0,0 +1,73 @@
+//! Integration tests for the request body read timeout.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{ACCEPT_BOTH, TestServer, read_sse_messages, tool_call_request},
+};
+use futures::StreamExt;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+async fn spawn_server() -> TestServer {
+    TestServer::spawn(|cfg| {
+        let service = StreamableHttpService::builder()
+            .service_factory(Arc::new(|| Ok(Calculator::new())))
+            .session_manager(Arc::new(LocalSessionManager::default()))
+            .body_read_timeout(Duration::from_millis(200))
+            .build();
+        cfg.service(service.scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn slow_body_is_rejected_with_408() {
+    let server = spawn_server().await;
+
+    // Announces a body that never arrives after its first chunk
+    let chunks =
+        futures::stream::iter([Ok::<_, std::io::Error>(b"{\"jsonrpc\":\"2.0\",".to_vec())])
+            .chain(futures::stream::pending());
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .header("Content-Type", "application/json")
+        .header("Content-Length", "1024")
+        .body(reqwest::Body::wrap_stream(chunks))
+        .send();
+    let response = tokio::time::timeout(Duration::from_secs(5), response)
+        .await
+        .expect("server did not answer the slow request")
+        .unwrap();
+
+    assert_eq!(response.status(), reqwest::StatusCode::REQUEST_TIMEOUT);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/problem+json"
+    );
+    let problem: Value = response.json().await.unwrap();
+    assert_eq!(problem["code"], "body_read_timeout");
+}
+
+#[actix_web::test]
+async fn complete_body_is_served() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sum", json!({ "a": 1, "b": 2 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages.last().unwrap()["id"], 2);
+}
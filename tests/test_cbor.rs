@@ -0,0 +1,215 @@
+//! Integration tests for CBOR content negotiation.
+
+#![cfg(feature = "cbor")]
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use base64::Engine;
+use common::{
+    calculator::Calculator,
+    test_server::{SseEventReader, TestServer, initialize_request, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Map, Value, json};
+
+const ACCEPT_CBOR: &str = "application/cbor, text/event-stream";
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+fn head(major: u8, n: u64, out: &mut Vec<u8>) {
+    out.push(major << 5 | 27);
+    out.extend_from_slice(&n.to_be_bytes());
+}
+
+/// Encodes the subset of CBOR needed by the tests, using indefinite-length
+/// arrays and maps to exercise the server's decoder.
+fn to_cbor(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xf6),
+        Value::Bool(b) => out.push(if *b { 0xf5 } else { 0xf4 }),
+        Value::Number(n) => match n.as_u64() {
+            Some(n) => head(0, n, out),
+            None => head(1, !n.as_i64().expect("integer") as u64, out),
+        },
+        Value::String(s) => {
+            head(3, s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(items) => {
+            out.push(0x9f);
+            items.iter().for_each(|item| to_cbor(item, out));
+            out.push(0xff);
+        }
+        Value::Object(map) => {
+            out.push(0xbf);
+            for (key, value) in map {
+                to_cbor(&Value::String(key.clone()), out);
+                to_cbor(value, out);
+            }
+            out.push(0xff);
+        }
+    }
+}
+
+/// Decodes the definite-length CBOR produced by the server.
+fn from_cbor(bytes: &mut &[u8]) -> Value {
+    fn take<'a>(bytes: &mut &'a [u8], len: usize) -> &'a [u8] {
+        let (head, tail) = bytes.split_at(len);
+        *bytes = tail;
+        head
+    }
+    fn argument(bytes: &mut &[u8], info: u8) -> u64 {
+        let len = match info {
+            0..=23 => return u64::from(info),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => panic!("unexpected additional information {info}"),
+        };
+        take(bytes, len)
+            .iter()
+            .fold(0, |acc, b| acc << 8 | u64::from(*b))
+    }
+
+    let initial = take(bytes, 1)[0];
+    let (major, info) = (initial >> 5, initial & 0x1f);
+    match major {
+        0 => json!(argument(bytes, info)),
+        1 => json!(-1 - argument(bytes, info) as i64),
+        3 => {
+            let len = argument(bytes, info) as usize;
+            Value::String(String::from_utf8(take(bytes, len).to_vec()).unwrap())
+        }
+        4 => {
+            let len = argument(bytes, info);
+            Value::Array((0..len).map(|_| from_cbor(bytes)).collect())
+        }
+        5 => {
+            let mut map = Map::new();
+            for _ in 0..argument(bytes, info) {
+                let Value::String(key) = from_cbor(bytes) else {
+                    panic!("non-text key");
+                };
+                map.insert(key, from_cbor(bytes));
+            }
+            Value::Object(map)
+        }
+        7 => match info {
+            20 => json!(false),
+            21 => json!(true),
+            22 => Value::Null,
+            27 => json!(f64::from_be_bytes(take(bytes, 8).try_into().unwrap())),
+            _ => panic!("unexpected simple value {info}"),
+        },
+        _ => panic!("unexpected major type {major}"),
+    }
+}
+
+fn encode(message: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    to_cbor(message, &mut bytes);
+    bytes
+}
+
+async fn post_cbor(
+    server: &TestServer,
+    session_id: Option<&str>,
+    body: Vec<u8>,
+) -> reqwest::Response {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_CBOR)
+        .header("Content-Type", "application/cbor")
+        .body(body);
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+/// Reads the next message event, decoding its base64 CBOR payload.
+async fn next_cbor_message(events: &mut SseEventReader) -> Value {
+    loop {
+        let event = events
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("stream must deliver a message");
+        if !event.data.is_empty() {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(&event.data)
+                .expect("payload must be base64");
+            let mut bytes = bytes.as_slice();
+            let message = from_cbor(&mut bytes);
+            assert!(bytes.is_empty(), "trailing bytes after {message}");
+            return message;
+        }
+    }
+}
+
+#[actix_web::test]
+async fn cbor_client_round_trip() {
+    let server = spawn_server().await;
+
+    let response = post_cbor(&server, None, encode(&initialize_request(1))).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers().get("X-MCP-Payload-Encoding").unwrap(),
+        "cbor"
+    );
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let message = next_cbor_message(&mut SseEventReader::new(response)).await;
+    assert_eq!(message["id"], 1);
+    assert!(message["result"]["serverInfo"].is_object(), "{message}");
+
+    let response = post_cbor(
+        &server,
+        Some(&session_id),
+        encode(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let response = post_cbor(
+        &server,
+        Some(&session_id),
+        encode(&tool_call_request(2, "sum", json!({ "a": 40, "b": -2 }))),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let message = next_cbor_message(&mut SseEventReader::new(response)).await;
+    assert_eq!(message["id"], 2);
+    assert_eq!(message["result"]["structuredContent"]["value"], 38);
+}
+
+#[actix_web::test]
+async fn malformed_cbor_is_a_bad_request() {
+    let server = spawn_server().await;
+    // An indefinite-length map missing its break marker
+    let response = post_cbor(&server, None, vec![0xbf]).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // A valid message followed by stray bytes
+    let mut body = encode(&initialize_request(1));
+    body.push(0xf6);
+    let response = post_cbor(&server, None, body).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,99 @@
+//! Tests for the circuit breaker around upstream calls.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::model::ErrorData;
+use rmcp_actix_web::transport::{
+    CircuitBreaker, CircuitState, circuit_breaker::UPSTREAM_UNAVAILABLE_CODE,
+};
+
+const OPEN_DURATION: Duration = Duration::from_millis(200);
+
+async fn fail(breaker: &CircuitBreaker, calls: &Arc<AtomicUsize>) -> Result<(), ErrorData> {
+    breaker
+        .call(async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(ErrorData::internal_error("upstream down", None))
+        })
+        .await
+}
+
+async fn succeed(breaker: &CircuitBreaker, calls: &Arc<AtomicUsize>) -> Result<(), ErrorData> {
+    breaker
+        .call(async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+        .await
+}
+
+#[actix_web::test]
+async fn opens_after_consecutive_failures() {
+    let breaker = CircuitBreaker::new(3, OPEN_DURATION);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    for _ in 0..3 {
+        assert!(fail(&breaker, &calls).await.is_err());
+    }
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    let error = succeed(&breaker, &calls).await.unwrap_err();
+    assert_eq!(error.code, UPSTREAM_UNAVAILABLE_CODE);
+    assert!(error.data.unwrap()["retry_after_secs"].is_u64());
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        3,
+        "open circuit skips the upstream"
+    );
+}
+
+#[actix_web::test]
+async fn success_resets_failure_count() {
+    let breaker = CircuitBreaker::new(2, OPEN_DURATION);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    assert!(fail(&breaker, &calls).await.is_err());
+    assert!(succeed(&breaker, &calls).await.is_ok());
+    assert!(fail(&breaker, &calls).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Closed);
+}
+
+#[actix_web::test]
+async fn half_open_trial_closes_or_reopens() {
+    let breaker = CircuitBreaker::new(1, OPEN_DURATION);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    assert!(fail(&breaker, &calls).await.is_err());
+    tokio::time::sleep(OPEN_DURATION).await;
+    assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+    // A failed trial reopens the circuit
+    assert!(fail(&breaker, &calls).await.is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+
+    tokio::time::sleep(OPEN_DURATION).await;
+    assert!(succeed(&breaker, &calls).await.is_ok());
+    assert_eq!(breaker.state(), CircuitState::Closed);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[actix_web::test]
+async fn timed_out_calls_count_as_failures() {
+    let breaker =
+        CircuitBreaker::new(1, OPEN_DURATION).with_call_timeout(Duration::from_millis(50));
+
+    let result = breaker
+        .call(async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        })
+        .await;
+    assert!(result.is_err());
+    assert_eq!(breaker.state(), CircuitState::Open);
+}
@@ -0,0 +1,227 @@
+//! Integration tests for loading and applying `TransportConfig`.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{
+    ConfigError, MessageFilter, StreamableHttpService, TransportConfig,
+};
+use serde_json::json;
+
+#[test]
+fn json_config_overrides_defaults() {
+    let config = TransportConfig::from_json_str(
+        r#"{
+            "bind_address": "0.0.0.0:9000",
+            "sse_keep_alive_secs": 15,
+            "allowed_origins": ["https://app.example.com"]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(config.bind_address, "0.0.0.0:9000");
+    assert_eq!(config.sse_keep_alive(), Some(Duration::from_secs(15)));
+    assert_eq!(config.allowed_origins, vec!["https://app.example.com"]);
+    // Fields missing from the document keep their defaults
+    assert!(config.stateful_mode);
+    assert_eq!(config.max_body_size, None);
+
+    assert!(matches!(
+        TransportConfig::from_json_str(r#"{ "stateful": false }"#),
+        Err(ConfigError::Json(_))
+    ));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_config_overrides_defaults() {
+    let config = TransportConfig::from_toml_str(
+        r#"
+            bind_address = "0.0.0.0:9000"
+            sse_keep_alive_secs = 15
+            allowed_origins = ["https://app.example.com"]
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.bind_address, "0.0.0.0:9000");
+    assert_eq!(config.sse_keep_alive(), Some(Duration::from_secs(15)));
+    assert_eq!(config.allowed_origins, vec!["https://app.example.com"]);
+    // Fields missing from the document keep their defaults
+    assert!(config.stateful_mode);
+    assert_eq!(config.max_body_size, None);
+
+    assert!(matches!(
+        TransportConfig::from_toml_str("stateful = false"),
+        Err(ConfigError::Toml(_))
+    ));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_config_is_read_from_file() {
+    let path = std::env::temp_dir().join(format!("rmcp-actix-web-{}.toml", std::process::id()));
+    std::fs::write(&path, "stateful_mode = false\nmax_body_size = 2048\n").unwrap();
+    let config = TransportConfig::from_toml_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let config = config.unwrap();
+    assert!(!config.stateful_mode);
+    assert_eq!(config.max_body_size, Some(2048));
+
+    assert!(matches!(
+        TransportConfig::from_toml_file(&path),
+        Err(ConfigError::Io(_))
+    ));
+}
+
+#[test]
+fn env_config_reads_prefixed_variables() {
+    // SAFETY: this is the only test in this binary touching these variables.
+    unsafe {
+        std::env::set_var("MCP_STATEFUL_MODE", "false");
+        std::env::set_var("MCP_MAX_BODY_SIZE", "1024");
+        std::env::set_var("MCP_CORS", "true");
+        std::env::set_var(
+            "MCP_ALLOWED_ORIGINS",
+            "https://a.example, https://b.example",
+        );
+    }
+    let config = TransportConfig::from_env().unwrap();
+    assert!(!config.stateful_mode);
+    assert_eq!(config.max_body_size, Some(1024));
+    assert!(config.cors);
+    assert_eq!(
+        config.allowed_origins,
+        vec!["https://a.example", "https://b.example"]
+    );
+    assert_eq!(config.bind_address, TransportConfig::default().bind_address);
+
+    unsafe { std::env::set_var("MCP_MAX_BODY_SIZE", "lots") };
+    let error = TransportConfig::from_env().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        r#"invalid value for MCP_MAX_BODY_SIZE: "lots""#
+    );
+
+    unsafe {
+        std::env::remove_var("MCP_STATEFUL_MODE");
+        std::env::remove_var("MCP_MAX_BODY_SIZE");
+        std::env::remove_var("MCP_CORS");
+        std::env::remove_var("MCP_ALLOWED_ORIGINS");
+    }
+}
+
+async fn spawn_from_config(config: TransportConfig) -> TestServer {
+    let service = StreamableHttpService::from_config(&config)
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn from_config_rejects_unlisted_origins() {
+    let server = spawn_from_config(TransportConfig {
+        allowed_origins: vec!["https://app.example.com".into()],
+        ..Default::default()
+    })
+    .await;
+
+    let send = |origin: &'static str| {
+        server
+            .client
+            .post(server.url("/mcp"))
+            .header("Accept", common::test_server::ACCEPT_BOTH)
+            .header("Origin", origin)
+            .json(&initialize_request(1))
+            .send()
+    };
+    let response = send("https://evil.example.com").await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    assert!(response.headers().get("Mcp-Session-Id").is_none());
+
+    let response = send("https://APP.example.com").await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // Clients that send no Origin are not browsers and are accepted
+    server.initialize("/mcp").await;
+}
+
+#[actix_web::test]
+async fn from_config_limits_body_size() {
+    let server = spawn_from_config(TransportConfig {
+        max_body_size: Some(512),
+        ..Default::default()
+    })
+    .await;
+
+    let session_id = server.initialize("/mcp").await;
+    let padding = "x".repeat(1024);
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/list",
+                "params": { "_meta": { "padding": padding } }
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[actix_web::test]
+async fn from_config_combines_with_builder_options() {
+    let config = TransportConfig::from_json_str(
+        r#"{ "allowed_origins": ["https://app.example.com"], "cors": true }"#,
+    )
+    .unwrap();
+    let service = StreamableHttpService::from_config(&config)
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .message_filter(MessageFilter::new().deny_tools(["sub"]))
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    // CORS from the configuration
+    let response = server
+        .client
+        .request(reqwest::Method::OPTIONS, server.url("/mcp"))
+        .header("Origin", "https://app.example.com")
+        .header("Access-Control-Request-Method", "POST")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()["Access-Control-Allow-Origin"],
+        "https://app.example.com"
+    );
+
+    // Message filter from the builder
+    let session_id = server.initialize("/mcp").await;
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sub", json!({ "a": 2, "b": 1 })),
+        )
+        .await;
+    let error: serde_json::Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 2);
+    assert_eq!(error["error"]["message"], "Tool not allowed: sub");
+}
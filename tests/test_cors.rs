@@ -0,0 +1,116 @@
+//! Integration tests for the built-in OPTIONS, HEAD and CORS handling.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+
+const APP_ORIGIN: &str = "https://app.example.com";
+
+async fn spawn_server(cors: bool) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .allowed_origins(vec![APP_ORIGIN.into()])
+        .cors(cors)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn preflight(server: &TestServer, origin: &str) -> reqwest::Response {
+    server
+        .client
+        .request(reqwest::Method::OPTIONS, server.url("/mcp"))
+        .header("Origin", origin)
+        .header("Access-Control-Request-Method", "POST")
+        .header(
+            "Access-Control-Request-Headers",
+            "content-type, mcp-session-id",
+        )
+        .send()
+        .await
+        .expect("Failed to send preflight")
+}
+
+fn header<'a>(response: &'a reqwest::Response, name: &str) -> Option<&'a str> {
+    response.headers().get(name).and_then(|v| v.to_str().ok())
+}
+
+#[actix_web::test]
+async fn preflight_follows_allowed_origins() {
+    let server = spawn_server(true).await;
+
+    let response = preflight(&server, APP_ORIGIN).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    assert_eq!(
+        header(&response, "Access-Control-Allow-Origin"),
+        Some(APP_ORIGIN)
+    );
+    assert_eq!(
+        header(&response, "Access-Control-Allow-Methods"),
+        Some("GET, POST, DELETE, HEAD, OPTIONS")
+    );
+    assert_eq!(
+        header(&response, "Access-Control-Allow-Headers"),
+        Some("content-type, mcp-session-id")
+    );
+
+    let response = preflight(&server, "https://evil.example.com").await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    assert!(header(&response, "Access-Control-Allow-Origin").is_none());
+}
+
+#[actix_web::test]
+async fn responses_expose_session_id_to_allowed_origins() {
+    let server = spawn_server(true).await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Origin", APP_ORIGIN)
+        .json(&initialize_request(1))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        header(&response, "Access-Control-Allow-Origin"),
+        Some(APP_ORIGIN)
+    );
+    assert!(
+        header(&response, "Access-Control-Expose-Headers")
+            .is_some_and(|exposed| exposed.contains("Mcp-Session-Id"))
+    );
+}
+
+#[actix_web::test]
+async fn options_and_head_without_cors() {
+    let server = spawn_server(false).await;
+
+    let response = preflight(&server, APP_ORIGIN).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    assert_eq!(
+        header(&response, "Allow"),
+        Some("GET, POST, DELETE, HEAD, OPTIONS")
+    );
+    assert!(header(&response, "Access-Control-Allow-Origin").is_none());
+
+    let response = server
+        .client
+        .head(server.url("/mcp"))
+        .send()
+        .await
+        .expect("Failed to send HEAD request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
@@ -0,0 +1,118 @@
+//! Integration tests for the cleanup of requests whose client disconnected.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{ACCEPT_BOTH, TestServer, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+async fn spawn_server(stateful: bool, cancelled: Arc<AtomicUsize>) -> TestServer {
+    // Request streams without event ids cannot be resumed
+    let mut session_manager = LocalSessionManager::default();
+    session_manager.session_config.sse_retry = None;
+    spawn_server_with(stateful, cancelled, session_manager).await
+}
+
+async fn spawn_server_with(
+    stateful: bool,
+    cancelled: Arc<AtomicUsize>,
+    session_manager: LocalSessionManager,
+) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            Ok(SlowService::with_cancel_counter(cancelled.clone()))
+        }))
+        .session_manager(Arc::new(session_manager))
+        .stateful_mode(stateful)
+        // Disconnects are noticed on the next write
+        .sse_keep_alive(Duration::from_millis(50))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn wait_until(condition: impl Fn() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("condition not reached after the client disconnected");
+}
+
+#[actix_web::test]
+async fn abandoned_request_is_cancelled_in_the_session() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(true, cancelled.clone()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    drop(response);
+
+    wait_until(|| cancelled.load(Ordering::SeqCst) == 1).await;
+}
+
+#[actix_web::test]
+async fn abandoned_stateless_request_is_aborted() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(false, cancelled.clone()).await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .json(&tool_call_request(1, "hang", json!({})))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    // The test, the service factory, and the hanging request's service instance
+    assert_eq!(Arc::strong_count(&cancelled), 3);
+    drop(response);
+
+    // Aborting the request task drops its service instance
+    wait_until(|| Arc::strong_count(&cancelled) == 2).await;
+}
+
+#[actix_web::test]
+async fn resumable_request_survives_disconnect() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    // Priming events give request streams an event id to resume from
+    let server = spawn_server_with(true, cancelled.clone(), LocalSessionManager::default()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    drop(response);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(cancelled.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,205 @@
+//! Integration tests for caching of discovery results.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use actix_web::{HttpMessage, dev::Service};
+use common::test_server::{ACCEPT_BOTH, TestServer, read_sse_messages, tool_call_request};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{StreamableHttpService, TenantId};
+use serde_json::{Value, json};
+
+mod catalog_service {
+    use std::{
+        borrow::Cow,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use rmcp::{
+        ErrorData as McpError, RoleServer, ServerHandler, model::*, service::RequestContext,
+    };
+
+    /// Service counting how often its tool list is computed.
+    #[derive(Clone)]
+    pub struct CatalogService {
+        pub listed: Arc<AtomicUsize>,
+    }
+
+    impl ServerHandler for CatalogService {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        }
+
+        async fn list_tools(
+            &self,
+            _request: Option<PaginatedRequestParams>,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<ListToolsResult, McpError> {
+            let listed = self.listed.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ListToolsResult::with_all_items(vec![Tool::new(
+                Cow::Owned(format!("tool_{listed}")),
+                "Changes the tool list",
+                Arc::new(JsonObject::new()),
+            )]))
+        }
+
+        /// Announces a change of the tool list, then answers
+        async fn call_tool(
+            &self,
+            _request: CallToolRequestParams,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            let _ = context.peer.notify_tool_list_changed().await;
+            Ok(CallToolResult::success(vec![Content::text("changed")]))
+        }
+    }
+}
+
+async fn spawn_server(stateful_mode: bool, listed: Arc<AtomicUsize>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            Ok(catalog_service::CatalogService {
+                listed: listed.clone(),
+            })
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful_mode)
+        .discovery_cache_ttl(Duration::from_secs(60))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Lists the tools and returns the name of the first one.
+async fn first_tool(server: &TestServer, session_id: Option<&str>, id: i64) -> String {
+    let request = json!({ "jsonrpc": "2.0", "id": id, "method": "tools/list" });
+    let response = server.post("/mcp", session_id, &request).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let result: &Value = messages
+        .iter()
+        .find(|m| m["id"] == id)
+        .expect("tools/list must be answered");
+    result["result"]["tools"][0]["name"]
+        .as_str()
+        .expect("tool name")
+        .to_string()
+}
+
+#[actix_web::test]
+async fn repeated_lists_are_answered_from_cache() {
+    let listed = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(true, listed.clone()).await;
+    let session_id = server.initialize("/mcp").await;
+    let other_session_id = server.initialize("/mcp").await;
+
+    assert_eq!(first_tool(&server, Some(&session_id), 2).await, "tool_1");
+    assert_eq!(first_tool(&server, Some(&session_id), 3).await, "tool_1");
+    assert_eq!(
+        first_tool(&server, Some(&other_session_id), 2).await,
+        "tool_1"
+    );
+    assert_eq!(listed.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn list_changed_notification_invalidates_cache() {
+    let listed = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(true, listed.clone()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    assert_eq!(first_tool(&server, Some(&session_id), 2).await, "tool_1");
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(3, "tool_1", json!({})),
+        )
+        .await;
+    // Nobody listens on the standalone stream; the cache is invalidated anyway
+    let messages = read_sse_messages(response).await;
+    assert!(messages.iter().any(|m| m["id"] == 3));
+
+    assert_eq!(first_tool(&server, Some(&session_id), 4).await, "tool_2");
+    assert_eq!(listed.load(Ordering::SeqCst), 2);
+}
+
+#[actix_web::test]
+async fn stateless_lists_are_answered_from_cache() {
+    let listed = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(false, listed.clone()).await;
+
+    assert_eq!(first_tool(&server, None, 1).await, "tool_1");
+    assert_eq!(first_tool(&server, None, 2).await, "tool_1");
+    assert_eq!(listed.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn lists_are_cached_per_tenant() {
+    let listed_by_a = Arc::new(AtomicUsize::new(0));
+    let listed_by_b = Arc::new(AtomicUsize::new(0));
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| {
+            Ok(catalog_service::CatalogService {
+                listed: Arc::new(AtomicUsize::new(0)),
+            })
+        }))
+        .tenant_service_factory({
+            let listed_by_a = listed_by_a.clone();
+            let listed_by_b = listed_by_b.clone();
+            Arc::new(move |tenant: &TenantId| {
+                let listed = match tenant.0.as_str() {
+                    "a" => listed_by_a.clone(),
+                    _ => listed_by_b.clone(),
+                };
+                Ok(catalog_service::CatalogService { listed })
+            })
+        })
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(false)
+        .discovery_cache_ttl(Duration::from_secs(60))
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp").wrap_fn(|req, srv| {
+            if let Some(tenant) = req.headers().get("X-Tenant").and_then(|v| v.to_str().ok()) {
+                let tenant = TenantId(tenant.to_string());
+                req.extensions_mut().insert(tenant);
+            }
+            srv.call(req)
+        }));
+    })
+    .await;
+
+    let list_as = |tenant: &'static str, id: i64| {
+        server
+            .client
+            .post(server.url("/mcp"))
+            .header("Accept", ACCEPT_BOTH)
+            .header("X-Tenant", tenant)
+            .json(&json!({ "jsonrpc": "2.0", "id": id, "method": "tools/list" }))
+            .send()
+    };
+    for (tenant, id) in [("a", 1), ("a", 2), ("b", 3), ("b", 4)] {
+        let response = list_as(tenant, id).await.expect("Failed to send request");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let messages = read_sse_messages(response).await;
+        assert!(messages.iter().any(|m| m["id"] == id));
+    }
+
+    // Each tenant's handler lists once; tenant b is never answered with a's page
+    assert_eq!(listed_by_a.load(Ordering::SeqCst), 1);
+    assert_eq!(listed_by_b.load(Ordering::SeqCst), 1);
+}
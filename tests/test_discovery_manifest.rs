@@ -0,0 +1,104 @@
+//! Integration tests for the well-known discovery manifest route.
+
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use actix_web::{App, test, web};
+use common::calculator::Calculator;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{DiscoveryAuth, StreamableHttpService};
+use serde_json::Value;
+
+#[actix_web::test]
+async fn manifest_describes_mounted_endpoint() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .discovery_auth(DiscoveryAuth::Bearer {
+            resource_metadata: Some(
+                "https://example.com/.well-known/oauth-protected-resource".to_string(),
+            ),
+        })
+        .build();
+
+    let app = test::init_service(
+        App::new()
+            .service(service.well_known_manifest("/api/mcp"))
+            .service(web::scope("/api/mcp").service(service.clone().scope())),
+    )
+    .await;
+
+    let req = test::TestRequest::get()
+        .uri("/.well-known/mcp.json")
+        .to_request();
+    let manifest: Value = test::call_and_read_body_json(&app, req).await;
+
+    let info = rmcp::ServerHandler::get_info(&Calculator::new());
+    assert_eq!(manifest["name"], info.server_info.name);
+    assert_eq!(manifest["version"], info.server_info.version);
+    assert_eq!(
+        manifest["protocolVersion"],
+        info.protocol_version.to_string()
+    );
+    assert_eq!(manifest["transport"]["type"], "streamable-http");
+    assert_eq!(manifest["transport"]["endpoint"], "/api/mcp");
+    assert_eq!(manifest["transport"]["stateful"], true);
+    assert_eq!(manifest["authentication"]["type"], "bearer");
+    assert_eq!(
+        manifest["authentication"]["resourceMetadata"],
+        "https://example.com/.well-known/oauth-protected-resource"
+    );
+    assert!(manifest["capabilities"]["tools"].is_object());
+}
+
+#[actix_web::test]
+async fn manifest_defaults_to_no_authentication() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(false)
+        .build();
+
+    let app = test::init_service(App::new().service(service.well_known_manifest("/mcp"))).await;
+
+    let req = test::TestRequest::get()
+        .uri("/.well-known/mcp.json")
+        .to_request();
+    let manifest: Value = test::call_and_read_body_json(&app, req).await;
+
+    assert_eq!(manifest["authentication"]["type"], "none");
+    assert_eq!(manifest["transport"]["stateful"], false);
+}
+
+#[actix_web::test]
+async fn manifest_constructs_a_handler_only_once() {
+    let constructed = Arc::new(AtomicUsize::new(0));
+    let service = StreamableHttpService::builder()
+        .service_factory({
+            let constructed = constructed.clone();
+            Arc::new(move || {
+                constructed.fetch_add(1, Ordering::SeqCst);
+                Ok(Calculator::new())
+            })
+        })
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+
+    // Every worker builds its own resource from a clone of the service
+    for _ in 0..2 {
+        let app = test::init_service(App::new().service(service.well_known_manifest("/mcp"))).await;
+        for _ in 0..3 {
+            let req = test::TestRequest::get()
+                .uri("/.well-known/mcp.json")
+                .to_request();
+            let manifest: Value = test::call_and_read_body_json(&app, req).await;
+            assert_eq!(manifest["transport"]["endpoint"], "/mcp");
+        }
+    }
+
+    assert_eq!(constructed.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,66 @@
+//! Integration tests for serving a handler chosen at runtime.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    structured_service::StructuredService,
+    test_server::{TestServer, read_sse_messages},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{
+    DynServerHandler, DynStreamableHttpService, StreamableHttpService,
+};
+use serde_json::json;
+
+/// Builds the handler named by a configuration value.
+fn handler_for(backend: &str) -> DynServerHandler {
+    match backend {
+        "calculator" => DynServerHandler::new(Calculator::new()),
+        "structured" => DynServerHandler::new(StructuredService::new()),
+        other => panic!("unknown backend {other}"),
+    }
+}
+
+async fn spawn_server(backend: &str) -> TestServer {
+    let handler = handler_for(backend);
+    let service: DynStreamableHttpService = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || Ok(handler.clone())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn tool_names(server: &TestServer) -> Vec<String> {
+    let session_id = server.initialize("/mcp").await;
+    let request = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" });
+    let response = server.post("/mcp", Some(&session_id), &request).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 2)
+        .expect("tools/list must be answered");
+    let mut names: Vec<String> = result["result"]["tools"]
+        .as_array()
+        .expect("tool list")
+        .iter()
+        .map(|tool| tool["name"].as_str().expect("tool name").to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[actix_web::test]
+async fn handler_is_selected_at_runtime() {
+    let server = spawn_server("calculator").await;
+    assert_eq!(tool_names(&server).await, ["sub", "sum"]);
+
+    let server = spawn_server("structured").await;
+    assert_eq!(tool_names(&server).await, ["stats"]);
+}
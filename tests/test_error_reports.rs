@@ -0,0 +1,134 @@
+//! Integration tests for reporting handler panics and internal errors.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::test_server::{TestServer, read_sse_messages};
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    model::{
+        ListResourceTemplatesResult, ListResourcesResult, PaginatedRequestParams,
+        ServerCapabilities, ServerInfo,
+    },
+    service::RequestContext,
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::{ErrorReport, ErrorReportKind, StreamableHttpService};
+use serde_json::{Value, json};
+
+/// Handler panicking while listing resources and failing to list templates.
+struct Crashing;
+
+impl ServerHandler for Crashing {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_resources().build())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        panic!("inventory corrupted")
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Err(McpError::internal_error("database unreachable", None))
+    }
+}
+
+/// Starts a runtime on its own thread and returns its handle.
+fn spawn_tool_runtime() -> tokio::runtime::Handle {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to build runtime");
+    let handle = runtime.handle().clone();
+    std::thread::spawn(move || runtime.block_on(std::future::pending::<()>()));
+    handle
+}
+
+async fn spawn_server(
+    runtime: Option<tokio::runtime::Handle>,
+) -> (TestServer, Arc<Mutex<Vec<ErrorReport>>>) {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Crashing)))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .maybe_handler_runtime(runtime)
+        .on_internal_error_fn({
+            let reports = reports.clone();
+            move |report| reports.lock().unwrap().push(report.clone())
+        })
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+    (server, reports)
+}
+
+async fn request(server: &TestServer, session_id: &str, id: i64, method: &str) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "id": id, "method": method });
+    let response = server.post("/mcp", Some(session_id), &request).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response)
+        .await
+        .into_iter()
+        .find(|m| m["id"] == id)
+        .expect("request must be answered")
+}
+
+async fn assert_panic_reported(runtime: Option<tokio::runtime::Handle>) {
+    let (server, reports) = spawn_server(runtime).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = request(&server, &session_id, 2, "resources/list").await;
+    assert_eq!(response["error"]["code"], -32603);
+    assert_eq!(response["error"]["message"], "Handler panicked");
+
+    let report = reports
+        .lock()
+        .unwrap()
+        .pop()
+        .expect("panic must be reported");
+    assert_eq!(report.kind, ErrorReportKind::Panic);
+    assert_eq!(report.message, "inventory corrupted");
+    assert_eq!(report.session_id.as_deref(), Some(session_id.as_str()));
+    assert_eq!(report.method, "resources/list");
+    assert!(report.connection.is_some());
+
+    // The session survives the panic
+    let response = request(&server, &session_id, 3, "ping").await;
+    assert!(response["result"].is_object());
+}
+
+#[actix_web::test]
+async fn handler_panic_is_answered_and_reported() {
+    assert_panic_reported(None).await;
+}
+
+#[actix_web::test]
+async fn handler_panic_on_handler_runtime_is_reported() {
+    assert_panic_reported(Some(spawn_tool_runtime())).await;
+}
+
+#[actix_web::test]
+async fn internal_error_is_reported() {
+    let (server, reports) = spawn_server(None).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = request(&server, &session_id, 2, "resources/templates/list").await;
+    assert_eq!(response["error"]["message"], "database unreachable");
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0].kind, ErrorReportKind::InternalError);
+    assert_eq!(reports[0].message, "database unreachable");
+    assert_eq!(reports[0].method, "resources/templates/list");
+}
@@ -0,0 +1,105 @@
+//! Integration tests for custom SSE event ids.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{SseEventReader, TestServer},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{InMemoryEventStore, StreamableHttpService};
+use serde_json::{Value, json};
+
+async fn spawn_server() -> TestServer {
+    let sequence = Arc::new(AtomicU64::new(0));
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .event_store(Arc::new(InMemoryEventStore::new(64)))
+        .event_id_generator(Arc::new(move |session_id, _native| {
+            format!(
+                "{session_id}-{:08}",
+                sequence.fetch_add(1, Ordering::Relaxed)
+            )
+        }))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Reads the events carrying a message until the stream ends.
+async fn read_messages(mut events: SseEventReader) -> Vec<(String, Value)> {
+    let mut messages = Vec::new();
+    while let Some(event) = events.next_event(Duration::from_secs(2)).await {
+        if !event.data.is_empty() {
+            let id = event.id.clone().expect("message events carry an id");
+            messages.push((id, event.json()));
+        }
+    }
+    messages
+}
+
+#[actix_web::test]
+async fn generated_ids_are_sent_and_resumed_from() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "tools/call",
+                "params": {
+                    "name": "progress",
+                    "arguments": {},
+                    "_meta": { "progressToken": "ids" }
+                },
+                "id": 2
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let received = read_messages(SseEventReader::new(response)).await;
+    assert_eq!(received.len(), 4, "{received:#?}");
+    for (id, _) in &received {
+        assert!(id.starts_with(&format!("{session_id}-")), "{id}");
+    }
+    let mut ids: Vec<_> = received.iter().map(|(id, _)| id.clone()).collect();
+    ids.sort();
+    assert_eq!(
+        ids,
+        received
+            .iter()
+            .map(|(id, _)| id.clone())
+            .collect::<Vec<_>>(),
+        "generated ids are sortable"
+    );
+
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .header("Last-Event-ID", &received[0].0)
+        .send()
+        .await
+        .expect("Failed to resume stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let replayed = read_messages(SseEventReader::new(response)).await;
+    let messages: Vec<_> = replayed.iter().map(|(_, message)| message).collect();
+    let expected: Vec<_> = received[1..].iter().map(|(_, message)| message).collect();
+    assert_eq!(messages, expected);
+    assert!(replayed.iter().all(|(id, _)| id > &received[3].0));
+}
@@ -0,0 +1,164 @@
+//! Integration tests for `Last-Event-ID` resumption backed by an event store.
+//!
+//! Missed events are replayed from the configured store, independently of the
+//! session manager, which only provides the live remainder of a stream.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    peer_service::PeerService,
+    slow_service::SlowService,
+    test_server::{SseEventReader, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::{ServerHandler, transport::streamable_http_server::session::local::LocalSessionManager};
+use rmcp_actix_web::transport::{EventStore, InMemoryEventStore, StreamableHttpService};
+use serde_json::{Value, json};
+
+async fn spawn_server<S>(factory: fn() -> S, store: &Arc<InMemoryEventStore>) -> TestServer
+where
+    S: ServerHandler + Clone,
+{
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || Ok(factory())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .event_store(store.clone())
+        // Writing keep-alives lets the server notice dropped streams quickly
+        .sse_keep_alive(Duration::from_millis(50))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn open_stream(
+    server: &TestServer,
+    session_id: &str,
+    last_event_id: Option<&str>,
+) -> SseEventReader {
+    let mut request = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", session_id);
+    if let Some(last_event_id) = last_event_id {
+        request = request.header("Last-Event-ID", last_event_id);
+    }
+    let response = request.send().await.expect("Failed to open stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    SseEventReader::new(response)
+}
+
+/// Reads the next event carrying a message, returning its id and message.
+async fn next_message(events: &mut SseEventReader) -> Option<(String, Value)> {
+    loop {
+        let event = events.next_event(Duration::from_secs(2)).await?;
+        if !event.data.is_empty() {
+            let id = event.id.clone().expect("message events carry an id");
+            return Some((id, event.json()));
+        }
+    }
+}
+
+/// Calls the `log` tool, which logs `message` on the standalone stream.
+async fn log(server: &TestServer, session_id: &str, id: i64, message: &str) {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &tool_call_request(id, "log", json!({ "message": message })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response).await;
+}
+
+#[actix_web::test]
+async fn completed_request_stream_is_replayed_from_the_store() {
+    let store = Arc::new(InMemoryEventStore::new(64));
+    let server = spawn_server(SlowService::new, &store).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "tools/call",
+                "params": {
+                    "name": "progress",
+                    "arguments": {},
+                    "_meta": { "progressToken": "resume" }
+                },
+                "id": 2
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let mut events = SseEventReader::new(response);
+    let mut received = Vec::new();
+    while let Some(message) = next_message(&mut events).await {
+        received.push(message);
+    }
+    assert_eq!(received.len(), 4, "{received:#?}");
+
+    // The session manager forgets the stream once the request completes: only
+    // the store can answer a client that lost the last three events
+    let mut events = open_stream(&server, &session_id, Some(&received[0].0)).await;
+    let mut replayed = Vec::new();
+    while let Some(message) = next_message(&mut events).await {
+        replayed.push(message);
+    }
+    assert_eq!(replayed, received[1..]);
+    assert_eq!(replayed[2].1["id"], 2);
+}
+
+#[actix_web::test]
+async fn standalone_stream_replays_then_continues_live() {
+    let store = Arc::new(InMemoryEventStore::new(64));
+    let server = spawn_server(PeerService::new, &store).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let mut standalone = open_stream(&server, &session_id, None).await;
+    log(&server, &session_id, 2, "first").await;
+    log(&server, &session_id, 3, "second").await;
+    let (first_id, first) = next_message(&mut standalone).await.expect("first log");
+    let (_, second) = next_message(&mut standalone).await.expect("second log");
+    assert_eq!(first["params"]["data"], "first");
+    drop(standalone);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut resumed = open_stream(&server, &session_id, Some(&first_id)).await;
+    let (_, replayed) = next_message(&mut resumed).await.expect("replayed log");
+    assert_eq!(replayed, second);
+
+    log(&server, &session_id, 4, "third").await;
+    let (_, live) = next_message(&mut resumed).await.expect("live log");
+    assert_eq!(live["params"]["data"], "third");
+}
+
+#[actix_web::test]
+async fn closing_a_session_removes_its_events() {
+    let store = Arc::new(InMemoryEventStore::new(64));
+    let server = spawn_server(PeerService::new, &store).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let mut standalone = open_stream(&server, &session_id, None).await;
+    log(&server, &session_id, 2, "first").await;
+    let (first_id, _) = next_message(&mut standalone).await.expect("first log");
+    let session = session_id.as_str().into();
+    assert!(store.replay(&session, &first_id).await.unwrap().is_some());
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send DELETE");
+    assert!(response.status().is_success());
+    assert!(store.replay(&session, &first_id).await.unwrap().is_none());
+}
@@ -0,0 +1,111 @@
+//! Integration tests for running handlers on a dedicated runtime.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::test_server::{TestServer, read_sse_messages};
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    model::{
+        ListResourcesResult, PaginatedRequestParams, RawResource, Resource, ServerCapabilities,
+        ServerInfo,
+    },
+    service::RequestContext,
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+/// Handler listing the name of the thread it runs on as its only resource.
+struct ThreadReporter;
+
+impl ServerHandler for ThreadReporter {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_resources().build())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("unnamed");
+        Ok(ListResourcesResult::with_all_items(vec![Resource::new(
+            RawResource::new(format!("thread://{name}"), name),
+            None,
+        )]))
+    }
+}
+
+/// Starts a runtime on a thread named `mcp-tools` and returns its handle.
+fn spawn_tool_runtime() -> tokio::runtime::Handle {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to build runtime");
+    let handle = runtime.handle().clone();
+    std::thread::Builder::new()
+        .name("mcp-tools".to_string())
+        .spawn(move || runtime.block_on(std::future::pending::<()>()))
+        .expect("Failed to spawn runtime thread");
+    handle
+}
+
+async fn spawn_server(stateful_mode: bool, runtime: Option<tokio::runtime::Handle>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(ThreadReporter)))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful_mode)
+        .maybe_handler_runtime(runtime)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn handler_thread(server: &TestServer, session_id: Option<&str>) -> String {
+    let request = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" });
+    let response = server.post("/mcp", session_id, &request).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 2)
+        .expect("resources/list must be answered");
+    result["result"]["resources"][0]["name"]
+        .as_str()
+        .expect("resource name")
+        .to_string()
+}
+
+#[actix_web::test]
+async fn stateful_requests_run_on_handler_runtime() {
+    let server = spawn_server(true, Some(spawn_tool_runtime())).await;
+    let session_id = server.initialize("/mcp").await;
+
+    assert_eq!(
+        handler_thread(&server, Some(&session_id)).await,
+        "mcp-tools"
+    );
+}
+
+#[actix_web::test]
+async fn stateless_requests_run_on_handler_runtime() {
+    let server = spawn_server(false, Some(spawn_tool_runtime())).await;
+
+    assert_eq!(handler_thread(&server, None).await, "mcp-tools");
+}
+
+#[actix_web::test]
+async fn requests_run_on_worker_by_default() {
+    let server = spawn_server(true, None).await;
+    let session_id = server.initialize("/mcp").await;
+
+    assert_ne!(
+        handler_thread(&server, Some(&session_id)).await,
+        "mcp-tools"
+    );
+}
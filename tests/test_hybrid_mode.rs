@@ -0,0 +1,108 @@
+//! Integration tests for serving session-less requests in stateful mode.
+
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+/// Spawns a hybrid server, counting the handlers it constructs.
+async fn spawn_server() -> (TestServer, Arc<AtomicUsize>) {
+    let constructed = Arc::new(AtomicUsize::new(0));
+    let service = StreamableHttpService::builder()
+        .service_factory({
+            let constructed = constructed.clone();
+            Arc::new(move || {
+                constructed.fetch_add(1, Ordering::SeqCst);
+                Ok(Calculator::new())
+            })
+        })
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .hybrid_mode(true)
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+    (server, constructed)
+}
+
+async fn post(server: &TestServer, session_id: Option<&str>, body: &Value) -> reqwest::Response {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .json(body);
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+#[actix_web::test]
+async fn bare_requests_share_one_handler() {
+    let (server, constructed) = spawn_server().await;
+
+    for id in 1..=3 {
+        let response = post(
+            &server,
+            None,
+            &tool_call_request(id, "sum", json!({ "a": 2, "b": 3 })),
+        )
+        .await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(response.headers().get("Mcp-Session-Id").is_none());
+        let messages = read_sse_messages(response).await;
+        assert_eq!(messages[0]["id"], id);
+        assert!(messages[0]["result"].is_object());
+    }
+
+    assert_eq!(constructed.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn sessions_are_served_alongside() {
+    let (server, constructed) = spawn_server().await;
+
+    let session_id = server.initialize("/mcp").await;
+    let response = post(
+        &server,
+        Some(&session_id),
+        &tool_call_request(2, "sum", json!({ "a": 1, "b": 1 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 2);
+
+    let response = post(
+        &server,
+        None,
+        &tool_call_request(3, "sum", json!({ "a": 1, "b": 1 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let _ = read_sse_messages(response).await;
+
+    // One handler for the session, one shared by session-less requests
+    assert_eq!(constructed.load(Ordering::SeqCst), 2);
+
+    // Unknown sessions are still reported as such
+    let response = post(
+        &server,
+        Some("definitely-not-a-real-session"),
+        &tool_call_request(4, "sum", json!({ "a": 1, "b": 1 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,105 @@
+//! Integration tests for the deduplication of retried POST requests.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{ACCEPT_BOTH, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .idempotency_window(Duration::from_secs(60))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Calls the `count` tool as request `id`, optionally with an idempotency key.
+async fn call_count(
+    server: &TestServer,
+    session_id: &str,
+    id: i64,
+    key: Option<&str>,
+) -> reqwest::Response {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .header("Mcp-Session-Id", session_id)
+        .json(&tool_call_request(id, "count", json!({})));
+    if let Some(key) = key {
+        request = request.header("Idempotency-Key", key);
+    }
+    let response = request.send().await.expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response
+}
+
+/// Reads the response to request `id` and returns its text.
+async fn result_text(response: reqwest::Response, id: i64) -> String {
+    let messages: Vec<Value> = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == id)
+        .unwrap_or_else(|| panic!("no response to {id}: {messages:#?}"));
+    result["result"]["content"][0]["text"]
+        .as_str()
+        .expect("text content")
+        .to_owned()
+}
+
+#[actix_web::test]
+async fn duplicate_id_in_flight_shares_the_original_response() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let (original, duplicate) = tokio::join!(call_count(&server, &session_id, 2, None), async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        call_count(&server, &session_id, 2, None).await
+    });
+    assert_eq!(result_text(original, 2).await, "call 1");
+    assert_eq!(result_text(duplicate, 2).await, "call 1");
+
+    // A new id is a new request
+    let response = call_count(&server, &session_id, 3, None).await;
+    assert_eq!(result_text(response, 3).await, "call 2");
+}
+
+#[actix_web::test]
+async fn idempotency_key_matches_requests_with_different_ids() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = call_count(&server, &session_id, 2, Some("order-42")).await;
+    assert_eq!(result_text(response, 2).await, "call 1");
+
+    // The retry is answered under its own JSON-RPC id
+    let response = call_count(&server, &session_id, 7, Some("order-42")).await;
+    assert_eq!(result_text(response, 7).await, "call 1");
+
+    let response = call_count(&server, &session_id, 8, Some("order-43")).await;
+    assert_eq!(result_text(response, 8).await, "call 2");
+}
+
+#[actix_web::test]
+async fn retry_after_disconnect_does_not_run_the_request_again() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    // The client gives up before the response arrives
+    drop(call_count(&server, &session_id, 2, None).await);
+    tokio::time::sleep(Duration::from_millis(400)).await;
+
+    let response = call_count(&server, &session_id, 2, None).await;
+    assert_eq!(result_text(response, 2).await, "call 1");
+}
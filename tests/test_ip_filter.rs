@@ -0,0 +1,122 @@
+//! Integration tests for the client address allow/deny lists.
+
+mod common;
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{IpFilter, StreamableHttpService, TrustedProxies};
+
+const LOOPBACK: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+const CORPORATE: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0));
+
+async fn spawn_server(filter: IpFilter, proxies: Option<TrustedProxies>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .ip_filter(filter)
+        .maybe_trusted_proxies(proxies)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn initialize_from(server: &TestServer, forwarded_for: Option<&str>) -> reqwest::StatusCode {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Content-Type", "application/json")
+        .json(&initialize_request(1));
+    if let Some(forwarded_for) = forwarded_for {
+        request = request.header("X-Forwarded-For", forwarded_for);
+    }
+    request
+        .send()
+        .await
+        .expect("Failed to send request")
+        .status()
+}
+
+#[actix_web::test]
+async fn peer_address_is_checked_against_the_lists() {
+    let server = spawn_server(IpFilter::new().deny_network(LOOPBACK, 8), None).await;
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "client_address_not_allowed");
+
+    let server = spawn_server(IpFilter::new().allow_network(LOOPBACK, 32), None).await;
+    assert_eq!(
+        initialize_from(&server, None).await,
+        reqwest::StatusCode::OK
+    );
+
+    // Forwarding headers from an untrusted peer are not believed
+    let server = spawn_server(IpFilter::new().allow_network(CORPORATE, 8), None).await;
+    assert_eq!(
+        initialize_from(&server, Some("10.1.2.3")).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+}
+
+#[actix_web::test]
+async fn client_address_is_resolved_through_trusted_proxies() {
+    let server = spawn_server(
+        IpFilter::new()
+            .allow_network(CORPORATE, 8)
+            .deny_network(IpAddr::V4(Ipv4Addr::new(10, 66, 0, 0)), 16),
+        Some(TrustedProxies::new().trust(LOOPBACK)),
+    )
+    .await;
+
+    assert_eq!(
+        initialize_from(&server, Some("10.1.2.3")).await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        initialize_from(&server, Some("10.66.0.7")).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+    assert_eq!(
+        initialize_from(&server, Some("192.0.2.1")).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+}
+
+#[test]
+fn unknown_addresses_only_pass_without_allow_list() {
+    assert!(IpFilter::new().deny_network(CORPORATE, 8).is_allowed(None));
+    assert!(!IpFilter::new().allow_network(CORPORATE, 8).is_allowed(None));
+}
+
+#[test]
+fn ipv4_mapped_networks_match_ipv4_addresses() {
+    let mapped: IpAddr = "::ffff:192.168.0.0".parse().unwrap();
+    let inside = Some(IpAddr::V4(Ipv4Addr::new(192, 168, 4, 2)));
+    let outside = Some(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3)));
+
+    let denied = IpFilter::new().deny_network(mapped, 112);
+    assert!(!denied.is_allowed(inside));
+    assert!(denied.is_allowed(outside));
+
+    let allowed = IpFilter::new().allow_network(mapped, 112);
+    assert!(allowed.is_allowed(inside));
+    assert!(!allowed.is_allowed(outside));
+}
+
+#[test]
+#[should_panic(expected = "at least 96 bits")]
+fn ipv4_mapped_networks_need_a_mapped_prefix() {
+    let _ = IpFilter::new().deny_network("::ffff:192.168.0.0".parse().unwrap(), 64);
+}
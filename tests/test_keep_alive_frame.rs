@@ -0,0 +1,77 @@
+//! Integration tests for configurable keep-alive frames.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{SseEventReader, TestServer},
+};
+use futures::StreamExt;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{KeepAliveFrame, StreamableHttpService};
+
+async fn spawn_server(frame: KeepAliveFrame) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .sse_keep_alive(Duration::from_millis(50))
+        .keep_alive_frame(frame)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn open_standalone_stream(server: &TestServer) -> reqwest::Response {
+    let session_id = server.initialize("/mcp").await;
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response
+}
+
+#[actix_web::test]
+async fn keep_alive_can_be_a_named_event() {
+    let server = spawn_server(KeepAliveFrame::event("keepalive", "{}")).await;
+    let mut events = SseEventReader::new(open_standalone_stream(&server).await);
+
+    loop {
+        let event = events
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("stream must deliver a keep-alive event");
+        if event.event.as_deref() == Some("keepalive") {
+            assert_eq!(event.data, "{}");
+            assert!(event.id.is_none(), "keep-alive events carry no id");
+            break;
+        }
+    }
+}
+
+#[actix_web::test]
+async fn keep_alive_comment_text_is_configurable() {
+    let server = spawn_server(KeepAliveFrame::comment("heartbeat")).await;
+    let mut stream = open_standalone_stream(&server).await.bytes_stream();
+
+    let mut received = String::new();
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while let Some(Ok(chunk)) = stream.next().await {
+            received.push_str(&String::from_utf8_lossy(&chunk));
+            if received.contains(":heartbeat\n\n") {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("stream must deliver a keep-alive comment");
+    assert!(!received.contains(":ping"), "{received:?}");
+}
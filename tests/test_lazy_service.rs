@@ -0,0 +1,134 @@
+//! Integration tests for lazy service instantiation.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use common::test_server::{TestServer, initialize_request, read_sse_messages};
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    model::{
+        Implementation, ListResourcesResult, PaginatedRequestParams, RawResource, Resource,
+        ServerCapabilities, ServerInfo,
+    },
+    service::{NotificationContext, RequestContext},
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+#[derive(Default)]
+struct Counters {
+    constructed: AtomicUsize,
+    initialized: AtomicUsize,
+}
+
+/// Handler recording its construction and the `initialized` notifications it sees.
+struct Inventory {
+    counters: Arc<Counters>,
+}
+
+impl ServerHandler for Inventory {
+    fn get_info(&self) -> ServerInfo {
+        info()
+    }
+
+    async fn on_initialized(&self, _context: NotificationContext<RoleServer>) {
+        self.counters.initialized.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult::with_all_items(vec![Resource::new(
+            RawResource::new("inventory://items", "items"),
+            None,
+        )]))
+    }
+}
+
+fn info() -> ServerInfo {
+    ServerInfo::new(ServerCapabilities::builder().enable_resources().build())
+        .with_server_info(Implementation::new("inventory", "1.0.0"))
+}
+
+async fn spawn_server(counters: Arc<Counters>, lazy: bool) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            counters.constructed.fetch_add(1, Ordering::SeqCst);
+            Ok(Inventory {
+                counters: counters.clone(),
+            })
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .maybe_lazy_service_info(lazy.then(info))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn list_resources(server: &TestServer, session_id: &str) -> serde_json::Value {
+    let request = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" });
+    let response = server.post("/mcp", Some(session_id), &request).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response)
+        .await
+        .into_iter()
+        .find(|m| m["id"] == 2)
+        .expect("resources/list must be answered")
+}
+
+#[actix_web::test]
+async fn initialize_is_answered_without_constructing_service() {
+    let counters = Arc::new(Counters::default());
+    let server = spawn_server(counters.clone(), true).await;
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["result"]["serverInfo"]["name"], "inventory");
+    assert!(messages[0]["result"]["capabilities"]["resources"].is_object());
+
+    server.initialize("/mcp").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(counters.constructed.load(Ordering::SeqCst), 0);
+    assert_eq!(counters.initialized.load(Ordering::SeqCst), 0);
+}
+
+#[actix_web::test]
+async fn first_request_constructs_service_and_delivers_initialized() {
+    let counters = Arc::new(Counters::default());
+    let server = spawn_server(counters.clone(), true).await;
+    let session_id = server.initialize("/mcp").await;
+
+    for _ in 0..2 {
+        let response = list_resources(&server, &session_id).await;
+        assert_eq!(
+            response["result"]["resources"][0]["uri"],
+            "inventory://items"
+        );
+    }
+    assert_eq!(counters.constructed.load(Ordering::SeqCst), 1);
+    assert_eq!(counters.initialized.load(Ordering::SeqCst), 1);
+}
+
+#[actix_web::test]
+async fn service_is_constructed_on_initialize_by_default() {
+    let counters = Arc::new(Counters::default());
+    let server = spawn_server(counters.clone(), false).await;
+
+    server.initialize("/mcp").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(counters.constructed.load(Ordering::SeqCst), 1);
+    assert_eq!(counters.initialized.load(Ordering::SeqCst), 1);
+}
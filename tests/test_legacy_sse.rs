@@ -0,0 +1,448 @@
+//! Integration tests for the legacy HTTP+SSE endpoints.
+//!
+//! These tests verify that old SSE clients can connect through `/sse` +
+//! `/message` while sharing sessions with the Streamable HTTP endpoint.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{
+        SseEventReader, TestServer, initialize_request, read_sse_messages, tool_call_request,
+    },
+};
+use futures::StreamExt;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{
+    LEGACY_SESSION_CLOSED_CODE, LEGACY_SESSION_NOT_FOUND_CODE, SseOverflowPolicy,
+    StreamableHttpService,
+};
+use serde_json::{Value, json};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        // Frequent keep-alives let the server notice disconnected streams quickly
+        .sse_keep_alive(Duration::from_millis(100))
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Opens a legacy event stream and returns it with the advertised message URL.
+async fn connect(server: &TestServer) -> (SseEventReader, String) {
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open SSE stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let mut events = SseEventReader::new(response);
+    let endpoint = events.next_event(TIMEOUT).await.expect("endpoint event");
+    assert_eq!(endpoint.event.as_deref(), Some("endpoint"));
+    assert!(endpoint.data.starts_with("/mcp/message?sessionId="));
+
+    (events, endpoint.data)
+}
+
+async fn post_message(server: &TestServer, endpoint: &str, body: &Value) -> reqwest::Response {
+    server
+        .client
+        .post(server.url(endpoint))
+        .header("Content-Type", "application/json")
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to post message")
+}
+
+#[actix_web::test]
+async fn legacy_client_round_trip() {
+    let server = spawn_server().await;
+    let (mut events, endpoint) = connect(&server).await;
+
+    let response = post_message(&server, &endpoint, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    let initialized = events
+        .next_response(1, TIMEOUT)
+        .await
+        .expect("initialize result");
+    assert!(initialized["result"]["protocolVersion"].is_string());
+
+    let response = post_message(
+        &server,
+        &endpoint,
+        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let response = post_message(
+        &server,
+        &endpoint,
+        &tool_call_request(2, "sum", json!({ "a": 20, "b": 22 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    let result = events.next_response(2, TIMEOUT).await.expect("tool result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 42);
+}
+
+#[actix_web::test]
+async fn message_events_carry_monotonic_ids() {
+    let server = spawn_server().await;
+    let (mut events, endpoint) = connect(&server).await;
+    let session_id = endpoint.split("sessionId=").nth(1).unwrap().to_string();
+
+    post_message(&server, &endpoint, &initialize_request(1)).await;
+    let first = events.next_event(TIMEOUT).await.expect("initialize result");
+    post_message(
+        &server,
+        &endpoint,
+        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+    post_message(
+        &server,
+        &endpoint,
+        &tool_call_request(2, "sum", json!({ "a": 1, "b": 1 })),
+    )
+    .await;
+    let second = events.next_event(TIMEOUT).await.expect("tool result");
+
+    assert_eq!(first.event.as_deref(), Some("message"));
+    assert_eq!(first.id, Some(format!("{session_id}/1")));
+    assert_eq!(second.id, Some(format!("{session_id}/2")));
+}
+
+#[actix_web::test]
+async fn legacy_session_is_visible_to_streamable_endpoint() {
+    let server = spawn_server().await;
+    let (mut events, endpoint) = connect(&server).await;
+    let session_id = endpoint
+        .split("sessionId=")
+        .nth(1)
+        .expect("session id in endpoint")
+        .to_string();
+
+    post_message(&server, &endpoint, &initialize_request(1)).await;
+    events
+        .next_response(1, TIMEOUT)
+        .await
+        .expect("initialize result");
+    post_message(
+        &server,
+        &endpoint,
+        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(7, "sub", json!({ "a": 10, "b": 4 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let result = messages.iter().find(|m| m["id"] == 7).expect("tool result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 6);
+}
+
+#[actix_web::test]
+async fn legacy_client_round_trip_with_small_non_blocking_queue() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        .legacy_sse_channel_capacity(1)
+        .legacy_sse_overflow(SseOverflowPolicy::DropNewest)
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+    let (mut events, endpoint) = connect(&server).await;
+
+    post_message(&server, &endpoint, &initialize_request(1)).await;
+    events
+        .next_response(1, TIMEOUT)
+        .await
+        .expect("initialize result");
+    post_message(
+        &server,
+        &endpoint,
+        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+
+    let response = post_message(
+        &server,
+        &endpoint,
+        &tool_call_request(2, "sum", json!({ "a": 1, "b": 2 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    let result = events.next_response(2, TIMEOUT).await.expect("tool result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 3);
+}
+
+#[actix_web::test]
+async fn legacy_message_with_unknown_session_returns_404() {
+    let server = spawn_server().await;
+
+    let response = post_message(
+        &server,
+        "/mcp/message?sessionId=definitely-not-a-real-session",
+        &initialize_request(1),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 1);
+    assert_eq!(error["error"]["code"], LEGACY_SESSION_NOT_FOUND_CODE.0);
+
+    let response = post_message(&server, "/mcp/message", &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["error"]["code"], -32600);
+}
+
+#[actix_web::test]
+async fn legacy_message_after_stream_closed_returns_410() {
+    let server = spawn_server().await;
+    let (events, endpoint) = connect(&server).await;
+    let response = post_message(&server, &endpoint, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    drop(events);
+
+    let deadline = tokio::time::Instant::now() + TIMEOUT;
+    let response = loop {
+        let response = post_message(
+            &server,
+            &endpoint,
+            &tool_call_request(2, "sum", json!({ "a": 1, "b": 2 })),
+        )
+        .await;
+        if response.status() != reqwest::StatusCode::ACCEPTED
+            || tokio::time::Instant::now() >= deadline
+        {
+            break response;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+
+    assert_eq!(response.status(), reqwest::StatusCode::GONE);
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 2);
+    assert_eq!(error["error"]["code"], LEGACY_SESSION_CLOSED_CODE.0);
+    assert_eq!(error["error"]["message"], "Session closed");
+}
+
+#[actix_web::test]
+async fn client_can_request_faster_keep_alive() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        .sse_keep_alive(Duration::from_secs(60))
+        .sse_keep_alive_bounds(Duration::from_secs(1)..=Duration::from_secs(60))
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    // Requests below the minimum are clamped to it
+    let response = server
+        .client
+        .get(server.url("/mcp/sse?keepAlive=0"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open SSE stream");
+    let mut body = response.bytes_stream();
+    let pinged = tokio::time::timeout(Duration::from_secs(3), async {
+        let mut received = String::new();
+        while let Some(Ok(chunk)) = body.next().await {
+            received.push_str(&String::from_utf8_lossy(&chunk));
+            if received.contains(":ping") {
+                return true;
+            }
+        }
+        false
+    })
+    .await
+    .unwrap_or(false);
+    assert!(
+        pinged,
+        "expected a keep-alive ping within the requested interval"
+    );
+}
+
+#[actix_web::test]
+async fn delete_closes_legacy_session() {
+    let server = spawn_server().await;
+    let (mut events, endpoint) = connect(&server).await;
+    post_message(&server, &endpoint, &initialize_request(1)).await;
+    events
+        .next_response(1, TIMEOUT)
+        .await
+        .expect("initialize result");
+
+    let response = server
+        .client
+        .delete(server.url(&endpoint))
+        .send()
+        .await
+        .expect("Failed to delete session");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    // The event stream ends and the session is gone
+    assert!(events.next_event(TIMEOUT).await.is_none());
+    let response = post_message(
+        &server,
+        &endpoint,
+        &tool_call_request(2, "sum", json!({ "a": 1, "b": 2 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::GONE);
+
+    let response = server
+        .client
+        .delete(server.url("/mcp/message?sessionId=definitely-not-a-real-session"))
+        .send()
+        .await
+        .expect("Failed to delete session");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn reconnect_with_last_event_id_resumes_session() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        .legacy_sse_resume_window(Duration::from_secs(5))
+        .sse_keep_alive(Duration::from_millis(100))
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+    let (mut events, endpoint) = connect(&server).await;
+
+    post_message(&server, &endpoint, &initialize_request(1)).await;
+    let initialized = events.next_event(TIMEOUT).await.expect("initialize result");
+    let initialize_event_id = initialized.id.expect("event id");
+    post_message(
+        &server,
+        &endpoint,
+        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+    drop(events);
+
+    // Sent while the client is away: queued until it reconnects
+    let response = post_message(
+        &server,
+        &endpoint,
+        &tool_call_request(2, "sum", json!({ "a": 2, "b": 3 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    // Let the server notice the disconnect through a failed keep-alive
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    // Resuming from before the initialize result replays it
+    let (session_id, _) = initialize_event_id.rsplit_once('/').unwrap();
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .header("Last-Event-ID", format!("{session_id}/0"))
+        .send()
+        .await
+        .expect("Failed to reopen SSE stream");
+    let mut events = SseEventReader::new(response);
+    let endpoint_event = events.next_event(TIMEOUT).await.expect("endpoint event");
+    assert_eq!(endpoint_event.data, endpoint);
+
+    let replayed = events.next_event(TIMEOUT).await.expect("replayed event");
+    assert_eq!(replayed.id.as_deref(), Some(initialize_event_id.as_str()));
+    assert_eq!(replayed.json()["id"], 1);
+    let result = events.next_response(2, TIMEOUT).await.expect("tool result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 5);
+}
+
+#[actix_web::test]
+async fn shutdown_sends_final_event_and_refuses_connections() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        .build();
+    let server = {
+        let service = service.clone();
+        TestServer::spawn(move |cfg| {
+            cfg.service(service.clone().scope_with_path("/mcp"));
+        })
+        .await
+    };
+    let (mut events, endpoint) = connect(&server).await;
+
+    service.shutdown(Duration::from_secs(1)).await;
+
+    let event = events.next_event(TIMEOUT).await.expect("shutdown event");
+    assert_eq!(event.event.as_deref(), Some("shutdown"));
+
+    // The session was closed by the shutdown
+    let response = post_message(&server, &endpoint, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::GONE);
+
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open SSE stream");
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("retry-after"));
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["error"]["code"], -32003);
+}
+
+#[actix_web::test]
+async fn legacy_endpoints_are_not_mounted_by_default() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
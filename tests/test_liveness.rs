@@ -0,0 +1,139 @@
+//! Integration tests for server-initiated liveness pings.
+
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::{
+    calculator::Calculator,
+    test_server::{SseEventReader, TestServer},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{SessionCloseReason, StreamableHttpService};
+use serde_json::{Value, json};
+
+type ClosedSessions = Arc<Mutex<Vec<(String, SessionCloseReason)>>>;
+
+fn build_service(
+    closed: &ClosedSessions,
+) -> StreamableHttpService<Calculator, LocalSessionManager> {
+    let closed = closed.clone();
+    StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .ping_interval(Duration::from_millis(100))
+        .ping_max_missed(2)
+        .on_session_closed_fn(move |session_id, reason| {
+            closed
+                .lock()
+                .unwrap()
+                .push((session_id.to_string(), reason));
+        })
+        .build()
+}
+
+async fn spawn_server(
+    service: &StreamableHttpService<Calculator, LocalSessionManager>,
+) -> TestServer {
+    let service = service.clone();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn open_standalone_stream(server: &TestServer, session_id: &str) -> SseEventReader {
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    SseEventReader::new(response)
+}
+
+/// Reads the next ping request, skipping priming events.
+async fn next_ping(events: &mut SseEventReader) -> Value {
+    loop {
+        let event = events
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("standalone stream must deliver a ping");
+        if !event.data.is_empty() {
+            let message = event.json();
+            assert_eq!(message["method"], "ping");
+            return message;
+        }
+    }
+}
+
+#[actix_web::test]
+async fn answered_pings_record_round_trip() {
+    let closed = ClosedSessions::default();
+    let service = build_service(&closed);
+    let server = spawn_server(&service).await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = open_standalone_stream(&server, &session_id).await;
+
+    // Answer more pings than the client may miss: the session must stay open
+    for _ in 0..3 {
+        let ping = next_ping(&mut events).await;
+        assert!(ping["id"].is_string(), "{ping}");
+        let response = server
+            .post(
+                "/mcp",
+                Some(&session_id),
+                &json!({ "jsonrpc": "2.0", "id": ping["id"], "result": {} }),
+            )
+            .await;
+        assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    }
+
+    let liveness = service
+        .session_liveness(&session_id.as_str().into())
+        .await
+        .expect("session is open");
+    assert!(liveness.pings_sent >= 3);
+    assert!(liveness.last_round_trip.is_some());
+    assert_eq!(liveness.missed_pings, 0);
+    assert!(closed.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn unanswered_pings_close_the_session() {
+    let closed = ClosedSessions::default();
+    let service = build_service(&closed);
+    let server = spawn_server(&service).await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = open_standalone_stream(&server, &session_id).await;
+
+    next_ping(&mut events).await;
+    next_ping(&mut events).await;
+    // The stream ends once the second ping has been missed too
+    while let Some(event) = events.next_event(Duration::from_secs(2)).await {
+        assert!(event.data.is_empty(), "unexpected message: {}", event.data);
+    }
+
+    assert_eq!(
+        closed.lock().unwrap().clone(),
+        vec![(session_id.clone(), SessionCloseReason::Unresponsive)]
+    );
+    assert_eq!(
+        service.session_liveness(&session_id.as_str().into()).await,
+        None
+    );
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
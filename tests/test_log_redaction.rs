@@ -0,0 +1,102 @@
+//! Tests for the redaction of sensitive data in log output.
+
+use rmcp::model::ClientJsonRpcMessage;
+use rmcp_actix_web::transport::{AuthorizationHeader, LogRedaction};
+use serde_json::json;
+
+fn login_request() -> ClientJsonRpcMessage {
+    serde_json::from_value(json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "tools/call",
+        "params": {
+            "name": "login",
+            "arguments": { "user": "alice", "password": "hunter2", "tags": ["a", "b"] }
+        }
+    }))
+    .expect("valid request")
+}
+
+#[test]
+fn default_redaction_keeps_routing_fields_only() {
+    let logged = LogRedaction::default()
+        .display(&login_request())
+        .to_string();
+    let logged: serde_json::Value = serde_json::from_str(&logged).expect("JSON output");
+
+    assert_eq!(logged["id"], 7);
+    assert_eq!(logged["method"], "tools/call");
+    assert_eq!(logged["params"]["name"], "login");
+    assert_eq!(
+        logged["params"]["arguments"],
+        json!({ "user": "[REDACTED]", "password": "[REDACTED]", "tags": ["[REDACTED]", "[REDACTED]"] })
+    );
+}
+
+#[test]
+fn default_fields_are_redacted_in_payloads() {
+    let request: ClientJsonRpcMessage = serde_json::from_value(json!({
+        "jsonrpc": "2.0",
+        "id": 8,
+        "method": "tools/call",
+        "params": {
+            "name": "verify",
+            "arguments": { "code": "492817", "name": "Alice Smith", "nested": { "id": "acct-1" } }
+        }
+    }))
+    .expect("valid request");
+    let logged = LogRedaction::default().display(&request).to_string();
+    assert!(!logged.contains("492817"), "{logged}");
+    assert!(!logged.contains("Alice Smith"), "{logged}");
+    assert!(!logged.contains("acct-1"), "{logged}");
+    let logged: serde_json::Value = serde_json::from_str(&logged).expect("JSON output");
+    assert_eq!(logged["id"], 8);
+    assert_eq!(logged["params"]["name"], "verify");
+    assert_eq!(logged["params"]["arguments"]["code"], "[REDACTED]");
+
+    let response = json!({
+        "jsonrpc": "2.0",
+        "id": 8,
+        "result": {
+            "content": [{ "type": "text", "text": "ok", "name": "secret-file" }],
+            "structuredContent": { "version": "internal-1.2" },
+            "isError": false
+        }
+    });
+    let redacted = LogRedaction::default().redact(&response);
+    assert_eq!(redacted["result"]["content"][0]["name"], "[REDACTED]");
+    assert_eq!(
+        redacted["result"]["structuredContent"]["version"],
+        "[REDACTED]"
+    );
+    assert_eq!(redacted["result"]["isError"], false);
+}
+
+#[test]
+fn allow_list_is_extensible_and_redaction_can_be_disabled() {
+    let logged = LogRedaction::default()
+        .allow("user")
+        .display(&login_request())
+        .to_string();
+    assert!(logged.contains("alice"), "{logged}");
+    assert!(!logged.contains("hunter2"), "{logged}");
+
+    let logged = LogRedaction::allowing(["password"])
+        .display(&login_request())
+        .to_string();
+    assert!(logged.contains("hunter2"), "{logged}");
+    assert!(!logged.contains("tools/call"), "{logged}");
+
+    let logged = LogRedaction::disabled()
+        .display(&login_request())
+        .to_string();
+    assert!(logged.contains("hunter2"), "{logged}");
+}
+
+#[test]
+fn authorization_header_debug_hides_the_token() {
+    let header = AuthorizationHeader("Bearer secret-token".to_string());
+    let debug = format!("{header:?}");
+    assert_eq!(debug, "AuthorizationHeader(\"Bearer [REDACTED]\")");
+    assert_eq!(header.0, "Bearer secret-token");
+}
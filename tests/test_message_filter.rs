@@ -0,0 +1,82 @@
+//! Integration tests for the method and tool allow/deny lists.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{MessageFilter, StreamableHttpService};
+use serde_json::{Value, json};
+
+async fn spawn_server(filter: MessageFilter) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .message_filter(filter)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn post_json(server: &TestServer, session_id: &str, body: &Value) -> Value {
+    let response = server.post("/mcp", Some(session_id), body).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    response.json().await.expect("JSON-RPC error body")
+}
+
+#[actix_web::test]
+async fn denied_tool_is_rejected_with_json_rpc_error() {
+    let server = spawn_server(MessageFilter::new().deny_tools(["sub"])).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let error = post_json(
+        &server,
+        &session_id,
+        &tool_call_request(2, "sub", json!({ "a": 5, "b": 3 })),
+    )
+    .await;
+    assert_eq!(error["id"], 2);
+    assert_eq!(error["error"]["code"], -32602);
+    assert_eq!(error["error"]["message"], "Tool not allowed: sub");
+
+    // Other tools are unaffected
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(3, "sum", json!({ "a": 2, "b": 3 })),
+        )
+        .await;
+    let messages = read_sse_messages(response).await;
+    let result = messages.iter().find(|m| m["id"] == 3).expect("sum result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 5);
+}
+
+#[actix_web::test]
+async fn method_allow_list_rejects_everything_else() {
+    let server = spawn_server(MessageFilter::new().allow_methods(["tools/call"])).await;
+
+    // initialize is never filtered
+    let session_id = server.initialize("/mcp").await;
+
+    let error = post_json(
+        &server,
+        &session_id,
+        &json!({ "jsonrpc": "2.0", "method": "tools/list", "id": 2 }),
+    )
+    .await;
+    assert_eq!(error["id"], 2);
+    assert_eq!(error["error"]["code"], -32601);
+    assert_eq!(error["error"]["message"], "Method not allowed: tools/list");
+}
@@ -0,0 +1,90 @@
+//! Integration tests for the inbound and outbound message transformers.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::{
+    model::{ClientJsonRpcMessage, ClientRequest, ServerJsonRpcMessage, ServerResult},
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+async fn spawn_server(stateful_mode: bool) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful_mode)
+        // Force `b` to 10 on every sum call
+        .transform_inbound_fn(|_http_req, message| {
+            if let ClientJsonRpcMessage::Request(request) = message
+                && let ClientRequest::CallToolRequest(call) = &mut request.request
+                && let Some(arguments) = call.params.arguments.as_mut()
+            {
+                arguments.insert("b".to_string(), json!(10));
+            }
+        })
+        // Strip structured content from tool results
+        .transform_outbound_fn(|message| {
+            if let ServerJsonRpcMessage::Response(response) = message
+                && let ServerResult::CallToolResult(result) = &mut response.result
+            {
+                result.structured_content = None;
+            }
+        })
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+fn assert_transformed(messages: &[serde_json::Value]) {
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 2)
+        .expect("tool call response");
+    assert!(result["result"].get("structuredContent").is_none());
+    let text = result["result"]["content"][0]["text"].as_str().unwrap();
+    let value: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(value["value"], 12);
+}
+
+#[actix_web::test]
+async fn transformers_rewrite_messages_in_stateful_mode() {
+    let server = spawn_server(true).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sum", json!({ "a": 2, "b": 3 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    assert_transformed(&read_sse_messages(response).await);
+}
+
+#[actix_web::test]
+async fn transformers_rewrite_messages_in_stateless_mode() {
+    let server = spawn_server(false).await;
+
+    let response = server
+        .post(
+            "/mcp",
+            None,
+            &tool_call_request(2, "sum", json!({ "a": 2, "b": 3 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    assert_transformed(&read_sse_messages(response).await);
+}
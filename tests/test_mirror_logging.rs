@@ -0,0 +1,128 @@
+//! Integration tests for mirroring MCP logging notifications into `tracing`.
+
+mod common;
+
+use std::{
+    io,
+    sync::{Arc, Mutex, OnceLock},
+    time::Duration,
+};
+
+use common::{
+    peer_service::PeerService,
+    test_server::{SseEventReader, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+/// Output of the global subscriber, shared by every test of this file.
+fn captured() -> &'static Arc<Mutex<Vec<u8>>> {
+    static CAPTURED: OnceLock<Arc<Mutex<Vec<u8>>>> = OnceLock::new();
+    CAPTURED.get_or_init(|| {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let writer = captured.clone();
+        // Workers run on their own threads, so only a global subscriber sees their events
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter("rmcp_actix_web::client_log=debug")
+            .with_ansi(false)
+            .with_writer(move || CaptureWriter(writer.clone()))
+            .try_init();
+        captured
+    })
+}
+
+struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the captured line mentioning `needle`, if any.
+fn captured_line(needle: &str) -> Option<String> {
+    let captured = captured().lock().unwrap();
+    String::from_utf8_lossy(&captured)
+        .lines()
+        .find(|line| line.contains(needle))
+        .map(str::to_string)
+}
+
+async fn spawn_server(mirror_logging: bool) -> TestServer {
+    captured();
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(PeerService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .mirror_logging(mirror_logging)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Calls the `log` tool with the standalone stream open, and returns the
+/// session id once the logging notification reached the client.
+async fn log_to_client(server: &TestServer, message: &str) -> String {
+    let session_id = server.initialize("/mcp").await;
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    let mut standalone = SseEventReader::new(response);
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "log", json!({ "message": message })),
+        )
+        .await;
+    let mut messages = read_sse_messages(response).await;
+    // The notification travels on the request stream or the standalone stream
+    while !messages
+        .iter()
+        .any(|m| m["method"] == "notifications/message")
+    {
+        let event = standalone
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("logging notification must reach the client");
+        if !event.data.is_empty() {
+            messages.push(event.json());
+        }
+    }
+    session_id
+}
+
+#[actix_web::test]
+async fn logging_notifications_are_mirrored_with_session_id() {
+    let server = spawn_server(true).await;
+    let session_id = log_to_client(&server, "Disk almost full").await;
+
+    let line = captured_line("Disk almost full").expect("log must be mirrored");
+    assert!(line.contains("WARN"), "{line}");
+    assert!(
+        line.contains(&format!("session_id=\"{session_id}\"")),
+        "{line}"
+    );
+    assert!(line.contains("logger=\"peer_service\""), "{line}");
+}
+
+#[actix_web::test]
+async fn logging_notifications_are_not_mirrored_by_default() {
+    let server = spawn_server(false).await;
+    log_to_client(&server, "Quota exceeded").await;
+
+    assert_eq!(captured_line("Quota exceeded"), None);
+}
@@ -0,0 +1,319 @@
+//! Integration tests for MessagePack content negotiation.
+
+#![cfg(feature = "msgpack")]
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use base64::Engine;
+use common::{
+    calculator::Calculator,
+    test_server::{ACCEPT_BOTH, SseEventReader, TestServer, initialize_request, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Map, Value, json};
+
+const ACCEPT_MSGPACK: &str = "application/msgpack, text/event-stream";
+
+async fn spawn_server(msgpack: bool) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .msgpack(msgpack)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Encodes the subset of MessagePack needed by the tests.
+fn to_msgpack(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(b) => out.push(if *b { 0xc3 } else { 0xc2 }),
+        Value::Number(n) => {
+            out.push(0xd3);
+            out.extend_from_slice(&n.as_i64().expect("integer").to_be_bytes());
+        }
+        Value::String(s) => {
+            out.push(0xdb);
+            out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(items) => {
+            out.push(0xdd);
+            out.extend_from_slice(&(items.len() as u32).to_be_bytes());
+            items.iter().for_each(|item| to_msgpack(item, out));
+        }
+        Value::Object(map) => {
+            out.push(0xdf);
+            out.extend_from_slice(&(map.len() as u32).to_be_bytes());
+            for (key, value) in map {
+                to_msgpack(&Value::String(key.clone()), out);
+                to_msgpack(value, out);
+            }
+        }
+    }
+}
+
+/// Decodes the subset of MessagePack produced for the test messages.
+fn from_msgpack(bytes: &mut &[u8]) -> Value {
+    fn take<'a>(bytes: &mut &'a [u8], len: usize) -> &'a [u8] {
+        let (head, tail) = bytes.split_at(len);
+        *bytes = tail;
+        head
+    }
+    fn be(bytes: &mut &[u8], len: usize) -> usize {
+        take(bytes, len)
+            .iter()
+            .fold(0, |acc, b| acc << 8 | usize::from(*b))
+    }
+    fn string(bytes: &mut &[u8], len: usize) -> Value {
+        Value::String(String::from_utf8(take(bytes, len).to_vec()).unwrap())
+    }
+    fn array(bytes: &mut &[u8], len: usize) -> Value {
+        Value::Array((0..len).map(|_| from_msgpack(bytes)).collect())
+    }
+    fn map(bytes: &mut &[u8], len: usize) -> Value {
+        let mut map = Map::new();
+        for _ in 0..len {
+            let Value::String(key) = from_msgpack(bytes) else {
+                panic!("non-string key");
+            };
+            map.insert(key, from_msgpack(bytes));
+        }
+        Value::Object(map)
+    }
+
+    let marker = take(bytes, 1)[0];
+    match marker {
+        0x00..=0x7f => json!(marker),
+        0x80..=0x8f => map(bytes, usize::from(marker & 0x0f)),
+        0x90..=0x9f => array(bytes, usize::from(marker & 0x0f)),
+        0xa0..=0xbf => string(bytes, usize::from(marker & 0x1f)),
+        0xc0 => Value::Null,
+        0xc2 => json!(false),
+        0xc3 => json!(true),
+        0xcb => json!(f64::from_be_bytes(take(bytes, 8).try_into().unwrap())),
+        0xcc => json!(be(bytes, 1)),
+        0xcd => json!(be(bytes, 2)),
+        0xce => json!(be(bytes, 4)),
+        0xd9 => {
+            let len = be(bytes, 1);
+            string(bytes, len)
+        }
+        0xda => {
+            let len = be(bytes, 2);
+            string(bytes, len)
+        }
+        0xdc => {
+            let len = be(bytes, 2);
+            array(bytes, len)
+        }
+        0xde => {
+            let len = be(bytes, 2);
+            map(bytes, len)
+        }
+        0xe0..=0xff => json!(marker as i8),
+        _ => panic!("unexpected marker {marker:#x}"),
+    }
+}
+
+async fn post_msgpack(
+    server: &TestServer,
+    session_id: Option<&str>,
+    accept: &str,
+    body: Vec<u8>,
+) -> reqwest::Response {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", accept)
+        .header("Content-Type", "application/msgpack")
+        .body(body);
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+fn encode(message: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    to_msgpack(message, &mut bytes);
+    bytes
+}
+
+/// Reads the next message event, decoding its base64 MessagePack payload.
+async fn next_msgpack_message(events: &mut SseEventReader) -> Value {
+    loop {
+        let event = events
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("stream must deliver a message");
+        if !event.data.is_empty() {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(&event.data)
+                .expect("payload must be base64");
+            let mut bytes = bytes.as_slice();
+            let message = from_msgpack(&mut bytes);
+            assert!(bytes.is_empty(), "trailing bytes after {message}");
+            return message;
+        }
+    }
+}
+
+#[actix_web::test]
+async fn msgpack_client_round_trip() {
+    let server = spawn_server(true).await;
+
+    let response = post_msgpack(
+        &server,
+        None,
+        ACCEPT_MSGPACK,
+        encode(&initialize_request(1)),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers().get("X-MCP-Payload-Encoding").unwrap(),
+        "msgpack"
+    );
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let message = next_msgpack_message(&mut SseEventReader::new(response)).await;
+    assert_eq!(message["id"], 1);
+    assert!(message["result"]["serverInfo"].is_object(), "{message}");
+
+    let response = post_msgpack(
+        &server,
+        Some(&session_id),
+        ACCEPT_MSGPACK,
+        encode(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let response = post_msgpack(
+        &server,
+        Some(&session_id),
+        ACCEPT_MSGPACK,
+        encode(&tool_call_request(2, "sum", json!({ "a": 40, "b": -2 }))),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let message = next_msgpack_message(&mut SseEventReader::new(response)).await;
+    assert_eq!(message["id"], 2);
+    assert_eq!(message["result"]["structuredContent"]["value"], 38);
+}
+
+#[actix_web::test]
+async fn json_clients_are_unaffected() {
+    let server = spawn_server(true).await;
+
+    // A MessagePack body answered in JSON, since the client does not accept msgpack
+    let response = post_msgpack(&server, None, ACCEPT_BOTH, encode(&initialize_request(1))).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert!(response.headers().get("X-MCP-Payload-Encoding").is_none());
+    let messages = common::test_server::read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 1);
+
+    let session_id = server.initialize("/mcp").await;
+    assert!(!session_id.is_empty());
+}
+
+#[actix_web::test]
+async fn msgpack_is_rejected_unless_enabled() {
+    let server = spawn_server(false).await;
+    let response = post_msgpack(&server, None, ACCEPT_BOTH, encode(&initialize_request(1))).await;
+    assert_eq!(
+        response.status(),
+        reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE
+    );
+    let problem: Value = response.json().await.unwrap();
+    let detail = problem["detail"].as_str().unwrap();
+    assert!(detail.contains("application/json"), "{detail}");
+    assert!(!detail.contains("application/msgpack"), "{detail}");
+}
+
+#[actix_web::test]
+async fn unsupported_media_type_lists_accepted_types() {
+    let server = spawn_server(true).await;
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .header("Content-Type", "text/plain")
+        .body("hello")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE
+    );
+    let problem: Value = response.json().await.unwrap();
+    let detail = problem["detail"].as_str().unwrap();
+    assert!(
+        detail.contains("application/json") && detail.contains("application/msgpack"),
+        "{detail}"
+    );
+}
+
+#[actix_web::test]
+async fn not_acceptable_lists_accepted_types() {
+    let server = spawn_server(true).await;
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .json(&initialize_request(1))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_ACCEPTABLE);
+    let problem: Value = response.json().await.unwrap();
+    let detail = problem["detail"].as_str().unwrap();
+    assert!(
+        detail.contains("application/json") && detail.contains("application/msgpack"),
+        "{detail}"
+    );
+
+    // Without MessagePack, only JSON is listed
+    let server = spawn_server(false).await;
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .json(&initialize_request(1))
+        .send()
+        .await
+        .unwrap();
+    let problem: Value = response.json().await.unwrap();
+    assert!(
+        !problem["detail"]
+            .as_str()
+            .unwrap()
+            .contains("application/msgpack")
+    );
+}
+
+#[actix_web::test]
+async fn malformed_msgpack_is_a_bad_request() {
+    let server = spawn_server(true).await;
+    // A map announcing one entry, cut off before its key
+    let response = post_msgpack(&server, None, ACCEPT_MSGPACK, vec![0x81]).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // A valid message followed by stray bytes
+    let mut body = encode(&initialize_request(1));
+    body.push(0xc0);
+    let response = post_msgpack(&server, None, ACCEPT_MSGPACK, body).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
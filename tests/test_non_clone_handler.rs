@@ -0,0 +1,80 @@
+//! Integration tests for handlers that do not implement `Clone`.
+
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use common::test_server::{TestServer, read_sse_messages};
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    model::{
+        ListResourcesResult, PaginatedRequestParams, RawResource, Resource, ServerCapabilities,
+        ServerInfo,
+    },
+    service::RequestContext,
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+/// Stand-in for a connection pool, which usually cannot be cloned.
+struct Pool {
+    connections: AtomicUsize,
+}
+
+/// Handler owning its pool by value; deliberately not `Clone`.
+struct PooledService {
+    pool: Pool,
+}
+
+impl ServerHandler for PooledService {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_resources().build())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let connection = self.pool.connections.fetch_add(1, Ordering::SeqCst);
+        Ok(ListResourcesResult::with_all_items(vec![Resource::new(
+            RawResource::new(format!("pool://connection/{connection}"), "connection"),
+            None,
+        )]))
+    }
+}
+
+#[actix_web::test]
+async fn non_clone_handler_is_served() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| {
+            Ok(PooledService {
+                pool: Pool {
+                    connections: AtomicUsize::new(0),
+                },
+            })
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+    let session_id = server.initialize("/mcp").await;
+
+    for (id, expected) in [(2, "pool://connection/0"), (3, "pool://connection/1")] {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": "resources/list" });
+        let response = server.post("/mcp", Some(&session_id), &request).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let messages = read_sse_messages(response).await;
+        let result = messages
+            .iter()
+            .find(|m| m["id"] == id)
+            .expect("resources/list must be answered");
+        assert_eq!(result["result"]["resources"][0]["uri"], expected);
+    }
+}
@@ -759,3 +759,48 @@ async fn test_on_request_builder_ergonomics() {
 
     // If this compiles, the test passes
 }
+
+/// Test that allow-listed extensions are copied without an on_request hook
+#[actix_web::test]
+async fn test_propagate_extensions_allow_list() {
+    use actix_web::web;
+    use common::test_server::{TestServer, tool_call_request};
+    use rmcp_actix_web::transport::ExtensionAllowList;
+
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(ExtensionTestService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .propagate_extensions(ExtensionAllowList::new().allow::<TestClaims>())
+        .build();
+
+    let server = TestServer::spawn(move |cfg: &mut web::ServiceConfig| {
+        cfg.service(
+            web::scope("/mcp")
+                .wrap_fn(|req, srv| {
+                    req.extensions_mut().insert(TestClaims {
+                        user_id: "allow-listed-user".to_string(),
+                        role: "viewer".to_string(),
+                    });
+                    srv.call(req)
+                })
+                .service(service.clone().scope()),
+        );
+    })
+    .await;
+
+    let session_id = server.initialize("/mcp").await;
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "get_claims", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let claims = extract_claims_from_sse_response(response)
+        .await
+        .expect("Should have received claims response");
+    assert_eq!(claims["user_id"], "allow-listed-user");
+    assert_eq!(claims["role"], "viewer");
+}
@@ -0,0 +1,72 @@
+//! Integration tests for the path normalization of the service scope.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{PathNormalization, StreamableHttpService};
+
+async fn spawn_server(path_normalization: Option<PathNormalization>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .maybe_path_normalization(path_normalization)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn initialize_at(server: &TestServer, path: &str) -> reqwest::StatusCode {
+    server
+        .client
+        .post(server.url(path))
+        .header("Accept", "application/json, text/event-stream")
+        .json(&initialize_request(1))
+        .send()
+        .await
+        .expect("Failed to send request")
+        .status()
+}
+
+#[actix_web::test]
+async fn trailing_slash_is_trimmed_by_default() {
+    let server = spawn_server(None).await;
+    assert_eq!(
+        initialize_at(&server, "/mcp/").await,
+        reqwest::StatusCode::OK
+    );
+}
+
+#[actix_web::test]
+async fn disabled_normalization_routes_paths_as_they_are() {
+    let server = spawn_server(Some(PathNormalization::Disabled)).await;
+    assert_eq!(
+        initialize_at(&server, "/mcp").await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        initialize_at(&server, "/mcp/").await,
+        reqwest::StatusCode::NOT_FOUND
+    );
+}
+
+#[actix_web::test]
+async fn merge_only_keeps_trailing_slash() {
+    let server = spawn_server(Some(PathNormalization::MergeOnly)).await;
+    assert_eq!(
+        initialize_at(&server, "/mcp").await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        initialize_at(&server, "/mcp/").await,
+        reqwest::StatusCode::NOT_FOUND
+    );
+}
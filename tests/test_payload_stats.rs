@@ -0,0 +1,83 @@
+//! Integration tests for payload size and serialization time statistics.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{PAYLOAD_SIZE_BUCKETS, StreamableHttpService};
+use serde_json::json;
+
+async fn spawn_server(
+    payload_stats: bool,
+) -> (
+    TestServer,
+    StreamableHttpService<Calculator, LocalSessionManager>,
+) {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .payload_stats(payload_stats)
+        .build();
+    let server = TestServer::spawn({
+        let service = service.clone();
+        move |cfg| {
+            cfg.service(service.clone().scope_with_path("/mcp"));
+        }
+    })
+    .await;
+    (server, service)
+}
+
+#[actix_web::test]
+async fn tool_results_are_recorded_per_tool() {
+    let (server, service) = spawn_server(true).await;
+    let session_id = server.initialize("/mcp").await;
+
+    for id in 2..4 {
+        let response = server
+            .post(
+                "/mcp",
+                Some(&session_id),
+                &tool_call_request(id, "sum", json!({ "a": 1, "b": 2 })),
+            )
+            .await;
+        read_sse_messages(response).await;
+    }
+
+    let stats = service.payload_stats();
+    assert!(stats.sources.contains_key("initialize"), "{stats:?}");
+    let sum = &stats.sources["tools/call:sum"];
+    assert_eq!(sum.messages, 2);
+    assert!(sum.size_bytes.sum > 0);
+    assert!(sum.size_bytes.max <= sum.size_bytes.sum);
+    assert_eq!(sum.size_bytes.buckets.len(), PAYLOAD_SIZE_BUCKETS.len() + 1);
+    // Buckets are cumulative, the unbounded one counts every message
+    let last = sum.size_bytes.buckets.last().unwrap();
+    assert_eq!((last.le, last.count), (None, 2));
+    assert_eq!(sum.serialization_micros.buckets.last().unwrap().count, 2);
+
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["sources"]["tools/call:sum"]["messages"], 2);
+    assert!(json["sources"]["tools/call:sum"]["sizeBytes"]["buckets"].is_array());
+}
+
+#[actix_web::test]
+async fn nothing_is_recorded_by_default() {
+    let (server, service) = spawn_server(false).await;
+    let session_id = server.initialize("/mcp").await;
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sum", json!({ "a": 1, "b": 2 })),
+        )
+        .await;
+    read_sse_messages(response).await;
+
+    assert!(service.payload_stats().sources.is_empty());
+}
@@ -0,0 +1,73 @@
+//! Integration tests for problem details answering HTTP-level failures.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{Problem, StreamableHttpService};
+use serde_json::json;
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn read_problem(response: reqwest::Response) -> Problem {
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/problem+json"
+    );
+    response.json().await.expect("problem details body")
+}
+
+#[actix_web::test]
+async fn unknown_session_is_a_problem_naming_the_session() {
+    let server = spawn_server().await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some("unknown-session"),
+            &tool_call_request(1, "sum", json!({ "a": 1, "b": 2 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    let problem = read_problem(response).await;
+    assert_eq!(problem.problem_type, "about:blank");
+    assert_eq!(problem.title, "Not Found");
+    assert_eq!(problem.status, 404);
+    assert_eq!(problem.code, "session_not_found");
+    assert_eq!(problem.session_id.as_deref(), Some("unknown-session"));
+}
+
+#[actix_web::test]
+async fn undecodable_body_is_a_problem() {
+    let server = spawn_server().await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Content-Type", "application/json")
+        .body("{not json")
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let problem = read_problem(response).await;
+    assert_eq!(problem.code, "bad_request");
+    assert!(problem.detail.is_some());
+    assert!(problem.session_id.is_none());
+}
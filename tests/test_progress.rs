@@ -0,0 +1,124 @@
+//! Integration tests for progress notifications.
+//!
+//! Progress reported by a handler during a tool call must travel on the SSE
+//! stream of the POST that started the call, ahead of the response, and never
+//! on the standalone stream or the stream of another request.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{SseEventReader, TestServer, read_sse_messages},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+fn build_service(stateful: bool) -> StreamableHttpService<SlowService, LocalSessionManager> {
+    StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful)
+        .build()
+}
+
+async fn spawn_server(
+    service: &StreamableHttpService<SlowService, LocalSessionManager>,
+) -> TestServer {
+    let service = service.clone();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Builds a call of the `progress` tool asking for progress under `token`.
+fn progress_call(id: i64, token: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "tools/call",
+        "params": {
+            "name": "progress",
+            "arguments": {},
+            "_meta": { "progressToken": token }
+        },
+        "id": id
+    })
+}
+
+/// Asserts `messages` are the three progress steps for `token`, then the response `id`.
+fn assert_progress_then_response(messages: &[Value], token: &str, id: i64) {
+    assert_eq!(messages.len(), 4, "{messages:#?}");
+    for (step, message) in messages[..3].iter().enumerate() {
+        assert_eq!(message["method"], "notifications/progress");
+        assert_eq!(message["params"]["progressToken"], token);
+        assert_eq!(message["params"]["progress"], json!((step + 1) as f64));
+    }
+    assert_eq!(messages[3]["id"], id);
+    assert_eq!(messages[3]["result"]["content"][0]["text"], "done");
+}
+
+#[actix_web::test]
+async fn progress_precedes_response_on_request_stream() {
+    let service = build_service(true);
+    let server = spawn_server(&service).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    let mut standalone = SseEventReader::new(response);
+
+    let response = server
+        .post("/mcp", Some(&session_id), &progress_call(2, "call-2"))
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert_progress_then_response(&messages, "call-2", 2);
+    assert_eq!(service.forwarded_progress_notifications(), 3);
+
+    // Nothing but priming events reaches the standalone stream
+    while let Some(event) = standalone.next_event(Duration::from_millis(200)).await {
+        assert!(event.data.is_empty(), "unexpected message: {}", event.data);
+    }
+}
+
+#[actix_web::test]
+async fn concurrent_calls_keep_their_own_progress() {
+    let service = build_service(true);
+    let server = spawn_server(&service).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let (first, second) = (progress_call(2, "first"), progress_call(3, "second"));
+    let (first, second) = tokio::join!(
+        server.post("/mcp", Some(&session_id), &first),
+        server.post("/mcp", Some(&session_id), &second),
+    );
+    let (first, second) = tokio::join!(read_sse_messages(first), read_sse_messages(second));
+
+    assert_progress_then_response(&first, "first", 2);
+    assert_progress_then_response(&second, "second", 3);
+    assert_eq!(service.forwarded_progress_notifications(), 6);
+}
+
+#[actix_web::test]
+async fn stateless_progress_precedes_response() {
+    let service = build_service(false);
+    let server = spawn_server(&service).await;
+
+    let response = server
+        .post("/mcp", None, &progress_call(1, "stateless"))
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+
+    assert_progress_then_response(&messages, "stateless", 1);
+    assert_eq!(service.forwarded_progress_notifications(), 3);
+}
@@ -0,0 +1,175 @@
+//! Integration tests for per-session protocol version handling.
+//!
+//! One deployment serves clients pinned to different spec revisions: the
+//! negotiated version is recorded per session, checked against the
+//! `MCP-Protocol-Version` header, and older clients get responses they
+//! understand.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    structured_service::StructuredService,
+    test_server::{ACCEPT_BOTH, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::{
+    model::ProtocolVersion, transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+async fn spawn_server() -> (
+    TestServer,
+    StreamableHttpService<StructuredService, LocalSessionManager>,
+) {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(StructuredService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let server = TestServer::spawn({
+        let service = service.clone();
+        move |cfg| {
+            cfg.service(service.clone().scope_with_path("/mcp"));
+        }
+    })
+    .await;
+    (server, service)
+}
+
+/// Runs the initialize handshake asking for `version` and returns the session id.
+async fn initialize_with_version(server: &TestServer, version: &str) -> String {
+    let response = server
+        .post(
+            "/mcp",
+            None,
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": version,
+                    "capabilities": {},
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                },
+                "id": 1
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["result"]["protocolVersion"], version);
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    session_id
+}
+
+/// Calls the `stats` tool, which answers with structured content only.
+async fn call_stats(server: &TestServer, session_id: &str) -> Value {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &tool_call_request(2, "stats", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response)
+        .await
+        .into_iter()
+        .find(|m| m["id"] == 2)
+        .expect("tool result")
+}
+
+async fn post_with_version_header(
+    server: &TestServer,
+    session_id: &str,
+    version: &str,
+) -> reqwest::Response {
+    server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .header("Mcp-Session-Id", session_id)
+        .header("MCP-Protocol-Version", version)
+        .json(&json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list" }))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+#[actix_web::test]
+async fn negotiated_version_is_recorded_per_session() {
+    let (server, service) = spawn_server().await;
+    let old = initialize_with_version(&server, "2024-11-05").await;
+    let new = initialize_with_version(&server, "2025-06-18").await;
+
+    assert_eq!(
+        service.session_protocol_version(&old.as_str().into()).await,
+        Some(ProtocolVersion::V_2024_11_05)
+    );
+    assert_eq!(
+        service.session_protocol_version(&new.as_str().into()).await,
+        Some(ProtocolVersion::V_2025_06_18)
+    );
+}
+
+#[actix_web::test]
+async fn structured_content_is_downgraded_for_older_sessions() {
+    let (server, _service) = spawn_server().await;
+
+    // Clients predating structuredContent read the result from a text block
+    let old = initialize_with_version(&server, "2025-03-26").await;
+    let result = call_stats(&server, &old).await;
+    let text = result["result"]["content"][0]["text"]
+        .as_str()
+        .expect("text content");
+    assert_eq!(serde_json::from_str::<Value>(text).unwrap()["sessions"], 3);
+    assert_eq!(result["result"]["structuredContent"]["sessions"], 3);
+
+    let new = initialize_with_version(&server, "2025-06-18").await;
+    let result = call_stats(&server, &new).await;
+    assert_eq!(result["result"]["content"], json!([]));
+    assert_eq!(result["result"]["structuredContent"]["sessions"], 3);
+}
+
+#[actix_web::test]
+async fn protocol_version_header_must_match_the_session() {
+    let (server, _service) = spawn_server().await;
+    let session_id = initialize_with_version(&server, "2025-06-18").await;
+
+    let response = post_with_version_header(&server, &session_id, "2025-06-18").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let response = post_with_version_header(&server, &session_id, "2025-03-26").await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "protocol_version_mismatch");
+    assert!(
+        body["detail"]
+            .as_str()
+            .unwrap()
+            .contains("does not match the negotiated version 2025-06-18")
+    );
+
+    let response = post_with_version_header(&server, &session_id, "1999-01-01").await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "unsupported_protocol_version");
+    assert_eq!(
+        body["detail"],
+        "Unsupported MCP-Protocol-Version: 1999-01-01"
+    );
+}
@@ -0,0 +1,113 @@
+//! Integration tests for access tokens passed in the query string of GET streams.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use actix_web::{HttpResponse, http::Method};
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+
+const TOKEN: &str = "short-lived token";
+
+/// Spawns a server whose guard requires `TOKEN` on GET requests, recording the
+/// query strings the guard sees.
+async fn spawn_server() -> (TestServer, Arc<Mutex<Vec<String>>>) {
+    let seen_queries = Arc::new(Mutex::new(Vec::new()));
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .session_id_query(true)
+        .query_token_param("access_token")
+        .request_guard_fn({
+            let seen_queries = seen_queries.clone();
+            move |req| {
+                if req.method() != Method::GET {
+                    return Ok(());
+                }
+                seen_queries
+                    .lock()
+                    .unwrap()
+                    .push(req.query_string().to_owned());
+                let authorized = req
+                    .headers()
+                    .get("Authorization")
+                    .and_then(|v| v.to_str().ok())
+                    == Some(&format!("Bearer {TOKEN}"));
+                if authorized {
+                    Ok(())
+                } else {
+                    Err(HttpResponse::Unauthorized().finish())
+                }
+            }
+        })
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+    (server, seen_queries)
+}
+
+async fn open_stream(
+    server: &TestServer,
+    query: &str,
+    authorization: Option<&str>,
+) -> reqwest::StatusCode {
+    let mut request = server
+        .client
+        .get(server.url(&format!("/mcp?{query}")))
+        .header("Accept", "text/event-stream");
+    if let Some(authorization) = authorization {
+        request = request.header("Authorization", authorization);
+    }
+    request
+        .send()
+        .await
+        .expect("Failed to open stream")
+        .status()
+}
+
+#[actix_web::test]
+async fn query_token_authenticates_the_stream() {
+    let (server, seen_queries) = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let status = open_stream(
+        &server,
+        &format!("access_token=short-lived%20token&session_id={session_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::OK);
+
+    // The token is gone from the URI the guard and the handlers see
+    assert_eq!(
+        seen_queries.lock().unwrap().as_slice(),
+        [format!("session_id={session_id}")]
+    );
+}
+
+#[actix_web::test]
+async fn authorization_header_takes_precedence() {
+    let (server, _) = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let status = open_stream(
+        &server,
+        &format!("access_token=short-lived%20token&session_id={session_id}"),
+        Some("Bearer revoked"),
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+
+    let status = open_stream(
+        &server,
+        &format!("access_token=wrong&session_id={session_id}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,112 @@
+//! Integration tests for `initialize` requests sent within an existing session.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{ReinitializePolicy, StreamableHttpService};
+use serde_json::{Value, json};
+
+async fn spawn_server(policy: Option<ReinitializePolicy>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .maybe_reinitialize_policy(policy)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Checks that `session_id` still answers tool calls.
+async fn assert_session_usable(server: &TestServer, session_id: &str) {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &tool_call_request(10, "sum", json!({ "a": 1, "b": 2 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert!(messages[0]["result"].is_object());
+}
+
+#[actix_web::test]
+async fn reinitialize_is_rejected_by_default() {
+    let server = spawn_server(None).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post("/mcp", Some(&session_id), &initialize_request(2))
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(body["id"], 2);
+    assert_eq!(body["error"]["code"], -32007);
+
+    assert_session_usable(&server, &session_id).await;
+}
+
+#[actix_web::test]
+async fn recreate_replaces_the_session() {
+    let server = spawn_server(Some(ReinitializePolicy::Recreate)).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post("/mcp", Some(&session_id), &initialize_request(2))
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let new_session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("recreated session must be announced")
+        .to_owned();
+    assert_ne!(new_session_id, session_id);
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 2);
+    assert!(messages[0]["result"]["serverInfo"].is_object());
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(3, "sum", json!({ "a": 1, "b": 2 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    assert_session_usable(&server, &new_session_id).await;
+}
+
+#[actix_web::test]
+async fn replay_answers_with_the_original_result() {
+    let server = spawn_server(Some(ReinitializePolicy::Replay)).await;
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_owned();
+    let original = read_sse_messages(response).await;
+
+    let response = server
+        .post("/mcp", Some(&session_id), &initialize_request(2))
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert!(response.headers().get("Mcp-Session-Id").is_none());
+    let replayed = read_sse_messages(response).await;
+    assert_eq!(replayed[0]["id"], 2);
+    assert_eq!(replayed[0]["result"], original[0]["result"]);
+
+    assert_session_usable(&server, &session_id).await;
+}
@@ -0,0 +1,162 @@
+//! Integration tests for the `RequestCancellation` handed to handlers.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{ACCEPT_BOTH, TestServer, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+const TOOL: &str = "hang_until_transport_cancels";
+
+async fn spawn_server(
+    stateful: bool,
+    cancelled: Arc<AtomicUsize>,
+) -> (TestServer, StreamableHttpService<SlowService>) {
+    // Request streams without event ids cannot be resumed
+    let mut session_manager = LocalSessionManager::default();
+    session_manager.session_config.sse_retry = None;
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            Ok(SlowService::with_cancel_counter(cancelled.clone()))
+        }))
+        .session_manager(Arc::new(session_manager))
+        .stateful_mode(stateful)
+        // Disconnects are noticed on the next write
+        .sse_keep_alive(Duration::from_millis(50))
+        .build();
+    let server = TestServer::spawn({
+        let service = service.clone();
+        move |cfg| {
+            cfg.service(service.clone().scope_with_path("/mcp"));
+        }
+    })
+    .await;
+    (server, service)
+}
+
+async fn wait_until(condition: impl Fn() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("request cancellation never fired");
+}
+
+async fn post_stateless(server: &TestServer) -> reqwest::Response {
+    server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .json(&tool_call_request(1, TOOL, json!({})))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+#[actix_web::test]
+async fn client_disconnect_cancels_request() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let (server, _) = spawn_server(true, cancelled.clone()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, TOOL, json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    drop(response);
+
+    wait_until(|| cancelled.load(Ordering::SeqCst) == 1).await;
+}
+
+#[actix_web::test]
+async fn session_close_cancels_request() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let (server, _) = spawn_server(true, cancelled.clone()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let request = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, TOOL, json!({})),
+        )
+        .await;
+    assert_eq!(request.status(), reqwest::StatusCode::OK);
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send DELETE");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    wait_until(|| cancelled.load(Ordering::SeqCst) == 1).await;
+    drop(request);
+}
+
+#[actix_web::test]
+async fn stateless_client_disconnect_cancels_request() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let (server, _) = spawn_server(false, cancelled.clone()).await;
+
+    let response = post_stateless(&server).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    drop(response);
+
+    wait_until(|| cancelled.load(Ordering::SeqCst) == 1).await;
+}
+
+#[actix_web::test]
+async fn shutdown_cancels_stateless_request() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let (server, service) = spawn_server(false, cancelled.clone()).await;
+
+    let request = post_stateless(&server).await;
+    assert_eq!(request.status(), reqwest::StatusCode::OK);
+
+    // Still running while the grace period lasts
+    service.shutdown(Duration::from_millis(200)).await;
+
+    wait_until(|| cancelled.load(Ordering::SeqCst) == 1).await;
+    drop(request);
+}
+
+#[actix_web::test]
+async fn running_request_is_not_cancelled() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let (server, _) = spawn_server(true, cancelled.clone()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let request = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, TOOL, json!({})),
+        )
+        .await;
+    assert_eq!(request.status(), reqwest::StatusCode::OK);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(cancelled.load(Ordering::SeqCst), 0);
+    drop(request);
+}
@@ -0,0 +1,206 @@
+//! Integration tests for request deadlines announced by clients.
+
+mod common;
+
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{ACCEPT_BOTH, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    model::{
+        ListResourcesResult, PaginatedRequestParams, RawResource, Resource, ServerCapabilities,
+        ServerInfo,
+    },
+    service::RequestContext,
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::{RequestDeadline, StreamableHttpService};
+use serde_json::{Value, json};
+
+/// Handler listing the remaining budget of the request, in milliseconds.
+struct BudgetReporter;
+
+impl ServerHandler for BudgetReporter {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_resources().build())
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let budget = match context.extensions.get::<RequestDeadline>() {
+            Some(deadline) => deadline.remaining().as_millis().to_string(),
+            None => "none".to_string(),
+        };
+        Ok(ListResourcesResult::with_all_items(vec![Resource::new(
+            RawResource::new("budget://request", budget),
+            None,
+        )]))
+    }
+}
+
+async fn spawn_slow_server(stateful: bool, headers: bool, timeout: Duration) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful)
+        .request_timeout(timeout)
+        .request_deadline_headers(headers)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn post_with_header(
+    server: &TestServer,
+    session_id: Option<&str>,
+    header: (&str, String),
+    body: &Value,
+) -> Vec<Value> {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .header("Content-Type", "application/json")
+        .header(header.0, header.1)
+        .json(body);
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    let response = request.send().await.expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    tokio::time::timeout(Duration::from_secs(5), read_sse_messages(response))
+        .await
+        .expect("stream must end")
+}
+
+fn timeout_message(messages: &[Value], id: i64) -> &str {
+    let error = messages
+        .iter()
+        .find(|m| m["id"] == id)
+        .expect("request must be answered");
+    assert_eq!(error["error"]["code"], -32008);
+    error["error"]["message"].as_str().unwrap()
+}
+
+#[actix_web::test]
+async fn request_timeout_header_shortens_timeout() {
+    let server = spawn_slow_server(true, true, Duration::from_secs(10)).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let messages = post_with_header(
+        &server,
+        Some(&session_id),
+        ("Request-Timeout", "0.2".to_string()),
+        &tool_call_request(2, "hang", json!({})),
+    )
+    .await;
+    assert_eq!(
+        timeout_message(&messages, 2),
+        "Request timed out after 200ms"
+    );
+}
+
+#[actix_web::test]
+async fn deadline_header_is_enforced_in_stateless_mode() {
+    let server = spawn_slow_server(false, true, Duration::from_secs(10)).await;
+    let deadline = SystemTime::now() + Duration::from_millis(300);
+    let millis = deadline.duration_since(UNIX_EPOCH).unwrap().as_millis();
+
+    let messages = post_with_header(
+        &server,
+        None,
+        ("X-Request-Deadline", millis.to_string()),
+        &tool_call_request(2, "hang", json!({})),
+    )
+    .await;
+    timeout_message(&messages, 2);
+}
+
+#[actix_web::test]
+async fn header_cannot_extend_timeout() {
+    let server = spawn_slow_server(true, true, Duration::from_millis(200)).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let messages = post_with_header(
+        &server,
+        Some(&session_id),
+        ("Request-Timeout", "60".to_string()),
+        &tool_call_request(2, "hang", json!({})),
+    )
+    .await;
+    assert_eq!(
+        timeout_message(&messages, 2),
+        "Request timed out after 200ms"
+    );
+}
+
+#[actix_web::test]
+async fn headers_are_ignored_by_default() {
+    let server = spawn_slow_server(true, false, Duration::from_millis(300)).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let messages = post_with_header(
+        &server,
+        Some(&session_id),
+        ("Request-Timeout", "0.05".to_string()),
+        &tool_call_request(2, "hang", json!({})),
+    )
+    .await;
+    assert_eq!(
+        timeout_message(&messages, 2),
+        "Request timed out after 300ms"
+    );
+}
+
+#[actix_web::test]
+async fn handler_sees_remaining_budget() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(BudgetReporter)))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .request_deadline_headers(true)
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+    let session_id = server.initialize("/mcp").await;
+    let request = json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" });
+
+    let messages = post_with_header(
+        &server,
+        Some(&session_id),
+        ("Request-Timeout", "5".to_string()),
+        &request,
+    )
+    .await;
+    let budget: u64 = messages[0]["result"]["resources"][0]["name"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .expect("budget in milliseconds");
+    assert!(
+        budget > 4000 && budget <= 5000,
+        "unexpected budget {budget}"
+    );
+
+    // Without a header nor a request timeout, there is no deadline
+    let messages = post_with_header(
+        &server,
+        Some(&session_id),
+        ("X-Other", "1".into()),
+        &request,
+    )
+    .await;
+    assert_eq!(messages[0]["result"]["resources"][0]["name"], "none");
+}
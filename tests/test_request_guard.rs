@@ -0,0 +1,70 @@
+//! Integration tests for the request guard hook.
+
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use actix_web::HttpResponse;
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+
+#[actix_web::test]
+async fn request_guard_short_circuits_before_session_work() {
+    let maintenance = Arc::new(AtomicBool::new(true));
+    let sessions_created = Arc::new(AtomicUsize::new(0));
+
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .request_guard_fn({
+            let maintenance = maintenance.clone();
+            move |_http_req| {
+                if maintenance.load(Ordering::SeqCst) {
+                    return Err(HttpResponse::ServiceUnavailable()
+                        .insert_header(("Retry-After", "120"))
+                        .body("Down for maintenance"));
+                }
+                Ok(())
+            }
+        })
+        .on_session_created_fn({
+            let sessions_created = sessions_created.clone();
+            move |_, _, _| {
+                sessions_created.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers().get("Retry-After").unwrap(), "120");
+    assert!(response.headers().get("Mcp-Session-Id").is_none());
+    assert_eq!(response.text().await.unwrap(), "Down for maintenance");
+    assert_eq!(sessions_created.load(Ordering::SeqCst), 0);
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", "whatever")
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    maintenance.store(false, Ordering::SeqCst);
+
+    server.initialize("/mcp").await;
+    assert_eq!(sessions_created.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,247 @@
+//! Integration tests for the request metadata extensions inserted by the
+//! transport (`ClientConnectionInfo` and `HeaderSnapshot`).
+
+mod common;
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+};
+
+use common::test_server::{TestServer, read_sse_messages, tool_call_request};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{StreamableHttpService, TrustedProxies};
+use serde_json::{Value, json};
+
+mod connection_service {
+    use rmcp::{
+        ErrorData as McpError, RoleServer, ServerHandler,
+        handler::server::router::tool::ToolRouter, model::*, service::RequestContext, tool,
+        tool_handler, tool_router,
+    };
+    use rmcp_actix_web::transport::{ClientConnectionInfo, HeaderSnapshot};
+    use serde_json::json;
+
+    #[derive(Clone)]
+    pub struct ConnectionService {
+        #[expect(
+            dead_code,
+            reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+        )]
+        tool_router: ToolRouter<ConnectionService>,
+    }
+
+    #[tool_router]
+    impl ConnectionService {
+        pub fn new() -> Self {
+            Self {
+                tool_router: Self::tool_router(),
+            }
+        }
+
+        /// Returns the connection info from the request context
+        #[tool(description = "Get client connection info")]
+        async fn whoami(
+            &self,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            let result = match context.extensions.get::<ClientConnectionInfo>() {
+                Some(info) => json!({
+                    "peer_ip": info.peer_addr.map(|a| a.ip().to_string()),
+                    "realip": info.realip_remote_addr,
+                    "scheme": info.scheme,
+                    "host": info.host,
+                    "user_agent": info.user_agent,
+                }),
+                None => json!(null),
+            };
+            Ok(CallToolResult::success(vec![Content::text(
+                result.to_string(),
+            )]))
+        }
+
+        /// Returns the captured request headers
+        #[tool(description = "Get captured request headers")]
+        async fn headers(
+            &self,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            let result = match context.extensions.get::<HeaderSnapshot>() {
+                Some(snapshot) => snapshot
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), json!(value)))
+                    .collect::<serde_json::Map<_, _>>()
+                    .into(),
+                None => json!(null),
+            };
+            Ok(CallToolResult::success(vec![Content::text(
+                result.to_string(),
+            )]))
+        }
+    }
+
+    #[tool_handler]
+    impl ServerHandler for ConnectionService {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        }
+    }
+}
+
+#[actix_web::test]
+async fn handlers_see_client_connection_info() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(
+            || Ok(connection_service::ConnectionService::new()),
+        ))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(false)
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", common::test_server::ACCEPT_BOTH)
+        .header("User-Agent", "connection-test/1.0")
+        .header("X-Forwarded-For", "203.0.113.7")
+        .json(&tool_call_request(1, "whoami", json!({})))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 1)
+        .expect("tool call response");
+    let info: Value =
+        serde_json::from_str(result["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+
+    assert_eq!(info["peer_ip"], "127.0.0.1");
+    assert_eq!(info["realip"], "203.0.113.7");
+    assert_eq!(info["scheme"], "http");
+    assert_eq!(info["host"], server.addr.to_string());
+    assert_eq!(info["user_agent"], "connection-test/1.0");
+}
+
+#[actix_web::test]
+async fn handlers_see_only_snapshotted_headers() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(
+            || Ok(connection_service::ConnectionService::new()),
+        ))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(false)
+        .snapshot_headers(vec!["Accept-Language".into(), "x-correlation-id".into()])
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", common::test_server::ACCEPT_BOTH)
+        .header("Accept-Language", "fr-CH")
+        .header("X-Correlation-Id", "abc-123")
+        .header("Authorization", "Bearer secret")
+        .json(&tool_call_request(1, "headers", json!({})))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 1)
+        .expect("tool call response");
+    let headers: Value =
+        serde_json::from_str(result["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+
+    assert_eq!(
+        headers,
+        json!({ "accept-language": "fr-CH", "x-correlation-id": "abc-123" })
+    );
+}
+
+async fn realip_with_trusted_proxies(proxies: TrustedProxies, forwarded_for: &str) -> Value {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(
+            || Ok(connection_service::ConnectionService::new()),
+        ))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(false)
+        .trusted_proxies(proxies)
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", common::test_server::ACCEPT_BOTH)
+        .header("X-Forwarded-For", forwarded_for)
+        .json(&tool_call_request(1, "whoami", json!({})))
+        .send()
+        .await
+        .expect("Failed to send request");
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 1)
+        .expect("tool call response");
+    let info: Value =
+        serde_json::from_str(result["result"]["content"][0]["text"].as_str().unwrap()).unwrap();
+    info["realip"].clone()
+}
+
+#[actix_web::test]
+async fn forwarded_for_is_only_believed_from_trusted_proxies() {
+    let loopback = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    // The test client connects from loopback, which acts as the trusted proxy; the
+    // chain is walked back to the first hop that is not trusted
+    let realip = realip_with_trusted_proxies(
+        TrustedProxies::new()
+            .trust(loopback)
+            .trust_network(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8),
+        "198.51.100.1, 203.0.113.7, 10.1.2.3",
+    )
+    .await;
+    assert_eq!(realip, "203.0.113.7");
+
+    // A peer outside the trusted networks cannot spoof its address
+    let realip = realip_with_trusted_proxies(
+        TrustedProxies::new().trust_network(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 0)), 8),
+        "203.0.113.7",
+    )
+    .await;
+    assert_eq!(realip, "127.0.0.1");
+}
+
+#[test]
+fn ipv4_mapped_proxy_networks_match_ipv4_peers() {
+    let proxies = TrustedProxies::new().trust_network("::ffff:10.0.0.0".parse().unwrap(), 104);
+    assert!(proxies.contains(IpAddr::V4(Ipv4Addr::new(10, 1, 2, 3))));
+    assert!(proxies.contains("::ffff:10.1.2.3".parse().unwrap()));
+    assert!(!proxies.contains(IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1))));
+    assert!(!proxies.contains(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+}
+
+#[test]
+#[should_panic(expected = "at least 96 bits")]
+fn ipv4_mapped_proxy_networks_need_a_mapped_prefix() {
+    let _ = TrustedProxies::new().trust_network("::ffff:10.0.0.0".parse().unwrap(), 80);
+}
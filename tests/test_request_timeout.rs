@@ -0,0 +1,113 @@
+//! Integration tests for the per-request processing timeout.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+async fn spawn_server(stateful: bool, cancelled: Arc<AtomicUsize>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            Ok(SlowService::with_cancel_counter(cancelled.clone()))
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful)
+        .request_timeout(Duration::from_millis(200))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn wait_for_cancellation(cancelled: &AtomicUsize) {
+    for _ in 0..50 {
+        if cancelled.load(Ordering::SeqCst) > 0 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("handler was not cancelled");
+}
+
+#[actix_web::test]
+async fn timed_out_request_gets_error_and_is_cancelled() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(true, cancelled.clone()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    // The stream ends with the timeout error instead of hanging
+    let messages = tokio::time::timeout(Duration::from_secs(5), read_sse_messages(response))
+        .await
+        .expect("stream must end after the timeout");
+    let error = messages
+        .iter()
+        .find(|m| m["id"] == 2)
+        .expect("timeout error");
+    assert_eq!(error["error"]["code"], -32008);
+    assert_eq!(error["error"]["message"], "Request timed out after 200ms");
+
+    wait_for_cancellation(&cancelled).await;
+
+    // The session keeps working after a timeout
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/list" }),
+        )
+        .await;
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 3)
+        .expect("tools/list result");
+    assert!(result["result"]["tools"].is_array());
+}
+
+#[actix_web::test]
+async fn stateless_request_times_out() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(false, cancelled).await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", common::test_server::ACCEPT_BOTH)
+        .json(&tool_call_request(1, "hang", json!({})))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let messages = tokio::time::timeout(Duration::from_secs(5), read_sse_messages(response))
+        .await
+        .expect("stream must end after the timeout");
+    let error = messages
+        .iter()
+        .find(|m| m["id"] == 1)
+        .expect("timeout error");
+    assert_eq!(error["error"]["code"], -32008);
+}
@@ -0,0 +1,128 @@
+//! Integration tests for handler-driven HTTP response headers.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::test_server::{TestServer, read_sse_messages, tool_call_request};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+mod rate_limited_service {
+    use std::time::Duration;
+
+    use rmcp::{
+        ErrorData as McpError, RoleServer, ServerHandler,
+        handler::server::router::tool::ToolRouter, model::*, service::RequestContext, tool,
+        tool_handler, tool_router,
+    };
+    use rmcp_actix_web::transport::ResponseHeaders;
+
+    #[derive(Clone)]
+    pub struct RateLimitedService {
+        #[expect(
+            dead_code,
+            reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+        )]
+        tool_router: ToolRouter<RateLimitedService>,
+    }
+
+    #[tool_router]
+    impl RateLimitedService {
+        pub fn new() -> Self {
+            Self {
+                tool_router: Self::tool_router(),
+            }
+        }
+
+        /// Simulates a slow downstream call that reports its rate limit
+        #[tool(description = "Call the downstream API")]
+        async fn fetch(
+            &self,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if let Some(headers) = context.extensions.get::<ResponseHeaders>() {
+                headers.insert("X-RateLimit-Remaining", "41");
+                headers.insert("Cache-Control", "private, max-age=60");
+            }
+            Ok(CallToolResult::success(vec![Content::text("fetched")]))
+        }
+    }
+
+    #[tool_handler]
+    impl ServerHandler for RateLimitedService {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        }
+    }
+}
+
+async fn spawn_server(stateful_mode: bool, response_headers: bool) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| {
+            Ok(rate_limited_service::RateLimitedService::new())
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful_mode)
+        .response_headers(response_headers)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn call_fetch(server: &TestServer, session_id: Option<&str>) -> reqwest::Response {
+    let response = server
+        .post(
+            "/mcp",
+            session_id,
+            &tool_call_request(2, "fetch", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response
+}
+
+#[actix_web::test]
+async fn handler_headers_are_applied_in_stateful_mode() {
+    let server = spawn_server(true, true).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = call_fetch(&server, Some(&session_id)).await;
+    assert_eq!(
+        response.headers().get("X-RateLimit-Remaining").unwrap(),
+        "41"
+    );
+    let cache_control: Vec<_> = response.headers().get_all("Cache-Control").iter().collect();
+    assert!(cache_control.contains(&&"private, max-age=60".parse().unwrap()));
+
+    let messages = read_sse_messages(response).await;
+    assert!(messages.iter().any(|m| m["id"] == 2));
+}
+
+#[actix_web::test]
+async fn handler_headers_are_applied_in_stateless_mode() {
+    let server = spawn_server(false, true).await;
+
+    let response = call_fetch(&server, None).await;
+    assert_eq!(
+        response.headers().get("X-RateLimit-Remaining").unwrap(),
+        "41"
+    );
+
+    let messages = read_sse_messages(response).await;
+    assert!(messages.iter().any(|m| m["id"] == 2));
+}
+
+#[actix_web::test]
+async fn handler_headers_are_ignored_when_disabled() {
+    let server = spawn_server(true, false).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = call_fetch(&server, Some(&session_id)).await;
+    assert!(response.headers().get("X-RateLimit-Remaining").is_none());
+}
@@ -0,0 +1,89 @@
+//! Integration tests for building URLs to the MCP routes with `url_for`.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use actix_web::{HttpRequest, HttpResponse, web};
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::Value;
+
+fn build_service(
+    route_name: Option<&str>,
+) -> StreamableHttpService<Calculator, LocalSessionManager> {
+    StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .blob_ttl(Duration::from_secs(60))
+        .upload_ttl(Duration::from_secs(60))
+        .legacy_sse(true)
+        .maybe_route_name(route_name)
+        .build()
+}
+
+/// Answers with the URLs of the routes named `{name}`, `{name}_blob`, ...
+async fn links(req: HttpRequest, name: web::Path<String>) -> HttpResponse {
+    let url = |suffix: &str, args: &[&str]| {
+        req.url_for(&format!("{name}{suffix}"), args)
+            .map(|url| url.path().to_string())
+            .unwrap_or_default()
+    };
+    HttpResponse::Ok().json(serde_json::json!({
+        "endpoint": url("", &[]),
+        "blob": url("_blob", &["abc"]),
+        "upload": url("_upload", &[]),
+        "sse": url("_sse", &[]),
+        "message": url("_message", &[]),
+    }))
+}
+
+async fn fetch_links(server: &TestServer, name: &str) -> Value {
+    let response = server
+        .client
+        .get(server.url(&format!("/links/{name}")))
+        .send()
+        .await
+        .expect("Failed to fetch links");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response.json().await.expect("links are JSON")
+}
+
+#[actix_web::test]
+async fn routes_have_default_names() {
+    let service = build_service(None);
+    let server = TestServer::spawn(move |cfg| {
+        cfg.route("/links/{name}", web::get().to(links))
+            .service(service.clone().scope_with_path("/api/mcp"));
+    })
+    .await;
+
+    let links = fetch_links(&server, "mcp").await;
+    assert_eq!(links["endpoint"], "/api/mcp");
+    assert_eq!(links["blob"], "/api/mcp/blobs/abc");
+    assert_eq!(links["upload"], "/api/mcp/uploads");
+    assert_eq!(links["sse"], "/api/mcp/sse");
+    assert_eq!(links["message"], "/api/mcp/message");
+}
+
+#[actix_web::test]
+async fn services_in_one_app_have_their_own_names() {
+    let first = build_service(None);
+    let second = build_service(Some("admin_mcp"));
+    let server = TestServer::spawn(move |cfg| {
+        cfg.route("/links/{name}", web::get().to(links))
+            .service(first.clone().scope_with_path("/mcp"))
+            .service(second.clone().scope_with_path("/admin/mcp"));
+    })
+    .await;
+
+    assert_eq!(fetch_links(&server, "mcp").await["endpoint"], "/mcp");
+    let links = fetch_links(&server, "admin_mcp").await;
+    assert_eq!(links["endpoint"], "/admin/mcp");
+    assert_eq!(links["sse"], "/admin/mcp/sse");
+
+    // The named endpoint still serves MCP
+    let session_id = server.initialize("/admin/mcp").await;
+    assert!(!session_id.is_empty());
+}
@@ -0,0 +1,70 @@
+//! Integration tests for the runtime statistics endpoint.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::Value;
+
+fn build_service() -> StreamableHttpService<Calculator, LocalSessionManager> {
+    StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build()
+}
+
+async fn fetch_stats(server: &TestServer) -> Value {
+    let response = server
+        .client
+        .get(server.url("/internal/stats"))
+        .send()
+        .await
+        .expect("Failed to fetch stats");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response.json().await.expect("stats are JSON")
+}
+
+#[actix_web::test]
+async fn stats_report_sessions_and_streams() {
+    let service = build_service();
+    let server = TestServer::spawn({
+        let service = service.clone();
+        move |cfg| {
+            cfg.service(service.stats_route("/internal/stats"))
+                .service(service.clone().scope_with_path("/mcp"));
+        }
+    })
+    .await;
+
+    let stats = fetch_stats(&server).await;
+    assert_eq!(stats["sessions"], 0);
+    assert_eq!(stats["sseStreams"], 0);
+    assert_eq!(stats["draining"], false);
+    assert_eq!(stats["build"]["name"], "rmcp-actix-web");
+    assert_eq!(stats["build"]["version"], env!("CARGO_PKG_VERSION"));
+
+    let session_id = server.initialize("/mcp").await;
+    let stream = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    assert_eq!(stream.status(), reqwest::StatusCode::OK);
+
+    let stats = fetch_stats(&server).await;
+    assert_eq!(stats["sessions"], 1);
+    assert_eq!(stats["halfOpenSessions"], 0);
+    assert_eq!(stats["sseStreams"], 1);
+    assert_eq!(stats["pendingRequests"], 0);
+
+    let snapshot = service.runtime_stats().await;
+    assert_eq!(snapshot.sessions, 1);
+    assert_eq!(snapshot.sse_streams, 1);
+    assert!(!snapshot.draining);
+}
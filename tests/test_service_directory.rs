@@ -0,0 +1,213 @@
+//! Integration tests for the combined discovery of several services.
+
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::{
+    ErrorData as McpError, RoleServer, ServerHandler,
+    model::{
+        Implementation, ListToolsResult, PaginatedRequestParams, ServerCapabilities, ServerInfo,
+    },
+    service::RequestContext,
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::{
+    DiscoveryAuth, MessageFilter, ServiceDirectory, StreamableHttpService,
+};
+use serde_json::Value;
+
+/// Handler offering resources only.
+struct Files;
+
+impl ServerHandler for Files {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_resources().build())
+            .with_server_info(Implementation::new("files", "2.0.0"))
+    }
+}
+
+/// Handler whose tool list never ends.
+struct EndlessTools;
+
+impl ServerHandler for EndlessTools {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let page = request
+            .and_then(|request| request.cursor)
+            .map_or(0, |cursor| cursor.parse::<u64>().unwrap() + 1);
+        Ok(ListToolsResult {
+            next_cursor: Some(page.to_string()),
+            ..Default::default()
+        })
+    }
+}
+
+async fn get_document(server: &TestServer) -> Value {
+    let response = server
+        .client
+        .get(server.url("/api/services"))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response.json().await.expect("JSON document")
+}
+
+fn tool_names(service: &Value) -> Vec<&str> {
+    let mut tools: Vec<_> = service["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    tools.sort_unstable();
+    tools
+}
+
+async fn fetch_directory() -> Value {
+    let calculator = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .discovery_auth(DiscoveryAuth::ApiKey {
+            header: "X-API-Key".to_string(),
+        })
+        .build();
+    let files = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Files)))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(false)
+        .build();
+    let broken = StreamableHttpService::<Calculator>::builder()
+        .service_factory(Arc::new(|| Err(std::io::Error::other("database down"))))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let directory = ServiceDirectory::new()
+        .service(calculator.directory_entry("/api/calculator"))
+        .service(files.directory_entry("/api/files"))
+        .service(broken.directory_entry("/api/broken"));
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(directory.resource("/api/services"))
+            .service(calculator.clone().scope_with_path("/api/calculator"))
+            .service(files.clone().scope_with_path("/api/files"))
+            .service(broken.clone().scope_with_path("/api/broken"));
+    })
+    .await;
+    get_document(&server).await
+}
+
+#[actix_web::test]
+async fn directory_describes_every_service() {
+    let document = fetch_directory().await;
+    let services = document["services"].as_array().unwrap();
+    assert_eq!(services.len(), 2);
+
+    let calculator = &services[0];
+    assert_eq!(calculator["transport"]["endpoint"], "/api/calculator");
+    assert_eq!(calculator["transport"]["stateful"], true);
+    assert_eq!(calculator["authentication"]["type"], "apiKey");
+    assert_eq!(tool_names(calculator), ["sub", "sum"]);
+
+    let files = &services[1];
+    assert_eq!(files["name"], "files");
+    assert_eq!(files["version"], "2.0.0");
+    assert_eq!(files["transport"]["stateful"], false);
+    assert!(files["capabilities"]["resources"].is_object());
+    assert_eq!(files["tools"], serde_json::json!([]));
+}
+
+#[actix_web::test]
+async fn failing_service_is_reported_unavailable() {
+    let document = fetch_directory().await;
+    assert_eq!(document["unavailable"], serde_json::json!(["/api/broken"]));
+}
+
+#[actix_web::test]
+async fn descriptions_are_cached() {
+    let constructed = Arc::new(AtomicUsize::new(0));
+    let calculator = StreamableHttpService::builder()
+        .service_factory({
+            let constructed = constructed.clone();
+            Arc::new(move || {
+                constructed.fetch_add(1, Ordering::SeqCst);
+                Ok(Calculator::new())
+            })
+        })
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let directory = ServiceDirectory::new().service(calculator.directory_entry("/api/calculator"));
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(directory.resource("/api/services"));
+    })
+    .await;
+
+    let first = get_document(&server).await;
+    let constructed_once = constructed.load(Ordering::SeqCst);
+    assert!(constructed_once > 0);
+    for _ in 0..3 {
+        assert_eq!(get_document(&server).await, first);
+    }
+    assert_eq!(constructed.load(Ordering::SeqCst), constructed_once);
+}
+
+#[actix_web::test]
+async fn endless_tool_pages_are_reported_unavailable() {
+    let endless = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(EndlessTools)))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let directory = ServiceDirectory::new().service(endless.directory_entry("/api/endless"));
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(directory.resource("/api/services"));
+    })
+    .await;
+
+    let document = get_document(&server).await;
+    assert_eq!(document["unavailable"], serde_json::json!(["/api/endless"]));
+}
+
+#[actix_web::test]
+async fn denied_and_disabled_tools_are_left_out() {
+    let calculator = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .message_filter(MessageFilter::new().deny_tools(["sub"]))
+        .build();
+    let directory = ServiceDirectory::new().service(calculator.directory_entry("/api/calculator"));
+    let server = TestServer::spawn({
+        let directory = directory.clone();
+        move |cfg| {
+            cfg.service(directory.resource("/api/services"));
+        }
+    })
+    .await;
+
+    let document = get_document(&server).await;
+    assert_eq!(tool_names(&document["services"][0]), ["sum"]);
+
+    // Runtime toggles apply to the cached description right away
+    assert!(calculator.disable_tool("sum").await);
+    let document = get_document(&server).await;
+    assert!(tool_names(&document["services"][0]).is_empty());
+
+    // A denied method hides every tool
+    let read_only = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .message_filter(MessageFilter::new().deny_methods(["tools/call"]))
+        .build();
+    let directory = ServiceDirectory::new().service(read_only.directory_entry("/api/calculator"));
+    assert!(directory.document().await.services[0].tools.is_empty());
+}
@@ -0,0 +1,87 @@
+//! Integration tests for service factory failures.
+//!
+//! A failing `service_factory` is treated as a transient condition: clients get
+//! `503 Service Unavailable` with `Retry-After` and a JSON-RPC error body.
+
+mod common;
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+async fn spawn_server(stateful: bool, available: Arc<AtomicBool>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            if available.load(Ordering::SeqCst) {
+                Ok(Calculator::new())
+            } else {
+                Err(std::io::Error::other("database connection pool exhausted"))
+            }
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful)
+        .legacy_sse(stateful)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn assert_service_unavailable(response: reqwest::Response, id: Value) {
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers().get("Retry-After").unwrap(), "5");
+    assert!(response.headers().get("Mcp-Session-Id").is_none());
+    let body: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(body["id"], id);
+    assert_eq!(body["error"]["code"], -32603);
+    assert_eq!(body["error"]["message"], "Service temporarily unavailable");
+    // The cause is logged, not sent to the client
+    assert!(!body.to_string().contains("pool exhausted"));
+}
+
+#[actix_web::test]
+async fn initialize_gets_503_while_factory_fails() {
+    let available = Arc::new(AtomicBool::new(false));
+    let server = spawn_server(true, available.clone()).await;
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_service_unavailable(response, json!(1)).await;
+
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open SSE stream");
+    assert_service_unavailable(response, Value::Null).await;
+
+    // Retrying once the condition clears succeeds
+    available.store(true, Ordering::SeqCst);
+    server.initialize("/mcp").await;
+}
+
+#[actix_web::test]
+async fn stateless_request_gets_503_while_factory_fails() {
+    let server = spawn_server(false, Arc::new(AtomicBool::new(false))).await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", common::test_server::ACCEPT_BOTH)
+        .json(&tool_call_request(7, "sum", json!({ "a": 1, "b": 2 })))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_service_unavailable(response, json!(7)).await;
+}
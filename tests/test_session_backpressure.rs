@@ -0,0 +1,114 @@
+//! Integration tests for the per-session cap on pending requests.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    slow_service::SlowService,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .max_pending_requests_per_session(1)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn saturated_session_refuses_requests() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let pending = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(pending.status(), reqwest::StatusCode::OK);
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(3, "slow", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers()["retry-after"], "1");
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 3);
+    assert_eq!(error["error"]["code"], -32005);
+
+    // Notifications are still accepted
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "method": "notifications/roots/list_changed" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+}
+
+#[actix_web::test]
+async fn finished_requests_free_their_slot() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "slow", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert!(messages.iter().any(|m| m["id"] == 2));
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(3, "slow", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn sessions_are_capped_independently() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let other_session_id = server.initialize("/mcp").await;
+
+    let pending = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(pending.status(), reqwest::StatusCode::OK);
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&other_session_id),
+            &tool_call_request(2, "slow", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
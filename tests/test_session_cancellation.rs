@@ -0,0 +1,116 @@
+//! Integration tests for the cancellation of in-flight handlers of closed sessions.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{TestServer, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+async fn spawn_server(
+    cancelled: Arc<AtomicUsize>,
+    session_manager: LocalSessionManager,
+) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            Ok(SlowService::with_cancel_counter(cancelled.clone()))
+        }))
+        .session_manager(Arc::new(session_manager))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn wait_until(condition: impl Fn() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("condition not reached after the session was closed");
+}
+
+#[actix_web::test]
+async fn delete_cancels_in_flight_handlers() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(cancelled.clone(), LocalSessionManager::default()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    // The request stream stays open, so only closing the session can cancel it
+    let request = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(request.status(), reqwest::StatusCode::OK);
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send DELETE");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+
+    wait_until(|| cancelled.load(Ordering::SeqCst) == 1).await;
+    drop(request);
+}
+
+#[actix_web::test]
+async fn expiry_cancels_in_flight_handlers() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let mut session_manager = LocalSessionManager::default();
+    session_manager.session_config.keep_alive = Some(Duration::from_millis(200));
+    let server = spawn_server(cancelled.clone(), session_manager).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let request = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(request.status(), reqwest::StatusCode::OK);
+
+    // Without cancellation, the service would wait for the handler to answer
+    wait_until(|| cancelled.load(Ordering::SeqCst) == 1).await;
+    drop(request);
+}
+
+#[actix_web::test]
+async fn open_session_keeps_its_handlers_running() {
+    let cancelled = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(cancelled.clone(), LocalSessionManager::default()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let request = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "hang", json!({})),
+        )
+        .await;
+    assert_eq!(request.status(), reqwest::StatusCode::OK);
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(cancelled.load(Ordering::SeqCst), 0);
+    drop(request);
+}
@@ -0,0 +1,137 @@
+//! Integration tests for the capabilities negotiated by `initialize`.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{SessionCapabilities, StreamableHttpService};
+use serde_json::{Value, json};
+
+/// Whether each request seen by the on_request hook carried client sampling
+/// support, or `None` if it carried no [`SessionCapabilities`].
+type SeenSampling = Arc<Mutex<Vec<Option<bool>>>>;
+
+async fn spawn_server(
+    seen: &SeenSampling,
+) -> (
+    TestServer,
+    StreamableHttpService<Calculator, LocalSessionManager>,
+) {
+    let seen = seen.clone();
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .on_request_fn(move |_req, extensions| {
+            let sampling = extensions
+                .get::<SessionCapabilities>()
+                .map(|capabilities| capabilities.client.sampling.is_some());
+            seen.lock().unwrap().push(sampling);
+        })
+        .build();
+    let server = TestServer::spawn({
+        let service = service.clone();
+        move |cfg| {
+            cfg.service(service.clone().scope_with_path("/mcp"));
+        }
+    })
+    .await;
+    (server, service)
+}
+
+/// Runs the initialize handshake declaring `capabilities` and returns the
+/// session id.
+async fn initialize_with_capabilities(server: &TestServer, capabilities: Value) -> String {
+    let response = server
+        .post(
+            "/mcp",
+            None,
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": capabilities,
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                },
+                "id": 1
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    read_sse_messages(response).await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    session_id
+}
+
+#[actix_web::test]
+async fn capabilities_are_recorded_per_session() {
+    let seen = SeenSampling::default();
+    let (server, service) = spawn_server(&seen).await;
+    let sampling = initialize_with_capabilities(&server, json!({ "sampling": {} })).await;
+    let plain = initialize_with_capabilities(&server, json!({})).await;
+
+    let capabilities = service
+        .session_capabilities(&sampling.as_str().into())
+        .await
+        .expect("session is initialized");
+    assert!(capabilities.client.sampling.is_some());
+    assert!(capabilities.server.tools.is_some());
+
+    let capabilities = service
+        .session_capabilities(&plain.as_str().into())
+        .await
+        .expect("session is initialized");
+    assert!(capabilities.client.sampling.is_none());
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &plain)
+        .send()
+        .await
+        .expect("Failed to send DELETE");
+    assert!(response.status().is_success());
+    assert_eq!(
+        service.session_capabilities(&plain.as_str().into()).await,
+        None
+    );
+}
+
+#[actix_web::test]
+async fn capabilities_are_injected_into_later_requests() {
+    let seen = SeenSampling::default();
+    let (server, _service) = spawn_server(&seen).await;
+    let session_id = initialize_with_capabilities(&server, json!({ "sampling": {} })).await;
+    seen.lock().unwrap().clear();
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response).await;
+
+    assert_eq!(seen.lock().unwrap().clone(), vec![Some(true)]);
+}
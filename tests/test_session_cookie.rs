@@ -0,0 +1,133 @@
+//! Integration tests for carrying the session id in a cookie.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+const COOKIE_NAME: &str = "mcp_session";
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .session_cookie(COOKIE_NAME)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn post_with_cookie(server: &TestServer, cookie: &str, body: &Value) -> reqwest::Response {
+    server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Cookie", cookie)
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+fn set_cookie(response: &reqwest::Response) -> &str {
+    response
+        .headers()
+        .get("Set-Cookie")
+        .and_then(|v| v.to_str().ok())
+        .expect("response must set the session cookie")
+}
+
+#[actix_web::test]
+async fn cookie_identifies_the_session() {
+    let server = spawn_server().await;
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_owned();
+    let cookie = set_cookie(&response);
+    assert!(cookie.starts_with(&format!("{COOKIE_NAME}={session_id};")));
+    assert!(cookie.contains("HttpOnly"));
+    assert!(cookie.contains("Path=/mcp"));
+    let _ = read_sse_messages(response).await;
+
+    let cookie = format!("theme=dark; {COOKIE_NAME}={session_id}");
+    let response = post_with_cookie(
+        &server,
+        &cookie,
+        &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let response = post_with_cookie(
+        &server,
+        &cookie,
+        &tool_call_request(2, "sum", json!({ "a": 2, "b": 3 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 2);
+    assert!(messages[0]["result"].is_object());
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Cookie", &cookie)
+        .send()
+        .await
+        .expect("Failed to send DELETE request");
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    assert!(set_cookie(&response).contains("Max-Age=0"));
+}
+
+#[actix_web::test]
+async fn header_takes_precedence_and_initialize_ignores_cookie() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let stale_cookie = format!("{COOKIE_NAME}=expired-session");
+
+    // The header wins over a stale cookie
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .header("Cookie", &stale_cookie)
+        .json(&tool_call_request(2, "sum", json!({ "a": 1, "b": 1 })))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let _ = read_sse_messages(response).await;
+
+    // A stale cookie alone points to a missing session
+    let response = post_with_cookie(
+        &server,
+        &stale_cookie,
+        &tool_call_request(3, "sum", json!({ "a": 1, "b": 1 })),
+    )
+    .await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // but does not keep the client from starting a new one
+    let response = post_with_cookie(&server, &stale_cookie, &initialize_request(4)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert!(set_cookie(&response).starts_with(&format!("{COOKIE_NAME}=")));
+    assert!(!set_cookie(&response).contains("expired-session"));
+}
@@ -0,0 +1,90 @@
+//! Integration tests for disabling session termination by clients.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{SseEventReader, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        .session_delete(false)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+fn assert_method_not_allowed(response: &reqwest::Response, allow: &str) {
+    assert_eq!(response.status(), reqwest::StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(
+        response
+            .headers()
+            .get("Allow")
+            .and_then(|v| v.to_str().ok()),
+        Some(allow)
+    );
+}
+
+#[actix_web::test]
+async fn delete_is_refused_and_session_survives() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_method_not_allowed(&response, "GET, POST, HEAD, OPTIONS");
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .json(&tool_call_request(2, "sum", json!({ "a": 1, "b": 2 })))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 2);
+}
+
+#[actix_web::test]
+async fn legacy_delete_is_refused() {
+    let server = spawn_server().await;
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open SSE stream");
+    let mut events = SseEventReader::new(response);
+    let endpoint = events.next_event(TIMEOUT).await.expect("endpoint event");
+
+    let response = server
+        .client
+        .delete(server.url(&endpoint.data))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_method_not_allowed(&response, "POST, OPTIONS");
+}
@@ -0,0 +1,130 @@
+//! Integration tests for session-scoped sticky extensions.
+//!
+//! Extensions computed from the `initialize` request must reach the handler on
+//! every later request of the same session, without being re-derived.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+/// Tenant resolved once, at initialize time
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tenant(pub String);
+
+mod tenant_service {
+    use rmcp::{
+        ErrorData as McpError, RoleServer, ServerHandler,
+        handler::server::router::tool::ToolRouter, model::*, service::RequestContext, tool,
+        tool_handler, tool_router,
+    };
+
+    use super::Tenant;
+
+    #[derive(Clone)]
+    pub struct TenantService {
+        #[expect(
+            dead_code,
+            reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+        )]
+        tool_router: ToolRouter<TenantService>,
+    }
+
+    #[tool_router]
+    impl TenantService {
+        pub fn new() -> Self {
+            Self {
+                tool_router: Self::tool_router(),
+            }
+        }
+
+        /// Returns the tenant from the request context, if any
+        #[tool(description = "Get tenant from request context")]
+        async fn get_tenant(
+            &self,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            let tenant = context
+                .extensions
+                .get::<Tenant>()
+                .map(|t| t.0.clone())
+                .unwrap_or_default();
+            Ok(CallToolResult::success(vec![Content::text(tenant)]))
+        }
+    }
+
+    #[tool_handler]
+    impl ServerHandler for TenantService {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        }
+    }
+}
+
+#[actix_web::test]
+async fn sticky_extensions_reach_every_request_of_the_session() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(tenant_service::TenantService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .session_extensions_fn(|http_req, ext| {
+            if let Some(tenant) = http_req
+                .headers()
+                .get("X-Tenant")
+                .and_then(|v| v.to_str().ok())
+            {
+                ext.insert(Tenant(tenant.to_string()));
+            }
+        })
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", common::test_server::ACCEPT_BOTH)
+        .header("X-Tenant", "acme")
+        .json(&initialize_request(1))
+        .send()
+        .await
+        .expect("Failed to send request");
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let _ = read_sse_messages(response).await;
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    // Later requests no longer carry the header, but still see the tenant
+    for id in 2..4 {
+        let response = server
+            .post(
+                "/mcp",
+                Some(&session_id),
+                &tool_call_request(id, "get_tenant", json!({})),
+            )
+            .await;
+        let messages = read_sse_messages(response).await;
+        let result = messages
+            .iter()
+            .find(|m| m["id"] == id)
+            .expect("tool call response");
+        assert_eq!(result["result"]["content"][0]["text"], "acme");
+    }
+}
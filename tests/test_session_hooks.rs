@@ -0,0 +1,182 @@
+//! Integration tests for the session lifecycle hooks.
+
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, read_sse_messages},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{SessionCloseReason, StreamableHttpService};
+
+#[derive(Debug, Clone)]
+struct CreatedSession {
+    session_id: String,
+    client_name: String,
+    path: String,
+}
+
+#[actix_web::test]
+async fn on_session_created_receives_initialize_params() {
+    let created = Arc::new(Mutex::new(Vec::<CreatedSession>::new()));
+
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .on_session_created_fn({
+            let created = created.clone();
+            move |http_req, session_id, params| {
+                created.lock().unwrap().push(CreatedSession {
+                    session_id: session_id.to_string(),
+                    client_name: params.client_info.name.clone(),
+                    path: http_req.path().to_string(),
+                });
+            }
+        })
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let session_id = server.initialize("/mcp").await;
+
+    let created = created.lock().unwrap().clone();
+    assert_eq!(created.len(), 1, "hook must run exactly once per session");
+    assert_eq!(created[0].session_id, session_id);
+    assert_eq!(created[0].client_name, "test-client");
+    assert_eq!(created[0].path, "/mcp");
+}
+
+type ClosedSessions = Arc<Mutex<Vec<(String, SessionCloseReason)>>>;
+
+async fn spawn_server_recording_closes(
+    legacy_sse: bool,
+    initialize_timeout: Option<Duration>,
+) -> (TestServer, ClosedSessions) {
+    let closed = ClosedSessions::default();
+
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(legacy_sse)
+        .maybe_initialize_timeout(initialize_timeout)
+        // Frequent keep-alives let the server notice disconnected streams quickly
+        .sse_keep_alive(Duration::from_millis(100))
+        .on_session_closed_fn({
+            let closed = closed.clone();
+            move |session_id, reason| {
+                closed
+                    .lock()
+                    .unwrap()
+                    .push((session_id.to_string(), reason));
+            }
+        })
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    (server, closed)
+}
+
+#[actix_web::test]
+async fn on_session_closed_fires_once_on_client_delete() {
+    let (server, closed) = spawn_server_recording_closes(false, None).await;
+
+    let session_id = server.initialize("/mcp").await;
+    assert!(closed.lock().unwrap().is_empty());
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert!(response.status().is_success());
+
+    // Give the service task time to observe the closed transport
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let closed = closed.lock().unwrap().clone();
+    assert_eq!(closed, vec![(session_id, SessionCloseReason::ClientDelete)]);
+}
+
+#[actix_web::test]
+async fn on_session_closed_fires_when_legacy_stream_disconnects() {
+    let (server, closed) = spawn_server_recording_closes(true, None).await;
+
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open SSE stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    drop(response);
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(2);
+    while closed.lock().unwrap().is_empty() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let closed = closed.lock().unwrap().clone();
+    assert_eq!(closed.len(), 1, "hook must run exactly once per session");
+    assert!(matches!(
+        closed[0].1,
+        SessionCloseReason::StreamDisconnected(_)
+    ));
+}
+
+#[actix_web::test]
+async fn half_open_session_is_closed_after_initialize_timeout() {
+    let (server, closed) =
+        spawn_server_recording_closes(false, Some(Duration::from_millis(200))).await;
+
+    // A client that crashed right after `initialize` never sends `initialized`
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    let half_open = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let _ = read_sse_messages(response).await;
+
+    let established = server.initialize("/mcp").await;
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let closed = closed.lock().unwrap().clone();
+    assert_eq!(
+        closed,
+        vec![(half_open.clone(), SessionCloseReason::InitializeTimeout)]
+    );
+    let response = server
+        .post(
+            "/mcp",
+            Some(&half_open),
+            &serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&established),
+            &serde_json::json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
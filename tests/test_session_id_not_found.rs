@@ -19,8 +19,18 @@ use serde_json::json;
 use std::sync::Arc;
 use std::time::Duration;
 
-const MISSING_SESSION_ID_BODY: &str = "Bad Request: Mcp-Session-Id header is required";
-const SESSION_NOT_FOUND_BODY: &str = "Session not found";
+const MISSING_SESSION_ID_CODE: &str = "missing_session_id";
+const SESSION_NOT_FOUND_CODE: &str = "session_not_found";
+
+/// Returns the `code` of the problem details document answering a request.
+async fn problem_code(response: reqwest::Response) -> String {
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/problem+json"
+    );
+    let body: serde_json::Value = response.json().await.expect("Failed to read problem");
+    body["code"].as_str().expect("problem code").to_owned()
+}
 
 struct TestServer {
     url: String,
@@ -91,8 +101,7 @@ async fn post_with_unknown_session_id_returns_404() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, SESSION_NOT_FOUND_BODY);
+    assert_eq!(problem_code(response).await, SESSION_NOT_FOUND_CODE);
 }
 
 #[actix_web::test]
@@ -109,8 +118,7 @@ async fn get_with_unknown_session_id_returns_404() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, SESSION_NOT_FOUND_BODY);
+    assert_eq!(problem_code(response).await, SESSION_NOT_FOUND_CODE);
 }
 
 #[actix_web::test]
@@ -126,8 +134,7 @@ async fn delete_with_unknown_session_id_returns_404() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, SESSION_NOT_FOUND_BODY);
+    assert_eq!(problem_code(response).await, SESSION_NOT_FOUND_CODE);
 }
 
 #[actix_web::test]
@@ -143,8 +150,7 @@ async fn get_with_missing_session_id_returns_400() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, MISSING_SESSION_ID_BODY);
+    assert_eq!(problem_code(response).await, MISSING_SESSION_ID_CODE);
 }
 
 #[actix_web::test]
@@ -159,8 +165,7 @@ async fn delete_with_missing_session_id_returns_400() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, MISSING_SESSION_ID_BODY);
+    assert_eq!(problem_code(response).await, MISSING_SESSION_ID_CODE);
 }
 
 #[actix_web::test]
@@ -184,8 +189,7 @@ async fn post_without_session_id_and_non_initialize_returns_400() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, MISSING_SESSION_ID_BODY);
+    assert_eq!(problem_code(response).await, MISSING_SESSION_ID_CODE);
 }
 
 #[actix_web::test]
@@ -210,8 +214,7 @@ async fn post_with_empty_session_id_returns_400() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, MISSING_SESSION_ID_BODY);
+    assert_eq!(problem_code(response).await, MISSING_SESSION_ID_CODE);
 }
 
 #[actix_web::test]
@@ -228,8 +231,7 @@ async fn get_with_empty_session_id_returns_400() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, MISSING_SESSION_ID_BODY);
+    assert_eq!(problem_code(response).await, MISSING_SESSION_ID_CODE);
 }
 
 #[actix_web::test]
@@ -245,8 +247,7 @@ async fn delete_with_empty_session_id_returns_400() {
         .expect("Failed to send request");
 
     assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
-    let body = response.text().await.expect("Failed to read response body");
-    assert_eq!(body, MISSING_SESSION_ID_BODY);
+    assert_eq!(problem_code(response).await, MISSING_SESSION_ID_CODE);
 }
 
 #[actix_web::test]
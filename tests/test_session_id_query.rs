@@ -0,0 +1,71 @@
+//! Integration tests for the session id query parameter of `GET` streams.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+
+async fn spawn_server(session_id_query: bool) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .session_id_query(session_id_query)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn open_stream(
+    server: &TestServer,
+    query_session_id: &str,
+    header_session_id: Option<&str>,
+) -> reqwest::Response {
+    let mut request = server
+        .client
+        .get(server.url(&format!("/mcp?session_id={query_session_id}")))
+        .header("Accept", "text/event-stream");
+    if let Some(session_id) = header_session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to open stream")
+}
+
+#[actix_web::test]
+async fn query_parameter_opens_the_stream() {
+    let server = spawn_server(true).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = open_stream(&server, &session_id, None).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get("Content-Type")
+            .and_then(|v| v.to_str().ok()),
+        Some("text/event-stream")
+    );
+}
+
+#[actix_web::test]
+async fn header_takes_precedence() {
+    let server = spawn_server(true).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = open_stream(&server, "not-a-session", Some(&session_id)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn query_parameter_is_ignored_unless_enabled() {
+    let server = spawn_server(false).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = open_stream(&server, &session_id, None).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,113 @@
+//! Integration tests for binding sessions to the authenticated identity.
+
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{HttpMessage, dev::Service};
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{AuthenticatedIdentity, StreamableHttpService};
+use serde_json::{Value, json};
+
+/// Stands in for token validation: the identity is taken from `X-User`.
+const USER_HEADER: &str = "X-User";
+
+async fn spawn_server(bind_session_identity: bool) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .bind_session_identity(bind_session_identity)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp").wrap_fn(|req, srv| {
+            if let Some(user) = req.headers().get(USER_HEADER).and_then(|v| v.to_str().ok()) {
+                let identity = AuthenticatedIdentity(user.to_string());
+                req.extensions_mut().insert(identity);
+            }
+            srv.call(req)
+        }));
+    })
+    .await
+}
+
+async fn post_as(
+    server: &TestServer,
+    user: Option<&str>,
+    session_id: Option<&str>,
+    body: &Value,
+) -> reqwest::Response {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Content-Type", "application/json")
+        .json(body);
+    if let Some(user) = user {
+        request = request.header(USER_HEADER, user);
+    }
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+async fn initialize_as(server: &TestServer, user: &str) -> String {
+    let response = post_as(server, Some(user), None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let _ = read_sse_messages(response).await;
+
+    let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    let response = post_as(server, Some(user), Some(&session_id), &initialized).await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    session_id
+}
+
+#[actix_web::test]
+async fn bound_session_rejects_other_identities() {
+    let server = spawn_server(true).await;
+    let session_id = initialize_as(&server, "alice").await;
+    let call = tool_call_request(2, "sum", json!({ "a": 2, "b": 3 }));
+
+    let response = post_as(&server, Some("mallory"), Some(&session_id), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = post_as(&server, None, Some(&session_id), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .header(USER_HEADER, "mallory")
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = post_as(&server, Some("alice"), Some(&session_id), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let result = messages.iter().find(|m| m["id"] == 2).expect("sum result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 5);
+}
+
+#[actix_web::test]
+async fn sessions_are_unbound_by_default() {
+    let server = spawn_server(false).await;
+    let session_id = initialize_as(&server, "alice").await;
+
+    let call = tool_call_request(2, "sum", json!({ "a": 2, "b": 3 }));
+    let response = post_as(&server, Some("bob"), Some(&session_id), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+}
@@ -0,0 +1,84 @@
+//! Integration tests for structured session lifecycle logging.
+
+mod common;
+
+use std::{
+    io,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+
+/// Output of the global subscriber, shared by every test of this file.
+fn captured() -> &'static Arc<Mutex<Vec<u8>>> {
+    static CAPTURED: OnceLock<Arc<Mutex<Vec<u8>>>> = OnceLock::new();
+    CAPTURED.get_or_init(|| {
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let writer = captured.clone();
+        // Workers run on their own threads, so only a global subscriber sees their events
+        let _ = tracing_subscriber::fmt()
+            .with_env_filter("rmcp_actix_web::session=info")
+            .with_ansi(false)
+            .with_writer(move || CaptureWriter(writer.clone()))
+            .try_init();
+        captured
+    })
+}
+
+struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the captured line of `event` for `session_id`, if any.
+fn captured_event(event: &str, session_id: &str) -> Option<String> {
+    let captured = captured().lock().unwrap();
+    String::from_utf8_lossy(&captured)
+        .lines()
+        .find(|line| {
+            line.contains(&format!("event=\"{event}\""))
+                && line.contains(&format!("session_id={session_id}"))
+        })
+        .map(str::to_string)
+}
+
+#[actix_web::test]
+async fn session_lifecycle_is_logged_with_stable_fields() {
+    captured();
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await;
+
+    let session_id = server.initialize("/mcp").await;
+    let created = captured_event("session.created", &session_id).expect("session.created");
+    assert!(created.contains(" INFO "), "{created}");
+    assert!(created.contains("rmcp_actix_web::session"), "{created}");
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to delete session");
+    assert!(response.status().is_success());
+
+    let closed = captured_event("session.closed", &session_id).expect("session.closed");
+    assert!(closed.contains("reason=\"client_delete\""), "{closed}");
+    assert!(closed.contains("duration_ms="), "{closed}");
+}
@@ -0,0 +1,164 @@
+//! Integration tests for pushing notifications to sessions from outside handlers.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{SseEventReader, TestServer},
+};
+use rmcp::{
+    model::{CustomNotification, ServerNotification},
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+const ADMIN_KEY: &str = "operator-key";
+
+fn build_service() -> StreamableHttpService<Calculator, LocalSessionManager> {
+    StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build()
+}
+
+async fn spawn_server(
+    service: StreamableHttpService<Calculator, LocalSessionManager>,
+) -> TestServer {
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.broadcast_route("/ops/broadcast", [ADMIN_KEY]))
+            .service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn open_standalone_stream(server: &TestServer, session_id: &str) -> SseEventReader {
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    SseEventReader::new(response)
+}
+
+/// Reads the next JSON-RPC message, skipping priming events.
+async fn next_message(events: &mut SseEventReader) -> Value {
+    loop {
+        let event = events
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("standalone stream must deliver the notification");
+        if !event.data.is_empty() {
+            return event.json();
+        }
+    }
+}
+
+#[actix_web::test]
+async fn broadcast_reaches_every_session() {
+    let service = build_service();
+    let server = spawn_server(service.clone()).await;
+    let first = server.initialize("/mcp").await;
+    let second = server.initialize("/mcp").await;
+    let mut first_events = open_standalone_stream(&server, &first).await;
+    let mut second_events = open_standalone_stream(&server, &second).await;
+
+    let reached = service
+        .broadcast(ServerNotification::CustomNotification(
+            CustomNotification::new("notifications/maintenance", Some(json!({ "in": 10 }))),
+        ))
+        .await;
+    assert_eq!(reached, 2);
+
+    for events in [&mut first_events, &mut second_events] {
+        let message = next_message(events).await;
+        assert_eq!(message["method"], "notifications/maintenance");
+        assert_eq!(message["params"]["in"], 10);
+    }
+}
+
+#[actix_web::test]
+async fn broadcast_route_sends_posted_notification() {
+    let server = spawn_server(build_service()).await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = open_standalone_stream(&server, &session_id).await;
+
+    let response = server
+        .client
+        .post(server.url("/ops/broadcast"))
+        .header("X-Api-Key", ADMIN_KEY)
+        .json(&json!({ "method": "notifications/tools/list_changed" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["sessions"], 1);
+
+    let message = next_message(&mut events).await;
+    assert_eq!(message["method"], "notifications/tools/list_changed");
+}
+
+#[actix_web::test]
+async fn broadcast_route_rejects_invalid_requests() {
+    let server = spawn_server(build_service()).await;
+
+    let response = server
+        .client
+        .post(server.url("/ops/broadcast"))
+        .json(&json!({ "method": "notifications/tools/list_changed" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let response = server
+        .client
+        .post(server.url("/ops/broadcast"))
+        .header("X-Api-Key", ADMIN_KEY)
+        .body("not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn send_to_session_reaches_only_that_session() {
+    let service = build_service();
+    let server = spawn_server(service.clone()).await;
+    let target = server.initialize("/mcp").await;
+    let other = server.initialize("/mcp").await;
+    let mut target_events = open_standalone_stream(&server, &target).await;
+    let mut other_events = open_standalone_stream(&server, &other).await;
+
+    let notification = ServerNotification::CustomNotification(CustomNotification::new(
+        "notifications/webhook",
+        Some(json!({ "event": "push" })),
+    ));
+    assert!(
+        service
+            .send_to_session(&target.clone().into(), notification.clone())
+            .await
+    );
+
+    let message = next_message(&mut target_events).await;
+    assert_eq!(message["method"], "notifications/webhook");
+    let other_message = other_events
+        .next_event(Duration::from_millis(300))
+        .await
+        .filter(|event| !event.data.is_empty());
+    assert!(other_message.is_none());
+
+    assert!(
+        !service
+            .send_to_session(&"unknown".to_string().into(), notification)
+            .await
+    );
+}
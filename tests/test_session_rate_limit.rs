@@ -0,0 +1,71 @@
+//! Integration tests for the session creation rate limit.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{SessionCreationLimit, StreamableHttpService};
+use serde_json::{Value, json};
+
+async fn spawn_server(limit: SessionCreationLimit) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .session_creation_limit(limit)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn new_sessions_are_limited_but_existing_ones_are_not() {
+    let server = spawn_server(SessionCreationLimit::per_minute(2)).await;
+    let session_id = server.initialize("/mcp").await;
+    server.initialize("/mcp").await;
+
+    let response = server.post("/mcp", None, &initialize_request(7)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["Retry-After"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&retry_after), "{retry_after}");
+    assert!(response.headers().get("Mcp-Session-Id").is_none());
+    let error: Value = response.json().await.unwrap();
+    assert_eq!(error["id"], 7);
+    assert!(error["error"]["message"].is_string());
+
+    // Requests on existing sessions are not counted
+    for id in 2..5 {
+        let response = server
+            .post(
+                "/mcp",
+                Some(&session_id),
+                &tool_call_request(id, "sum", json!({ "a": 2, "b": 3 })),
+            )
+            .await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let _ = read_sse_messages(response).await;
+    }
+}
+
+#[actix_web::test]
+async fn limit_resets_after_the_window() {
+    let server = spawn_server(SessionCreationLimit::new(1, Duration::from_millis(300))).await;
+    server.initialize("/mcp").await;
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    server.initialize("/mcp").await;
+}
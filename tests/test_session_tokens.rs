@@ -0,0 +1,99 @@
+//! Integration tests for stateless signed session tokens.
+#![cfg(feature = "signed-session-tokens")]
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{AuthenticatedIdentity, SessionTokens, StreamableHttpService};
+use serde_json::json;
+
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+async fn spawn_replica(tokens: SessionTokens) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(false)
+        .session_tokens(tokens)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn initialize(server: &TestServer) -> String {
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry a session token")
+        .to_string()
+}
+
+#[actix_web::test]
+async fn token_is_required_and_accepted_by_every_replica() {
+    let first = spawn_replica(SessionTokens::new(SECRET)).await;
+    let second = spawn_replica(SessionTokens::new(SECRET)).await;
+    let token = initialize(&first).await;
+    let call = tool_call_request(2, "sum", json!({ "a": 2, "b": 3 }));
+
+    let response = first.post("/mcp", None, &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    let forged = format!("{token}x");
+    let response = first.post("/mcp", Some(&forged), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // No session store: the other replica validates the token on its own
+    let response = second.post("/mcp", Some(&token), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let result = messages.iter().find(|m| m["id"] == 2).expect("sum result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 5);
+
+    // A replica with another secret rejects it
+    let other = spawn_replica(SessionTokens::new(b"another secret")).await;
+    let response = other.post("/mcp", Some(&token), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn expired_token_is_rejected() {
+    let server = spawn_replica(SessionTokens::new(SECRET).ttl(Duration::ZERO)).await;
+    let token = initialize(&server).await;
+
+    let call = tool_call_request(2, "sum", json!({ "a": 2, "b": 3 }));
+    let response = server.post("/mcp", Some(&token), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn tokens_carry_the_creating_identity() {
+    let tokens = SessionTokens::new(SECRET);
+    let alice = AuthenticatedIdentity("alice".to_string());
+    let (token, session) = tokens.issue(Some(alice.clone()));
+
+    let verified = tokens.verify(&token).expect("valid token");
+    assert_eq!(verified, session);
+    assert_eq!(verified.identity, Some(alice));
+    assert!(verified.expires_at > verified.issued_at);
+}
+
+#[test]
+fn huge_ttl_is_capped() {
+    let tokens = SessionTokens::new(SECRET).ttl(Duration::MAX);
+    let (token, issued) = tokens.issue(None);
+    let lifetime = issued.expires_at.duration_since(issued.issued_at).unwrap();
+    assert_eq!(lifetime, Duration::from_secs(100 * 365 * 24 * 60 * 60));
+    assert_eq!(tokens.verify(&token), Some(issued));
+}
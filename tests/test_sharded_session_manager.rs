@@ -0,0 +1,95 @@
+//! Integration tests for the sharded session manager.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::{
+    SessionId, SessionManager, local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::{ShardedSessionManager, StreamableHttpService};
+use serde_json::json;
+
+async fn spawn_server(manager: Arc<ShardedSessionManager<LocalSessionManager>>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(manager)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn sessions_are_spread_over_shards_and_served() {
+    let manager = Arc::new(ShardedSessionManager::new(4, LocalSessionManager::default));
+    let server = spawn_server(manager.clone()).await;
+
+    let mut session_ids = Vec::new();
+    for _ in 0..4 {
+        session_ids.push(server.initialize("/mcp").await);
+    }
+    let shards: Vec<_> = session_ids
+        .iter()
+        .map(|id| id.split_once('.').expect("shard prefix").0.to_string())
+        .collect();
+    assert_eq!(shards, ["s0", "s1", "s2", "s3"]);
+
+    for (id, session_id) in (2..).zip(&session_ids) {
+        let response = server
+            .post(
+                "/mcp",
+                Some(session_id),
+                &tool_call_request(id, "sum", json!({ "a": 2, "b": 3 })),
+            )
+            .await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let messages = read_sse_messages(response).await;
+        assert_eq!(
+            messages[0]["result"]["content"][0]["text"],
+            r#"{"value":5}"#
+        );
+    }
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_ids[1])
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert!(response.status().is_success());
+    let closed = SessionId::from(session_ids[1].as_str());
+    assert!(!manager.has_session(&closed).await.unwrap());
+    let open = SessionId::from(session_ids[2].as_str());
+    assert!(manager.has_session(&open).await.unwrap());
+}
+
+#[actix_web::test]
+async fn unknown_sessions_are_not_found() {
+    let manager = Arc::new(ShardedSessionManager::new(2, LocalSessionManager::default));
+    let server = spawn_server(manager.clone()).await;
+
+    for session_id in ["s1.missing", "s9.missing", "no-prefix"] {
+        let response = server
+            .post(
+                "/mcp",
+                Some(session_id),
+                &json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+            )
+            .await;
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+}
+
+#[test]
+fn default_has_a_shard_per_cpu() {
+    let manager = ShardedSessionManager::<LocalSessionManager>::default();
+    let cpus = std::thread::available_parallelism().map_or(1, usize::from);
+    assert_eq!(manager.shard_count(), cpus);
+}
@@ -0,0 +1,170 @@
+//! Integration tests for graceful shutdown.
+//!
+//! Shutting the service down must refuse new work, let in-flight requests
+//! complete, and close the remaining sessions.
+
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::{
+    slow_service::SlowService,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{SessionCloseReason, StreamableHttpService};
+use serde_json::{Value, json};
+
+type ClosedSessions = Arc<Mutex<Vec<(String, SessionCloseReason)>>>;
+
+async fn spawn_server() -> (
+    TestServer,
+    StreamableHttpService<SlowService, LocalSessionManager>,
+    ClosedSessions,
+) {
+    let closed: ClosedSessions = Default::default();
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .on_session_closed_fn({
+            let closed = closed.clone();
+            move |session_id, reason| {
+                closed
+                    .lock()
+                    .unwrap()
+                    .push((session_id.to_string(), reason));
+            }
+        })
+        .build();
+    let server = {
+        let service = service.clone();
+        TestServer::spawn(move |cfg| {
+            cfg.service(service.readiness_probe("/readyz"))
+                .service(service.clone().scope_with_path("/mcp"));
+        })
+        .await
+    };
+    (server, service, closed)
+}
+
+#[actix_web::test]
+async fn shutdown_lets_in_flight_requests_complete() {
+    let (server, service, _) = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "slow", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let in_flight = tokio::spawn(read_sse_messages(response));
+
+    let shutdown = tokio::spawn(async move {
+        service.shutdown(Duration::from_secs(5)).await;
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // New requests are refused while draining
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(3, "slow", json!({})),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("retry-after"));
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 3);
+    assert_eq!(error["error"]["code"], -32003);
+
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    // The request that was already running still gets its result
+    let messages = in_flight.await.unwrap();
+    let result = messages.iter().find(|m| m["id"] == 2).expect("tool result");
+    assert_eq!(result["result"]["content"][0]["text"], "done");
+
+    tokio::time::timeout(Duration::from_secs(5), shutdown)
+        .await
+        .expect("shutdown must resolve once requests have drained")
+        .unwrap();
+}
+
+#[actix_web::test]
+async fn shutdown_closes_remaining_sessions() {
+    let (server, service, closed) = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    service.shutdown(Duration::from_secs(1)).await;
+
+    assert_eq!(
+        *closed.lock().unwrap(),
+        vec![(session_id.clone(), SessionCloseReason::ServerShutdown)]
+    );
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn readiness_probe_fails_once_draining() {
+    let (server, service, _) = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .client
+        .get(server.url("/readyz"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.json::<Value>().await.unwrap()["status"], "ready");
+
+    // Keep a request in flight so the service stays in the draining phase
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "slow", json!({})),
+        )
+        .await;
+    let in_flight = tokio::spawn(read_sse_messages(response));
+    let shutdown = tokio::spawn(async move {
+        service.shutdown(Duration::from_secs(5)).await;
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let response = server
+        .client
+        .get(server.url("/readyz"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("retry-after"));
+    assert_eq!(
+        response.json::<Value>().await.unwrap()["status"],
+        "draining"
+    );
+
+    // New sessions are turned away the same way
+    let response = server.post("/mcp", None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().contains_key("retry-after"));
+
+    in_flight.await.unwrap();
+    shutdown.await.unwrap();
+}
@@ -0,0 +1,95 @@
+//! Integration tests for the simd-json codec.
+
+#![cfg(feature = "simd-json")]
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{ACCEPT_BOTH, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::json;
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn messages_round_trip() {
+    let server = spawn_server().await;
+
+    // Escapes and non-ASCII text survive decoding and encoding
+    let client_name = "cliënt \"quoted\" \\ ✓";
+    let response = server
+        .post(
+            "/mcp",
+            None,
+            &json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": { "name": client_name, "version": "1.0.0" }
+                }
+            }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 1);
+    assert!(messages[0]["result"]["serverInfo"].is_object());
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sum", json!({ "a": 40, "b": -2 })),
+        )
+        .await;
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 2);
+    assert_eq!(messages[0]["result"]["structuredContent"]["value"], 38);
+}
+
+#[actix_web::test]
+async fn malformed_json_is_a_bad_request() {
+    let server = spawn_server().await;
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", ACCEPT_BOTH)
+        .header("Content-Type", "application/json")
+        .body(r#"{"jsonrpc": "2.0", "id": 1,"#)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,93 @@
+//! Integration tests for the slow consumer policies.
+//!
+//! The buffering itself is covered by unit tests; these check that clients
+//! keeping up with their streams see no difference under any policy.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    slow_service::SlowService,
+    test_server::{ACCEPT_BOTH, TestServer, read_sse_messages},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{SlowConsumerPolicy, StreamableHttpService};
+use serde_json::{Value, json};
+
+const POLICIES: [SlowConsumerPolicy; 2] = [
+    SlowConsumerPolicy::DropOldestNotifications,
+    SlowConsumerPolicy::Disconnect,
+];
+
+async fn spawn_server(stateful: bool, policy: SlowConsumerPolicy) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful)
+        .slow_consumer_policy(policy)
+        .slow_consumer_buffer(2)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+fn progress_call(id: i64) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "tools/call",
+        "params": {
+            "name": "progress",
+            "arguments": {},
+            "_meta": { "progressToken": "call" }
+        },
+        "id": id
+    })
+}
+
+fn assert_progress_then_response(messages: &[Value], id: i64) {
+    assert_eq!(messages.len(), 4, "{messages:#?}");
+    assert!(
+        messages[..3]
+            .iter()
+            .all(|message| message["method"] == "notifications/progress")
+    );
+    assert_eq!(messages[3]["id"], id);
+    assert_eq!(messages[3]["result"]["content"][0]["text"], "done");
+}
+
+#[actix_web::test]
+async fn session_stream_is_delivered_in_full() {
+    for policy in POLICIES {
+        let server = spawn_server(true, policy).await;
+        let session_id = server.initialize("/mcp").await;
+
+        let response = server
+            .post("/mcp", Some(&session_id), &progress_call(2))
+            .await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        assert_progress_then_response(&read_sse_messages(response).await, 2);
+    }
+}
+
+#[actix_web::test]
+async fn stateless_stream_is_delivered_in_full() {
+    for policy in POLICIES {
+        let server = spawn_server(false, policy).await;
+
+        let response = server
+            .client
+            .post(server.url("/mcp"))
+            .header("Accept", ACCEPT_BOTH)
+            .json(&progress_call(1))
+            .send()
+            .await
+            .expect("Failed to send request");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        assert_progress_then_response(&read_sse_messages(response).await, 1);
+    }
+}
@@ -0,0 +1,81 @@
+//! Integration tests for the per-client cap on open SSE streams.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .max_sse_streams_per_client(2)
+        .sse_keep_alive(Duration::from_millis(50))
+        .legacy_sse(true)
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn open_stream(server: &TestServer, session_id: &str) -> reqwest::Response {
+    server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", session_id)
+        .send()
+        .await
+        .expect("Failed to open SSE stream")
+}
+
+#[actix_web::test]
+async fn streams_over_the_cap_are_refused_until_one_closes() {
+    let server = spawn_server().await;
+    let mut sessions = Vec::new();
+    for _ in 0..3 {
+        sessions.push(server.initialize("/mcp").await);
+    }
+
+    let first = open_stream(&server, &sessions[0]).await;
+    assert_eq!(first.status(), reqwest::StatusCode::OK);
+    let second = open_stream(&server, &sessions[1]).await;
+    assert_eq!(second.status(), reqwest::StatusCode::OK);
+
+    let refused = open_stream(&server, &sessions[2]).await;
+    assert_eq!(refused.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+    // POST requests are not affected by the cap
+    server.initialize("/mcp").await;
+
+    // The slot is released once the server notices the disconnect
+    drop(first);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let third = open_stream(&server, &sessions[2]).await;
+    assert_eq!(third.status(), reqwest::StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn legacy_sse_connections_count_against_the_cap() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let standalone = open_stream(&server, &session_id).await;
+    assert_eq!(standalone.status(), reqwest::StatusCode::OK);
+
+    let legacy = |server: &TestServer| {
+        server
+            .client
+            .get(server.url("/mcp/sse"))
+            .header("Accept", "text/event-stream")
+            .send()
+    };
+    let first = legacy(&server).await.expect("Failed to open SSE stream");
+    assert_eq!(first.status(), reqwest::StatusCode::OK);
+    let refused = legacy(&server).await.expect("Failed to open SSE stream");
+    assert_eq!(refused.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+}
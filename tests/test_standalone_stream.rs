@@ -0,0 +1,230 @@
+//! Integration tests for the standalone GET stream.
+//!
+//! Server-initiated messages that do not belong to a POSTed request, such as
+//! resource update and list-changed notifications, must be delivered on the
+//! stream the client opens with `GET`. Server-initiated requests, such as
+//! sampling and elicitation, travel the same way and are answered by the client
+//! with a POST.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    peer_service::PeerService,
+    test_server::{SseEventReader, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(PeerService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn open_standalone_stream(server: &TestServer, session_id: &str) -> SseEventReader {
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    SseEventReader::new(response)
+}
+
+/// Reads the next JSON-RPC message, skipping priming events.
+async fn next_message(events: &mut SseEventReader) -> Value {
+    loop {
+        let event = events
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("standalone stream must deliver the notification");
+        if !event.data.is_empty() {
+            return event.json();
+        }
+    }
+}
+
+/// Calls `tool` and returns its pending response stream together with the
+/// request the server sent on the standalone stream while handling it.
+async fn call_and_read_server_request(
+    server: &TestServer,
+    session_id: &str,
+    events: &mut SseEventReader,
+    tool: &str,
+) -> (reqwest::Response, Value) {
+    let body = tool_call_request(2, tool, json!({ "question": "What is 6 x 7?" }));
+    let call = server.post("/mcp", Some(session_id), &body);
+    let (response, request) = tokio::join!(call, next_message(events));
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    (response, request)
+}
+
+/// Returns the text answered by the tool call with id 2, parsed as JSON.
+async fn tool_output(response: reqwest::Response) -> Value {
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 2)
+        .expect("tool call must be answered");
+    let text = result["result"]["content"][0]["text"]
+        .as_str()
+        .expect("tool answers with text");
+    serde_json::from_str(text).unwrap_or(Value::String(text.to_string()))
+}
+
+async fn touch(server: &TestServer, session_id: &str, uri: &str) -> Vec<Value> {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &tool_call_request(2, "touch", json!({ "uri": uri })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response).await
+}
+
+#[actix_web::test]
+async fn notifications_outside_requests_arrive_on_standalone_stream() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = open_standalone_stream(&server, &session_id).await;
+
+    // The request stream only carries the response
+    let messages = touch(&server, &session_id, "file:///notes.txt").await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["id"], 2);
+
+    let updated = next_message(&mut events).await;
+    assert_eq!(updated["method"], "notifications/resources/updated");
+    assert_eq!(updated["params"]["uri"], "file:///notes.txt");
+    let list_changed = next_message(&mut events).await;
+    assert_eq!(
+        list_changed["method"],
+        "notifications/resources/list_changed"
+    );
+}
+
+#[actix_web::test]
+async fn notifications_sent_before_stream_opens_are_delivered() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    touch(&server, &session_id, "file:///early.txt").await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let mut events = open_standalone_stream(&server, &session_id).await;
+    let updated = next_message(&mut events).await;
+    assert_eq!(updated["method"], "notifications/resources/updated");
+    assert_eq!(updated["params"]["uri"], "file:///early.txt");
+}
+
+#[actix_web::test]
+async fn sampling_request_is_answered_through_post() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = open_standalone_stream(&server, &session_id).await;
+
+    let (response, request) =
+        call_and_read_server_request(&server, &session_id, &mut events, "ask_llm").await;
+    assert_eq!(request["method"], "sampling/createMessage");
+    assert_eq!(
+        request["params"]["messages"][0]["content"]["text"],
+        "What is 6 x 7?"
+    );
+
+    let answer = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "model": "test-model",
+                    "role": "assistant",
+                    "content": { "type": "text", "text": "42" }
+                }
+            }),
+        )
+        .await;
+    assert_eq!(answer.status(), reqwest::StatusCode::ACCEPTED);
+
+    let output = tool_output(response).await;
+    assert_eq!(output["model"], "test-model");
+    assert_eq!(output["content"]["text"], "42");
+}
+
+#[actix_web::test]
+async fn elicitation_request_is_answered_through_post() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = open_standalone_stream(&server, &session_id).await;
+
+    let (response, request) =
+        call_and_read_server_request(&server, &session_id, &mut events, "ask_user").await;
+    assert_eq!(request["method"], "elicitation/create");
+    assert_eq!(request["params"]["message"], "What is 6 x 7?");
+    assert_eq!(request["params"]["requestedSchema"]["required"][0], "name");
+
+    let answer = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": { "action": "accept", "content": { "name": "Ada" } }
+            }),
+        )
+        .await;
+    assert_eq!(answer.status(), reqwest::StatusCode::ACCEPTED);
+
+    let output = tool_output(response).await;
+    assert_eq!(output["action"], "accept");
+    assert_eq!(output["content"]["name"], "Ada");
+}
+
+#[actix_web::test]
+async fn error_answer_to_server_request_reaches_the_handler() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = open_standalone_stream(&server, &session_id).await;
+
+    let (response, request) =
+        call_and_read_server_request(&server, &session_id, &mut events, "ask_llm").await;
+
+    let answer = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -1, "message": "User rejected sampling request" }
+            }),
+        )
+        .await;
+    assert_eq!(answer.status(), reqwest::StatusCode::ACCEPTED);
+
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 2)
+        .expect("tool call must be answered");
+    let error = result["error"]["message"]
+        .as_str()
+        .expect("tool call fails with the client's error");
+    assert!(error.contains("User rejected sampling request"), "{error}");
+}
@@ -0,0 +1,172 @@
+//! Integration tests for tracking why SSE streams end.
+
+mod common;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{
+    SessionCloseReason, StreamEndReason, StreamableHttpService, TransportEvent,
+};
+use tokio::sync::broadcast;
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Closed = Arc<Mutex<Vec<SessionCloseReason>>>;
+
+async fn spawn_server() -> (
+    TestServer,
+    StreamableHttpService<Calculator, LocalSessionManager>,
+    Closed,
+) {
+    let closed = Closed::default();
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .legacy_sse(true)
+        .sse_keep_alive(Duration::from_millis(50))
+        .on_session_closed_fn({
+            let closed = closed.clone();
+            move |_, reason| closed.lock().unwrap().push(reason)
+        })
+        .build();
+    let server = TestServer::spawn({
+        let service = service.clone();
+        move |cfg| {
+            cfg.service(service.clone().scope_with_path("/mcp"));
+        }
+    })
+    .await;
+    (server, service, closed)
+}
+
+/// Waits for the reason of the next ended stream.
+async fn stream_end_reason(events: &mut broadcast::Receiver<TransportEvent>) -> StreamEndReason {
+    tokio::time::timeout(EVENT_TIMEOUT, async {
+        loop {
+            if let TransportEvent::StreamDropped { reason, .. } = events.recv().await.unwrap() {
+                return reason;
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for the stream to end")
+}
+
+async fn open_stream(server: &TestServer, session_id: &str) -> reqwest::Response {
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", session_id)
+        .send()
+        .await
+        .expect("Failed to open stream");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    response
+}
+
+#[actix_web::test]
+async fn closing_the_session_ends_the_stream_from_the_server() {
+    let (server, service, _) = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = service.subscribe_events();
+    let _stream = open_stream(&server, &session_id).await;
+
+    server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to delete session");
+
+    assert_eq!(
+        stream_end_reason(&mut events).await,
+        StreamEndReason::ServerClose
+    );
+}
+
+#[actix_web::test]
+async fn client_going_away_is_detected() {
+    let (server, service, _) = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let mut events = service.subscribe_events();
+    drop(open_stream(&server, &session_id).await);
+
+    // Noticed on the next keep-alive write, or earlier if actix sees the close
+    let reason = stream_end_reason(&mut events).await;
+    assert!(
+        matches!(
+            reason,
+            StreamEndReason::ClientDisconnect
+                | StreamEndReason::KeepAliveFailure
+                | StreamEndReason::WriteError
+        ),
+        "{reason:?}"
+    );
+}
+
+#[actix_web::test]
+async fn shutdown_is_reported_for_legacy_sessions() {
+    let (server, service, closed) = spawn_server().await;
+    let mut events = service.subscribe_events();
+    let _stream = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open legacy stream");
+
+    service.shutdown(Duration::from_secs(1)).await;
+
+    assert_eq!(
+        stream_end_reason(&mut events).await,
+        StreamEndReason::Shutdown
+    );
+    let deadline = tokio::time::Instant::now() + EVENT_TIMEOUT;
+    while closed.lock().unwrap().is_empty() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(
+        closed.lock().unwrap().as_slice(),
+        [SessionCloseReason::ServerShutdown]
+    );
+}
+
+#[actix_web::test]
+async fn legacy_disconnect_reason_reaches_the_session_closed_hook() {
+    let (server, _service, closed) = spawn_server().await;
+    let response = server
+        .client
+        .get(server.url("/mcp/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .expect("Failed to open legacy stream");
+    drop(response);
+
+    let deadline = tokio::time::Instant::now() + EVENT_TIMEOUT;
+    while closed.lock().unwrap().is_empty() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let closed = closed.lock().unwrap().clone();
+    assert_eq!(closed.len(), 1);
+    assert!(
+        matches!(
+            closed[0],
+            SessionCloseReason::StreamDisconnected(
+                StreamEndReason::ClientDisconnect
+                    | StreamEndReason::KeepAliveFailure
+                    | StreamEndReason::WriteError
+            )
+        ),
+        "{:?}",
+        closed[0]
+    );
+}
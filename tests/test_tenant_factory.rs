@@ -0,0 +1,137 @@
+//! Integration tests for constructing handlers per tenant.
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use actix_web::{HttpMessage, dev::Service};
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{StreamableHttpService, TenantId};
+use serde_json::{Value, json};
+
+/// Stands in for tenant resolution: the tenant is taken from `X-Tenant`.
+const TENANT_HEADER: &str = "X-Tenant";
+
+/// Spawns a server recording, for every constructed handler, the tenant it was
+/// constructed for (`-` for the default factory).
+async fn spawn_server(stateful_mode: bool) -> (TestServer, Arc<Mutex<Vec<String>>>) {
+    let constructed = Arc::new(Mutex::new(Vec::new()));
+    let service = StreamableHttpService::builder()
+        .service_factory({
+            let constructed = constructed.clone();
+            Arc::new(move || {
+                constructed.lock().unwrap().push("-".to_string());
+                Ok(Calculator::new())
+            })
+        })
+        .tenant_service_factory({
+            let constructed = constructed.clone();
+            Arc::new(move |tenant: &TenantId| {
+                constructed.lock().unwrap().push(tenant.0.clone());
+                Ok(Calculator::new())
+            })
+        })
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful_mode)
+        .build();
+
+    let server = TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp").wrap_fn(|req, srv| {
+            if let Some(tenant) = req
+                .headers()
+                .get(TENANT_HEADER)
+                .and_then(|v| v.to_str().ok())
+            {
+                let tenant = TenantId(tenant.to_string());
+                req.extensions_mut().insert(tenant);
+            }
+            srv.call(req)
+        }));
+    })
+    .await;
+    (server, constructed)
+}
+
+async fn post_as(
+    server: &TestServer,
+    tenant: Option<&str>,
+    session_id: Option<&str>,
+    body: &Value,
+) -> reqwest::Response {
+    let mut request = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Content-Type", "application/json")
+        .json(body);
+    if let Some(tenant) = tenant {
+        request = request.header(TENANT_HEADER, tenant);
+    }
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+async fn initialize_as(server: &TestServer, tenant: Option<&str>) -> String {
+    let response = post_as(server, tenant, None, &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let session_id = response
+        .headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .expect("initialize response must carry Mcp-Session-Id")
+        .to_string();
+    let _ = read_sse_messages(response).await;
+
+    let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    let response = post_as(server, tenant, Some(&session_id), &initialized).await;
+    assert_eq!(response.status(), reqwest::StatusCode::ACCEPTED);
+    session_id
+}
+
+#[actix_web::test]
+async fn sessions_get_handlers_of_their_tenant() {
+    let (server, constructed) = spawn_server(true).await;
+
+    initialize_as(&server, Some("acme")).await;
+    initialize_as(&server, Some("globex")).await;
+    initialize_as(&server, None).await;
+
+    assert_eq!(*constructed.lock().unwrap(), ["acme", "globex", "-"]);
+}
+
+#[actix_web::test]
+async fn session_is_bound_to_its_tenant() {
+    let (server, _) = spawn_server(true).await;
+    let session_id = initialize_as(&server, Some("acme")).await;
+    let call = tool_call_request(2, "sum", json!({ "a": 2, "b": 3 }));
+
+    let response = post_as(&server, Some("acme"), Some(&session_id), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    assert_eq!(messages[0]["id"], 2);
+    assert!(messages[0]["result"].is_object());
+
+    let response = post_as(&server, Some("globex"), Some(&session_id), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let response = post_as(&server, None, Some(&session_id), &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn stateless_requests_get_handlers_of_their_tenant() {
+    let (server, constructed) = spawn_server(false).await;
+    let call = tool_call_request(2, "sum", json!({ "a": 2, "b": 3 }));
+
+    let response = post_as(&server, Some("acme"), None, &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let _ = read_sse_messages(response).await;
+
+    assert_eq!(*constructed.lock().unwrap(), ["acme"]);
+}
@@ -0,0 +1,140 @@
+//! Integration tests for quotas per tenant.
+
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{HttpMessage, dev::Service};
+use common::{
+    slow_service::SlowService,
+    test_server::{TestServer, initialize_request, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{
+    QUOTA_EXCEEDED_CODE, StreamableHttpService, TenantId, TenantQuota, TenantQuotas,
+};
+use serde_json::{Value, json};
+
+/// Stands in for tenant resolution: the tenant is taken from `X-Tenant`.
+const TENANT_HEADER: &str = "X-Tenant";
+
+fn build_service(
+    stateful_mode: bool,
+    quotas: TenantQuotas,
+) -> StreamableHttpService<SlowService, LocalSessionManager> {
+    StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(SlowService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .stateful_mode(stateful_mode)
+        .tenant_quotas(quotas)
+        .build()
+}
+
+async fn spawn_server(
+    service: StreamableHttpService<SlowService, LocalSessionManager>,
+) -> TestServer {
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp").wrap_fn(|req, srv| {
+            if let Some(tenant) = req
+                .headers()
+                .get(TENANT_HEADER)
+                .and_then(|v| v.to_str().ok())
+            {
+                let tenant = TenantId(tenant.to_string());
+                req.extensions_mut().insert(tenant);
+            }
+            srv.call(req)
+        }));
+    })
+    .await
+}
+
+async fn post_as(server: &TestServer, tenant: &str, body: &Value) -> reqwest::Response {
+    server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Content-Type", "application/json")
+        .header(TENANT_HEADER, tenant)
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+#[actix_web::test]
+async fn sessions_over_the_cap_are_refused() {
+    let quotas = TenantQuotas::new().tenant("acme", TenantQuota::new().max_sessions(1));
+    let service = build_service(true, quotas);
+    let server = spawn_server(service.clone()).await;
+
+    let response = post_as(&server, "acme", &initialize_request(1)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let _ = read_sse_messages(response).await;
+
+    let response = post_as(&server, "acme", &initialize_request(2)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    let body: Value = response.json().await.expect("error body is JSON");
+    assert_eq!(body["id"], 2);
+    assert_eq!(body["error"]["code"], QUOTA_EXCEEDED_CODE.0);
+
+    // Other tenants are not limited by acme's quota
+    let response = post_as(&server, "globex", &initialize_request(3)).await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let usage = service.tenant_usage(&TenantId("acme".to_string())).await;
+    assert_eq!(usage.sessions, 1);
+    assert_eq!(usage.rejected_sessions, 1);
+}
+
+#[actix_web::test]
+async fn requests_over_the_rate_get_retry_after() {
+    let quotas = TenantQuotas::new().default_quota(TenantQuota::new().requests_per_minute(2));
+    let service = build_service(false, quotas);
+    let server = spawn_server(service.clone()).await;
+    let call = tool_call_request(1, "count", json!({}));
+
+    for _ in 0..2 {
+        let response = post_as(&server, "acme", &call).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        let _ = read_sse_messages(response).await;
+    }
+
+    let response = post_as(&server, "acme", &call).await;
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response
+        .headers()
+        .get("Retry-After")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse().ok())
+        .expect("429 must carry a Retry-After header in seconds");
+    assert!((1..=60).contains(&retry_after));
+
+    let usage = service.tenant_usage(&TenantId("acme".to_string())).await;
+    assert_eq!(usage.requests_in_window, 2);
+    assert_eq!(usage.rejected_requests, 1);
+}
+
+#[actix_web::test]
+async fn tool_calls_over_the_concurrency_cap_are_refused() {
+    let quotas =
+        TenantQuotas::new().tenant("acme", TenantQuota::new().max_concurrent_tool_calls(1));
+    let service = build_service(false, quotas);
+    let server = spawn_server(service.clone()).await;
+
+    let first_call = tool_call_request(1, "slow", json!({}));
+    let second_call = tool_call_request(2, "slow", json!({}));
+    let (first, second) = tokio::join!(post_as(&server, "acme", &first_call), async {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        post_as(&server, "acme", &second_call).await
+    });
+    let first = read_sse_messages(first).await;
+    let second = read_sse_messages(second).await;
+    assert!(first[0]["result"].is_object());
+    assert_eq!(second[0]["id"], 2);
+    assert_eq!(second[0]["error"]["code"], QUOTA_EXCEEDED_CODE.0);
+
+    let usage = service.tenant_usage(&TenantId("acme".to_string())).await;
+    assert_eq!(usage.concurrent_tool_calls, 0);
+    assert_eq!(usage.rejected_tool_calls, 1);
+}
@@ -0,0 +1,90 @@
+//! Integration tests for serving over TLS with rustls.
+
+#![cfg(feature = "rustls")]
+
+mod common;
+
+use std::{io, path::PathBuf, sync::Arc};
+
+use common::calculator::Calculator;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{StreamableHttpService, rustls_config};
+use serde_json::json;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+}
+
+#[test]
+fn config_is_built_from_pem_files() {
+    let config = rustls_config(fixture("cert.pem"), fixture("key.pem")).unwrap();
+    assert_eq!(
+        config.alpn_protocols,
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    );
+}
+
+#[test]
+fn missing_file_is_not_found() {
+    let error = rustls_config(fixture("missing.pem"), fixture("key.pem")).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn file_without_certificate_is_invalid_data() {
+    let error = rustls_config(fixture("key.pem"), fixture("key.pem")).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn mismatched_key_is_invalid_input() {
+    let error = rustls_config(fixture("cert.pem"), fixture("other_key.pem")).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[actix_web::test]
+async fn service_is_served_over_https() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let server = service
+        .serve_rustls("/mcp", addr, fixture("cert.pem"), fixture("key.pem"))
+        .unwrap();
+    let handle = server.handle();
+    actix_web::rt::spawn(server);
+
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+    let response = client
+        .post(format!("https://localhost:{}/mcp", addr.port()))
+        .header("Accept", "application/json, text/event-stream")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test-client", "version": "1.0.0" }
+            }
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    // ALPN negotiated HTTP/2
+    assert_eq!(response.version(), reqwest::Version::HTTP_2);
+    assert!(response.headers().contains_key("Mcp-Session-Id"));
+
+    handle.stop(false).await;
+}
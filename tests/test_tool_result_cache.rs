@@ -0,0 +1,205 @@
+//! Integration tests for result caching of idempotent tools.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use common::test_server::{TestServer, read_sse_messages, tool_call_request};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{CacheableTools, StreamableHttpService};
+use serde_json::{Value, json};
+
+mod lookup_service {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use rmcp::{
+        ErrorData as McpError, ServerHandler,
+        handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+        model::*,
+        schemars, tool, tool_handler, tool_router,
+    };
+
+    #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+    pub struct LookupRequest {
+        /// Key to look up
+        pub key: String,
+        /// Optional locale of the answer
+        pub locale: Option<String>,
+    }
+
+    #[derive(Clone)]
+    pub struct LookupService {
+        /// Number of tool executions, across sessions
+        pub executions: Arc<AtomicUsize>,
+        #[expect(
+            dead_code,
+            reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+        )]
+        tool_router: ToolRouter<LookupService>,
+    }
+
+    #[tool_router]
+    impl LookupService {
+        pub fn new(executions: Arc<AtomicUsize>) -> Self {
+            Self {
+                executions,
+                tool_router: Self::tool_router(),
+            }
+        }
+
+        /// Answers with the key, the locale and the execution count
+        #[tool(description = "Look up a key")]
+        async fn lookup(
+            &self,
+            Parameters(LookupRequest { key, locale }): Parameters<LookupRequest>,
+        ) -> Result<CallToolResult, McpError> {
+            let execution = self.executions.fetch_add(1, Ordering::SeqCst) + 1;
+            let locale = locale
+                .map(|locale| format!("@{locale}"))
+                .unwrap_or_default();
+            Ok(CallToolResult::success(vec![Content::text(format!(
+                "{key}{locale} {execution}"
+            ))]))
+        }
+
+        /// Always answers with a tool error
+        #[tool(description = "Fail to look up")]
+        async fn broken(&self) -> Result<CallToolResult, McpError> {
+            self.executions.fetch_add(1, Ordering::SeqCst);
+            Ok(CallToolResult::error(vec![Content::text("unavailable")]))
+        }
+
+        /// Not cacheable
+        #[tool(description = "Look up without caching")]
+        async fn uncached(&self) -> Result<CallToolResult, McpError> {
+            self.executions.fetch_add(1, Ordering::SeqCst);
+            Ok(CallToolResult::success(vec![Content::text("fresh")]))
+        }
+    }
+
+    #[tool_handler]
+    impl ServerHandler for LookupService {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        }
+    }
+}
+
+async fn spawn_server(executions: Arc<AtomicUsize>, ttl: Duration) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            Ok(lookup_service::LookupService::new(executions.clone()))
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .cacheable_tools(
+            CacheableTools::new()
+                .cache("lookup", ttl)
+                .cache("broken", ttl),
+        )
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+/// Calls `tool` and returns the text of its answer.
+async fn call(
+    server: &TestServer,
+    session_id: &str,
+    id: i64,
+    tool: &str,
+    arguments: Value,
+) -> String {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &tool_call_request(id, tool, arguments),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == id)
+        .expect("tool call must be answered");
+    result["result"]["content"][0]["text"]
+        .as_str()
+        .expect("text content")
+        .to_string()
+}
+
+#[actix_web::test]
+async fn same_arguments_are_answered_from_cache() {
+    let executions = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(executions.clone(), Duration::from_secs(60)).await;
+    let session_id = server.initialize("/mcp").await;
+    let other_session_id = server.initialize("/mcp").await;
+
+    let arguments = json!({ "key": "fr", "locale": "en" });
+    assert_eq!(
+        call(&server, &session_id, 2, "lookup", arguments).await,
+        "fr@en 1"
+    );
+    // Key order does not matter
+    let reordered = json!({ "locale": "en", "key": "fr" });
+    assert_eq!(
+        call(&server, &other_session_id, 2, "lookup", reordered).await,
+        "fr@en 1"
+    );
+    assert_eq!(executions.load(Ordering::SeqCst), 1);
+
+    let other = json!({ "key": "de", "locale": "en" });
+    assert_eq!(
+        call(&server, &session_id, 3, "lookup", other).await,
+        "de@en 2"
+    );
+}
+
+#[actix_web::test]
+async fn expired_results_are_computed_again() {
+    let executions = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(executions.clone(), Duration::from_millis(200)).await;
+    let session_id = server.initialize("/mcp").await;
+
+    assert_eq!(
+        call(&server, &session_id, 2, "lookup", json!({ "key": "fr" })).await,
+        "fr 1"
+    );
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(
+        call(&server, &session_id, 3, "lookup", json!({ "key": "fr" })).await,
+        "fr 2"
+    );
+}
+
+#[actix_web::test]
+async fn errors_and_unlisted_tools_are_not_cached() {
+    let executions = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(executions.clone(), Duration::from_secs(60)).await;
+    let session_id = server.initialize("/mcp").await;
+
+    for id in 2..4 {
+        assert_eq!(
+            call(&server, &session_id, id, "broken", json!({})).await,
+            "unavailable"
+        );
+    }
+    for id in 4..6 {
+        assert_eq!(
+            call(&server, &session_id, id, "uncached", json!({})).await,
+            "fresh"
+        );
+    }
+    assert_eq!(executions.load(Ordering::SeqCst), 4);
+}
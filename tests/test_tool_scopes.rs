@@ -0,0 +1,118 @@
+//! Integration tests for OAuth scope requirements on tools.
+
+mod common;
+
+use std::sync::Arc;
+
+use actix_web::{HttpMessage, dev::Service};
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::{GrantedScopes, StreamableHttpService, ToolScopes};
+use serde_json::{Value, json};
+
+/// Stands in for token validation: grants the scopes listed in `X-Test-Scopes`.
+const SCOPES_HEADER: &str = "X-Test-Scopes";
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .tool_scopes(
+            ToolScopes::new()
+                .require("mcp:admin", ["sub"])
+                .require("mcp:write", ["sub"]),
+        )
+        .build();
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp").wrap_fn(|req, srv| {
+            if let Some(claim) = req
+                .headers()
+                .get(SCOPES_HEADER)
+                .and_then(|v| v.to_str().ok())
+            {
+                let granted = GrantedScopes::from_scope_claim(claim);
+                req.extensions_mut().insert(granted);
+            }
+            srv.call(req)
+        }));
+    })
+    .await
+}
+
+async fn call_sub(server: &TestServer, session_id: &str, id: i64, scopes: &str) -> Value {
+    let response = server
+        .client
+        .post(server.url("/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .header("Content-Type", "application/json")
+        .header("Mcp-Session-Id", session_id)
+        .header(SCOPES_HEADER, scopes)
+        .json(&tool_call_request(id, "sub", json!({ "a": 5, "b": 3 })))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    // Rejections are answered directly with JSON, results over SSE
+    if response.headers().get("content-type").unwrap() == "application/json" {
+        return response.json().await.expect("JSON-RPC error body");
+    }
+    read_sse_messages(response)
+        .await
+        .into_iter()
+        .find(|m| m["id"] == id)
+        .expect("response to the tool call")
+}
+
+#[actix_web::test]
+async fn tool_requires_every_mapped_scope() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    let error = call_sub(&server, &session_id, 2, "mcp:admin mcp:read").await;
+    assert_eq!(error["error"]["code"], -32600);
+    assert_eq!(error["error"]["message"], "Insufficient scope for tool sub");
+    assert_eq!(
+        error["error"]["data"]["required_scopes"],
+        json!(["mcp:write"])
+    );
+
+    let result = call_sub(&server, &session_id, 3, "mcp:admin mcp:write").await;
+    assert_eq!(result["result"]["structuredContent"]["value"], 2);
+}
+
+#[actix_web::test]
+async fn unmapped_tools_and_requests_without_scopes() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+
+    // No GrantedScopes extension at all: mapped tools are rejected...
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sub", json!({ "a": 5, "b": 3 })),
+        )
+        .await;
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 2);
+    assert_eq!(
+        error["error"]["data"]["required_scopes"],
+        json!(["mcp:admin", "mcp:write"])
+    );
+
+    // ...while unmapped tools are not restricted
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(3, "sum", json!({ "a": 2, "b": 3 })),
+        )
+        .await;
+    let messages = read_sse_messages(response).await;
+    let result = messages.iter().find(|m| m["id"] == 3).expect("sum result");
+    assert_eq!(result["result"]["structuredContent"]["value"], 5);
+}
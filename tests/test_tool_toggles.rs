@@ -0,0 +1,165 @@
+//! Integration tests for switching tools off and on at runtime.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{SseEventReader, TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+const ADMIN_KEY: &str = "operator-key";
+
+fn build_service() -> StreamableHttpService<Calculator, LocalSessionManager> {
+    StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build()
+}
+
+async fn spawn_server(
+    service: StreamableHttpService<Calculator, LocalSessionManager>,
+) -> TestServer {
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.tool_admin("/admin", [ADMIN_KEY]))
+            .service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn admin_post(server: &TestServer, path: &str) -> reqwest::Response {
+    server
+        .client
+        .post(server.url(path))
+        .header("X-Api-Key", ADMIN_KEY)
+        .send()
+        .await
+        .expect("Failed to send admin request")
+}
+
+async fn tool_names(server: &TestServer, session_id: &str) -> Vec<String> {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &json!({ "jsonrpc": "2.0", "method": "tools/list", "id": 5 }),
+        )
+        .await;
+    let messages = read_sse_messages(response).await;
+    let tools = messages[0]["result"]["tools"]
+        .as_array()
+        .expect("tools/list answers with tools");
+    tools
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[actix_web::test]
+async fn disabled_tool_is_hidden_and_rejected() {
+    let server = spawn_server(build_service()).await;
+    let session_id = server.initialize("/mcp").await;
+
+    let response = admin_post(&server, "/admin/tools/sub/disable").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["changed"], true);
+
+    assert_eq!(tool_names(&server, &session_id).await, ["sum"]);
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sub", json!({ "a": 5, "b": 3 })),
+        )
+        .await;
+    let error: Value = response.json().await.expect("JSON-RPC error body");
+    assert_eq!(error["id"], 2);
+    assert_eq!(error["error"]["code"], -32602);
+    assert_eq!(error["error"]["message"], "Tool disabled: sub");
+
+    let response = admin_post(&server, "/admin/tools/sub/enable").await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+    let mut names = tool_names(&server, &session_id).await;
+    names.sort();
+    assert_eq!(names, ["sub", "sum"]);
+}
+
+#[actix_web::test]
+async fn toggling_notifies_running_sessions() {
+    let server = spawn_server(build_service()).await;
+    let session_id = server.initialize("/mcp").await;
+    let response = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to open standalone stream");
+    let mut events = SseEventReader::new(response);
+
+    admin_post(&server, "/admin/tools/sum/disable").await;
+
+    let notification = loop {
+        let event = events
+            .next_event(Duration::from_secs(2))
+            .await
+            .expect("standalone stream must deliver the notification");
+        if !event.data.is_empty() {
+            break event.json();
+        }
+    };
+    assert_eq!(notification["method"], "notifications/tools/list_changed");
+}
+
+#[actix_web::test]
+async fn repeated_toggle_reports_no_change() {
+    let service = build_service();
+    assert!(service.disable_tool("sum").await);
+    assert!(!service.disable_tool("sum").await);
+    assert_eq!(service.disabled_tools(), ["sum"]);
+
+    let server = spawn_server(service.clone()).await;
+    let response = server
+        .client
+        .get(server.url("/admin/tools"))
+        .header("X-Api-Key", ADMIN_KEY)
+        .send()
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["disabled"], json!(["sum"]));
+
+    assert!(service.enable_tool("sum").await);
+    assert!(!service.enable_tool("sum").await);
+    assert!(service.disabled_tools().is_empty());
+}
+
+#[actix_web::test]
+async fn admin_routes_require_api_key() {
+    let server = spawn_server(build_service()).await;
+
+    let response = server
+        .client
+        .post(server.url("/admin/tools/sum/disable"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let response = server
+        .client
+        .get(server.url("/admin/tools"))
+        .header("X-Api-Key", "wrong")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+}
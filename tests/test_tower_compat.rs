@@ -0,0 +1,83 @@
+//! Integration tests for mounting rmcp's tower Streamable HTTP service.
+//!
+//! These tests verify that `TowerStreamableHttpService` forwards requests to
+//! the wrapped rmcp service and streams its responses back unchanged, so that
+//! applications migrating from the Axum transport keep the same wire behavior.
+
+mod common;
+
+use std::sync::Arc;
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::TowerStreamableHttpService;
+use serde_json::json;
+
+async fn spawn_server() -> TestServer {
+    let service = TowerStreamableHttpService::new(StreamableHttpService::new(
+        || Ok(Calculator::new()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    ));
+
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn tower_service_handles_session_lifecycle() {
+    let server = spawn_server().await;
+
+    let session_id = server.initialize("/mcp").await;
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sum", json!({ "a": 2, "b": 3 })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    let messages = read_sse_messages(response).await;
+    let result = messages
+        .iter()
+        .find(|m| m["id"] == 2)
+        .expect("tool call response");
+    assert_eq!(result["result"]["structuredContent"]["value"], 5);
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert!(response.status().is_success());
+}
+
+#[actix_web::test]
+async fn tower_service_answers_unsupported_methods() {
+    let server = spawn_server().await;
+
+    let response = server
+        .client
+        .put(server.url("/mcp"))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(response.status(), reqwest::StatusCode::METHOD_NOT_ALLOWED);
+    assert!(response.headers().get("allow").is_some());
+}
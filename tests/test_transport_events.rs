@@ -0,0 +1,130 @@
+//! Integration tests for the transport event broadcast.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::{
+    calculator::Calculator,
+    test_server::{TestServer, read_sse_messages, tool_call_request},
+};
+use rmcp::{
+    model::{NumberOrString, RequestId},
+    transport::streamable_http_server::session::local::LocalSessionManager,
+};
+use rmcp_actix_web::transport::{SessionCloseReason, StreamableHttpService, TransportEvent};
+use serde_json::json;
+use tokio::sync::broadcast;
+
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+async fn next_event(events: &mut broadcast::Receiver<TransportEvent>) -> TransportEvent {
+    tokio::time::timeout(EVENT_TIMEOUT, events.recv())
+        .await
+        .expect("timed out waiting for a transport event")
+        .expect("event channel closed")
+}
+
+#[actix_web::test]
+async fn session_request_and_stream_events_are_broadcast() {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(Calculator::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .build();
+    let mut events = service.subscribe_events();
+    let server = TestServer::spawn({
+        let service = service.clone();
+        move |cfg| {
+            cfg.service(service.clone().scope_with_path("/mcp"));
+        }
+    })
+    .await;
+
+    let session_id = server.initialize("/mcp").await;
+    let session: Arc<str> = session_id.as_str().into();
+    assert_eq!(
+        next_event(&mut events).await,
+        TransportEvent::RequestStarted {
+            session_id: None,
+            request_id: RequestId::from(NumberOrString::Number(1)),
+            method: "initialize".to_owned(),
+        }
+    );
+    assert_eq!(
+        next_event(&mut events).await,
+        TransportEvent::SessionCreated {
+            session_id: session.clone(),
+        }
+    );
+    assert!(matches!(
+        next_event(&mut events).await,
+        TransportEvent::RequestCompleted { session_id: None, ref method, .. } if method == "initialize"
+    ));
+
+    let response = server
+        .post(
+            "/mcp",
+            Some(&session_id),
+            &tool_call_request(2, "sum", json!({ "a": 1, "b": 2 })),
+        )
+        .await;
+    read_sse_messages(response).await;
+    assert_eq!(
+        next_event(&mut events).await,
+        TransportEvent::RequestStarted {
+            session_id: Some(session.clone()),
+            request_id: RequestId::from(NumberOrString::Number(2)),
+            method: "tools/call".to_owned(),
+        }
+    );
+    assert!(matches!(
+        next_event(&mut events).await,
+        TransportEvent::RequestCompleted { session_id: Some(ref id), ref method, .. }
+            if *id == session && method == "tools/call"
+    ));
+
+    let stream = server
+        .client
+        .get(server.url("/mcp"))
+        .header("Accept", "text/event-stream")
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to open stream");
+    assert_eq!(stream.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        next_event(&mut events).await,
+        TransportEvent::StreamOpened {
+            session_id: session.clone(),
+        }
+    );
+
+    let response = server
+        .client
+        .delete(server.url("/mcp"))
+        .header("Mcp-Session-Id", &session_id)
+        .send()
+        .await
+        .expect("Failed to delete session");
+    assert!(response.status().is_success());
+    drop(stream);
+
+    let mut closed = false;
+    let mut dropped = false;
+    while !(closed && dropped) {
+        match next_event(&mut events).await {
+            TransportEvent::SessionClosed {
+                session_id, reason, ..
+            } => {
+                assert_eq!(session_id, session);
+                assert_eq!(reason, SessionCloseReason::ClientDelete);
+                closed = true;
+            }
+            TransportEvent::StreamDropped { session_id, .. } => {
+                assert_eq!(session_id, session);
+                dropped = true;
+            }
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Integration tests for payload uploads referenced by handle.
+
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::test_server::{TestServer, read_sse_messages, tool_call_request};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+use serde_json::{Value, json};
+
+mod ingest_service {
+    use rmcp::{
+        ErrorData as McpError, RoleServer, ServerHandler,
+        handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+        model::*,
+        schemars,
+        service::RequestContext,
+        tool, tool_handler, tool_router,
+    };
+    use rmcp_actix_web::transport::Uploads;
+
+    #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+    pub struct IngestRequest {
+        /// Handle returned by the upload route
+        pub upload: String,
+    }
+
+    #[derive(Clone)]
+    pub struct IngestService {
+        #[expect(
+            dead_code,
+            reason = "Initialized by Self::new(); the #[tool_handler] macro reads the router via Self::tool_router(), not this field."
+        )]
+        tool_router: ToolRouter<IngestService>,
+    }
+
+    #[tool_router]
+    impl IngestService {
+        pub fn new() -> Self {
+            Self {
+                tool_router: Self::tool_router(),
+            }
+        }
+
+        /// Describes an uploaded payload
+        #[tool(description = "Ingest an uploaded payload")]
+        async fn ingest(
+            &self,
+            Parameters(IngestRequest { upload }): Parameters<IngestRequest>,
+            context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            let upload = context
+                .extensions
+                .get::<Uploads>()
+                .and_then(|uploads| uploads.get(&upload))
+                .ok_or_else(|| McpError::invalid_params("unknown upload", None))?;
+            Ok(CallToolResult::success(vec![Content::text(format!(
+                "{} {}",
+                upload.mime_type,
+                upload.data.len()
+            ))]))
+        }
+    }
+
+    #[tool_handler]
+    impl ServerHandler for IngestService {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
+        }
+    }
+}
+
+async fn spawn_server() -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(|| Ok(ingest_service::IngestService::new())))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .upload_ttl(Duration::from_secs(60))
+        .max_upload_size(1024 * 1024)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+async fn upload(server: &TestServer, session_id: Option<&str>, body: Vec<u8>) -> reqwest::Response {
+    let mut request = server
+        .client
+        .post(server.url("/mcp/uploads"))
+        .header("Content-Type", "text/csv")
+        .body(body);
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    request.send().await.expect("Failed to upload")
+}
+
+async fn upload_handle(server: &TestServer, session_id: &str, body: Vec<u8>) -> String {
+    let response = upload(server, Some(session_id), body).await;
+    assert_eq!(response.status(), reqwest::StatusCode::CREATED);
+    let body: Value = response.json().await.expect("upload answer must be JSON");
+    body["handle"].as_str().expect("handle").to_string()
+}
+
+async fn ingest(server: &TestServer, session_id: &str, handle: &str) -> Value {
+    let response = server
+        .post(
+            "/mcp",
+            Some(session_id),
+            &tool_call_request(2, "ingest", json!({ "upload": handle })),
+        )
+        .await;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    read_sse_messages(response).await.remove(0)
+}
+
+#[actix_web::test]
+async fn uploaded_payload_is_resolved_by_handle() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let handle = upload_handle(&server, &session_id, vec![b'x'; 300 * 1024]).await;
+
+    let message = ingest(&server, &session_id, &handle).await;
+    assert_eq!(message["result"]["content"][0]["text"], "text/csv 307200");
+}
+
+#[actix_web::test]
+async fn uploads_are_scoped_to_their_session() {
+    let server = spawn_server().await;
+    let session_id = server.initialize("/mcp").await;
+    let other_session_id = server.initialize("/mcp").await;
+    let handle = upload_handle(&server, &session_id, b"a,b\n1,2\n".to_vec()).await;
+
+    let message = ingest(&server, &other_session_id, &handle).await;
+    assert!(message["error"].is_object(), "{message}");
+}
+
+#[actix_web::test]
+async fn upload_requires_a_known_session_and_respects_the_limit() {
+    let server = spawn_server().await;
+
+    let response = upload(&server, None, b"data".to_vec()).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let response = upload(&server, Some("unknown"), b"data".to_vec()).await;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let session_id = server.initialize("/mcp").await;
+    let response = upload(&server, Some(&session_id), vec![0; 2 * 1024 * 1024]).await;
+    assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+}
@@ -0,0 +1,77 @@
+//! Integration tests for the warm pool of service instances.
+
+mod common;
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use common::{calculator::Calculator, test_server::TestServer};
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp_actix_web::transport::StreamableHttpService;
+
+const CONSTRUCTION_TIME: Duration = Duration::from_millis(300);
+
+async fn spawn_server(constructed: Arc<AtomicUsize>) -> TestServer {
+    let service = StreamableHttpService::builder()
+        .service_factory(Arc::new(move || {
+            // Stands in for loading a model or warming up a connection pool
+            std::thread::sleep(CONSTRUCTION_TIME);
+            constructed.fetch_add(1, Ordering::SeqCst);
+            Ok(Calculator::new())
+        }))
+        .session_manager(Arc::new(LocalSessionManager::default()))
+        .warm_pool_size(2)
+        .build();
+    TestServer::spawn(move |cfg| {
+        cfg.service(service.clone().scope_with_path("/mcp"));
+    })
+    .await
+}
+
+#[actix_web::test]
+async fn pool_is_filled_when_mounted() {
+    let constructed = Arc::new(AtomicUsize::new(0));
+    let _server = spawn_server(constructed.clone()).await;
+
+    tokio::time::sleep(CONSTRUCTION_TIME * 2).await;
+    assert_eq!(constructed.load(Ordering::SeqCst), 2);
+}
+
+#[actix_web::test]
+async fn sessions_take_instances_from_pool_and_pool_is_refilled() {
+    let constructed = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(constructed.clone()).await;
+    tokio::time::sleep(CONSTRUCTION_TIME * 2).await;
+
+    for _ in 0..2 {
+        let started = Instant::now();
+        server.initialize("/mcp").await;
+        assert!(
+            started.elapsed() < CONSTRUCTION_TIME,
+            "session creation waited for the factory"
+        );
+    }
+
+    // Both instances taken are replaced in the background
+    tokio::time::sleep(CONSTRUCTION_TIME * 2).await;
+    assert_eq!(constructed.load(Ordering::SeqCst), 4);
+}
+
+#[actix_web::test]
+async fn dry_pool_falls_back_to_factory() {
+    let constructed = Arc::new(AtomicUsize::new(0));
+    let server = spawn_server(constructed.clone()).await;
+    tokio::time::sleep(CONSTRUCTION_TIME * 2).await;
+
+    for _ in 0..3 {
+        server.initialize("/mcp").await;
+    }
+    tokio::time::sleep(CONSTRUCTION_TIME * 2).await;
+    // Two from the pool, one directly, and the pool refilled to two
+    assert_eq!(constructed.load(Ordering::SeqCst), 5);
+}